@@ -0,0 +1,125 @@
+//! Thumbnails drawn with colored half blocks inside the ratatui frame, for
+//! terminals without an image protocol.
+//!
+//! Each cell shows two pixels, as the foreground and background of `▀`.
+//! Being ordinary cells they are redrawn, covered and cleared along with
+//! the rest of the screen. Images are decoded and scaled once per size.
+
+use crate::config::Graphics;
+use crate::iterm;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Scaled images kept before the cache starts over.
+const MAX_CACHED: usize = 64;
+
+/// Whether `graphics` comes down to half blocks in this terminal.
+pub fn wanted(graphics: Graphics) -> bool {
+    match graphics {
+        Graphics::Blocks => true,
+        Graphics::Auto => {
+            viuer::get_kitty_support() == viuer::KittySupport::None
+                && !iterm::supported()
+                && !viuer::is_sixel_supported()
+        }
+        Graphics::Kitty | Graphics::Iterm | Graphics::Sixel | Graphics::Off => false,
+    }
+}
+
+/// Top and bottom pixel of each cell, row by row.
+type Cells = Vec<(Color, Color)>;
+
+#[derive(Default)]
+pub struct HalfBlocks {
+    /// By file and size; `None` when the file couldn't be decoded.
+    cells: HashMap<(PathBuf, u16, u16), Option<Cells>>,
+}
+
+impl HalfBlocks {
+    /// Draws the image in `path` stretched over `area`, which already has
+    /// its aspect ratio.
+    pub fn draw(&mut self, path: &Path, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        if area.is_empty() {
+            return;
+        }
+        let key = (path.to_path_buf(), area.width, area.height);
+        if !self.cells.contains_key(&key) && self.cells.len() >= MAX_CACHED {
+            self.cells.clear();
+        }
+        let cells = self
+            .cells
+            .entry(key)
+            .or_insert_with(|| scale(path, area.width, area.height));
+        let Some(cells) = cells else {
+            return;
+        };
+        for (index, (top, bottom)) in cells.iter().enumerate() {
+            let x = area.x + (index % usize::from(area.width)) as u16;
+            let y = area.y + (index / usize::from(area.width)) as u16;
+            buf.get_mut(x, y).set_char('▀').set_fg(*top).set_bg(*bottom);
+        }
+    }
+}
+
+fn scale(path: &Path, width: u16, height: u16) -> Option<Cells> {
+    let image = match image::open(path) {
+        Ok(image) => image,
+        Err(err) => {
+            log::warn!("Could not decode {}: {err}", path.display());
+            return None;
+        }
+    };
+    let (width, height) = (u32::from(width), u32::from(height));
+    let pixels = image::imageops::resize(
+        &image.to_rgb8(),
+        width,
+        height * 2,
+        image::imageops::FilterType::Triangle,
+    );
+    let truecolor = std::env::var("COLORTERM")
+        .is_ok_and(|colorterm| colorterm == "truecolor" || colorterm == "24bit");
+    let color = |x, y| {
+        let [r, g, b] = pixels.get_pixel(x, y).0;
+        if truecolor {
+            Color::Rgb(r, g, b)
+        } else {
+            Color::Indexed(xterm_index(r, g, b))
+        }
+    };
+    Some(
+        (0..height)
+            .flat_map(|row| (0..width).map(move |column| (column, row)))
+            .map(|(x, y)| (color(x, y * 2), color(x, y * 2 + 1)))
+            .collect(),
+    )
+}
+
+/// The nearest of the 256 xterm colors, from its 6x6x6 cube or gray ramp.
+fn xterm_index(r: u8, g: u8, b: u8) -> u8 {
+    let level = |value: u8| match value {
+        0..=47 => 0,
+        48..=114 => 1,
+        value => (value - 35) / 40,
+    };
+    let step = |level: u8| if level == 0 { 0 } else { 55 + level * 40 };
+    let (cr, cg, cb) = (level(r), level(g), level(b));
+    let cube = 16 + 36 * cr + 6 * cg + cb;
+    let gray_level = ((u16::from(r) + u16::from(g) + u16::from(b)) / 3).saturating_sub(3) / 10;
+    let gray_level = gray_level.min(23) as u8;
+    let gray = 8 + gray_level * 10;
+    let distance = |(tr, tg, tb): (u8, u8, u8)| {
+        [(r, tr), (g, tg), (b, tb)]
+            .iter()
+            .map(|&(a, b)| (i32::from(a) - i32::from(b)).pow(2))
+            .sum::<i32>()
+    };
+    if distance((gray, gray, gray)) < distance((step(cr), step(cg), step(cb))) {
+        232 + gray_level
+    } else {
+        cube
+    }
+}
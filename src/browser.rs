@@ -0,0 +1,38 @@
+//! Opening links in the default web browser.
+//!
+//! `$BROWSER` wins when it is set; otherwise the platform's opener is used
+//! (`xdg-open`, `open` or `start`).
+
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Opens `url` without waiting for the browser, returning the program used.
+pub fn open(url: &str) -> Result<String, String> {
+    let (program, args) = opener();
+    let mut child = Command::new(&program)
+        .args(args)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("Failed to run {program}: {err}"))
+        .inspect_err(|err| log::error!("{err}"))?;
+    // Reaped in the background so the opener doesn't linger as a zombie.
+    thread::spawn(move || child.wait());
+    Ok(program)
+}
+
+fn opener() -> (String, &'static [&'static str]) {
+    if let Some(browser) = std::env::var("BROWSER").ok().filter(|b| !b.is_empty()) {
+        return (browser, &[]);
+    }
+    if cfg!(target_os = "macos") {
+        ("open".to_string(), &[])
+    } else if cfg!(windows) {
+        // `start` is a cmd builtin; its first quoted argument is a window title.
+        ("cmd".to_string(), &["/C", "start", ""])
+    } else {
+        ("xdg-open".to_string(), &[])
+    }
+}
@@ -0,0 +1,226 @@
+//! Casting to Chromecasts and DLNA/UPnP media renderers on the local
+//! network.
+//!
+//! DLNA renderers are found with an SSDP search and driven through their
+//! AVTransport service; Chromecasts are handled in `chromecast`.
+
+use crate::chromecast::{self, Chromecast};
+use regex::Regex;
+use std::net::UdpSocket;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SSDP_ADDR: &str = "239.255.255.250:1900";
+const AV_TRANSPORT: &str = "urn:schemas-upnp-org:service:AVTransport:1";
+
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    pub name: String,
+    device: Device,
+}
+
+#[derive(Debug, Clone)]
+enum Device {
+    /// A DLNA renderer and the control URL of its AVTransport service.
+    Dlna {
+        control_url: String,
+    },
+    Chromecast(Chromecast),
+}
+
+/// Looks for Chromecasts and DLNA renderers at the same time and collects
+/// those that answer within `timeout`. Fails only when both searches do.
+pub fn discover(timeout: Duration) -> Result<Vec<Renderer>, String> {
+    let chromecasts = thread::spawn(move || chromecast::discover(timeout));
+    let dlna = discover_dlna(timeout);
+    let chromecasts = chromecasts
+        .join()
+        .unwrap_or_else(|_| Err("mDNS search failed.".to_string()));
+    match (dlna, chromecasts) {
+        (Err(err), Err(_)) => Err(err),
+        (dlna, chromecasts) => {
+            let dlna = dlna.unwrap_or_else(|err| {
+                log::warn!("{err}");
+                Vec::new()
+            });
+            let chromecasts = chromecasts.unwrap_or_else(|err| {
+                log::warn!("{err}");
+                Vec::new()
+            });
+            Ok(chromecasts
+                .into_iter()
+                .map(|device| Renderer {
+                    name: device.name.clone(),
+                    device: Device::Chromecast(device),
+                })
+                .chain(dlna)
+                .collect())
+        }
+    }
+}
+
+/// Sends an SSDP search and collects the DLNA renderers that answer within
+/// `timeout`.
+fn discover_dlna(timeout: Duration) -> Result<Vec<Renderer>, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|err| format!("SSDP error: {err}"))?;
+    let search = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {SSDP_ADDR}\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\nST: {AV_TRANSPORT}\r\n\r\n"
+    );
+    socket
+        .send_to(search.as_bytes(), SSDP_ADDR)
+        .map_err(|err| format!("SSDP error: {err}"))?;
+
+    let deadline = Instant::now() + timeout;
+    let mut locations = Vec::new();
+    let mut buf = [0u8; 2048];
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        if left.is_zero() || socket.set_read_timeout(Some(left)).is_err() {
+            break;
+        }
+        let Ok((len, _)) = socket.recv_from(&mut buf) else {
+            break;
+        };
+        let response = String::from_utf8_lossy(&buf[..len]);
+        let location = response.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("location")
+                .then(|| value.trim().to_string())
+        });
+        if let Some(location) = location {
+            if !locations.contains(&location) {
+                locations.push(location);
+            }
+        }
+    }
+
+    // Devices that can't be described are skipped rather than failing the search.
+    Ok(locations
+        .iter()
+        .filter_map(|location| describe(location).ok())
+        .collect())
+}
+
+/// Reads a device description and finds its AVTransport control URL.
+fn describe(location: &str) -> Result<Renderer, String> {
+    let xml = reqwest::blocking::get(location)
+        .and_then(|response| response.text())
+        .map_err(|err| format!("Device error: {err}"))?;
+    let name = Regex::new(r"(?s)<friendlyName>(.*?)</friendlyName>")
+        .ok()
+        .and_then(|re| re.captures(&xml))
+        .map_or_else(|| location.to_string(), |caps| caps[1].trim().to_string());
+    let service = Regex::new(r"(?s)<service>(.*?)</service>").map_err(|err| err.to_string())?;
+    let control =
+        Regex::new(r"(?s)<controlURL>(.*?)</controlURL>").map_err(|err| err.to_string())?;
+    let control_path = service
+        .captures_iter(&xml)
+        .map(|caps| caps[1].to_string())
+        .find(|block| block.contains(AV_TRANSPORT))
+        .and_then(|block| {
+            control
+                .captures(&block)
+                .map(|caps| caps[1].trim().to_string())
+        })
+        .ok_or_else(|| format!("{name} has no AVTransport service."))?;
+    let control_url = reqwest::Url::parse(location)
+        .and_then(|base| base.join(&control_path))
+        .map_err(|err| format!("Device error: {err}"))?;
+    Ok(Renderer {
+        name,
+        device: Device::Dlna {
+            control_url: control_url.to_string(),
+        },
+    })
+}
+
+impl Renderer {
+    /// What kind of device this is, for the picker.
+    pub fn kind(&self) -> &'static str {
+        match self.device {
+            Device::Dlna { .. } => "DLNA",
+            Device::Chromecast(_) => "Chromecast",
+        }
+    }
+
+    /// Loads `url` on the renderer and starts playing it.
+    pub fn play_url(&self, url: &str, title: &str) -> Result<(), String> {
+        let control_url = match &self.device {
+            Device::Dlna { control_url } => control_url,
+            Device::Chromecast(device) => return device.load(url, title),
+        };
+        let metadata = format!(
+            "<DIDL-Lite xmlns=\"urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/\" \
+             xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+             xmlns:upnp=\"urn:schemas-upnp-org:metadata-1-0/upnp/\">\
+             <item id=\"0\" parentID=\"-1\" restricted=\"1\"><dc:title>{}</dc:title>\
+             <upnp:class>object.item.videoItem</upnp:class>\
+             <res protocolInfo=\"http-get:*:video/mp4:*\">{}</res></item></DIDL-Lite>",
+            escape_xml(title),
+            escape_xml(url)
+        );
+        self.action(
+            control_url,
+            "SetAVTransportURI",
+            &format!(
+                "<CurrentURI>{}</CurrentURI><CurrentURIMetaData>{}</CurrentURIMetaData>",
+                escape_xml(url),
+                escape_xml(&metadata)
+            ),
+        )?;
+        self.resume()
+    }
+
+    pub fn resume(&self) -> Result<(), String> {
+        match &self.device {
+            Device::Dlna { control_url } => self.action(control_url, "Play", "<Speed>1</Speed>"),
+            Device::Chromecast(device) => device.resume(),
+        }
+    }
+
+    pub fn pause(&self) -> Result<(), String> {
+        match &self.device {
+            Device::Dlna { control_url } => self.action(control_url, "Pause", ""),
+            Device::Chromecast(device) => device.pause(),
+        }
+    }
+
+    pub fn stop(&self) -> Result<(), String> {
+        match &self.device {
+            Device::Dlna { control_url } => self.action(control_url, "Stop", ""),
+            Device::Chromecast(device) => device.stop(),
+        }
+    }
+
+    fn action(&self, control_url: &str, action: &str, arguments: &str) -> Result<(), String> {
+        let body = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+             <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+             s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\"><s:Body>\
+             <u:{action} xmlns:u=\"{AV_TRANSPORT}\"><InstanceID>0</InstanceID>{arguments}</u:{action}>\
+             </s:Body></s:Envelope>"
+        );
+        let response = reqwest::blocking::Client::new()
+            .post(control_url)
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header("SOAPAction", format!("\"{AV_TRANSPORT}#{action}\""))
+            .body(body)
+            .send()
+            .map_err(|err| format!("Cast error: {err}"))?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} rejected {action} ({}).",
+                self.name,
+                response.status()
+            ))
+        }
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,657 @@
+//! Casting to Chromecasts with the Cast v2 protocol.
+//!
+//! Devices are found with an mDNS query for `_googlecast._tcp`. Every
+//! command opens its own TLS connection to the device (which presents a
+//! self-signed certificate) and exchanges length-prefixed protobuf
+//! `CastMessage`s carrying JSON. Videos play in the Default Media Receiver,
+//! which keeps playing after the connection is closed.
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, ClientConnection, DigitallySignedStruct, SignatureScheme, StreamOwned};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpStream, UdpSocket};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const MDNS_ADDR: &str = "224.0.0.251:5353";
+const SERVICE: &str = "_googlecast._tcp.local";
+const CAST_PORT: u16 = 8009;
+/// App id of the Default Media Receiver.
+const MEDIA_RECEIVER: &str = "CC1AD845";
+const CONNECTION: &str = "urn:x-cast:com.google.cast.tp.connection";
+const HEARTBEAT: &str = "urn:x-cast:com.google.cast.tp.heartbeat";
+const RECEIVER: &str = "urn:x-cast:com.google.cast.receiver";
+const MEDIA: &str = "urn:x-cast:com.google.cast.media";
+const SENDER_ID: &str = "sender-0";
+const RECEIVER_ID: &str = "receiver-0";
+/// How long a device gets to answer a request, launching the receiver
+/// included.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(15);
+/// Largest message accepted from a device; the protocol's limit is 64 KiB.
+const MAX_MESSAGE: usize = 1 << 16;
+
+#[derive(Debug, Clone)]
+pub struct Chromecast {
+    pub name: String,
+    address: SocketAddr,
+}
+
+/// Sends an mDNS query and collects the Chromecasts that answer within
+/// `timeout`.
+pub fn discover(timeout: Duration) -> Result<Vec<Chromecast>, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|err| format!("mDNS error: {err}"))?;
+    socket
+        .send_to(&query(), MDNS_ADDR)
+        .map_err(|err| format!("mDNS error: {err}"))?;
+
+    let deadline = Instant::now() + timeout;
+    let mut devices: Vec<Chromecast> = Vec::new();
+    let mut buf = [0u8; 4096];
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        if left.is_zero() || socket.set_read_timeout(Some(left)).is_err() {
+            break;
+        }
+        let Ok((len, from)) = socket.recv_from(&mut buf) else {
+            break;
+        };
+        // Answers that can't be parsed are skipped, like other services'.
+        for device in parse_response(&buf[..len], from.ip()).unwrap_or_default() {
+            if !devices.iter().any(|known| known.address == device.address) {
+                devices.push(device);
+            }
+        }
+    }
+    Ok(devices)
+}
+
+/// A query for the Cast service's PTR records, asking for a unicast answer
+/// since the socket isn't on the mDNS port.
+fn query() -> Vec<u8> {
+    let mut packet = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in SERVICE.split('.') {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    // Type PTR, class IN with the unicast-response bit set.
+    packet.extend_from_slice(&[0, 12, 0x80, 1]);
+    packet
+}
+
+/// The Chromecasts announced in an mDNS response sent from `from`. PTR
+/// records name them, SRV records give their port and host, A records the
+/// host's address and TXT records the friendly name; whatever is missing
+/// falls back to the sender's address, the default port or the instance
+/// name.
+fn parse_response(packet: &[u8], from: IpAddr) -> Option<Vec<Chromecast>> {
+    let count = |at: usize| -> Option<usize> {
+        Some(u16::from_be_bytes([*packet.get(at)?, *packet.get(at + 1)?]) as usize)
+    };
+    let questions = count(4)?;
+    let records = count(6)? + count(8)? + count(10)?;
+    let mut pos = 12;
+    for _ in 0..questions {
+        read_name(packet, &mut pos)?;
+        pos += 4;
+    }
+
+    let mut instances = Vec::new();
+    let mut services = HashMap::new();
+    let mut names = HashMap::new();
+    let mut addresses = HashMap::new();
+    for _ in 0..records {
+        let owner = read_name(packet, &mut pos)?.to_ascii_lowercase();
+        let header = packet.get(pos..pos + 10)?;
+        let kind = u16::from_be_bytes([header[0], header[1]]);
+        let len = u16::from_be_bytes([header[8], header[9]]) as usize;
+        let start = pos + 10;
+        let data = packet.get(start..start + len)?;
+        pos = start + len;
+        match kind {
+            1 if len == 4 => {
+                addresses.insert(owner, IpAddr::from([data[0], data[1], data[2], data[3]]));
+            }
+            12 if owner == SERVICE => {
+                let instance = read_name(packet, &mut { start })?;
+                if !instances.contains(&instance) {
+                    instances.push(instance);
+                }
+            }
+            16 => {
+                let mut at = 0;
+                while let Some(&len) = data.get(at) {
+                    let entry = data.get(at + 1..at + 1 + len as usize)?;
+                    if let Some(name) = entry.strip_prefix(b"fn=") {
+                        names.insert(owner.clone(), String::from_utf8_lossy(name).into_owned());
+                    }
+                    at += 1 + len as usize;
+                }
+            }
+            33 if len >= 6 => {
+                let port = u16::from_be_bytes([data[4], data[5]]);
+                let host = read_name(packet, &mut { start + 6 })?.to_ascii_lowercase();
+                services.insert(owner, (port, host));
+            }
+            _ => {}
+        }
+    }
+
+    Some(
+        instances
+            .into_iter()
+            .map(|instance| {
+                let key = instance.to_ascii_lowercase();
+                let (port, host) = services
+                    .get(&key)
+                    .map_or((CAST_PORT, None), |(port, host)| (*port, Some(host)));
+                let ip = host
+                    .and_then(|host| addresses.get(host))
+                    .copied()
+                    .unwrap_or(from);
+                let name = names.remove(&key).unwrap_or_else(|| {
+                    let label = instance.strip_suffix(SERVICE).unwrap_or(&instance);
+                    label.trim_end_matches('.').to_string()
+                });
+                Chromecast {
+                    name,
+                    address: SocketAddr::new(ip, port),
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Reads the possibly compressed domain name at `pos` and moves `pos` past
+/// it.
+fn read_name(packet: &[u8], pos: &mut usize) -> Option<String> {
+    let mut labels = Vec::new();
+    let mut at = *pos;
+    let mut jumped = false;
+    // Bounds the pointers a malformed packet could loop through.
+    for _ in 0..128 {
+        let len = *packet.get(at)? as usize;
+        if len == 0 {
+            if !jumped {
+                *pos = at + 1;
+            }
+            return Some(labels.join("."));
+        }
+        if len & 0xc0 == 0xc0 {
+            if !jumped {
+                *pos = at + 2;
+            }
+            jumped = true;
+            at = (len & 0x3f) << 8 | *packet.get(at + 1)? as usize;
+            continue;
+        }
+        let label = packet.get(at + 1..at + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        at += 1 + len;
+    }
+    None
+}
+
+impl Chromecast {
+    /// Launches the Default Media Receiver and has it play `url`.
+    pub fn load(&self, url: &str, title: &str) -> Result<(), String> {
+        let mut connection = Connection::open(self.address)?;
+        let status = connection.request(
+            RECEIVER_ID,
+            RECEIVER,
+            json!({ "type": "LAUNCH", "appId": MEDIA_RECEIVER }),
+        )?;
+        let app = media_receiver(&status)
+            .ok_or_else(|| format!("{} couldn't start its media receiver.", self.name))?;
+        connection.connect(&app.transport_id)?;
+        // YouTube only offers HLS for live streams.
+        let (content_type, stream_type) = if url.contains(".m3u8") || url.contains("/hls_") {
+            ("application/x-mpegURL", "LIVE")
+        } else {
+            ("video/mp4", "BUFFERED")
+        };
+        let reply = connection.request(
+            &app.transport_id,
+            MEDIA,
+            json!({
+                "type": "LOAD",
+                "autoplay": true,
+                "media": {
+                    "contentId": url,
+                    "contentType": content_type,
+                    "streamType": stream_type,
+                    "metadata": { "metadataType": 0, "title": title },
+                },
+            }),
+        )?;
+        self.expect_media_status(&reply, "load the video")
+    }
+
+    pub fn resume(&self) -> Result<(), String> {
+        self.media_command("PLAY", "resume")
+    }
+
+    pub fn pause(&self) -> Result<(), String> {
+        self.media_command("PAUSE", "pause")
+    }
+
+    /// Closes the media receiver; nothing to do when it isn't running.
+    pub fn stop(&self) -> Result<(), String> {
+        let mut connection = Connection::open(self.address)?;
+        let status = connection.request(RECEIVER_ID, RECEIVER, json!({ "type": "GET_STATUS" }))?;
+        let Some(app) = media_receiver(&status) else {
+            return Ok(());
+        };
+        connection.request(
+            RECEIVER_ID,
+            RECEIVER,
+            json!({ "type": "STOP", "sessionId": app.session_id }),
+        )?;
+        Ok(())
+    }
+
+    /// Sends `command` for the media the receiver is playing.
+    fn media_command(&self, command: &str, what: &str) -> Result<(), String> {
+        let mut connection = Connection::open(self.address)?;
+        let status = connection.request(RECEIVER_ID, RECEIVER, json!({ "type": "GET_STATUS" }))?;
+        let app = media_receiver(&status)
+            .ok_or_else(|| format!("Nothing is playing on {}.", self.name))?;
+        connection.connect(&app.transport_id)?;
+        let status =
+            connection.request(&app.transport_id, MEDIA, json!({ "type": "GET_STATUS" }))?;
+        let session = status["status"][0]["mediaSessionId"]
+            .as_u64()
+            .ok_or_else(|| format!("Nothing is playing on {}.", self.name))?;
+        let reply = connection.request(
+            &app.transport_id,
+            MEDIA,
+            json!({ "type": command, "mediaSessionId": session }),
+        )?;
+        self.expect_media_status(&reply, what)
+    }
+
+    fn expect_media_status(&self, reply: &Value, what: &str) -> Result<(), String> {
+        match reply["type"].as_str() {
+            Some("MEDIA_STATUS") => Ok(()),
+            kind => Err(format!(
+                "{} couldn't {what} ({}).",
+                self.name,
+                reply["reason"]
+                    .as_str()
+                    .or(kind)
+                    .unwrap_or("no reason given")
+            )),
+        }
+    }
+}
+
+/// The running Default Media Receiver in a `RECEIVER_STATUS`.
+struct ReceiverApp {
+    session_id: String,
+    transport_id: String,
+}
+
+fn media_receiver(status: &Value) -> Option<ReceiverApp> {
+    let app = status["status"]["applications"]
+        .as_array()?
+        .iter()
+        .find(|app| app["appId"] == MEDIA_RECEIVER)?;
+    Some(ReceiverApp {
+        session_id: app["sessionId"].as_str()?.to_string(),
+        transport_id: app["transportId"].as_str()?.to_string(),
+    })
+}
+
+/// A TLS connection to a device, with the virtual connection to the
+/// receiver opened.
+struct Connection {
+    stream: StreamOwned<ClientConnection, TcpStream>,
+    next_request: u64,
+}
+
+impl Connection {
+    fn open(address: SocketAddr) -> Result<Self, String> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let config = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|err| format!("Cast error: {err}"))?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(SelfSigned(provider)))
+            .with_no_client_auth();
+        let tls = ClientConnection::new(Arc::new(config), ServerName::from(address.ip()))
+            .map_err(|err| format!("Cast error: {err}"))?;
+        let socket = TcpStream::connect_timeout(&address, Duration::from_secs(5))
+            .map_err(|err| format!("Cast error: {err}"))?;
+        socket
+            .set_read_timeout(Some(REPLY_TIMEOUT))
+            .map_err(|err| format!("Cast error: {err}"))?;
+        let mut connection = Connection {
+            stream: StreamOwned::new(tls, socket),
+            next_request: 1,
+        };
+        connection.connect(RECEIVER_ID)?;
+        Ok(connection)
+    }
+
+    /// Opens a virtual connection to `destination`: the receiver, or an app
+    /// running on it.
+    fn connect(&mut self, destination: &str) -> Result<(), String> {
+        self.send(destination, CONNECTION, &json!({ "type": "CONNECT" }))
+    }
+
+    fn send(&mut self, destination: &str, namespace: &str, payload: &Value) -> Result<(), String> {
+        let message = encode(SENDER_ID, destination, namespace, &payload.to_string());
+        let mut frame = (message.len() as u32).to_be_bytes().to_vec();
+        frame.extend(message);
+        self.stream
+            .write_all(&frame)
+            .and_then(|()| self.stream.flush())
+            .map_err(|err| format!("Cast error: {err}"))
+    }
+
+    fn receive(&mut self) -> Result<Message, String> {
+        let mut len = [0u8; 4];
+        self.stream
+            .read_exact(&mut len)
+            .map_err(|err| format!("Cast error: {err}"))?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len > MAX_MESSAGE {
+            return Err("Cast error: message too long".to_string());
+        }
+        let mut message = vec![0u8; len];
+        self.stream
+            .read_exact(&mut message)
+            .map_err(|err| format!("Cast error: {err}"))?;
+        decode(&message).ok_or_else(|| "Cast error: malformed message".to_string())
+    }
+
+    /// Sends `payload` with a new request id and waits for the answer to
+    /// it, replying to heartbeats meanwhile.
+    fn request(
+        &mut self,
+        destination: &str,
+        namespace: &str,
+        mut payload: Value,
+    ) -> Result<Value, String> {
+        let id = self.next_request;
+        self.next_request += 1;
+        payload["requestId"] = json!(id);
+        self.send(destination, namespace, &payload)?;
+        let deadline = Instant::now() + REPLY_TIMEOUT;
+        while Instant::now() < deadline {
+            let message = self.receive()?;
+            let Ok(reply) = serde_json::from_str::<Value>(&message.payload) else {
+                continue;
+            };
+            if message.namespace == HEARTBEAT {
+                if reply["type"] == "PING" {
+                    self.send(&message.source, HEARTBEAT, &json!({ "type": "PONG" }))?;
+                }
+            } else if reply["requestId"].as_u64() == Some(id) {
+                return Ok(reply);
+            }
+        }
+        Err("Cast error: the device didn't answer".to_string())
+    }
+}
+
+/// Accepts the self-signed certificate Chromecasts present, while still
+/// checking the handshake is signed with it.
+#[derive(Debug)]
+struct SelfSigned(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for SelfSigned {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// The parts of a received `CastMessage` that are used.
+#[derive(Debug, PartialEq)]
+struct Message {
+    source: String,
+    namespace: String,
+    /// Empty for binary payloads.
+    payload: String,
+}
+
+/// Encodes a `CastMessage` with a string payload:
+///
+/// ```text
+/// protocol_version = 1 (varint, CASTV2_1_0 = 0)
+/// source_id = 2, destination_id = 3, namespace = 4 (strings)
+/// payload_type = 5 (varint, STRING = 0), payload_utf8 = 6 (string)
+/// ```
+fn encode(source: &str, destination: &str, namespace: &str, payload: &str) -> Vec<u8> {
+    let mut message = vec![1 << 3, 0];
+    for (field, text) in [(2, source), (3, destination), (4, namespace)] {
+        push_bytes(&mut message, field, text.as_bytes());
+    }
+    message.extend_from_slice(&[5 << 3, 0]);
+    push_bytes(&mut message, 6, payload.as_bytes());
+    message
+}
+
+fn push_bytes(message: &mut Vec<u8>, field: u8, bytes: &[u8]) {
+    // Wire type 2: length-delimited.
+    message.push(field << 3 | 2);
+    let mut len = bytes.len();
+    while len >= 0x80 {
+        message.push(len as u8 | 0x80);
+        len >>= 7;
+    }
+    message.push(len as u8);
+    message.extend_from_slice(bytes);
+}
+
+fn decode(message: &[u8]) -> Option<Message> {
+    let mut pos = 0;
+    let (mut source, mut namespace, mut payload) = (None, None, String::new());
+    while pos < message.len() {
+        let key = read_varint(message, &mut pos)?;
+        match key & 7 {
+            0 => {
+                read_varint(message, &mut pos)?;
+            }
+            2 => {
+                let len = read_varint(message, &mut pos)? as usize;
+                let bytes = message.get(pos..pos.checked_add(len)?)?;
+                pos += len;
+                let text = || String::from_utf8_lossy(bytes).into_owned();
+                match key >> 3 {
+                    2 => source = Some(text()),
+                    4 => namespace = Some(text()),
+                    6 => payload = text(),
+                    _ => {}
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(Message {
+        source: source?,
+        namespace: namespace?,
+        payload,
+    })
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn decodes_what_it_encodes() {
+        let payload = "x".repeat(300);
+        let message = encode("sender-0", "receiver-0", CONNECTION, &payload);
+        assert_eq!(
+            decode(&message),
+            Some(Message {
+                source: "sender-0".to_string(),
+                namespace: CONNECTION.to_string(),
+                payload,
+            })
+        );
+    }
+
+    #[test]
+    fn encodes_a_cast_message() {
+        let message = encode("a", "b", "c", "{}");
+        assert_eq!(
+            message,
+            [0x08, 0, 0x12, 1, b'a', 0x1a, 1, b'b', 0x22, 1, b'c', 0x28, 0, 0x32, 2, b'{', b'}']
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_messages() {
+        let message = encode("sender-0", "receiver-0", HEARTBEAT, "{\"type\":\"PING\"}");
+        assert!(decode(&message[..message.len() - 1]).is_none());
+        assert!(decode(&[0x12]).is_none());
+    }
+
+    /// Appends `name` as uncompressed labels.
+    fn push_name(packet: &mut Vec<u8>, name: &str) {
+        for label in name.split('.') {
+            packet.push(label.len() as u8);
+            packet.extend_from_slice(label.as_bytes());
+        }
+        packet.push(0);
+    }
+
+    fn push_record(packet: &mut Vec<u8>, kind: u16, data: &[u8]) {
+        packet.extend_from_slice(&kind.to_be_bytes());
+        packet.extend_from_slice(&[0x80, 1, 0, 0, 0, 120]);
+        packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        packet.extend_from_slice(data);
+    }
+
+    #[test]
+    fn parses_an_announcement() {
+        let mut packet = vec![0, 0, 0x84, 0, 0, 0, 0, 4, 0, 0, 0, 0];
+        // The PTR owner is at offset 12; later names point back into it.
+        push_name(&mut packet, SERVICE);
+        let mut instance = vec![14];
+        instance.extend_from_slice(b"Chromecast-abc");
+        instance.extend_from_slice(&[0xc0, 12]);
+        push_record(&mut packet, 12, &instance);
+        let instance_at = packet.len() - instance.len();
+        packet.extend_from_slice(&[0xc0, instance_at as u8]);
+        let mut txt = vec![7];
+        txt.extend_from_slice(b"id=1234");
+        txt.push(14);
+        txt.extend_from_slice(b"fn=Living Room");
+        push_record(&mut packet, 16, &txt);
+        packet.extend_from_slice(&[0xc0, instance_at as u8]);
+        let mut srv = vec![0, 0, 0, 0, 0x1f, 0x49];
+        push_name(&mut srv, "abc.local");
+        push_record(&mut packet, 33, &srv);
+        push_name(&mut packet, "abc.local");
+        push_record(&mut packet, 1, &[192, 168, 1, 20]);
+
+        let devices = parse_response(&packet, IpAddr::from(Ipv4Addr::LOCALHOST)).unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].name, "Living Room");
+        assert_eq!(
+            devices[0].address,
+            SocketAddr::from(([192, 168, 1, 20], 8009))
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_sender_and_instance_name() {
+        let mut packet = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        push_name(&mut packet, SERVICE);
+        let mut instance = vec![3];
+        instance.extend_from_slice(b"Den");
+        instance.extend_from_slice(&[0xc0, 12]);
+        push_record(&mut packet, 12, &instance);
+
+        let from = IpAddr::from([10, 0, 0, 5]);
+        let devices = parse_response(&packet, from).unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].name, "Den");
+        assert_eq!(devices[0].address, SocketAddr::new(from, CAST_PORT));
+    }
+
+    #[test]
+    fn rejects_malformed_announcements() {
+        let mut packet = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        // A name pointing at itself.
+        packet.extend_from_slice(&[0xc0, 12]);
+        assert!(parse_response(&packet, IpAddr::from(Ipv4Addr::LOCALHOST)).is_none());
+        assert!(parse_response(&packet[..6], IpAddr::from(Ipv4Addr::LOCALHOST)).is_none());
+    }
+
+    #[test]
+    fn finds_the_media_receiver() {
+        let status = json!({
+            "type": "RECEIVER_STATUS",
+            "status": { "applications": [
+                { "appId": "E8C28D3C", "sessionId": "backdrop", "transportId": "t-0" },
+                { "appId": MEDIA_RECEIVER, "sessionId": "s-1", "transportId": "t-1" },
+            ] },
+        });
+        let app = media_receiver(&status).unwrap();
+        assert_eq!(
+            (app.session_id.as_str(), app.transport_id.as_str()),
+            ("s-1", "t-1")
+        );
+        assert!(media_receiver(&json!({ "status": {} })).is_none());
+    }
+}
@@ -0,0 +1,84 @@
+//! Copying text to the system clipboard.
+//!
+//! The text is always sent as an OSC 52 escape, which most terminals honour
+//! even over SSH, and is also piped to a local clipboard tool when one is
+//! available.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Copies `text`, returning a short description of how it was copied.
+pub fn copy(text: &str) -> Result<String, String> {
+    let osc52 = write_osc52(text);
+    for (tool, args) in local_tools() {
+        if pipe_to(tool, args, text).is_ok() {
+            return Ok(tool.to_string());
+        }
+    }
+    osc52
+        .map(|()| "OSC 52".to_string())
+        .map_err(|err| format!("Clipboard error: {err}"))
+}
+
+fn local_tools() -> Vec<(&'static str, &'static [&'static str])> {
+    let mut tools: Vec<(&str, &[&str])> = Vec::new();
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        tools.push(("wl-copy", &[]));
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        tools.push(("xclip", &["-selection", "clipboard"]));
+        tools.push(("xsel", &["--clipboard", "--input"]));
+    }
+    if cfg!(target_os = "macos") {
+        tools.push(("pbcopy", &[]));
+    }
+    tools
+}
+
+fn pipe_to(tool: &str, args: &[&str], text: &str) -> std::io::Result<()> {
+    let mut child = Command::new(tool)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "{tool} exited with {status}"
+        )))
+    }
+}
+
+fn write_osc52(text: &str) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+pub fn base64(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,384 @@
+//! The `:` command line: parsing and Tab completion.
+//!
+//! Commands and their fixed arguments can be shortened to any unambiguous
+//! prefix, e.g. `:down` or `:sort v`.
+
+use crate::theme;
+
+/// Command names with a short description, in the order completion offers
+/// them.
+pub const COMMANDS: [(&str, &str); 18] = [
+    ("quit", "Quit ytbv"),
+    ("search", "Search for the rest of the line"),
+    ("trending", "Show trending videos"),
+    ("feed", "Show the subscriptions feed"),
+    ("download", "Download the selected video"),
+    (
+        "queue",
+        "Queue the selected video, or every listed one with `all`",
+    ),
+    ("filter", "Filter the results, e.g. `duration>10m views>1m`"),
+    ("sort", "Sort by default, views, newest, duration or title"),
+    ("theme", "Switch to a built-in theme"),
+    (
+        "view",
+        "Show search, feed, history, queue, downloads, favorites, playlists, watch later or stats",
+    ),
+    (
+        "clear",
+        "Clear the history, thumbnail or search cache (asks first)",
+    ),
+    (
+        "import",
+        "Import subscriptions from a NewPipe, Takeout CSV or OPML file",
+    ),
+    (
+        "export",
+        "Write history, bookmarks, subscriptions, watch later and playlists to JSON or CSV files",
+    ),
+    (
+        "cache",
+        "Show how much the thumbnail and search caches hold",
+    ),
+    ("reload-config", "Reload the config file and keys"),
+    (
+        "login",
+        "Log in to YouTube, or use the cookies in a cookies.txt file",
+    ),
+    ("logout", "Log out of YouTube"),
+    ("logs", "Show the end of the log file"),
+];
+
+/// Arguments of `:sort`, in `SortOrder::ALL` order.
+pub const SORT_ORDERS: [&str; 5] = ["default", "views", "newest", "duration", "title"];
+
+/// Arguments of `:view`, in `View::ALL` order.
+pub const VIEWS: [&str; 9] = [
+    "search",
+    "feed",
+    "history",
+    "queue",
+    "downloads",
+    "favorites",
+    "playlists",
+    "later",
+    "stats",
+];
+
+/// Arguments of `:clear`.
+pub const CLEAR_TARGETS: [&str; 3] = ["history", "thumbnails", "searches"];
+
+/// Formats taken by `:export` and `ytbv export`.
+pub const EXPORT_FORMATS: [&str; 2] = ["json", "csv"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Quit,
+    Search(String),
+    Trending,
+    Feed,
+    Download,
+    Queue {
+        all: bool,
+    },
+    Filter(String),
+    /// Index into `SORT_ORDERS`.
+    Sort(usize),
+    Theme(&'static str),
+    /// Index into `VIEWS`.
+    View(usize),
+    /// Index into `CLEAR_TARGETS`.
+    Clear(usize),
+    /// Path of the file to import subscriptions from.
+    Import(String),
+    /// `dir` is empty for the current directory.
+    Export {
+        csv: bool,
+        dir: String,
+    },
+    Cache,
+    ReloadConfig,
+    /// Path of a cookies.txt file; empty for the device-code login.
+    Login(String),
+    Logout,
+    Logs,
+}
+
+pub fn parse(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    let (name, argument) = match line.split_once(char::is_whitespace) {
+        Some((name, argument)) => (name, argument.trim()),
+        None => (line, ""),
+    };
+    if name.is_empty() {
+        return Err("No command given.".to_string());
+    }
+    let name = match name {
+        "q" => "quit",
+        _ => find(name, COMMANDS.iter().map(|(name, _)| *name), "command")?,
+    };
+    let command = match name {
+        "quit" => Command::Quit,
+        "search" if argument.is_empty() => return Err("Usage: :search <query>".to_string()),
+        "search" => Command::Search(argument.to_string()),
+        "trending" => Command::Trending,
+        "feed" => Command::Feed,
+        "download" => Command::Download,
+        "queue" => match argument {
+            "" => Command::Queue { all: false },
+            "all" => Command::Queue { all: true },
+            _ => return Err(format!("Unknown queue argument '{argument}'.")),
+        },
+        "filter" => Command::Filter(argument.to_string()),
+        "sort" => {
+            let order = find(argument, SORT_ORDERS, "sort order")?;
+            Command::Sort(position(&SORT_ORDERS, order))
+        }
+        "theme" => Command::Theme(find(argument, theme::BUILTIN, "theme")?),
+        "view" => {
+            let view = find(argument, VIEWS, "view")?;
+            Command::View(position(&VIEWS, view))
+        }
+        "clear" => {
+            let target = find(argument, CLEAR_TARGETS, "clear target")?;
+            Command::Clear(position(&CLEAR_TARGETS, target))
+        }
+        "cache" => Command::Cache,
+        "reload-config" => Command::ReloadConfig,
+        "login" => Command::Login(argument.to_string()),
+        "logout" => Command::Logout,
+        "logs" => Command::Logs,
+        "import" if argument.is_empty() => return Err("Usage: :import <file>".to_string()),
+        "import" => Command::Import(argument.to_string()),
+        "export" if argument.is_empty() => {
+            return Err("Usage: :export <json|csv> [dir]".to_string())
+        }
+        "export" => {
+            let (format, dir) = argument
+                .split_once(char::is_whitespace)
+                .unwrap_or((argument, ""));
+            let format = find(format, EXPORT_FORMATS, "export format")?;
+            Command::Export {
+                csv: format == "csv",
+                dir: dir.trim().to_string(),
+            }
+        }
+        _ => unreachable!("every command name is handled"),
+    };
+    Ok(command)
+}
+
+/// Completes the command name, or the argument of a command that takes a
+/// fixed set of them, to the longest prefix shared by the candidates.
+/// Returns `None` when nothing matches.
+pub fn complete(line: &str) -> Option<String> {
+    match line.split_once(' ') {
+        None => {
+            let names = COMMANDS.iter().map(|(name, _)| *name);
+            let completed = common_prefix(line, names)?;
+            let unique = COMMANDS
+                .iter()
+                .filter(|(name, _)| name.starts_with(line))
+                .count()
+                == 1;
+            Some(if unique { completed + " " } else { completed })
+        }
+        Some((name, argument)) => {
+            let candidates: &[&str] = match find(name, COMMANDS.iter().map(|(name, _)| *name), "") {
+                Ok("queue") => &["all"],
+                Ok("sort") => &SORT_ORDERS,
+                Ok("theme") => &theme::BUILTIN,
+                Ok("view") => &VIEWS,
+                Ok("clear") => &CLEAR_TARGETS,
+                Ok("export") => &EXPORT_FORMATS,
+                _ => return None,
+            };
+            let argument = argument.trim_start();
+            let completed = common_prefix(argument, candidates.iter().copied())?;
+            Some(format!("{name} {completed}"))
+        }
+    }
+}
+
+/// Commands whose name starts with what has been typed so far.
+pub fn candidates(line: &str) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+    let name = line.split_whitespace().next().unwrap_or("");
+    COMMANDS
+        .iter()
+        .copied()
+        .filter(move |(command, _)| command.starts_with(name))
+}
+
+/// The entry `input` names exactly or is an unambiguous prefix of. A
+/// prefix of several entries is an error listing them.
+fn find<'a>(
+    input: &str,
+    options: impl IntoIterator<Item = &'a str>,
+    what: &str,
+) -> Result<&'a str, String> {
+    let options: Vec<&str> = options.into_iter().collect();
+    if let Some(exact) = options.iter().find(|option| **option == input) {
+        return Ok(exact);
+    }
+    let matches: Vec<&str> = options
+        .into_iter()
+        .filter(|option| option.starts_with(input))
+        .collect();
+    match matches[..] {
+        _ if input.is_empty() => Err(format!("Missing {what}: {}.", matches.join(", "))),
+        [only] => Ok(only),
+        [] => Err(format!("Unknown {what} '{input}'.")),
+        _ => Err(format!(
+            "Ambiguous {what} '{input}': {}.",
+            matches.join(", ")
+        )),
+    }
+}
+
+fn position(options: &[&str], option: &str) -> usize {
+    options
+        .iter()
+        .position(|entry| *entry == option)
+        .unwrap_or(0)
+}
+
+fn common_prefix<'a>(input: &str, options: impl Iterator<Item = &'a str>) -> Option<String> {
+    let mut prefix: Option<&str> = None;
+    for option in options.filter(|option| option.starts_with(input)) {
+        prefix = Some(match prefix {
+            None => option,
+            Some(prefix) => {
+                let shared = prefix
+                    .char_indices()
+                    .zip(option.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map_or(prefix.len().min(option.len()), |((index, _), _)| index);
+                &prefix[..shared]
+            }
+        });
+    }
+    prefix.map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_full_names_and_arguments() {
+        assert_eq!(parse("quit"), Ok(Command::Quit));
+        assert_eq!(parse("  q  "), Ok(Command::Quit));
+        assert_eq!(
+            parse("search  rust  tui "),
+            Ok(Command::Search("rust  tui".to_string()))
+        );
+        assert_eq!(parse("queue all"), Ok(Command::Queue { all: true }));
+        assert_eq!(parse("sort views"), Ok(Command::Sort(1)));
+        assert_eq!(parse("view stats"), Ok(Command::View(8)));
+        assert_eq!(parse("clear searches"), Ok(Command::Clear(2)));
+        assert_eq!(parse("filter"), Ok(Command::Filter(String::new())));
+        assert_eq!(parse("login"), Ok(Command::Login(String::new())));
+        assert_eq!(
+            parse("export csv  ~/backup "),
+            Ok(Command::Export {
+                csv: true,
+                dir: "~/backup".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parses_unambiguous_prefixes() {
+        assert_eq!(parse("down"), Ok(Command::Download));
+        assert_eq!(parse("tr"), Ok(Command::Trending));
+        assert_eq!(parse("reload"), Ok(Command::ReloadConfig));
+        assert_eq!(parse("so v"), Ok(Command::Sort(1)));
+        assert_eq!(parse("vi lat"), Ok(Command::View(7)));
+        assert_eq!(
+            parse("exp j"),
+            Ok(Command::Export {
+                csv: false,
+                dir: String::new()
+            })
+        );
+        // An exact name wins over the longer names it is a prefix of.
+        assert_eq!(parse("logs"), Ok(Command::Logs));
+        assert_eq!(parse("view search"), Ok(Command::View(0)));
+    }
+
+    #[test]
+    fn rejects_ambiguous_prefixes() {
+        assert_eq!(
+            parse("lo"),
+            Err("Ambiguous command 'lo': login, logout, logs.".to_string())
+        );
+        assert_eq!(
+            parse("s"),
+            Err("Ambiguous command 's': search, sort.".to_string())
+        );
+        assert_eq!(
+            parse("view f"),
+            Err("Ambiguous view 'f': feed, favorites.".to_string())
+        );
+        assert_eq!(
+            parse("clear th"),
+            Ok(Command::Clear(1)),
+            "one match is not ambiguous"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_missing_input() {
+        assert_eq!(parse(""), Err("No command given.".to_string()));
+        assert_eq!(parse("   "), Err("No command given.".to_string()));
+        assert_eq!(parse("nope"), Err("Unknown command 'nope'.".to_string()));
+        assert_eq!(parse("search"), Err("Usage: :search <query>".to_string()));
+        assert_eq!(
+            parse("queue some"),
+            Err("Unknown queue argument 'some'.".to_string())
+        );
+        assert_eq!(
+            parse("sort"),
+            Err("Missing sort order: default, views, newest, duration, title.".to_string())
+        );
+        assert_eq!(
+            parse("sort loudest"),
+            Err("Unknown sort order 'loudest'.".to_string())
+        );
+        assert_eq!(
+            parse("export xml").unwrap_err(),
+            "Unknown export format 'xml'."
+        );
+    }
+
+    #[test]
+    fn completes_command_names() {
+        assert_eq!(complete("tre"), Some("trending ".to_string()));
+        assert_eq!(complete("trending"), Some("trending ".to_string()));
+        // Ambiguous: only as far as the names agree, without a space.
+        assert_eq!(complete("lo"), Some("log".to_string()));
+        assert_eq!(complete("s"), Some("s".to_string()));
+        assert_eq!(complete("xyz"), None);
+    }
+
+    #[test]
+    fn completes_fixed_arguments() {
+        assert_eq!(complete("sort n"), Some("sort newest".to_string()));
+        assert_eq!(complete("so d"), Some("so d".to_string()));
+        assert_eq!(complete("view fa"), Some("view favorites".to_string()));
+        assert_eq!(complete("queue "), Some("queue all".to_string()));
+        assert_eq!(complete("export c"), Some("export csv".to_string()));
+        assert_eq!(complete("sort x"), None);
+        // Free-form arguments and ambiguous commands aren't completed.
+        assert_eq!(complete("search ru"), None);
+        assert_eq!(complete("lo x"), None);
+    }
+
+    #[test]
+    fn lists_candidates_for_the_typed_name() {
+        let names: Vec<&str> = candidates("lo").map(|(name, _)| name).collect();
+        assert_eq!(names, ["login", "logout", "logs"]);
+        assert_eq!(candidates("").count(), COMMANDS.len());
+        assert_eq!(candidates("zzz").count(), 0);
+    }
+}
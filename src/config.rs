@@ -0,0 +1,397 @@
+//! User configuration read from `config.json` in the ytbv config directory.
+
+use crate::paths;
+use regex::Regex;
+use rustypipe::param::{Country, Language};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = "config.json";
+/// Default for `thumbnail_cache_mb`.
+const DEFAULT_THUMBNAIL_CACHE_MB: u64 = 200;
+/// Default for `search_cache_minutes`.
+const DEFAULT_SEARCH_CACHE_MINUTES: u64 = 30;
+/// Default for `max_height`.
+const DEFAULT_MAX_HEIGHT: u32 = 1080;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Language used for YouTube queries, e.g. `"de"` or `"en-GB"`.
+    pub language: Option<Language>,
+    /// Region used for YouTube queries, e.g. `"DE"`.
+    pub country: Option<Country>,
+    /// Make the random pick favour videos with more views.
+    pub lucky_weighted: bool,
+    /// Results whose title matches any entry are hidden. Entries are
+    /// case-insensitive keywords, or regexes when wrapped in slashes
+    /// (`"/^\\[free\\]/"`).
+    pub blocklist: Vec<String>,
+    /// Replaces the built-in mpv invocation, e.g.
+    /// `{"program": "vlc", "args": ["--fullscreen", "{url}"]}`, or a list of
+    /// them tried in order until one starts.
+    pub player: Option<PlayerSetting>,
+    /// Directory for `D` downloads; `~/Downloads` when unset.
+    pub download_dir: Option<String>,
+    /// Skip SponsorBlock sponsor segments during playback.
+    pub sponsorblock_skip: bool,
+    /// Replace titles and thumbnails with community ones from DeArrow.
+    pub dearrow: bool,
+    /// Preferred subtitle language, e.g. `"en"`; passed to mpv as `--slang`
+    /// and preselected in the subtitle picker.
+    pub subtitle_language: Option<String>,
+    /// Resolve stream URLs with RustyPipe and hand them straight to mpv
+    /// instead of letting mpv's yt-dlp hook do it.
+    pub direct_streams: bool,
+    /// Open new videos in another player instead of replacing the running one.
+    pub keep_players: bool,
+    /// Stop every player ytbv started when quitting.
+    pub quit_stops_players: bool,
+    /// mpv window presets offered in the playback options, e.g.
+    /// `{"name": "Corner", "geometry": "25%-0-0", "ontop": true}`. The
+    /// first one is used by default; built-in presets when empty.
+    pub window_presets: Vec<WindowPreset>,
+    /// Repeat mode on startup: `"off"`, `"one"` or `"all"`.
+    pub repeat: Repeat,
+    /// Playback speed new videos start at, e.g. `1.5`.
+    pub speed: Option<f64>,
+    /// Search automatically when typing in the search box pauses.
+    pub search_as_you_type: bool,
+    /// Arrangement of the results and Details panes; cycled with `v`.
+    pub layout: LayoutPreset,
+    /// Height of the Details pane in rows, borders included; a third of
+    /// the screen (at most 14 rows) when unset. Changed with Ctrl+Up/Down.
+    pub details_height: Option<u16>,
+    /// Widest the Details thumbnail may get, in columns; as wide as the pane
+    /// allows when unset. Changed with Ctrl+Left/Right.
+    pub thumbnail_width: Option<u16>,
+    /// Take videos off the watch later list once they start playing.
+    pub watch_later_auto_remove: bool,
+    /// Most the thumbnail cache may hold, in megabytes; the least recently
+    /// shown thumbnails are deleted past it. 200 when unset.
+    pub thumbnail_cache_mb: Option<u64>,
+    /// How long a search is answered from the cache instead of asking
+    /// YouTube again, in minutes; 0 turns the cache off. Older results are
+    /// still shown when YouTube can't be reached. 30 when unset.
+    pub search_cache_minutes: Option<u64>,
+    /// Highest resolution videos play at, e.g. `720`; the quality menu
+    /// still offers the others. 1080 when unset.
+    pub max_height: Option<u32>,
+    /// How thumbnails are drawn: `"auto"`, `"kitty"`, `"iterm"`, `"sixel"`,
+    /// `"blocks"` (colored half blocks) or `"off"`.
+    pub graphics: Graphics,
+    /// UI colors, e.g. `{"name": "gruvbox", "accent": "#83a598"}`.
+    pub theme: ThemeConfig,
+    /// Keys for the actions of the Search and Feed views, by action name,
+    /// e.g. `{"watch-later": "u"}`; unset actions keep their default key.
+    pub keys: BTreeMap<String, char>,
+}
+
+/// A built-in theme by `name` (`default` when unset), with color roles
+/// overridden by the other keys.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Repeat {
+    #[default]
+    Off,
+    /// Loop the current video (`--loop-file` in mpv).
+    One,
+    /// Put finished videos back at the end of the queue.
+    All,
+}
+
+impl Repeat {
+    pub fn next(self) -> Repeat {
+        match self {
+            Repeat::Off => Repeat::One,
+            Repeat::One => Repeat::All,
+            Repeat::All => Repeat::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Repeat::Off => "Off",
+            Repeat::One => "Repeat one",
+            Repeat::All => "Repeat all",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Graphics {
+    /// The first protocol the terminal supports, else colored blocks.
+    #[default]
+    Auto,
+    Kitty,
+    Iterm,
+    Sixel,
+    Blocks,
+    Off,
+}
+
+impl Graphics {
+    /// The explicit choices, best first.
+    pub const CHOICES: [Graphics; 5] = [
+        Graphics::Kitty,
+        Graphics::Iterm,
+        Graphics::Sixel,
+        Graphics::Blocks,
+        Graphics::Off,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Graphics::Auto => "Automatic",
+            Graphics::Kitty => "Kitty graphics",
+            Graphics::Iterm => "iTerm2 inline images",
+            Graphics::Sixel => "Sixel",
+            Graphics::Blocks => "Colored blocks",
+            Graphics::Off => "No thumbnails",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LayoutPreset {
+    /// Details under the results, with the thumbnail on their right.
+    #[default]
+    Default,
+    /// Details to the right of the results, with the thumbnail on top.
+    DetailsRight,
+    /// Details under the results, with the thumbnail on their left.
+    ThumbnailLeft,
+    /// The results alone.
+    NoDetails,
+    /// Results across the whole window above a short, text-only Details strip.
+    WideList,
+}
+
+impl LayoutPreset {
+    pub const ALL: [LayoutPreset; 5] = [
+        LayoutPreset::Default,
+        LayoutPreset::DetailsRight,
+        LayoutPreset::ThumbnailLeft,
+        LayoutPreset::NoDetails,
+        LayoutPreset::WideList,
+    ];
+
+    pub fn next(self) -> LayoutPreset {
+        let index = LayoutPreset::ALL
+            .iter()
+            .position(|preset| *preset == self)
+            .unwrap_or(0);
+        LayoutPreset::ALL[(index + 1) % LayoutPreset::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LayoutPreset::Default => "Default",
+            LayoutPreset::DetailsRight => "Details right",
+            LayoutPreset::ThumbnailLeft => "Thumbnail left",
+            LayoutPreset::NoDetails => "No details",
+            LayoutPreset::WideList => "Wide list",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PlayerSetting {
+    Single(PlayerCommand),
+    Fallbacks(Vec<PlayerCommand>),
+}
+
+/// External player template. `{url}` and `{start}` (seconds) in `args`
+/// are substituted; the URL is appended when there is no `{url}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerCommand {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// mpv window placement: `geometry` is passed as `--geometry`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowPreset {
+    pub name: String,
+    #[serde(default)]
+    pub geometry: Option<String>,
+    #[serde(default)]
+    pub ontop: bool,
+}
+
+impl WindowPreset {
+    fn new(name: &str, geometry: Option<&str>, ontop: bool) -> WindowPreset {
+        WindowPreset {
+            name: name.to_string(),
+            geometry: geometry.map(str::to_string),
+            ontop,
+        }
+    }
+
+    pub fn mpv_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(geometry) = &self.geometry {
+            args.push(format!("--geometry={geometry}"));
+        }
+        if self.ontop {
+            args.push("--ontop".to_string());
+        }
+        args
+    }
+}
+
+impl PlayerCommand {
+    /// Program file name, for status messages.
+    pub fn name(&self) -> String {
+        Path::new(&self.program).file_name().map_or_else(
+            || self.program.clone(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+    }
+
+    /// mpv keeps the IPC playback controls; other players are just spawned.
+    pub fn is_mpv(&self) -> bool {
+        Path::new(&self.program)
+            .file_stem()
+            .is_some_and(|stem| stem == "mpv")
+    }
+
+    pub fn expand_args(&self, url: &str, start: u32) -> Vec<String> {
+        let mut args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| {
+                arg.replace("{url}", url)
+                    .replace("{start}", &start.to_string())
+            })
+            .collect();
+        if !self.args.iter().any(|arg| arg.contains("{url}")) {
+            args.push(url.to_string());
+        }
+        args
+    }
+}
+
+impl Config {
+    /// Configured players in the order they should be tried; empty means
+    /// the built-in mpv.
+    pub fn players(&self) -> &[PlayerCommand] {
+        match &self.player {
+            Some(PlayerSetting::Single(command)) => std::slice::from_ref(command),
+            Some(PlayerSetting::Fallbacks(commands)) => commands,
+            None => &[],
+        }
+    }
+
+    /// `window_presets`, or the built-in ones when none are configured.
+    pub fn window_presets(&self) -> Vec<WindowPreset> {
+        if !self.window_presets.is_empty() {
+            return self.window_presets.clone();
+        }
+        vec![
+            WindowPreset::new("Normal", None, false),
+            WindowPreset::new("PiP bottom-right 30%", Some("30%-0-0"), true),
+            WindowPreset::new("PiP top-right 30%", Some("30%-0+0"), true),
+            WindowPreset::new("PiP bottom-left 30%", Some("30%+0-0"), true),
+        ]
+    }
+
+    /// `thumbnail_cache_mb` in bytes.
+    pub fn thumbnail_cache_bytes(&self) -> u64 {
+        self.thumbnail_cache_mb
+            .unwrap_or(DEFAULT_THUMBNAIL_CACHE_MB)
+            .saturating_mul(1024 * 1024)
+    }
+
+    pub fn max_height(&self) -> u32 {
+        self.max_height.unwrap_or(DEFAULT_MAX_HEIGHT)
+    }
+
+    /// The mpv argument picking formats up to `max_height`.
+    pub fn ytdl_format(&self) -> String {
+        let height = self.max_height();
+        format!("--ytdl-format=bestvideo[height<={height}]+bestaudio/best")
+    }
+
+    /// `search_cache_minutes` in seconds.
+    pub fn search_cache_secs(&self) -> u64 {
+        self.search_cache_minutes
+            .unwrap_or(DEFAULT_SEARCH_CACHE_MINUTES)
+            .saturating_mul(60)
+    }
+
+    /// Resolves `download_dir`, expanding a leading `~/`.
+    pub fn download_dir(&self) -> Result<PathBuf, String> {
+        match self.download_dir.as_deref() {
+            Some(dir) => match dir.strip_prefix("~/") {
+                Some(rest) => Ok(paths::home_dir()?.join(rest)),
+                None => Ok(PathBuf::from(dir)),
+            },
+            None => Ok(paths::home_dir()?.join("Downloads")),
+        }
+    }
+
+    /// Compiles `blocklist` into regexes, failing on the first invalid one.
+    pub fn blocklist_regexes(&self) -> Result<Vec<Regex>, String> {
+        self.blocklist
+            .iter()
+            .map(|entry| {
+                let pattern = match entry
+                    .strip_prefix('/')
+                    .and_then(|rest| rest.strip_suffix('/'))
+                {
+                    Some(pattern) => format!("(?i){pattern}"),
+                    None => format!("(?i){}", regex::escape(entry)),
+                };
+                Regex::new(&pattern)
+                    .map_err(|err| format!("Invalid blocklist entry '{entry}': {err}"))
+            })
+            .collect()
+    }
+}
+
+/// Loads the config, treating a missing file as all defaults.
+pub fn load_config() -> Result<Config, String> {
+    let path = paths::config_dir()?.join(CONFIG_FILE);
+    let data = match fs::read(&path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(err) => return Err(format!("Read error ({}): {err}", path.display())),
+    };
+    serde_json::from_slice(&data).map_err(|e| format!("Parse error ({}): {e}", path.display()))
+}
+
+/// Whether there is no config file yet, i.e. ytbv runs for the first time.
+pub fn is_first_run() -> bool {
+    paths::config_dir().is_ok_and(|dir| !dir.join(CONFIG_FILE).exists())
+}
+
+/// When the config file last changed; `None` while there is none.
+pub fn config_modified() -> Option<std::time::SystemTime> {
+    let path = paths::config_dir().ok()?.join(CONFIG_FILE);
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+pub fn save_config(config: &Config) -> Result<(), String> {
+    let dir = paths::config_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Config dir error: {e}"))?;
+    let data = serde_json::to_vec_pretty(config).map_err(|e| format!("Encode error: {e}"))?;
+    let path = dir.join(CONFIG_FILE);
+    let tmp_path = dir.join(format!("{CONFIG_FILE}.tmp"));
+    fs::write(&tmp_path, data).map_err(|e| format!("Write error: {e}"))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Write error: {e}"))
+}
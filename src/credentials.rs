@@ -0,0 +1,364 @@
+//! Keeping the YouTube login encrypted at rest.
+//!
+//! RustyPipe stores its OAuth token and login cookies in its cache file,
+//! next to client versions and player code. `Storage` moves them out into
+//! `credentials.enc` in the data dir, sealed with ChaCha20-Poly1305 under a
+//! key derived from a passphrase with PBKDF2-HMAC-SHA256, and writes the
+//! rest of the cache as before.
+//!
+//! Until `unlock` has been given the passphrase the login is neither read
+//! nor written, so a stored one is left as it is. A login still in plain
+//! text in the cache file, from earlier versions or a session that stayed
+//! locked, is kept there until `unlock` moves it over. The secrets only
+//! leave the cache file once they are saved encrypted.
+
+use crate::paths;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use rustypipe::cache::CacheStorage;
+use serde_json::{Map, Value};
+use std::fs;
+use std::io;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const FILE: &str = "credentials.enc";
+const MAGIC: &[u8] = b"ytbv-credentials-1\n";
+const SALT_LEN: usize = 16;
+const ITERATIONS: u32 = 600_000;
+/// The fields of RustyPipe's cache that hold the login.
+const SECRET_FIELDS: [&str; 2] = ["oauth_token", "auth_cookie"];
+
+static KEY: Mutex<Option<Key>> = Mutex::new(None);
+
+struct Key {
+    salt: [u8; SALT_LEN],
+    key: [u8; 32],
+}
+
+impl Key {
+    fn derive(passphrase: &str, salt: [u8; SALT_LEN]) -> Key {
+        let mut key = [0; 32];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            NonZeroU32::new(ITERATIONS).expect("iteration count is not zero"),
+            &salt,
+            passphrase.as_bytes(),
+            &mut key,
+        );
+        Key { salt, key }
+    }
+
+    fn aead(&self) -> LessSafeKey {
+        LessSafeKey::new(
+            UnboundKey::new(&CHACHA20_POLY1305, &self.key).expect("key has the right length"),
+        )
+    }
+
+    /// `MAGIC`, the salt, a fresh nonce and the sealed `plaintext`.
+    fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| "No random numbers available".to_string())?;
+        let mut sealed = plaintext.to_vec();
+        self.aead()
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(MAGIC),
+                &mut sealed,
+            )
+            .map_err(|_| "Encrypt error".to_string())?;
+        Ok([MAGIC, &self.salt, &nonce, &sealed].concat())
+    }
+
+    fn open(&self, file: &[u8]) -> Result<Vec<u8>, String> {
+        let (_, rest) = split(file)?;
+        let (nonce, sealed) = rest.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| "Corrupt credentials file".to_string())?;
+        let mut sealed = sealed.to_vec();
+        let plaintext = self
+            .aead()
+            .open_in_place(nonce, Aad::from(MAGIC), &mut sealed)
+            .map_err(|_| "Wrong passphrase.".to_string())?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+/// RustyPipe's cache file, minus the login.
+pub struct Storage {
+    cache_file: PathBuf,
+}
+
+impl Storage {
+    pub fn new(cache_file: PathBuf) -> Storage {
+        Storage { cache_file }
+    }
+}
+
+impl CacheStorage for Storage {
+    fn write(&self, data: &str) {
+        let Ok(Value::Object(mut cache)) = serde_json::from_str::<Value>(data) else {
+            return;
+        };
+        if let Some(key) = KEY.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            let secrets = take_secrets(&mut cache);
+            if let Err(err) = save(key, &secrets) {
+                log::error!("Login not saved: {err}");
+                cache.extend(secrets);
+            }
+        }
+        let written = write_private(
+            &self.cache_file,
+            Value::Object(cache).to_string().as_bytes(),
+        );
+        if let Err(err) = written {
+            log::error!("RustyPipe cache not saved: {err}");
+        }
+    }
+
+    fn read(&self) -> Option<String> {
+        let mut cache = match fs::read_to_string(&self.cache_file) {
+            Ok(data) => match serde_json::from_str::<Value>(&data) {
+                Ok(Value::Object(cache)) => cache,
+                _ => Map::new(),
+            },
+            Err(_) => Map::new(),
+        };
+        if let Some(key) = KEY.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            if let Ok(Some(secrets)) = load(key) {
+                cache.extend(secrets);
+            }
+        }
+        Some(Value::Object(cache).to_string())
+    }
+}
+
+/// Whether a login is stored, encrypted or still in plain text in the
+/// cache file written by earlier versions.
+pub fn stored(cache_file: &Path) -> bool {
+    if path().is_ok_and(|path| path.exists()) {
+        return true;
+    }
+    let Ok(data) = fs::read_to_string(cache_file) else {
+        return false;
+    };
+    matches!(
+        serde_json::from_str::<Value>(&data),
+        Ok(Value::Object(cache)) if SECRET_FIELDS.iter().any(|field| cache.contains_key(*field))
+    )
+}
+
+pub fn unlocked() -> bool {
+    KEY.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+/// Derives the key from `passphrase`. A stored login has to open with it;
+/// otherwise it becomes the passphrase for the next login. A login in
+/// plain text in `cache_file` is then moved into the encrypted store.
+pub fn unlock(passphrase: &str, cache_file: &Path) -> Result<(), String> {
+    let key = match fs::read(path()?) {
+        Ok(file) => {
+            let (salt, _) = split(&file)?;
+            let key = Key::derive(passphrase, salt);
+            key.open(&file)?;
+            key
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let mut salt = [0; SALT_LEN];
+            SystemRandom::new()
+                .fill(&mut salt)
+                .map_err(|_| "No random numbers available".to_string())?;
+            Key::derive(passphrase, salt)
+        }
+        Err(err) => return Err(format!("Read error: {err}")),
+    };
+    if let Err(err) = migrate(&key, cache_file) {
+        log::error!("Login left in the RustyPipe cache: {err}");
+    }
+    *KEY.lock().unwrap_or_else(|e| e.into_inner()) = Some(key);
+    Ok(())
+}
+
+/// Saves the login found in `cache_file` encrypted, over the stored one,
+/// and then writes the cache without it.
+fn migrate(key: &Key, cache_file: &Path) -> Result<(), String> {
+    let Ok(data) = fs::read_to_string(cache_file) else {
+        return Ok(());
+    };
+    let Ok(Value::Object(mut cache)) = serde_json::from_str::<Value>(&data) else {
+        return Ok(());
+    };
+    let plain = take_secrets(&mut cache);
+    if plain.is_empty() {
+        return Ok(());
+    }
+    let mut secrets = load(key)?.unwrap_or_default();
+    secrets.extend(plain);
+    save(key, &secrets)?;
+    write_private(cache_file, Value::Object(cache).to_string().as_bytes())
+}
+
+/// Removes the login fields from RustyPipe's cache.
+fn take_secrets(cache: &mut Map<String, Value>) -> Map<String, Value> {
+    SECRET_FIELDS
+        .iter()
+        .filter_map(|field| Some((field.to_string(), cache.remove(*field)?)))
+        .collect()
+}
+
+/// Deletes the stored login without needing the passphrase.
+pub fn forget() -> Result<bool, String> {
+    match fs::remove_file(path()?) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(format!("Delete error: {err}")),
+    }
+}
+
+fn path() -> Result<PathBuf, String> {
+    Ok(paths::data_dir()?.join(FILE))
+}
+
+fn load(key: &Key) -> Result<Option<Map<String, Value>>, String> {
+    let file = match fs::read(path()?) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(format!("Read error: {err}")),
+    };
+    let plaintext = key.open(&file)?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Decode error: {e}"))
+}
+
+/// Writes `secrets`, or deletes the file once they are gone after a logout.
+fn save(key: &Key, secrets: &Map<String, Value>) -> Result<(), String> {
+    if secrets.is_empty() {
+        return forget().map(|_| ());
+    }
+    let plaintext = serde_json::to_vec(secrets).map_err(|e| format!("Encode error: {e}"))?;
+    let path = path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Data dir error: {e}"))?;
+    }
+    write_private(&path, &key.seal(&plaintext)?)
+}
+
+/// The salt of a credentials file, and the nonce and sealed data after it.
+fn split(file: &[u8]) -> Result<([u8; SALT_LEN], &[u8]), String> {
+    let corrupt = || "Corrupt credentials file".to_string();
+    let rest = file.strip_prefix(MAGIC).ok_or_else(corrupt)?;
+    if rest.len() < SALT_LEN + NONCE_LEN {
+        return Err(corrupt());
+    }
+    let (salt, rest) = rest.split_at(SALT_LEN);
+    Ok((salt.try_into().map_err(|_| corrupt())?, rest))
+}
+
+/// Writes a file only the user can read, narrowing the permissions of one
+/// that was already there.
+fn write_private(path: &Path, data: &[u8]) -> Result<(), String> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("Write error: {e}"))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Permission error: {e}"))?;
+    }
+    io::Write::write_all(&mut file, data).map_err(|e| format!("Write error: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> Key {
+        Key {
+            salt: [byte; SALT_LEN],
+            key: [byte; 32],
+        }
+    }
+
+    #[test]
+    fn opens_what_it_sealed() {
+        let sealed = key(1).seal(b"{\"oauth_token\":\"secret\"}").unwrap();
+        assert!(sealed.starts_with(MAGIC));
+        assert_eq!(split(&sealed).unwrap().0, [1; SALT_LEN]);
+        assert_eq!(
+            key(1).open(&sealed).unwrap(),
+            b"{\"oauth_token\":\"secret\"}"
+        );
+    }
+
+    #[test]
+    fn seals_with_a_fresh_nonce() {
+        assert_ne!(
+            key(1).seal(b"login").unwrap(),
+            key(1).seal(b"login").unwrap()
+        );
+    }
+
+    #[test]
+    fn derives_the_same_key_from_the_same_passphrase() {
+        let salt = [7; SALT_LEN];
+        let sealed = Key::derive("correct horse", salt).seal(b"login").unwrap();
+        assert_eq!(
+            Key::derive("correct horse", salt).open(&sealed).unwrap(),
+            b"login"
+        );
+        assert_eq!(
+            Key::derive("wrong horse", salt).open(&sealed).unwrap_err(),
+            "Wrong passphrase."
+        );
+    }
+
+    #[test]
+    fn rejects_a_wrong_key() {
+        let sealed = key(1).seal(b"login").unwrap();
+        assert_eq!(key(2).open(&sealed).unwrap_err(), "Wrong passphrase.");
+    }
+
+    #[test]
+    fn rejects_a_truncated_file() {
+        let sealed = key(1).seal(b"login").unwrap();
+        for len in [
+            0,
+            MAGIC.len() - 1,
+            MAGIC.len() + SALT_LEN,
+            MAGIC.len() + SALT_LEN + NONCE_LEN - 1,
+        ] {
+            assert_eq!(
+                key(1).open(&sealed[..len]).unwrap_err(),
+                "Corrupt credentials file",
+                "{len} bytes"
+            );
+        }
+        // Cut into the tag, the data no longer authenticates.
+        assert!(key(1).open(&sealed[..sealed.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn rejects_a_corrupt_header_or_body() {
+        let sealed = key(1).seal(b"login").unwrap();
+        let mut header = sealed.clone();
+        header[0] ^= 1;
+        assert_eq!(
+            key(1).open(&header).unwrap_err(),
+            "Corrupt credentials file"
+        );
+        let mut body = sealed.clone();
+        *body.last_mut().unwrap() ^= 1;
+        assert_eq!(key(1).open(&body).unwrap_err(), "Wrong passphrase.");
+    }
+}
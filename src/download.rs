@@ -0,0 +1,130 @@
+//! Video downloads through yt-dlp.
+
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// File name template handed to yt-dlp's `-o`.
+const OUTPUT_TEMPLATE: &str = "%(title)s [%(id)s].%(ext)s";
+const WAIT_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone)]
+pub enum DownloadEvent {
+    /// Percentage of the file currently being downloaded (0-100).
+    Progress(f64),
+    Finished(Result<(), String>),
+}
+
+pub struct Download {
+    child: Arc<Mutex<Child>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Download {
+    /// Starts yt-dlp for `url`, saving into `dir`. Progress is passed to
+    /// `on_event` from a background thread.
+    pub fn spawn<F>(ytdlp_bin: &str, url: &str, dir: &Path, on_event: F) -> Result<Download, String>
+    where
+        F: Fn(DownloadEvent) + Send + 'static,
+    {
+        std::fs::create_dir_all(dir).map_err(|err| format!("Download dir error: {err}"))?;
+        let mut child = Command::new(ytdlp_bin)
+            .args([
+                "--newline",
+                "--no-colors",
+                "--no-playlist",
+                "-o",
+                OUTPUT_TEMPLATE,
+            ])
+            .arg("-P")
+            .arg(dir)
+            .arg(url)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| format!("Failed to start yt-dlp ('{ytdlp_bin}'): {err}"))
+            .inspect_err(|err| log::error!("{err}"))?;
+        log::info!("Downloading {url} to {}", dir.display());
+
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let child = Arc::new(Mutex::new(child));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let thread_child = Arc::clone(&child);
+        let thread_cancelled = Arc::clone(&cancelled);
+        thread::spawn(move || {
+            // Keep the last error line so a failure can say why.
+            let last_error = Arc::new(Mutex::new(None));
+            let stderr_reader = stderr.map(|stderr| {
+                let last_error = Arc::clone(&last_error);
+                thread::spawn(move || {
+                    for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                        if let Some(message) = line.strip_prefix("ERROR: ") {
+                            *last_error.lock().unwrap_or_else(|err| err.into_inner()) =
+                                Some(message.to_string());
+                        }
+                    }
+                })
+            });
+            if let Some(stdout) = stdout {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    if let Some(percent) = parse_progress(&line) {
+                        on_event(DownloadEvent::Progress(percent));
+                    }
+                }
+            }
+            if let Some(reader) = stderr_reader {
+                let _ = reader.join();
+            }
+
+            // Poll rather than block in `wait` so `cancel` can take the lock.
+            let status = loop {
+                let status = thread_child
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner())
+                    .try_wait();
+                match status {
+                    Ok(None) => thread::sleep(WAIT_INTERVAL),
+                    Ok(Some(status)) => break Ok(status),
+                    Err(err) => break Err(err),
+                }
+            };
+            let result = if thread_cancelled.load(Ordering::Relaxed) {
+                Err("Cancelled".to_string())
+            } else {
+                match status {
+                    Ok(status) if status.success() => Ok(()),
+                    Ok(status) => Err(last_error
+                        .lock()
+                        .unwrap_or_else(|err| err.into_inner())
+                        .take()
+                        .unwrap_or_else(|| format!("yt-dlp exited with {status}"))),
+                    Err(err) => Err(format!("yt-dlp error: {err}")),
+                }
+            };
+            on_event(DownloadEvent::Finished(result));
+        });
+
+        Ok(Download { child, cancelled })
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        let mut child = self.child.lock().unwrap_or_else(|err| err.into_inner());
+        if let Err(err) = child.kill() {
+            log::debug!("Could not kill yt-dlp: {err}");
+        }
+    }
+}
+
+/// Reads the percentage from a line like `[download]  42.3% of 10.00MiB ...`.
+fn parse_progress(line: &str) -> Option<f64> {
+    let rest = line.strip_prefix("[download]")?.trim_start();
+    let (percent, _) = rest.split_once('%')?;
+    percent.trim().parse().ok()
+}
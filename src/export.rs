@@ -0,0 +1,315 @@
+//! Dumping the watch history, bookmarks, subscriptions, watch later list and
+//! local playlists for backup and scripting.
+//!
+//! JSON goes to one `ytbv-export.json` holding `history`, `bookmarks`,
+//! `subscriptions`, `watch_later` and `playlists` arrays with the same
+//! fields as the stored data. CSV goes to one file per list, each with a
+//! header row:
+//!
+//! - `ytbv-history.csv`: `id,title,channel,watched_at,watched_secs`
+//! - `ytbv-bookmarks.csv`: `id,title,channel,tags,added_at` (tags joined by `;`)
+//! - `ytbv-subscriptions.csv`: `id,name`
+//! - `ytbv-watch-later.csv`: `id,title,channel,added_at`
+//! - `ytbv-playlists.csv`: `playlist,position,id,title,url` (positions from
+//!   1; an empty playlist gets one row with only its name)
+//!
+//! Times are Unix timestamps in seconds; missing values are left empty.
+
+use crate::storage::{Bookmark, HistoryEntry, LocalPlaylist, Subscription, WatchLaterEntry};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+pub struct Data<'a> {
+    pub history: &'a [HistoryEntry],
+    pub bookmarks: &'a [Bookmark],
+    pub subscriptions: &'a [Subscription],
+    pub watch_later: &'a [WatchLaterEntry],
+    pub playlists: &'a [LocalPlaylist],
+}
+
+/// Writes `data` into `dir` and returns the paths written.
+pub fn write(data: &Data<'_>, csv: bool, dir: &Path) -> Result<Vec<PathBuf>, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Export dir error: {e}"))?;
+    let files = if csv {
+        vec![
+            ("ytbv-history.csv", history_csv(data.history)),
+            ("ytbv-bookmarks.csv", bookmarks_csv(data.bookmarks)),
+            (
+                "ytbv-subscriptions.csv",
+                subscriptions_csv(data.subscriptions),
+            ),
+            ("ytbv-watch-later.csv", watch_later_csv(data.watch_later)),
+            ("ytbv-playlists.csv", playlists_csv(data.playlists)),
+        ]
+    } else {
+        let json = serde_json::to_string_pretty(data).map_err(|e| format!("Encode error: {e}"))?;
+        vec![("ytbv-export.json", json + "\n")]
+    };
+    let mut written = Vec::new();
+    for (name, contents) in files {
+        let path = dir.join(name);
+        fs::write(&path, contents).map_err(|e| format!("Write error ({}): {e}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+fn history_csv(history: &[HistoryEntry]) -> String {
+    let mut csv = String::from("id,title,channel,watched_at,watched_secs\n");
+    for entry in history {
+        push_row(
+            &mut csv,
+            &[
+                &entry.id,
+                &entry.title,
+                entry.channel.as_deref().unwrap_or_default(),
+                &entry.watched_at.to_string(),
+                &entry
+                    .watched_secs
+                    .map(|secs| secs.to_string())
+                    .unwrap_or_default(),
+            ],
+        );
+    }
+    csv
+}
+
+fn bookmarks_csv(bookmarks: &[Bookmark]) -> String {
+    let mut csv = String::from("id,title,channel,tags,added_at\n");
+    for bookmark in bookmarks {
+        push_row(
+            &mut csv,
+            &[
+                &bookmark.id,
+                &bookmark.title,
+                bookmark.channel.as_deref().unwrap_or_default(),
+                &bookmark.tags.join(";"),
+                &bookmark.added_at.to_string(),
+            ],
+        );
+    }
+    csv
+}
+
+fn subscriptions_csv(subscriptions: &[Subscription]) -> String {
+    let mut csv = String::from("id,name\n");
+    for subscription in subscriptions {
+        push_row(&mut csv, &[&subscription.id, &subscription.name]);
+    }
+    csv
+}
+
+fn watch_later_csv(entries: &[WatchLaterEntry]) -> String {
+    let mut csv = String::from("id,title,channel,added_at\n");
+    for entry in entries {
+        push_row(
+            &mut csv,
+            &[
+                &entry.id,
+                &entry.title,
+                entry.channel.as_deref().unwrap_or_default(),
+                &entry.added_at.to_string(),
+            ],
+        );
+    }
+    csv
+}
+
+fn playlists_csv(playlists: &[LocalPlaylist]) -> String {
+    let mut csv = String::from("playlist,position,id,title,url\n");
+    for playlist in playlists {
+        if playlist.videos.is_empty() {
+            push_row(&mut csv, &[&playlist.name, "", "", "", ""]);
+        }
+        for (index, video) in playlist.videos.iter().enumerate() {
+            push_row(
+                &mut csv,
+                &[
+                    &playlist.name,
+                    &(index + 1).to_string(),
+                    &video.id,
+                    &video.title,
+                    &video.url,
+                ],
+            );
+        }
+    }
+    csv
+}
+
+/// Appends one row, quoting fields that need it: those with a comma, quote
+/// or line break, and those with spaces at either end that readers trim.
+fn push_row(csv: &mut String, fields: &[&str]) {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            csv.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) || field.trim() != *field {
+            csv.push('"');
+            csv.push_str(&field.replace('"', "\"\""));
+            csv.push('"');
+        } else {
+            csv.push_str(field);
+        }
+    }
+    csv.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::csv_fields;
+    use crate::storage::PlaylistVideo;
+
+    fn row(fields: &[&str]) -> String {
+        let mut csv = String::new();
+        push_row(&mut csv, fields);
+        csv
+    }
+
+    fn round_trip(fields: &[&str]) {
+        let csv = row(fields);
+        let line = csv.strip_suffix('\n').unwrap();
+        assert_eq!(csv_fields(line), fields, "{csv:?}");
+    }
+
+    #[test]
+    fn leaves_plain_fields_unquoted() {
+        assert_eq!(
+            row(&["abc", "Some title", "", "12"]),
+            "abc,Some title,,12\n"
+        );
+    }
+
+    #[test]
+    fn quotes_fields_that_need_it() {
+        assert_eq!(row(&["a,b"]), "\"a,b\"\n");
+        assert_eq!(row(&[r#"say "hi""#]), "\"say \"\"hi\"\"\"\n");
+        assert_eq!(row(&["two\nlines"]), "\"two\nlines\"\n");
+        assert_eq!(row(&[" padded "]), "\" padded \"\n");
+    }
+
+    #[test]
+    fn reads_back_what_it_wrote() {
+        round_trip(&["plain", "", "12"]);
+        round_trip(&["Tom Scott, plus", r#"5" screen"#, r#""quoted""#]);
+        round_trip(&["line\nbreak", "carriage\r\nreturn", " padded ", "\ttab"]);
+        round_trip(&[",", "\"", "\"\"", ",\","]);
+        round_trip(&["emoji 🎵, ünïcode"]);
+    }
+
+    #[test]
+    fn writes_a_header_and_a_row_per_entry() {
+        let history = [
+            HistoryEntry {
+                id: "dQw4w9WgXcQ".to_string(),
+                title: "Never, \"ever\"".to_string(),
+                channel: None,
+                watched_at: 1_700_000_000,
+                watched_secs: Some(212),
+            },
+            HistoryEntry {
+                id: "jNQXAC9IVRw".to_string(),
+                title: "Me at the zoo".to_string(),
+                channel: Some("jawed".to_string()),
+                watched_at: 1_700_000_100,
+                watched_secs: None,
+            },
+        ];
+        let csv = history_csv(&history);
+        let lines: Vec<Vec<String>> = csv.lines().map(csv_fields).collect();
+        assert_eq!(
+            lines,
+            [
+                vec!["id", "title", "channel", "watched_at", "watched_secs"],
+                vec!["dQw4w9WgXcQ", "Never, \"ever\"", "", "1700000000", "212"],
+                vec!["jNQXAC9IVRw", "Me at the zoo", "jawed", "1700000100", ""],
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_empty_playlists() {
+        let playlists = [
+            LocalPlaylist {
+                name: "Later, maybe".to_string(),
+                videos: Vec::new(),
+            },
+            LocalPlaylist {
+                name: "Music".to_string(),
+                videos: vec![PlaylistVideo {
+                    id: "dQw4w9WgXcQ".to_string(),
+                    title: "Never".to_string(),
+                    url: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
+                }],
+            },
+        ];
+        let csv = playlists_csv(&playlists);
+        let lines: Vec<Vec<String>> = csv.lines().map(csv_fields).collect();
+        assert_eq!(
+            lines,
+            [
+                vec!["playlist", "position", "id", "title", "url"],
+                vec!["Later, maybe", "", "", "", ""],
+                vec![
+                    "Music",
+                    "1",
+                    "dQw4w9WgXcQ",
+                    "Never",
+                    "https://www.youtube.com/watch?v=dQw4w9WgXcQ"
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_every_list() {
+        let dir = std::env::temp_dir().join(format!("ytbv-export-test-{}", std::process::id()));
+        let subscriptions = [Subscription {
+            id: "UC4QobU6STFB0P71PMvOGN5A".to_string(),
+            name: "jawed".to_string(),
+        }];
+        let watch_later = [WatchLaterEntry {
+            id: "jNQXAC9IVRw".to_string(),
+            title: "Me at the zoo".to_string(),
+            channel: None,
+            added_at: 1_700_000_000,
+        }];
+        let data = Data {
+            history: &[],
+            bookmarks: &[],
+            subscriptions: &subscriptions,
+            watch_later: &watch_later,
+            playlists: &[],
+        };
+        let written = write(&data, true, &dir).unwrap();
+        let names: Vec<_> = written
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "ytbv-history.csv",
+                "ytbv-bookmarks.csv",
+                "ytbv-subscriptions.csv",
+                "ytbv-watch-later.csv",
+                "ytbv-playlists.csv"
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("ytbv-watch-later.csv")).unwrap(),
+            "id,title,channel,added_at\njNQXAC9IVRw,Me at the zoo,,1700000000\n"
+        );
+
+        let written = write(&data, false, &dir).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&written[0]).unwrap()).unwrap();
+        assert_eq!(json["subscriptions"][0]["name"], "jawed");
+        assert_eq!(json["watch_later"][0]["id"], "jNQXAC9IVRw");
+        assert_eq!(json["playlists"], serde_json::json!([]));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
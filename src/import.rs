@@ -0,0 +1,402 @@
+//! Reading subscriptions exported by other apps.
+//!
+//! Understands NewPipe's JSON export, the `subscriptions.csv` of a Google
+//! Takeout (or any CSV with channel IDs or URLs) and OPML feed lists. Only
+//! entries for YouTube channels are kept; anything else is counted as
+//! skipped.
+
+use crate::storage::Subscription;
+use serde::Deserialize;
+
+/// NewPipe's service ID for YouTube.
+const NEWPIPE_YOUTUBE: i64 = 0;
+
+#[derive(Debug)]
+pub struct Imported {
+    pub subscriptions: Vec<Subscription>,
+    /// `(url, name)` of channels given by a handle or custom URL, which
+    /// still have to be resolved to an ID.
+    pub unresolved: Vec<(String, String)>,
+    pub skipped: usize,
+}
+
+impl Imported {
+    fn new() -> Imported {
+        Imported {
+            subscriptions: Vec::new(),
+            unresolved: Vec::new(),
+            skipped: 0,
+        }
+    }
+
+    /// Files a channel under its ID, its URL or as skipped.
+    fn add(&mut self, id: Option<String>, url: Option<&str>, name: String) {
+        match (id, url) {
+            (Some(id), _) => {
+                let name = if name.is_empty() { id.clone() } else { name };
+                self.subscriptions.push(Subscription { id, name });
+            }
+            (None, Some(url)) if is_youtube_url(url) => {
+                self.unresolved.push((url.to_string(), name));
+            }
+            (None, _) => self.skipped += 1,
+        }
+    }
+}
+
+/// Parses an export in whichever supported format `text` is in.
+pub fn parse(text: &str) -> Result<Imported, String> {
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with('{') {
+        newpipe(text)
+    } else if text.starts_with('<') {
+        opml(text)
+    } else {
+        Ok(csv(text))
+    }
+}
+
+#[derive(Deserialize)]
+struct NewPipeExport {
+    subscriptions: Vec<NewPipeSubscription>,
+}
+
+#[derive(Deserialize)]
+struct NewPipeSubscription {
+    #[serde(default)]
+    service_id: i64,
+    url: String,
+    name: String,
+}
+
+/// The `subscriptions.json` written by NewPipe's "Export to file".
+fn newpipe(text: &str) -> Result<Imported, String> {
+    let export: NewPipeExport =
+        serde_json::from_str(text).map_err(|err| format!("Not a NewPipe export: {err}"))?;
+    let mut imported = Imported::new();
+    for entry in export.subscriptions {
+        if entry.service_id != NEWPIPE_YOUTUBE {
+            imported.skipped += 1;
+            continue;
+        }
+        imported.add(channel_id(&entry.url), Some(&entry.url), entry.name);
+    }
+    Ok(imported)
+}
+
+/// Rows of channel IDs, URLs and names in any order, like Takeout's
+/// `Channel Id,Channel Url,Channel Title`. Rows with neither an ID nor a
+/// YouTube URL, such as the header, are ignored.
+fn csv(text: &str) -> Imported {
+    let mut imported = Imported::new();
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let fields = csv_fields(line);
+        let id = fields
+            .iter()
+            .find(|field| is_channel_id(field))
+            .cloned()
+            .or_else(|| fields.iter().find_map(|field| channel_id(field)));
+        let url = fields.iter().find(|field| is_youtube_url(field));
+        if id.is_none() && url.is_none() {
+            continue;
+        }
+        let name = fields
+            .iter()
+            .find(|field| !is_channel_id(field) && !is_youtube_url(field))
+            .cloned()
+            .unwrap_or_default();
+        imported.add(id, url.map(String::as_str), name);
+    }
+    imported
+}
+
+/// Splits a CSV line, unquoting `"..."` fields with `""` escapes. Spaces
+/// around a field are dropped, but not those inside quotes.
+pub fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+        let mut field = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            while let Some(c) = chars.next() {
+                if c == '"' && chars.next_if_eq(&'"').is_none() {
+                    break;
+                }
+                field.push(c);
+            }
+        }
+        // An unquoted field, or whatever follows the closing quote.
+        let mut rest = String::new();
+        while let Some(c) = chars.next_if(|c| *c != ',') {
+            rest.push(c);
+        }
+        field.push_str(rest.trim_end());
+        fields.push(field);
+        if chars.next().is_none() {
+            return fields;
+        }
+    }
+}
+
+/// `<outline>` elements whose `xmlUrl` is a channel feed, as written by
+/// RSS readers and YouTube's old subscription manager.
+fn opml(text: &str) -> Result<Imported, String> {
+    if !text.contains("<opml") {
+        return Err("Not an OPML file.".to_string());
+    }
+    let mut imported = Imported::new();
+    for element in text.split("<outline").skip(1) {
+        let tag = element.split('>').next().unwrap_or_default();
+        let attribute = |name: &str| xml_attribute(tag, name);
+        let Some(url) = attribute("xmlUrl").or_else(|| attribute("htmlUrl")) else {
+            // A folder of outlines.
+            continue;
+        };
+        let name = attribute("title")
+            .or_else(|| attribute("text"))
+            .unwrap_or_default();
+        imported.add(channel_id(&url), Some(&url), name);
+    }
+    Ok(imported)
+}
+
+/// The unescaped value of `name="..."` (or `'...'`) in a tag.
+fn xml_attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    while let Some(start) = rest.find(name) {
+        let preceded = rest[..start].ends_with(char::is_whitespace);
+        rest = &rest[start + name.len()..];
+        let Some(value) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        let value = &value[1..];
+        let end = value.find(quote)?;
+        if preceded {
+            return Some(xml_unescape(&value[..end]));
+        }
+    }
+    None
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// The ID in a `youtube.com/channel/<id>` URL or a
+/// `feeds/videos.xml?channel_id=<id>` feed.
+fn channel_id(url: &str) -> Option<String> {
+    let (_, rest) = url
+        .split_once("/channel/")
+        .or_else(|| url.split_once("channel_id="))?;
+    let id = rest.split(['/', '?', '&', '#']).next().unwrap_or_default();
+    is_channel_id(id).then(|| id.to_string())
+}
+
+fn is_channel_id(text: &str) -> bool {
+    text.len() == 24
+        && text.starts_with("UC")
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn is_youtube_url(text: &str) -> bool {
+    text.contains("youtube.com/") || text.contains("youtu.be/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "UCuAXFkgsw1L7xaCfnd5JJOw";
+    const OTHER_ID: &str = "UC-lHJZR3Gqxm24_Vd_AJ5Yw";
+
+    fn ids(imported: &Imported) -> Vec<(&str, &str)> {
+        imported
+            .subscriptions
+            .iter()
+            .map(|subscription| (subscription.id.as_str(), subscription.name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn splits_csv_fields() {
+        assert_eq!(csv_fields("a,b,c"), ["a", "b", "c"]);
+        assert_eq!(csv_fields(" a , b ,c "), ["a", "b", "c"]);
+        assert_eq!(csv_fields("a,,c,"), ["a", "", "c", ""]);
+        assert_eq!(csv_fields(""), [""]);
+    }
+
+    #[test]
+    fn unquotes_csv_fields() {
+        assert_eq!(csv_fields(r#""a, b",c"#), ["a, b", "c"]);
+        assert_eq!(csv_fields(r#""say ""hi""",x"#), [r#"say "hi""#, "x"]);
+        assert_eq!(csv_fields(r#"" padded ", "#), [" padded ", ""]);
+        assert_eq!(csv_fields(r#""""#), [""]);
+        assert_eq!(csv_fields("\"two\nlines\""), ["two\nlines"]);
+    }
+
+    #[test]
+    fn reads_malformed_csv_fields_leniently() {
+        // An unterminated quote runs to the end of the line.
+        assert_eq!(csv_fields(r#""open, still open"#), ["open, still open"]);
+        // Text after a closing quote is kept.
+        assert_eq!(csv_fields(r#""quoted"tail ,next"#), ["quotedtail", "next"]);
+        // A quote inside an unquoted field is taken literally.
+        assert_eq!(csv_fields(r#"5" screen,x"#), [r#"5" screen"#, "x"]);
+    }
+
+    #[test]
+    fn imports_takeout_csv() {
+        let text = format!(
+            "\u{feff}Channel Id,Channel Url,Channel Title\r\n\
+             {ID},http://www.youtube.com/channel/{ID},\"Tom Scott, plus\"\r\n\
+             \r\n\
+             {OTHER_ID},http://www.youtube.com/channel/{OTHER_ID},\r\n"
+        );
+        let imported = parse(&text).unwrap();
+        assert_eq!(
+            ids(&imported),
+            [(ID, "Tom Scott, plus"), (OTHER_ID, OTHER_ID)]
+        );
+        assert!(imported.unresolved.is_empty());
+        assert_eq!(imported.skipped, 0);
+    }
+
+    #[test]
+    fn imports_csv_in_any_column_order() {
+        let text = format!(
+            "Some Channel,https://youtube.com/channel/{ID}/videos\n\
+             https://www.youtube.com/@handle,Handle Channel\n\
+             https://example.com/feed,Elsewhere\n\
+             not,a,channel\n"
+        );
+        let imported = parse(&text).unwrap();
+        assert_eq!(ids(&imported), [(ID, "Some Channel")]);
+        assert_eq!(
+            imported.unresolved,
+            [(
+                "https://www.youtube.com/@handle".to_string(),
+                "Handle Channel".to_string()
+            )]
+        );
+        // Rows without an ID or YouTube URL are ignored, not skipped.
+        assert_eq!(imported.skipped, 0);
+    }
+
+    #[test]
+    fn imports_newpipe_export() {
+        let text = format!(
+            r#"{{"app_version": "0.26.1", "subscriptions": [
+                {{"service_id": 0, "url": "https://www.youtube.com/channel/{ID}", "name": "Tom Scott"}},
+                {{"service_id": 0, "url": "https://www.youtube.com/c/Custom", "name": "Custom"}},
+                {{"service_id": 1, "url": "https://soundcloud.com/someone", "name": "SoundCloud"}},
+                {{"service_id": 0, "url": "https://peertube.example/channel/x", "name": "Not YouTube"}}
+            ]}}"#
+        );
+        let imported = parse(&text).unwrap();
+        assert_eq!(ids(&imported), [(ID, "Tom Scott")]);
+        assert_eq!(
+            imported.unresolved,
+            [(
+                "https://www.youtube.com/c/Custom".to_string(),
+                "Custom".to_string()
+            )]
+        );
+        assert_eq!(imported.skipped, 2);
+    }
+
+    #[test]
+    fn rejects_malformed_newpipe_export() {
+        assert!(parse(r#"{"subscriptions": [{"url": 1}]}"#)
+            .unwrap_err()
+            .starts_with("Not a NewPipe export"));
+        assert!(parse(r#"{"app_version": "0.26.1"}"#).is_err());
+        assert!(parse("{").is_err());
+    }
+
+    #[test]
+    fn imports_opml() {
+        let text = format!(
+            r#"<?xml version="1.0"?>
+            <opml version="1.1"><body>
+              <outline text="YouTube Subscriptions" title="YouTube Subscriptions">
+                <outline text="Tom &amp; Friends" title="Tom &amp; Friends" type="rss"
+                  xmlUrl="https://www.youtube.com/feeds/videos.xml?channel_id={ID}" />
+                <outline subtitle="wrong" text='Single &quot;quoted&quot;'
+                  xmlUrl='https://www.youtube.com/feeds/videos.xml?channel_id={OTHER_ID}'/>
+                <outline text="Blog" xmlUrl="https://example.com/rss"/>
+              </outline>
+            </body></opml>"#
+        );
+        let imported = parse(&text).unwrap();
+        assert_eq!(
+            ids(&imported),
+            [(ID, "Tom & Friends"), (OTHER_ID, r#"Single "quoted""#)]
+        );
+        assert_eq!(imported.skipped, 1);
+    }
+
+    #[test]
+    fn rejects_xml_that_is_not_opml() {
+        assert_eq!(
+            parse("<html><body></body></html>").unwrap_err(),
+            "Not an OPML file."
+        );
+    }
+
+    #[test]
+    fn reads_xml_attributes() {
+        let tag = r#" text="a &lt;b&gt;" subtitle="no" title = 'yes' broken="#;
+        assert_eq!(xml_attribute(tag, "text").as_deref(), Some("a <b>"));
+        assert_eq!(xml_attribute(tag, "title").as_deref(), Some("yes"));
+        assert_eq!(xml_attribute(tag, "broken"), None);
+        assert_eq!(xml_attribute(tag, "missing"), None);
+        assert_eq!(xml_attribute(r#" open="unterminated"#, "open"), None);
+    }
+
+    #[test]
+    fn unescapes_xml_entities_once() {
+        assert_eq!(xml_unescape("&lt;&gt;&quot;&apos;&#39;&amp;"), r#"<>"''&"#);
+        assert_eq!(xml_unescape("&amp;lt;"), "&lt;");
+        assert_eq!(xml_unescape("AT&T &unknown;"), "AT&T &unknown;");
+    }
+
+    #[test]
+    fn finds_channel_ids() {
+        assert!(is_channel_id(ID));
+        assert!(is_channel_id(OTHER_ID));
+        assert!(!is_channel_id(&ID[..23]));
+        assert!(!is_channel_id(&format!("{ID}x")));
+        assert!(!is_channel_id("XXuAXFkgsw1L7xaCfnd5JJOw"));
+        assert!(!is_channel_id("UCuAXFkgsw1L7xaCfnd5JJ.w"));
+
+        let id = Some(ID.to_string());
+        assert_eq!(
+            channel_id(&format!("https://www.youtube.com/channel/{ID}")),
+            id
+        );
+        assert_eq!(
+            channel_id(&format!("https://youtube.com/channel/{ID}/videos?view=0")),
+            id
+        );
+        assert_eq!(
+            channel_id(&format!(
+                "https://www.youtube.com/feeds/videos.xml?channel_id={ID}&x=1"
+            )),
+            id
+        );
+        assert_eq!(channel_id("https://www.youtube.com/channel/UCshort"), None);
+        assert_eq!(channel_id("https://www.youtube.com/@handle"), None);
+    }
+}
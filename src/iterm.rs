@@ -0,0 +1,46 @@
+//! Drawing images with iTerm2's inline image protocol (`OSC 1337 File=`),
+//! which WezTerm and mintty speak too.
+//!
+//! The image file is sent as it is and the terminal decodes and scales it
+//! into the given cells, so nothing is resized here. Inside tmux the escape
+//! is wrapped for passthrough (`set -g allow-passthrough on`).
+
+use crate::clipboard;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Terminal programs known to draw inline images.
+const TERMINALS: [&str; 3] = ["iTerm", "WezTerm", "mintty"];
+
+/// Whether the terminal says it is one that draws inline images. Checks
+/// `LC_TERMINAL` as well, which iTerm2 sets and ssh and tmux pass on.
+pub fn supported() -> bool {
+    if env::var_os("ITERM_SESSION_ID").is_some() || env::var_os("WEZTERM_PANE").is_some() {
+        return true;
+    }
+    ["TERM_PROGRAM", "LC_TERMINAL"].iter().any(|variable| {
+        env::var(variable).is_ok_and(|name| TERMINALS.iter().any(|known| name.contains(known)))
+    })
+}
+
+/// Draws the image in `path` over the cells `width` by `height` at `x`, `y`,
+/// keeping its aspect ratio. The cursor is left where it was.
+pub fn print(path: &Path, x: u16, y: u16, width: u16, height: u16) -> Result<(), String> {
+    let data = fs::read(path).map_err(|e| format!("Read error: {e}"))?;
+    let image = format!(
+        "\x1b]1337;File=inline=1;size={};width={width};height={height};preserveAspectRatio=1;doNotMoveCursor=1:{}\x07",
+        data.len(),
+        clipboard::base64(&data)
+    );
+    let image = if env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;{}\x1b\\", image.replace('\x1b', "\x1b\x1b"))
+    } else {
+        image
+    };
+    let mut stdout = io::stdout().lock();
+    write!(stdout, "\x1b7\x1b[{};{}H{image}\x1b8", y + 1, x + 1)
+        .and_then(|()| stdout.flush())
+        .map_err(|e| format!("Write error: {e}"))
+}
@@ -0,0 +1,243 @@
+//! The single-key actions of the Search and Feed views and the keys they
+//! are bound to, which the config's `keys` can change, e.g.
+//! `{"watch-later": "u", "sort": "o"}`.
+
+use std::collections::{BTreeMap, HashMap};
+
+/// Keys that keep their meaning whatever is configured: view switching,
+/// the command line, Vim motions, playback and the keys the panes handle
+/// themselves.
+const FIXED: &str = "123456789:/qjkgG []-+=x<>{}FnNc";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Trending,
+    Feed,
+    ShowQueue,
+    HoverPreview,
+    Region,
+    OriginalBranding,
+    NextTheme,
+    NextLayout,
+    ResultsStyle,
+    Zen,
+    PlaybackOptions,
+    PlayerLog,
+    Shuffle,
+    HideShorts,
+    HideWatched,
+    LiveFilter,
+    Related,
+    Comments,
+    Description,
+    Transcript,
+    Sort,
+    OpenInBrowser,
+    Bookmark,
+    AddToPlaylist,
+    WatchLater,
+    Lucky,
+    Enqueue,
+    Download,
+    Subtitles,
+    PlayAll,
+    Cast,
+    Clip,
+    Label,
+    LabelAndPlay,
+    Jump,
+    CopyUrl,
+    CopyStreamUrl,
+    Subscribe,
+    PlayPlaylist,
+}
+
+impl Action {
+    /// Every action with its name in the config and its default key; an
+    /// action listed twice has two.
+    pub const ALL: [(Action, &'static str, char); 40] = [
+        (Action::Trending, "trending", 't'),
+        (Action::Feed, "feed", 'f'),
+        (Action::ShowQueue, "show-queue", 'Q'),
+        (Action::HoverPreview, "hover-preview", 'h'),
+        (Action::Region, "region", 'R'),
+        (Action::OriginalBranding, "original-branding", 'B'),
+        (Action::NextTheme, "next-theme", 'M'),
+        (Action::NextLayout, "next-layout", 'v'),
+        (Action::ResultsStyle, "results-style", 'V'),
+        (Action::Zen, "zen", 'Z'),
+        (Action::PlaybackOptions, "playback-options", 'O'),
+        (Action::PlayerLog, "player-log", 'E'),
+        (Action::Shuffle, "shuffle", 'z'),
+        (Action::HideShorts, "hide-shorts", 'H'),
+        (Action::HideWatched, "hide-watched", 'W'),
+        (Action::LiveFilter, "live-filter", 'L'),
+        (Action::Related, "related", 'r'),
+        (Action::Comments, "comments", 'C'),
+        (Action::Description, "description", 'd'),
+        (Action::Transcript, "transcript", 'T'),
+        (Action::Sort, "sort", 'e'),
+        (Action::OpenInBrowser, "open-in-browser", 'o'),
+        (Action::Bookmark, "bookmark", 'm'),
+        (Action::AddToPlaylist, "add-to-playlist", 'l'),
+        (Action::AddToPlaylist, "add-to-playlist", 'b'),
+        (Action::WatchLater, "watch-later", 'w'),
+        (Action::Lucky, "lucky", '!'),
+        (Action::Enqueue, "enqueue", 'a'),
+        (Action::Download, "download", 'D'),
+        (Action::Subtitles, "subtitles", 'S'),
+        (Action::PlayAll, "play-all", 'P'),
+        (Action::Cast, "cast", 'K'),
+        (Action::Clip, "clip", 'A'),
+        (Action::Label, "label", '\''),
+        (Action::LabelAndPlay, "label-and-play", '"'),
+        (Action::Jump, "jump", 'J'),
+        (Action::CopyUrl, "copy-url", 'y'),
+        (Action::CopyStreamUrl, "copy-stream-url", 'Y'),
+        (Action::Subscribe, "subscribe", 's'),
+        (Action::PlayPlaylist, "play-playlist", 'p'),
+    ];
+
+    pub fn name(self) -> &'static str {
+        Action::ALL
+            .iter()
+            .find(|(action, _, _)| *action == self)
+            .map_or("", |(_, name, _)| name)
+    }
+}
+
+/// Which action each key runs.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    actions: HashMap<char, Action>,
+    /// The key of each bound action, as shown in the key hints.
+    labels: HashMap<Action, String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        Keymap::new(&BTreeMap::new()).expect("the default keys don't conflict")
+    }
+}
+
+impl Keymap {
+    /// The default keys with `overrides` (action name to key) applied. An
+    /// action whose key is given to another one is left without a key.
+    pub fn new(overrides: &BTreeMap<String, char>) -> Result<Keymap, String> {
+        let mut rebound = HashMap::new();
+        for (name, key) in overrides {
+            let action = Action::ALL
+                .iter()
+                .find(|(_, known, _)| known == name)
+                .map(|(action, _, _)| *action)
+                .ok_or_else(|| format!("Unknown action '{name}' in keys."))?;
+            if FIXED.contains(*key) {
+                return Err(format!(
+                    "'{key}' can't be bound to {name}; it is a fixed key."
+                ));
+            }
+            if let Some(other) = rebound.insert(*key, action) {
+                return Err(format!(
+                    "'{key}' is bound to both {} and {name}.",
+                    other.name()
+                ));
+            }
+        }
+        let mut actions = rebound.clone();
+        for (action, name, key) in Action::ALL {
+            if !overrides.contains_key(name) && !rebound.contains_key(&key) {
+                actions.insert(key, action);
+            }
+        }
+        // An action with two default keys is shown with the first.
+        let mut labels: HashMap<Action, String> = rebound
+            .iter()
+            .map(|(key, action)| (*action, key.to_string()))
+            .collect();
+        for (action, _, key) in Action::ALL {
+            if actions.get(&key) == Some(&action) {
+                labels.entry(action).or_insert_with(|| key.to_string());
+            }
+        }
+        Ok(Keymap { actions, labels })
+    }
+
+    pub fn action(&self, key: char) -> Option<Action> {
+        self.actions.get(&key).copied()
+    }
+
+    /// The key bound to `action`, if any.
+    pub fn label(&self, action: Action) -> Option<&str> {
+        self.labels.get(&action).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(pairs: &[(&str, char)]) -> BTreeMap<String, char> {
+        pairs
+            .iter()
+            .map(|(name, key)| (name.to_string(), *key))
+            .collect()
+    }
+
+    #[test]
+    fn binds_every_action_to_its_default_key() {
+        let keymap = Keymap::default();
+        for (action, _, key) in Action::ALL {
+            assert_eq!(keymap.action(key), Some(action));
+        }
+        assert_eq!(keymap.label(Action::Sort), Some("e"));
+        assert_eq!(keymap.action('u'), None);
+
+        // Both keys of an action with two, shown with the first.
+        assert_eq!(keymap.action('b'), Some(Action::AddToPlaylist));
+        assert_eq!(keymap.label(Action::AddToPlaylist), Some("l"));
+    }
+
+    #[test]
+    fn moves_overridden_actions_to_their_new_key() {
+        let keymap = Keymap::new(&keys(&[("watch-later", 'u')])).unwrap();
+        assert_eq!(keymap.action('u'), Some(Action::WatchLater));
+        assert_eq!(keymap.action('w'), None);
+        assert_eq!(keymap.label(Action::WatchLater), Some("u"));
+    }
+
+    #[test]
+    fn moves_both_keys_of_an_action_with_two() {
+        let keymap = Keymap::new(&keys(&[("add-to-playlist", 'u')])).unwrap();
+        assert_eq!(keymap.action('u'), Some(Action::AddToPlaylist));
+        assert_eq!(keymap.action('l'), None);
+        assert_eq!(keymap.action('b'), None);
+    }
+
+    #[test]
+    fn takes_the_key_from_the_action_it_belonged_to() {
+        let keymap = Keymap::new(&keys(&[("sort", 'o')])).unwrap();
+        assert_eq!(keymap.action('o'), Some(Action::Sort));
+        assert_eq!(keymap.label(Action::OpenInBrowser), None);
+
+        // Unless that action moves elsewhere too.
+        let keymap = Keymap::new(&keys(&[("sort", 'o'), ("open-in-browser", 'e')])).unwrap();
+        assert_eq!(keymap.action('e'), Some(Action::OpenInBrowser));
+        assert_eq!(keymap.action('o'), Some(Action::Sort));
+    }
+
+    #[test]
+    fn rejects_unknown_actions_fixed_keys_and_conflicts() {
+        assert_eq!(
+            Keymap::new(&keys(&[("fly", 'u')])).unwrap_err(),
+            "Unknown action 'fly' in keys."
+        );
+        assert_eq!(
+            Keymap::new(&keys(&[("sort", 'j')])).unwrap_err(),
+            "'j' can't be bound to sort; it is a fixed key."
+        );
+        assert_eq!(
+            Keymap::new(&keys(&[("sort", 'u'), ("zen", 'u')])).unwrap_err(),
+            "'u' is bound to both sort and zen."
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! The log file, `ytbv.log` in the data dir.
+//!
+//! ytbv logs at info level, or debug with `--verbose`; libraries only get
+//! their warnings and errors in. Once the file passes `MAX_BYTES` it is
+//! moved to `ytbv.log.1`, replacing the previous one.
+
+use crate::paths;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use time::{format_description, OffsetDateTime};
+
+const FILE: &str = "ytbv.log";
+const MAX_BYTES: u64 = 1024 * 1024;
+
+struct Logger {
+    path: PathBuf,
+    level: LevelFilter,
+    /// The open file and how much has been written to it.
+    file: Mutex<Option<(File, u64)>>,
+}
+
+/// Installs the logger. Nothing is logged when the data dir can't be
+/// created; the returned error says why.
+pub fn init(verbose: bool) -> Result<(), String> {
+    let path = path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Data dir error: {e}"))?;
+    }
+    let level = if verbose {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    };
+    let logger = Logger {
+        file: Mutex::new(open(&path).ok()),
+        path,
+        level,
+    };
+    log::set_boxed_logger(Box::new(logger)).map_err(|e| format!("Logger error: {e}"))?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+pub fn path() -> Result<PathBuf, String> {
+    Ok(paths::data_dir()?.join(FILE))
+}
+
+/// The last `count` lines of the log.
+pub fn tail(count: usize) -> Result<Vec<String>, String> {
+    let text = match fs::read_to_string(path()?) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(format!("Read error: {err}")),
+    };
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(count);
+    Ok(lines[start..].iter().map(|line| line.to_string()).collect())
+}
+
+/// The level a line of the log was written at.
+pub fn level(line: &str) -> Option<Level> {
+    line.split_whitespace().nth(2)?.parse().ok()
+}
+
+fn open(path: &Path) -> io::Result<(File, u64)> {
+    let file = File::options().create(true).append(true).open(path)?;
+    let size = file.metadata()?.len();
+    Ok((file, size))
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        let target = metadata.target();
+        // Entering and leaving RustyPipe's spans, not events.
+        if target.starts_with("tracing::span") {
+            return false;
+        }
+        let own = target.starts_with(env!("CARGO_CRATE_NAME"));
+        let level = if own { self.level } else { LevelFilter::Warn };
+        metadata.level() <= level
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let format = format_description::parse_borrowed::<2>(
+            "[year]-[month]-[day] [hour]:[minute]:[second]",
+        )
+        .expect("valid format");
+        let time = OffsetDateTime::now_utc()
+            .format(&format)
+            .unwrap_or_default();
+        let line = format!(
+            "{time} {:<5} {}: {}\n",
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if file.as_ref().is_some_and(|(_, size)| *size > MAX_BYTES) {
+            *file = None;
+            let _ = fs::rename(&self.path, self.path.with_extension("log.1"));
+        }
+        if file.is_none() {
+            *file = open(&self.path).ok();
+        }
+        if let Some((handle, size)) = file.as_mut() {
+            if handle.write_all(line.as_bytes()).is_ok() {
+                *size += line.len() as u64;
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Some((handle, _)) = self.file.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            let _ = handle.flush();
+        }
+    }
+}
@@ -1,35 +1,86 @@
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+mod blocks;
+mod browser;
+mod cast;
+mod chromecast;
+mod clipboard;
+mod command;
+mod config;
+mod credentials;
+mod download;
+mod export;
+mod import;
+mod iterm;
+mod keys;
+mod logging;
+mod paths;
+mod player;
+mod pool;
+mod search_cache;
+mod setup;
+mod sqlite;
+mod stats;
+mod storage;
+mod storyboard;
+mod theme;
+mod toast;
+mod views;
+
+use cast::Renderer;
+use command::Command;
+use config::{Config, Graphics, LayoutPreset, PlayerCommand, Repeat};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use download::{Download, DownloadEvent};
+use keys::{Action, Keymap};
+use player::{PlaybackState, Player, PlayerEvent, PlayerProperty};
 use ratatui::layout::{Alignment, Constraint, Direction, Layout};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{
     Block, Borders, Clear, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
-    ScrollbarState,
+    ScrollbarState, Tabs, Wrap,
 };
 use ratatui::Terminal;
 use ratatui::{backend::CrosstermBackend, Frame};
-use rustypipe::client::RustyPipe;
-use rustypipe::model::paginator::ContinuationEndpoint;
-use rustypipe::model::{ChannelItem, VideoItem, YouTubeItem};
-use rustypipe::param::{search_filter::SearchFilter, ChannelOrder};
+use rustypipe::client::{ClientType, RustyPipe, RustyPipeQuery};
+use rustypipe::model::paginator::{ContinuationEndpoint, Paginator};
+use rustypipe::model::richtext::{RichText, TextComponent};
+use rustypipe::model::{ChannelItem, Comment, PlaylistItem, UrlTarget, VideoItem, YouTubeItem};
+use rustypipe::param::{
+    search_filter::SearchFilter, ChannelVideoTab, Country, Language, StreamFilter, COUNTRIES,
+    LANGUAGES,
+};
+use search_cache::CachedSearch;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::env;
 use std::fs;
-use std::io;
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
 use std::sync::mpsc::{self, Receiver, Sender};
-use std::sync::OnceLock;
+use std::sync::{OnceLock, RwLock};
 use std::thread;
 use std::time::{Duration, Instant};
+use storage::{
+    Bookmark, HistoryEntry, LocalPlaylist, PlaylistVideo, Subscription, WatchLaterEntry,
+    WatchProgress,
+};
+use theme::Theme;
 use time::{format_description, OffsetDateTime};
+use toast::{Level, Toasts};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use views::{View, Views};
 use viuer::Config as ViuerConfig;
 
 static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
 static RUSTYPIPE: OnceLock<RustyPipe> = OnceLock::new();
+/// Language and region applied to every query; changed from the locale picker.
+static LOCALE: RwLock<(Language, Country)> = RwLock::new((Language::En, Country::Us));
 
 #[derive(Debug, Clone)]
 struct Video {
+    id: String,
     title: String,
     url: String,
     channel: Option<String>,
@@ -38,10 +89,85 @@ struct Video {
     view_count: Option<u64>,
     publish_date: Option<OffsetDateTime>,
     publish_date_txt: Option<String>,
-    thumbnail_url: Option<String>,
+    /// The sizes YouTube has the thumbnail in, as `(width, url)`.
+    thumbnails: Vec<(u32, String)>,
     thumbnail_path: Option<PathBuf>,
     thumbnail_size: Option<(u32, u32)>,
     thumbnail_loading: bool,
+    is_live: bool,
+    is_short: bool,
+    chapters: Option<Vec<VideoChapter>>,
+    chapters_loading: bool,
+    /// SponsorBlock segments, `(start, end)` in seconds.
+    sponsor_segments: Option<Vec<(f64, f64)>>,
+    sponsor_loading: bool,
+}
+
+impl Video {
+    /// The DeArrow title when `branding` has one.
+    fn display_title<'a>(&'a self, branding: Option<&'a Branding>) -> &'a str {
+        branding
+            .and_then(|branding| branding.title.as_deref())
+            .unwrap_or(&self.title)
+    }
+
+    /// The DeArrow thumbnail when `branding` has one, else the smallest
+    /// of YouTube's at least `width` pixels wide, or the largest.
+    fn display_thumbnail_url<'a>(
+        &'a self,
+        branding: Option<&'a Branding>,
+        width: u32,
+    ) -> Option<&'a str> {
+        if let Some(url) = branding.and_then(|branding| branding.thumbnail_url.as_deref()) {
+            return Some(url);
+        }
+        let wide_enough = self
+            .thumbnails
+            .iter()
+            .filter(|(thumb_width, _)| *thumb_width >= width)
+            .min_by_key(|(thumb_width, _)| *thumb_width);
+        wide_enough
+            .or_else(|| {
+                self.thumbnails
+                    .iter()
+                    .max_by_key(|(thumb_width, _)| *thumb_width)
+            })
+            .map(|(_, url)| url.as_str())
+    }
+}
+
+/// Like and dislike estimates from Return YouTube Dislike.
+#[derive(Debug, Clone, Copy)]
+struct Votes {
+    likes: u64,
+    dislikes: u64,
+}
+
+/// Details of a video beyond what search results carry, looked up once it
+/// stays selected for `INFO_DELAY`.
+#[derive(Debug, Clone)]
+struct VideoInfo {
+    views: u64,
+    likes: Option<u32>,
+    subscribers: Option<u64>,
+    tags: Vec<String>,
+    /// Caption track names; auto-generated ones are marked.
+    captions: Vec<String>,
+    /// The description as plain text, one entry per line.
+    description: Vec<String>,
+}
+
+/// Community-submitted title and thumbnail from DeArrow.
+#[derive(Debug, Clone, Default)]
+struct Branding {
+    title: Option<String>,
+    thumbnail_url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct VideoChapter {
+    name: String,
+    position: u32,
 }
 
 struct Channel {
@@ -52,974 +178,10957 @@ struct Channel {
     description: Option<String>,
 }
 
-enum SearchResultItem {
-    Video(Video),
-    Channel(Channel),
+/// Metadata shown above the list while browsing a channel.
+struct ChannelHeader {
+    id: String,
+    name: String,
+    handle: Option<String>,
+    subscriber_count: Option<u64>,
+    video_count: Option<u64>,
+    description: String,
+    has_live: bool,
+    has_shorts: bool,
+    avatar_url: Option<String>,
+    avatar_path: Option<PathBuf>,
+    avatar_size: Option<(u32, u32)>,
 }
 
-struct App {
-    query: String,
-    cursor: usize,
-    results: Vec<SearchResultItem>,
-    page: usize,
-    results_per_page: usize,
-    results_view_rows: usize,
-    results_nav_slots: usize,
-    selected_row: usize,
-    selected: usize,
-    results_state: ListState,
-    search_ctoken: Option<String>,
-    search_visitor_data: Option<String>,
-    search_endpoint: Option<ContinuationEndpoint>,
-    loading_more: bool,
-    pending_next_target: Option<usize>,
-    status: String,
-    rx: Receiver<AppMsg>,
-    tx: Sender<AppMsg>,
-    searching: bool,
-    focus: Focus,
-    thumb_area: Option<ratatui::layout::Rect>,
-    last_thumb: Option<ThumbRender>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChannelTab {
+    Videos,
+    Live,
+    Shorts,
+    Playlists,
 }
 
-const THUMB_CACHE_MAX_BYTES: u64 = 50 * 1024 * 1024;
-
-#[derive(Debug, Clone, Copy)]
-enum ResultsEntry {
-    PreviousPage { enabled: bool },
-    NextPage { enabled: bool },
-    Result(usize),
-}
+impl ChannelTab {
+    const ALL: [ChannelTab; 4] = [
+        ChannelTab::Videos,
+        ChannelTab::Live,
+        ChannelTab::Shorts,
+        ChannelTab::Playlists,
+    ];
 
-#[derive(Clone)]
-struct ThumbRender {
-    path: PathBuf,
-    area: ratatui::layout::Rect,
+    fn label(self) -> &'static str {
+        match self {
+            ChannelTab::Videos => "Videos",
+            ChannelTab::Live => "Live",
+            ChannelTab::Shorts => "Shorts",
+            ChannelTab::Playlists => "Playlists",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Focus {
-    Search,
-    Results,
-    Details,
+enum TrendingCategory {
+    Now,
+    Music,
+    Gaming,
+    Movies,
 }
 
-enum AppMsg {
-    Search(Result<SearchPage, String>),
-    MoreResults(Result<SearchPage, String>),
-    Thumbnail {
-        index: usize,
-        result: Result<PathBuf, String>,
-    },
+impl TrendingCategory {
+    const ALL: [TrendingCategory; 4] = [
+        TrendingCategory::Now,
+        TrendingCategory::Music,
+        TrendingCategory::Gaming,
+        TrendingCategory::Movies,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            TrendingCategory::Now => "Now",
+            TrendingCategory::Music => "Music",
+            TrendingCategory::Gaming => "Gaming",
+            TrendingCategory::Movies => "Movies",
+        }
+    }
+
+    /// `params` of the `FEtrending` browse request that selects the category.
+    fn browse_params(self) -> &'static str {
+        match self {
+            TrendingCategory::Now => "4gIOGgxtb3N0X3BvcHVsYXI%3D",
+            TrendingCategory::Music => "4gINGgt5dG1hX2NoYXJ0cw%3D%3D",
+            TrendingCategory::Gaming => "4gIcGhpnYW1pbmdfY29ycHVzX21vc3RfcG9wdWxhcg%3D%3D",
+            TrendingCategory::Movies => "4gIKGgh0cmFpbGVycw%3D%3D",
+        }
+    }
 }
 
-struct SearchPage {
-    results: Vec<SearchResultItem>,
-    ctoken: Option<String>,
-    visitor_data: Option<String>,
-    endpoint: ContinuationEndpoint,
+/// How much of the screen is given up for the results list, cycled with `Z`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ZenMode {
+    Off,
+    /// No Details pane, and the search box only while it has focus.
+    Compact,
+    /// Nothing but the now-playing line.
+    Zen,
 }
 
-fn main() -> io::Result<()> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    crossterm::execute!(stdout, crossterm::terminal::EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+impl ZenMode {
+    fn next(self) -> Self {
+        match self {
+            ZenMode::Off => ZenMode::Compact,
+            ZenMode::Compact => ZenMode::Zen,
+            ZenMode::Zen => ZenMode::Off,
+        }
+    }
 
-    let (tx, rx) = mpsc::channel();
-    let mut app = App {
-        query: String::new(),
-        cursor: 0,
-        results: Vec::new(),
-        page: 1,
-        results_per_page: 1,
-        results_view_rows: 0,
-        results_nav_slots: 0,
-        selected_row: 0,
-        selected: 0,
-        results_state: ListState::default(),
-        search_ctoken: None,
-        search_visitor_data: None,
-        search_endpoint: None,
-        loading_more: false,
-        pending_next_target: None,
-        status: "Type a query and press Enter.".to_string(),
-        rx,
-        tx,
-        searching: false,
-        focus: Focus::Search,
-        thumb_area: None,
-        last_thumb: None,
-    };
+    fn label(self) -> &'static str {
+        match self {
+            ZenMode::Off => "Normal",
+            ZenMode::Compact => "Compact",
+            ZenMode::Zen => "Zen",
+        }
+    }
+}
 
-    let mut last_tick = Instant::now();
-    let tick_rate = Duration::from_millis(200);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LiveFilter {
+    All,
+    LiveOnly,
+    NoLive,
+}
 
-    loop {
-        terminal.draw(|f| ui(f, &mut app))?;
-        render_thumbnail(&mut app)?;
+impl LiveFilter {
+    fn next(self) -> Self {
+        match self {
+            LiveFilter::All => LiveFilter::LiveOnly,
+            LiveFilter::LiveOnly => LiveFilter::NoLive,
+            LiveFilter::NoLive => LiveFilter::All,
+        }
+    }
 
-        let timeout = tick_rate.saturating_sub(last_tick.elapsed());
-        if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    if handle_key(&mut app, key.code)? {
-                        break;
-                    }
-                }
-            }
+    fn label(self) -> &'static str {
+        match self {
+            LiveFilter::All => "all videos",
+            LiveFilter::LiveOnly => "livestreams only",
+            LiveFilter::NoLive => "no livestreams",
         }
+    }
 
-        if last_tick.elapsed() >= tick_rate {
-            last_tick = Instant::now();
+    fn allows(self, item: &SearchResultItem) -> bool {
+        match (self, item) {
+            (LiveFilter::All, _) => true,
+            (LiveFilter::LiveOnly, SearchResultItem::Video(video)) => video.is_live,
+            (LiveFilter::LiveOnly, _) => false,
+            (LiveFilter::NoLive, SearchResultItem::Video(video)) => !video.is_live,
+            (LiveFilter::NoLive, _) => true,
         }
+    }
+}
 
-        while let Ok(msg) = app.rx.try_recv() {
-            match msg {
-                AppMsg::Search(result) => {
-                    app.searching = false;
-                    match result {
-                        Ok(results) => {
-                            app.results = results.results;
-                            app.page = 1;
-                            app.selected = 0;
-                            app.selected_row = first_result_row(&app);
-                            app.results_state = ListState::default();
-                            app.search_ctoken = results.ctoken;
-                            app.search_visitor_data = results.visitor_data;
-                            app.search_endpoint = Some(results.endpoint);
-                            app.loading_more = false;
-                            app.pending_next_target = None;
-                            if !app.results.is_empty() {
-                                app.focus = Focus::Results;
-                                let selected = app.selected;
-                                queue_thumbnail(&mut app, selected);
-                            }
-                            app.status = format!("Found {} results.", app.results.len());
-                        }
-                        Err(err) => {
-                            app.status = err;
-                        }
-                    }
-                }
-                AppMsg::MoreResults(result) => {
-                    app.loading_more = false;
-                    match result {
-                        Ok(results) => {
-                            if results.results.is_empty() {
-                                app.pending_next_target = None;
-                                app.status = "No more results.".to_string();
-                            } else {
-                                app.results.extend(results.results);
-                                app.search_ctoken = results.ctoken;
-                                app.search_visitor_data = results.visitor_data;
-                                app.search_endpoint = Some(results.endpoint);
-                                if app.pending_next_target.is_some() {
-                                    maybe_advance_pending_page(&mut app);
-                                } else {
-                                    app.status =
-                                        format!("Found {} results.", app.results.len());
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            app.pending_next_target = None;
-                            app.status = err;
-                        }
-                    }
-                }
-                AppMsg::Thumbnail { index, result } => {
-                    if let Some(item) = app.results.get_mut(index) {
-                        match item {
-                            SearchResultItem::Video(video) => {
-                                video.thumbnail_loading = false;
-                                match result {
-                                    Ok(path) => {
-                                        video.thumbnail_size = thumbnail_size_from_path(&path);
-                                        video.thumbnail_path = Some(path);
-                                        app.status = "Thumbnail ready.".to_string();
-                                    }
-                                    Err(err) => {
-                                        app.status = err;
-                                    }
-                                }
-                            }
-                            SearchResultItem::Channel(_) => {}
-                        }
-                    }
-                }
-            }
+/// Rows of the playback options menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlaybackOption {
+    Window,
+    Repeat,
+    Speed,
+}
+
+impl PlaybackOption {
+    const ALL: [PlaybackOption; 3] = [
+        PlaybackOption::Window,
+        PlaybackOption::Repeat,
+        PlaybackOption::Speed,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            PlaybackOption::Window => "Window",
+            PlaybackOption::Repeat => "Repeat",
+            PlaybackOption::Speed => "Speed",
         }
     }
+}
 
-    disable_raw_mode()?;
-    crossterm::execute!(terminal.backend_mut(), crossterm::terminal::LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+/// Speeds stepped through with `{`/`}`.
+const SPEED_STEPS: [f64; 10] = [0.5, 0.75, 1.0, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0, 4.0];
 
-    Ok(())
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortOrder {
+    Default,
+    Views,
+    Newest,
+    Duration,
+    Title,
 }
 
-fn handle_key(app: &mut App, key: KeyCode) -> io::Result<bool> {
-    match key {
-        KeyCode::Char('q') => return Ok(true),
-        KeyCode::Tab => {
-            app.focus = match app.focus {
-                Focus::Search => {
-                    if app.results.is_empty() {
-                        Focus::Search
-                    } else {
-                        sync_selected_result(app);
-                        Focus::Results
-                    }
-                }
-                Focus::Results => Focus::Details,
-                Focus::Details => Focus::Search,
-            };
-        }
-        KeyCode::BackTab => {
-            app.focus = match app.focus {
-                Focus::Search => Focus::Details,
-                Focus::Results => Focus::Search,
-                Focus::Details => {
-                    if app.results.is_empty() {
-                        Focus::Search
-                    } else {
-                        sync_selected_result(app);
-                        Focus::Results
-                    }
-                }
-            };
-        }
-        KeyCode::Enter => {
-            match app.focus {
-                Focus::Search => {
-                    let query = app.query.trim().to_string();
-                    start_search(app, query);
-                }
-                Focus::Results => {
-                    let entries = results_entries(app);
-                    if entries.is_empty() {
-                        return Ok(false);
-                    }
-                    app.selected_row = app.selected_row.min(entries.len().saturating_sub(1));
-                    match entries[app.selected_row] {
-                        ResultsEntry::PreviousPage { enabled } => {
-                            if enabled && app.page > 1 {
-                                app.page -= 1;
-                                app.selected_row = first_result_row(app);
-                                app.results_state = ListState::default();
-                                sync_selected_result(app);
-                            }
-                        }
-                        ResultsEntry::NextPage { enabled } => {
-                            if !enabled {
-                                app.status = "No more results.".to_string();
-                                return Ok(false);
-                            }
-                            let per_page = results_page_size(app);
-                            let desired_count = (app.page + 1).saturating_mul(per_page);
-                            if app.results.len() >= desired_count {
-                                app.page += 1;
-                                app.selected_row = first_result_row(app);
-                                app.results_state = ListState::default();
-                                sync_selected_result(app);
-                            } else if app.search_ctoken.is_some() && !app.loading_more {
-                                app.loading_more = true;
-                                app.pending_next_target = Some(desired_count);
-                                app.status = "Loading more results...".to_string();
-                                let tx = app.tx.clone();
-                                let ctoken = app.search_ctoken.clone().unwrap_or_default();
-                                let visitor = app.search_visitor_data.clone();
-                                let endpoint = app.search_endpoint.unwrap_or(ContinuationEndpoint::Search);
-                                let target_count = desired_count.saturating_sub(app.results.len());
-                                thread::spawn(move || {
-                                    let result = if ctoken.is_empty() {
-                                        Err("No more results.".to_string())
-                                    } else {
-                                        search_rustypipe_continuation(
-                                            &ctoken,
-                                            visitor.as_deref(),
-                                            endpoint,
-                                            target_count,
-                                        )
-                                    };
-                                    let _ = tx.send(AppMsg::MoreResults(result));
-                                });
-                            } else {
-                                app.status = "No more results.".to_string();
-                            }
-                        }
-                        ResultsEntry::Result(index) => {
-                            if let Some(item) = app.results.get(index) {
-                                match item {
-                                    SearchResultItem::Video(video) => match play_video(video) {
-                                        Ok(()) => {
-                                            app.status = format!("Playing: {}", video.title);
-                                        }
-                                        Err(err) => {
-                                            app.status = err;
-                                        }
-                                    },
-                                    SearchResultItem::Channel(channel) => {
-                                        app.query = channel.name.clone();
-                                        app.cursor = app.query.chars().count();
-                                        start_channel_videos(
-                                            app,
-                                            channel.id.clone(),
-                                            channel.name.clone(),
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                Focus::Details => {}
-            }
-        }
-        KeyCode::Up => {
-            if app.focus == Focus::Results {
-                if app.selected_row > 0 {
-                    app.selected_row -= 1;
-                    sync_selected_result(app);
-                }
-            }
-        }
-        KeyCode::Down => {
-            match app.focus {
-                Focus::Results => {
-                    let entries = results_entries(app);
-                    if app.selected_row + 1 < entries.len() {
-                        app.selected_row += 1;
-                        sync_selected_result(app);
-                    }
-                }
-                Focus::Details => {}
-                Focus::Search => {}
-            }
-        }
-        KeyCode::Backspace => {
-            if app.focus == Focus::Search {
-                if app.cursor > 0 {
-                    app.cursor -= 1;
-                    app.query.remove(app.cursor);
-                }
-            }
+impl SortOrder {
+    const ALL: [SortOrder; 5] = [
+        SortOrder::Default,
+        SortOrder::Views,
+        SortOrder::Newest,
+        SortOrder::Duration,
+        SortOrder::Title,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            SortOrder::Default => "Default order",
+            SortOrder::Views => "Most viewed",
+            SortOrder::Newest => "Newest",
+            SortOrder::Duration => "Longest",
+            SortOrder::Title => "Title",
         }
-        KeyCode::Left => {
-            if app.focus == Focus::Search && app.cursor > 0 {
-                app.cursor -= 1;
+    }
+
+    /// Reorders result indices in place; the sort is stable and items without
+    /// the sorted attribute go last.
+    fn apply(self, results: &[SearchResultItem], indices: &mut [usize]) {
+        let video = |index: &usize| match &results[*index] {
+            SearchResultItem::Video(video) => Some(video),
+            SearchResultItem::Channel(_) | SearchResultItem::Playlist(_) => None,
+        };
+        match self {
+            SortOrder::Default => indices.sort_unstable(),
+            SortOrder::Views => {
+                indices.sort_by_key(|i| Reverse(video(i).and_then(|video| video.view_count)));
             }
-        }
-        KeyCode::Right => {
-            if app.focus == Focus::Search && app.cursor < app.query.chars().count() {
-                app.cursor += 1;
+            SortOrder::Newest => {
+                indices.sort_by_key(|i| Reverse(video(i).and_then(|video| video.publish_date)));
             }
-        }
-        KeyCode::Char(c) => {
-            if app.focus == Focus::Search {
-                app.query.insert(app.cursor, c);
-                app.cursor += 1;
-            } else if c == 'c' && app.focus == Focus::Details {
-                if let Some((channel_id, channel_name)) = selected_channel_info(app) {
-                    app.query = channel_name.clone();
-                    app.cursor = app.query.chars().count();
-                    start_channel_videos(app, channel_id, channel_name);
-                } else {
-                    app.status = "No channel info for this result.".to_string();
-                }
+            SortOrder::Duration => {
+                indices.sort_by_key(|i| Reverse(video(i).and_then(|video| video.duration)));
             }
+            SortOrder::Title => indices.sort_by_cached_key(|i| match &results[*i] {
+                SearchResultItem::Video(video) => video.title.to_lowercase(),
+                SearchResultItem::Channel(channel) => channel.name.to_lowercase(),
+                SearchResultItem::Playlist(playlist) => playlist.name.to_lowercase(),
+            }),
         }
-        _ => {}
     }
+}
 
-    Ok(false)
+struct Playlist {
+    name: String,
+    id: String,
+    channel: Option<String>,
+    channel_id: Option<String>,
+    video_count: Option<u64>,
 }
 
-fn total_pages(result_count: usize, per_page: usize) -> usize {
-    let per_page = per_page.max(1);
-    if result_count == 0 {
-        1
-    } else {
-        (result_count + per_page - 1) / per_page
-    }
+enum SearchResultItem {
+    Video(Video),
+    Channel(Channel),
+    Playlist(Playlist),
 }
 
-fn first_result_row(app: &App) -> usize {
-    let entries = results_entries(app);
-    for (idx, entry) in entries.iter().enumerate() {
-        if matches!(entry, ResultsEntry::Result(_)) {
-            return idx;
-        }
-    }
-    0
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ResultsSource {
+    Search(String),
+    Channel {
+        id: String,
+        name: String,
+        tab: ChannelTab,
+    },
+    Playlist {
+        id: String,
+        name: String,
+    },
+    Trending(TrendingCategory),
+    Related {
+        id: String,
+        title: String,
+    },
+    Feed,
 }
 
-fn update_results_layout(app: &mut App, view_rows: usize) {
-    app.results_view_rows = view_rows;
-    if view_rows >= 3 {
-        app.results_nav_slots = 2;
-        app.results_per_page = view_rows.saturating_sub(2);
-    } else {
-        app.results_nav_slots = 0;
-        app.results_per_page = view_rows;
+impl ResultsSource {
+    /// Whether opening this source keeps the previous results on the back stack.
+    fn stacks(&self) -> bool {
+        matches!(
+            self,
+            ResultsSource::Channel { .. }
+                | ResultsSource::Playlist { .. }
+                | ResultsSource::Related { .. }
+        )
     }
 }
 
-fn results_page_size(app: &App) -> usize {
-    app.results_per_page.max(1)
+enum Popup {
+    Comments(CommentsView),
+    Description(DescriptionView),
+    Transcript(TranscriptView),
+    Locale(LocaleView),
+    Sort(usize),
+    Quality(QualityView),
+    Resume(ResumeView),
+    Subtitles(SubtitleView),
+    Cast(CastView),
+    Clip(ClipView),
+    Jump(JumpView),
+    PlaybackOptions(usize),
+    /// Player error output, scrolled by this many lines.
+    PlayerLog(u16),
+    Confirm(ConfirmView),
+    Error(ErrorView),
+    Tags(TagsView),
+    AddToPlaylist(PlaylistPickerView),
+    CacheStats(CacheStats),
+    Login(LoginView),
+    Logs(LogsView),
+    Setup(setup::Setup),
 }
 
-fn results_entries(app: &App) -> Vec<ResultsEntry> {
-    if app.results.is_empty() || app.results_view_rows == 0 {
-        return Vec::new();
-    }
-    let per_page = results_page_size(app);
-    let total = total_pages(app.results.len(), per_page);
+/// The end of the log file, shown by `:logs`.
+struct LogsView {
+    lines: Vec<String>,
+    /// How many lines up from the newest one the view is scrolled.
+    scroll: usize,
+}
 
-    let page = app.page.clamp(1, total);
-    let start = (page - 1) * per_page;
-    let end = (start + per_page).min(app.results.len());
-    let mut entries = Vec::new();
-    let has_more = app.search_ctoken.is_some() || app.loading_more;
-    let has_prev = page > 1;
-    let has_next = page < total || has_more;
+/// The login started by `:login`.
+struct LoginView {
+    /// The cookies.txt to log in with; empty for the device code.
+    cookies: String,
+    /// Being typed while the passphrase for the saved login is asked for.
+    passphrase: Option<String>,
+    /// `(user code, verification URL)` once YouTube has handed them out.
+    code: Option<(String, String)>,
+}
 
-    if app.results_nav_slots >= 1 {
-        entries.push(ResultsEntry::PreviousPage { enabled: has_prev });
-    }
+/// What the thumbnail and search caches hold, for `:cache`.
+struct CacheStats {
+    dir: PathBuf,
+    files: usize,
+    bytes: u64,
+    limit: u64,
+    searches: usize,
+    search_bytes: u64,
+    /// `search_cache_minutes` in seconds.
+    search_ttl: u64,
+}
 
-    for index in start..end {
-        entries.push(ResultsEntry::Result(index));
-    }
+/// Picks the local playlist to add videos to, or names a new one.
+struct PlaylistPickerView {
+    items: Vec<QueueItem>,
+    /// A playlist, or the "New playlist" row after them.
+    selected: usize,
+    /// The name being typed for a new playlist.
+    new_name: Option<String>,
+}
 
-    if app.results_nav_slots >= 2 {
-        entries.push(ResultsEntry::NextPage { enabled: has_next });
-    }
+/// Editing the tags of a bookmark, as a comma-separated list.
+struct TagsView {
+    video_id: String,
+    input: String,
+}
 
-    entries
+/// A failed search or download, with what it takes to try again.
+struct ErrorView {
+    title: String,
+    text: String,
+    retry: Retry,
+    scroll: u16,
 }
 
-fn sync_selected_result(app: &mut App) {
-    let entries = results_entries(app);
-    if entries.is_empty() {
-        return;
-    }
-    app.selected_row = app.selected_row.min(entries.len().saturating_sub(1));
-    if let ResultsEntry::Result(index) = entries[app.selected_row] {
-        if app.selected != index {
-            app.selected = index;
-            queue_thumbnail(app, index);
-        }
-    }
+enum Retry {
+    Source(ResultsSource),
+    /// Restarts the download with this `DownloadItem::id`.
+    Download(u64),
 }
 
-fn maybe_advance_pending_page(app: &mut App) {
-    let Some(target) = app.pending_next_target else {
+/// A yes/no question asked before something that can't be undone.
+struct ConfirmView {
+    question: String,
+    action: ConfirmAction,
+}
+
+enum ConfirmAction {
+    ClearHistory,
+    /// Cancels the download with this `DownloadItem::id`.
+    CancelDownload(u64),
+    /// Deletes the local playlist of this name.
+    DeleteLocalPlaylist(String),
+    PurgeThumbnails,
+    PurgeSearches,
+}
+
+/// Renderer picker for casting the selected video.
+struct CastView {
+    video_id: String,
+    video_title: String,
+    renderers: Vec<Renderer>,
+    selected: usize,
+    loading: bool,
+    error: Option<String>,
+}
+
+/// Start and end timestamps for playing part of a video.
+struct ClipView {
+    video_id: String,
+    title: String,
+    url: String,
+    start: String,
+    end: String,
+    /// True while the end field has focus.
+    editing_end: bool,
+    error: Option<String>,
+}
+
+/// Timestamp prompt for playing the selected video from an offset.
+struct JumpView {
+    video_id: String,
+    title: String,
+    input: String,
+    error: Option<String>,
+}
+
+/// Stream URLs resolved through RustyPipe, played without mpv's ytdl hook.
+struct DirectStreams {
+    title: String,
+    /// Video (or combined, or HLS for livestreams) stream.
+    video: String,
+    /// Separate audio stream, passed as `--audio-file`.
+    audio: Option<String>,
+    /// When YouTube stops serving the URLs.
+    valid_until: OffsetDateTime,
+}
+
+/// Streams resolved ahead of time for the next queued video.
+struct Prefetch {
+    video_id: String,
+    /// `None` while the lookup is still running.
+    streams: Option<DirectStreams>,
+}
+
+/// Caption track picker shown before playback.
+struct SubtitleView {
+    video_id: String,
+    video_title: String,
+    url: String,
+    tracks: Vec<CaptionTrack>,
+    /// 0 is "no subtitles", otherwise `tracks[selected - 1]`.
+    selected: usize,
+    loading: bool,
+    error: Option<String>,
+}
+
+/// "Resume / start over" prompt for a partially watched video.
+struct ResumeView {
+    video_id: String,
+    title: String,
+    url: String,
+    position: f64,
+    /// 0 resumes, 1 starts over.
+    selected: usize,
+}
+
+struct QualityView {
+    video_id: String,
+    video_title: String,
+    url: String,
+    options: Vec<Quality>,
+    selected: usize,
+    loading: bool,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Quality {
+    /// Best video stream up to this height, e.g. 720.
+    Height {
+        height: u32,
+        label: String,
+    },
+    AudioOnly,
+}
+
+impl Quality {
+    fn label(&self) -> &str {
+        match self {
+            Quality::Height { label, .. } => label,
+            Quality::AudioOnly => "Audio only",
+        }
+    }
+
+    fn mpv_args(&self) -> Vec<String> {
+        match self {
+            Quality::Height { height, .. } => vec![format!(
+                "--ytdl-format=bestvideo[height<={height}]+bestaudio/best[height<={height}]"
+            )],
+            Quality::AudioOnly => vec![
+                "--ytdl-format=bestaudio/best".to_string(),
+                "--no-video".to_string(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct QueueItem {
+    id: String,
+    title: String,
+    url: String,
+}
+
+/// Queue entries handed to one mpv instance as a playlist. mpv moves
+/// between them itself and reports `playlist-pos`; the entries after
+/// `pos` are what `App::queue` shows.
+struct Handoff {
+    entries: Vec<QueueItem>,
+    pos: usize,
+}
+
+struct DownloadItem {
+    id: u64,
+    video_id: String,
+    title: String,
+    url: String,
+    /// Percentage reported by yt-dlp for the current file.
+    progress: f64,
+    state: DownloadState,
+    handle: Option<Download>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DownloadState {
+    Running,
+    Done,
+    Failed(String),
+    Cancelled,
+}
+
+struct LocaleView {
+    /// True while the region column has focus, otherwise the language column.
+    country_focus: bool,
+    language: usize,
+    country: usize,
+}
+
+struct TranscriptView {
+    video_id: String,
+    video_title: String,
+    tracks: Vec<CaptionTrack>,
+    /// Track whose cues are shown; `None` while the language picker is open.
+    track: Option<usize>,
+    picker_selected: usize,
+    cues: Vec<Cue>,
+    cue_selected: usize,
+    loading: bool,
+    error: Option<String>,
+    view_height: usize,
+}
+
+#[derive(Debug, Clone)]
+struct CaptionTrack {
+    url: String,
+    lang: String,
+    lang_name: String,
+    auto_generated: bool,
+}
+
+struct Cue {
+    start_ms: u64,
+    text: String,
+}
+
+struct DescriptionView {
+    video_id: String,
+    video_title: String,
+    segments: Vec<DescriptionSegment>,
+    links: Vec<DescriptionLink>,
+    selected_link: Option<usize>,
+    loading: bool,
+    error: Option<String>,
+    scroll: usize,
+    max_scroll: usize,
+    view_height: usize,
+}
+
+struct DescriptionSegment {
+    text: String,
+    link: Option<usize>,
+}
+
+struct DescriptionLink {
+    label: String,
+    target: LinkTarget,
+}
+
+enum LinkTarget {
+    Timestamp(u32),
+    YouTube(UrlTarget),
+    Web(String),
+}
+
+struct DescriptionPage {
+    segments: Vec<DescriptionSegment>,
+    links: Vec<DescriptionLink>,
+}
+
+struct CommentsView {
+    video_id: String,
+    video_title: String,
+    comments: Vec<CommentEntry>,
+    total: Option<u64>,
+    ctoken: Option<String>,
+    visitor_data: Option<String>,
+    loading: bool,
+    error: Option<String>,
+    /// Index into all timestamps of the loaded comments, in order.
+    selected_timestamp: Option<usize>,
+    /// Scroll the selected timestamp into view on the next draw.
+    follow_selection: bool,
+    scroll: usize,
+    max_scroll: usize,
+    view_height: usize,
+}
+
+struct CommentEntry {
+    author: String,
+    published: String,
+    like_count: Option<u32>,
+    reply_count: u32,
+    pinned: bool,
+    by_owner: bool,
+    /// Text segments; timestamps into the video carry their offset.
+    text: Vec<(String, Option<u32>)>,
+}
+
+struct CommentsPage {
+    comments: Vec<CommentEntry>,
+    total: Option<u64>,
+    ctoken: Option<String>,
+    visitor_data: Option<String>,
+}
+
+#[derive(Default)]
+struct ResultsSnapshot {
+    results: Vec<SearchResultItem>,
+    source: Option<ResultsSource>,
+    channel_header: Option<ChannelHeader>,
+    selected_row: usize,
+    selected: usize,
+    search_ctoken: Option<String>,
+    search_visitor_data: Option<String>,
+    search_endpoint: Option<ContinuationEndpoint>,
+    pages_loaded: usize,
+}
+
+struct App {
+    /// The results screen shown in the Search or Feed view: the active
+    /// search tab, or the Feed.
+    screen: ResultsScreen,
+    /// First display column of `query` shown in the search box.
+    query_scroll: usize,
+    filter_editing: bool,
+    /// The `:` command line, open while `command_editing` is set.
+    command: String,
+    command_editing: bool,
+    /// The last filter confirmed with Enter, which `n`/`N` search for.
+    last_filter: String,
+    /// Set after a first `g`, so a second one jumps to the top.
+    pending_g: bool,
+    /// Set after `'` (or `"`, to play as well) while the rows on screen show
+    /// their labels; the next key picks one.
+    pending_label: Option<bool>,
+    live_filter: LiveFilter,
+    zen: ZenMode,
+    hide_shorts: bool,
+    hide_watched: bool,
+    /// DeArrow lookups by video id; `None` while one is running.
+    branding: HashMap<String, Option<Branding>>,
+    /// Show the original titles and thumbnails instead of DeArrow's.
+    original_branding: bool,
+    /// Vote lookups by video id; `None` while one is running or if it failed.
+    votes: HashMap<String, Option<Votes>>,
+    /// Extended details by video id; `None` while a lookup is running or if
+    /// it failed.
+    video_info: HashMap<String, Option<VideoInfo>>,
+    /// Tallest video stream of each video whose qualities were looked up,
+    /// for the 4K/HD badges.
+    max_heights: HashMap<String, u32>,
+    /// The selected video and when it was selected, until its details are
+    /// looked up.
+    info_candidate: Option<(String, Instant)>,
+    /// First description line shown in Details, and the video it's for.
+    description_scroll: Option<(String, usize)>,
+    /// Play a random result as soon as the running search completes.
+    lucky_pending: bool,
+    sort: SortOrder,
+    /// Height of the results list, the distance PageUp/PageDown move.
+    results_view_rows: usize,
+    /// The side panes drawn in the last frame, top to bottom; Tab moves
+    /// through them after Details.
+    side_panes: Vec<Focus>,
+    toasts: Toasts,
+    rx: Receiver<TabMsg>,
+    tx: MsgSender,
+    /// Inactive search tabs in display order; the active tab sits at
+    /// `active_tab` and is in `screen`, or parked while the Feed is shown.
+    tabs: Vec<ResultsScreen>,
+    active_tab: usize,
+    next_tab_id: u64,
+    /// Batch tabs whose search hasn't been started yet, searched one at a time.
+    batch_queue: VecDeque<TabId>,
+    views: Views,
+    /// The running search was started by typing rather than Enter, so its
+    /// results leave the focus in the search box.
+    live_search: bool,
+    /// When the query was last typed into, for search-as-you-type.
+    query_edited: Option<Instant>,
+    thumb_area: Option<ratatui::layout::Rect>,
+    /// Height the Details pane was last drawn with, and the most it could
+    /// take, for resizing it with Ctrl+Up/Down.
+    details_height: u16,
+    max_details_height: u16,
+    /// Widest the Details thumbnail could be at the last draw.
+    max_thumb_width: u16,
+    /// The most cells the Details pane had for a thumbnail at the last
+    /// draw, whether one was shown or not.
+    thumb_bounds: (u16, u16),
+    last_thumb: Option<ThumbRender>,
+    /// Show the results as a grid of thumbnails instead of a list.
+    grid_view: bool,
+    /// Let list titles wrap, with the channel and stats on a line below.
+    wrap_rows: bool,
+    grid_columns: usize,
+    /// Width of a grid cell at the last draw, for picking thumbnail sizes.
+    grid_cell_width: u16,
+    /// First grid row on screen.
+    grid_scroll: usize,
+    /// Result index and thumbnail area of each grid cell in the last frame.
+    grid_cells: Vec<(usize, ratatui::layout::Rect)>,
+    /// Grid thumbnails currently printed on the terminal.
+    grid_drawn: Vec<ThumbRender>,
+    avatar_area: Option<ratatui::layout::Rect>,
+    last_avatar: Option<ThumbRender>,
+    /// Where the last frame put images, before overlays covered any.
+    image_areas: Vec<ratatui::layout::Rect>,
+    /// Thumbnails scaled for drawing as half blocks.
+    half_blocks: blocks::HalfBlocks,
+    thumbnail_pool: pool::Pool,
+    /// Thumbnail downloads waiting or running.
+    thumbnail_downloads: usize,
+    /// Cycle storyboard frames in the selected video's thumbnail.
+    hover_preview: bool,
+    preview: Option<Preview>,
+    subscriptions: Vec<Subscription>,
+    history: Vec<HistoryEntry>,
+    /// IDs of every video in `history`, for quick lookups while drawing.
+    watched: HashSet<String>,
+    /// Bookmarked videos, oldest first; listed newest first in Favorites.
+    bookmarks: Vec<Bookmark>,
+    /// Oldest first.
+    watch_later: Vec<WatchLaterEntry>,
+    playlists: Vec<LocalPlaylist>,
+    /// Videos marked with Space for a batch action, in marking order.
+    marked: Vec<QueueItem>,
+    progress: HashMap<String, WatchProgress>,
+    /// The video the player was started for, if it's a single video.
+    playing_video: Option<QueueItem>,
+    /// Videos played before `playing_video`, most recent last.
+    played: Vec<QueueItem>,
+    /// Sponsor segments of `playing_video` to skip over.
+    skip_segments: Vec<(f64, f64)>,
+    /// Bumped whenever playback changes, so stale stream lookups are dropped.
+    stream_request: u64,
+    prefetch: Option<Prefetch>,
+    config: Config,
+    player: Option<Player>,
+    /// Older players left running when `keep_players` is set.
+    detached_players: Vec<Player>,
+    playback: Option<PlaybackState>,
+    /// Videos to play after the current one, in order.
+    queue: VecDeque<QueueItem>,
+    /// Set while the running mpv plays the queue as its own playlist.
+    handoff: Option<Handoff>,
+    /// Play the queue, playlists and "play all" in random order.
+    shuffle: bool,
+    /// Index into the configured window presets used for new mpv windows.
+    window_preset: usize,
+    repeat: Repeat,
+    /// mpv speed, applied to the running player and new ones.
+    speed: f64,
+    ended_at_eof: bool,
+    /// stderr of the last player that failed, shown with `E`.
+    player_log: Vec<String>,
+    /// Renderer that was last sent a video, for pause/stop.
+    cast: Option<Renderer>,
+    cast_paused: bool,
+    downloads: Vec<DownloadItem>,
+    next_download_id: u64,
+    /// Compiled `config.blocklist`.
+    blocklist: Vec<regex::Regex>,
+    /// `config.keys` applied to the default keys.
+    keys: Keymap,
+    /// Modification time of the config file when it was last read, and
+    /// when that was checked.
+    config_modified: Option<std::time::SystemTime>,
+    config_checked: Instant,
+    /// The config file didn't load, so the defaults are in use; it isn't
+    /// saved over until it loads again.
+    config_broken: bool,
+    popup: Option<Popup>,
+    needs_full_redraw: bool,
+    chapter_selected: usize,
+    theme: Theme,
+}
+
+const FEED_CONCURRENCY: usize = 6;
+/// Thumbnail and avatar downloads running at once.
+const THUMBNAIL_WORKERS: usize = 4;
+/// Thumbnails of rows on screen and the page below waiting or downloading
+/// at once; the selected video's is queued regardless.
+const THUMBNAIL_PREFETCHES: usize = 4;
+/// How many of the newest log lines `:logs` shows.
+const LOG_LINES: usize = 500;
+/// How often the config file is checked for changes.
+const CONFIG_POLL: Duration = Duration::from_secs(1);
+/// Pause in typing after which search-as-you-type starts a search.
+const LIVE_SEARCH_DELAY: Duration = Duration::from_millis(400);
+/// How long a video has to stay selected before its details are fetched.
+const INFO_DELAY: Duration = Duration::from_millis(600);
+/// How long each storyboard frame of a hover preview is shown.
+const PREVIEW_FRAME: Duration = Duration::from_millis(500);
+/// Smallest Details pane Ctrl+Down shrinks to: borders and one line.
+const MIN_DETAILS_HEIGHT: u16 = 3;
+/// Narrowest thumbnail Ctrl+Left shrinks to, and the columns each press
+/// changes it by.
+const MIN_THUMB_WIDTH: u16 = 10;
+const THUMB_WIDTH_STEP: u16 = 4;
+/// Labels of the rows on screen for `'` and `"`, from the top.
+const ROW_LABELS: [char; 35] = [
+    '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j',
+    'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+/// Frames of the spinner in the Results title while a search runs.
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+/// Most lines a title takes in the wrapped list.
+const WRAPPED_TITLE_LINES: usize = 3;
+
+#[derive(Clone, PartialEq)]
+struct ThumbRender {
+    path: PathBuf,
+    area: ratatui::layout::Rect,
+}
+
+/// Storyboard frames of the selected video, cycled in place of its
+/// thumbnail while hover previews are on.
+struct Preview {
+    video_id: String,
+    /// When the video was selected, until the frames are fetched after
+    /// `INFO_DELAY`; then when they arrived, for picking the frame.
+    since: Instant,
+    requested: bool,
+    /// Empty while loading, and for videos without a storyboard.
+    frames: Vec<PathBuf>,
+}
+
+/// Identifies a results screen, and tags the background work started from
+/// it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TabId {
+    /// A search tab, numbered in the order they were opened.
+    Search(u64),
+    /// The Feed view's screen.
+    Feed,
+}
+
+/// A list of results with its query, filter and navigation: a search tab,
+/// or the Feed. The one shown is `App::screen`.
+struct ResultsScreen {
+    id: TabId,
+    query: String,
+    /// Byte offset of the cursor in `query`, always on a grapheme boundary.
+    cursor: usize,
+    focus: Focus,
+    results: Vec<SearchResultItem>,
+    /// Indices into `results` that pass the active filter, in display order.
+    visible: Vec<usize>,
+    filter: String,
+    source: Option<ResultsSource>,
+    channel_header: Option<ChannelHeader>,
+    back_stack: Vec<ResultsSnapshot>,
+    /// Position of the selection in `visible`.
+    selected_row: usize,
+    selected: usize,
+    results_state: ListState,
+    search_ctoken: Option<String>,
+    search_visitor_data: Option<String>,
+    search_endpoint: Option<ContinuationEndpoint>,
+    /// Pages of the current results fetched so far.
+    pages_loaded: usize,
+    loading_more: bool,
+    /// Row to select once more results have loaded.
+    pending_next_target: Option<usize>,
+    searching: bool,
+    /// When the running request was sent, for the time shown while searching.
+    search_started: Instant,
+    /// Bumped by every results request; see `AppMsg::Search`.
+    search_generation: u64,
+}
+
+impl ResultsScreen {
+    fn new(id: TabId) -> Self {
+        ResultsScreen {
+            id,
+            query: String::new(),
+            cursor: 0,
+            // The Feed has no search box to start in.
+            focus: match id {
+                TabId::Search(_) => Focus::Search,
+                TabId::Feed => Focus::Results,
+            },
+            results: Vec::new(),
+            visible: Vec::new(),
+            filter: String::new(),
+            source: None,
+            channel_header: None,
+            back_stack: Vec::new(),
+            selected_row: 0,
+            selected: 0,
+            results_state: ListState::default(),
+            search_ctoken: None,
+            search_visitor_data: None,
+            search_endpoint: None,
+            pages_loaded: 0,
+            loading_more: false,
+            pending_next_target: None,
+            searching: false,
+            search_started: Instant::now(),
+            search_generation: 0,
+        }
+    }
+}
+
+/// A message from background work, tagged with the tab that started it.
+struct TabMsg {
+    tab: TabId,
+    msg: AppMsg,
+}
+
+/// Sender handed to worker threads; it tags every message with the tab that
+/// was active when the work was started.
+#[derive(Clone)]
+struct MsgSender {
+    tab: TabId,
+    tx: Sender<TabMsg>,
+}
+
+impl MsgSender {
+    fn send(&self, msg: AppMsg) -> Result<(), mpsc::SendError<()>> {
+        self.tx
+            .send(TabMsg { tab: self.tab, msg })
+            .map_err(|_| mpsc::SendError(()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Search,
+    Results,
+    Details,
+    /// The queue, beside the results while it has videos.
+    Queue,
+    /// The downloads, beside the results while one runs.
+    Downloads,
+}
+
+impl Focus {
+    /// The panes of the Search and Feed views, in Tab order.
+    const ALL: [Focus; 5] = [
+        Focus::Search,
+        Focus::Results,
+        Focus::Details,
+        Focus::Queue,
+        Focus::Downloads,
+    ];
+}
+
+/// Where an inactive results screen is kept.
+#[derive(Debug, Clone, Copy)]
+enum TabSlot {
+    /// A search tab in `App::tabs`.
+    Search(usize),
+    /// `Views::parked`.
+    Parked,
+}
+
+enum AppMsg {
+    Player {
+        id: u64,
+        event: PlayerEvent,
+    },
+    Download {
+        id: u64,
+        event: DownloadEvent,
+    },
+    /// `generation` is the tab's `search_generation` when the request was
+    /// made; responses from older requests are dropped.
+    Search {
+        generation: u64,
+        source: ResultsSource,
+        result: Result<SearchPage, String>,
+    },
+    MoreResults {
+        generation: u64,
+        result: Result<SearchPage, String>,
+    },
+    Resolved(Result<UrlTarget, String>),
+    /// Imported subscriptions, with every channel resolved to an ID.
+    Imported(import::Imported),
+    /// The code to enter on the login page, as `(user code, URL)`.
+    LoginCode(String, String),
+    /// Outcome of logging in or out, shown in the status line.
+    Account(Result<String, String>),
+    Comments {
+        video_id: String,
+        result: Result<CommentsPage, String>,
+    },
+    Description {
+        video_id: String,
+        result: Result<DescriptionPage, String>,
+    },
+    Chapters {
+        video_id: String,
+        result: Result<Vec<VideoChapter>, String>,
+    },
+    CastDevices(Result<Vec<Renderer>, String>),
+    /// A resolved stream URL to put on the clipboard.
+    StreamUrl(Result<String, String>),
+    /// Outcome of a cast command, shown in the status line.
+    CastStatus(Result<String, String>),
+    SponsorSegments {
+        video_id: String,
+        result: Result<Vec<(f64, f64)>, String>,
+    },
+    Branding {
+        video_id: String,
+        branding: Branding,
+    },
+    Votes {
+        video_id: String,
+        result: Result<Votes, String>,
+    },
+    VideoInfo {
+        video_id: String,
+        result: Result<VideoInfo, String>,
+    },
+    Preview {
+        video_id: String,
+        result: Result<Vec<PathBuf>, String>,
+    },
+    Formats {
+        video_id: String,
+        result: Result<Vec<Quality>, String>,
+    },
+    Prefetched {
+        video_id: String,
+        result: Result<DirectStreams, String>,
+    },
+    Streams {
+        request: u64,
+        url: String,
+        start: Option<u32>,
+        result: Result<DirectStreams, String>,
+    },
+    CaptionTracks {
+        video_id: String,
+        result: Result<Vec<CaptionTrack>, String>,
+    },
+    Transcript {
+        video_id: String,
+        lang: String,
+        result: Result<Vec<Cue>, String>,
+    },
+    ChannelAvatar {
+        channel_id: String,
+        result: Result<PathBuf, String>,
+    },
+    Thumbnail {
+        index: usize,
+        url: String,
+        result: Result<PathBuf, String>,
+    },
+}
+
+struct SearchPage {
+    results: Vec<SearchResultItem>,
+    channel: Option<ChannelHeader>,
+    ctoken: Option<String>,
+    visitor_data: Option<String>,
+    endpoint: ContinuationEndpoint,
+    /// Set when the results came from the search cache.
+    cache: Option<CacheHit>,
+}
+
+enum CacheHit {
+    /// Cached this many seconds ago and still within the TTL.
+    Fresh { age: u64 },
+    /// Shown because the search failed with `error`.
+    Offline { age: u64, error: String },
+}
+
+fn main() -> io::Result<()> {
+    let mut args = env::args().skip(1);
+    let mut batch_path = None;
+    let mut verbose = false;
+    let mut overrides = paths::Overrides::default();
+    let mut export = None;
+    while let Some(arg) = args.next() {
+        let mut value = |what: &str| {
+            args.next().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("{arg} needs {what}"))
+            })
+        };
+        match arg.as_str() {
+            "--verbose" | "-v" => verbose = true,
+            "--batch" | "-b" => batch_path = Some(value("a file or -")?),
+            "--data-dir" => overrides.data_dir = Some(PathBuf::from(value("a directory")?)),
+            "--cache-dir" => overrides.cache_dir = Some(PathBuf::from(value("a directory")?)),
+            "--portable" => overrides.portable = true,
+            "export" => {
+                let format = args.next().unwrap_or_else(|| "json".to_string());
+                let dir = args.next().unwrap_or_else(|| ".".to_string());
+                export = Some((format, dir));
+                break;
+            }
+            other => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown argument: {other}"),
+                ));
+            }
+        }
+    }
+    paths::init(overrides);
+    if let Some((format, dir)) = export {
+        return export_from_cli(&format, &dir);
+    }
+    // Read the queries before the TUI takes over the terminal; crossterm
+    // falls back to /dev/tty when stdin is a pipe.
+    let batch_queries = match batch_path {
+        Some(path) => read_batch_queries(&path)?,
+        None => Vec::new(),
+    };
+    let logging_error = logging::init(verbose).err();
+    log::info!("ytbv {} starting", env!("CARGO_PKG_VERSION"));
+    let credentials_error = unlock_credentials()?;
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    crossterm::execute!(
+        stdout,
+        crossterm::terminal::EnterAlternateScreen,
+        crossterm::event::EnableBracketedPaste
+    )?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let (tx, rx) = mpsc::channel();
+    let (subscriptions, subscriptions_error) = match storage::load_subscriptions() {
+        Ok(subscriptions) => (subscriptions, None),
+        Err(err) => (Vec::new(), Some(err)),
+    };
+    let (history, history_error) = match storage::load_history() {
+        Ok(history) => (history, None),
+        Err(err) => (Vec::new(), Some(err)),
+    };
+    let watched = history.iter().map(|entry| entry.id.clone()).collect();
+    let (bookmarks, bookmarks_error) = match storage::load_bookmarks() {
+        Ok(bookmarks) => (bookmarks, None),
+        Err(err) => (Vec::new(), Some(err)),
+    };
+    let (watch_later, watch_later_error) = match storage::load_watch_later() {
+        Ok(watch_later) => (watch_later, None),
+        Err(err) => (Vec::new(), Some(err)),
+    };
+    let (playlists, playlists_error) = match storage::load_playlists() {
+        Ok(playlists) => (playlists, None),
+        Err(err) => (Vec::new(), Some(err)),
+    };
+    let (progress, progress_error) = match storage::load_progress() {
+        Ok(progress) => (progress, None),
+        Err(err) => (HashMap::new(), Some(err)),
+    };
+    let first_run = config::is_first_run();
+    let (config, config_error) = match config::load_config() {
+        Ok(config) => (config, None),
+        Err(err) => (Config::default(), Some(err)),
+    };
+    set_locale(&config);
+    let (blocklist, blocklist_error) = match config.blocklist_regexes() {
+        Ok(blocklist) => (blocklist, None),
+        Err(err) => (Vec::new(), Some(err)),
+    };
+    let (keys, keys_error) = match Keymap::new(&config.keys) {
+        Ok(keys) => (keys, None),
+        Err(err) => (Keymap::default(), Some(err)),
+    };
+    let (theme, theme_error) = match Theme::from_config(&config.theme) {
+        Ok(theme) => (theme, None),
+        Err(err) => (Theme::default(), Some(err)),
+    };
+    let repeat = config.repeat;
+    let speed = config.speed.unwrap_or(1.0);
+    let mut app = App {
+        screen: ResultsScreen::new(TabId::Search(0)),
+        query_scroll: 0,
+        filter_editing: false,
+        command: String::new(),
+        command_editing: false,
+        last_filter: String::new(),
+        pending_g: false,
+        pending_label: None,
+        live_filter: LiveFilter::All,
+        zen: ZenMode::Off,
+        sort: SortOrder::Default,
+        hide_watched: false,
+        branding: HashMap::new(),
+        original_branding: false,
+        votes: HashMap::new(),
+        video_info: HashMap::new(),
+        max_heights: HashMap::new(),
+        info_candidate: None,
+        description_scroll: None,
+        lucky_pending: false,
+        hide_shorts: env::var("YTBV_HIDE_SHORTS").is_ok_and(|value| value == "1"),
+        results_view_rows: 0,
+        side_panes: Vec::new(),
+        toasts: Toasts::default(),
+        rx,
+        tx: MsgSender {
+            tab: TabId::Search(0),
+            tx,
+        },
+        tabs: Vec::new(),
+        active_tab: 0,
+        next_tab_id: 1,
+        batch_queue: VecDeque::new(),
+        views: Views::default(),
+        live_search: false,
+        query_edited: None,
+        thumb_area: None,
+        details_height: 0,
+        max_details_height: 0,
+        max_thumb_width: 0,
+        thumb_bounds: (0, 0),
+        last_thumb: None,
+        grid_view: false,
+        wrap_rows: false,
+        grid_columns: 1,
+        grid_cell_width: GRID_CELL_WIDTH,
+        grid_scroll: 0,
+        grid_cells: Vec::new(),
+        grid_drawn: Vec::new(),
+        avatar_area: None,
+        last_avatar: None,
+        image_areas: Vec::new(),
+        half_blocks: blocks::HalfBlocks::default(),
+        thumbnail_pool: pool::Pool::new(THUMBNAIL_WORKERS),
+        thumbnail_downloads: 0,
+        hover_preview: false,
+        preview: None,
+        subscriptions,
+        history,
+        watched,
+        bookmarks,
+        watch_later,
+        playlists,
+        marked: Vec::new(),
+        progress,
+        playing_video: None,
+        played: Vec::new(),
+        skip_segments: Vec::new(),
+        stream_request: 0,
+        prefetch: None,
+        config,
+        player: None,
+        detached_players: Vec::new(),
+        playback: None,
+        queue: VecDeque::new(),
+        handoff: None,
+        shuffle: false,
+        window_preset: 0,
+        repeat,
+        speed,
+        ended_at_eof: false,
+        player_log: Vec::new(),
+        cast: None,
+        cast_paused: false,
+        downloads: Vec::new(),
+        next_download_id: 1,
+        blocklist,
+        keys,
+        config_modified: config::config_modified(),
+        config_checked: Instant::now(),
+        config_broken: config_error.is_some(),
+        popup: None,
+        needs_full_redraw: false,
+        chapter_selected: 0,
+        theme,
+    };
+
+    if first_run {
+        app.popup = Some(Popup::Setup(setup::Setup::new(&app.config)));
+    }
+    app.toasts.push(
+        Level::Info,
+        "Type a query and press Enter (empty query for trending).".to_string(),
+    );
+    if batch_queries.is_empty() {
+        start_trending(&mut app);
+    } else {
+        open_batch_tabs(&mut app, batch_queries);
+    }
+    if let Some(err) = logging_error {
+        app.toasts
+            .push(Level::Warning, format!("Not logging to a file: {err}"));
+    }
+    if let Some(err) = credentials_error {
+        app.toasts
+            .push(Level::Error, format!("Saved login not loaded: {err}"));
+    }
+    if let Some(err) = subscriptions_error {
+        app.toasts
+            .push(Level::Error, format!("Failed to load subscriptions: {err}"));
+    }
+    if let Some(err) = history_error {
+        app.toasts
+            .push(Level::Error, format!("Failed to load watch history: {err}"));
+    }
+    if let Some(err) = bookmarks_error {
+        app.toasts
+            .push(Level::Error, format!("Failed to load bookmarks: {err}"));
+    }
+    if let Some(err) = watch_later_error {
+        app.toasts
+            .push(Level::Error, format!("Failed to load watch later: {err}"));
+    }
+    if let Some(err) = playlists_error {
+        app.toasts
+            .push(Level::Error, format!("Failed to load playlists: {err}"));
+    }
+    if let Some(err) = progress_error {
+        app.toasts.push(
+            Level::Error,
+            format!("Failed to load watch progress: {err}"),
+        );
+    }
+    if let Some(err) = config_error {
+        app.toasts.push(
+            Level::Error,
+            format!("Failed to load config: {err}. Changes won't be saved until it loads."),
+        );
+    }
+    if let Some(err) = blocklist_error {
+        app.toasts.push(Level::Error, err);
+    }
+    if let Some(err) = keys_error {
+        app.toasts.push(Level::Error, err);
+    }
+    if let Some(err) = theme_error {
+        app.toasts.push(Level::Error, err);
+    }
+
+    let mut last_tick = Instant::now();
+    let tick_rate = Duration::from_millis(200);
+
+    loop {
+        watch_config(&mut app);
+        run_live_search(&mut app);
+        queue_branding(&mut app);
+        queue_votes(&mut app);
+        queue_video_info(&mut app);
+        queue_preview(&mut app);
+        if app.needs_full_redraw {
+            // Image pixels aren't tracked by ratatui; wipe everything so
+            // overlays and thumbnails are painted from scratch.
+            terminal.clear()?;
+            forget_printed_images(&mut app);
+            app.needs_full_redraw = false;
+        }
+        app.toasts.expire();
+        terminal.draw(|f| ui(f, &mut app))?;
+        if images_moved(&app) {
+            // Blank cells ratatui already thinks are blank aren't written
+            // again, so only a clear removes the old pixels.
+            terminal.clear()?;
+            forget_printed_images(&mut app);
+            terminal.draw(|f| ui(f, &mut app))?;
+        }
+        if !blocks::wanted(app.config.graphics) {
+            render_thumbnail(&mut app)?;
+            render_grid_thumbnails(&mut app)?;
+            render_channel_avatar(&mut app)?;
+        }
+
+        let timeout = tick_rate.saturating_sub(last_tick.elapsed());
+        if event::poll(timeout)? {
+            let event = event::read()?;
+            if let Event::Paste(text) = &event {
+                paste(&mut app, text);
+            }
+            if let Event::Resize(..) = event {
+                // Resizing clears the screen, images included.
+                app.needs_full_redraw = true;
+            }
+            if let Event::Key(key) = event {
+                if key.kind == KeyEventKind::Press {
+                    let handled = handle_line_edit_key(&mut app, key.code, key.modifiers)
+                        || (key.modifiers.contains(KeyModifiers::CONTROL)
+                            && (handle_tab_key(&mut app, key.code)
+                                || handle_half_page_key(&mut app, key.code)
+                                || handle_resize_key(&mut app, key.code)))
+                        || (key.modifiers.contains(KeyModifiers::SHIFT)
+                            && handle_description_scroll_key(&mut app, key.code))
+                        || (key.modifiers.contains(KeyModifiers::ALT)
+                            && key.code == KeyCode::Enter
+                            && open_quality_menu(&mut app))
+                        || (key.modifiers.contains(KeyModifiers::ALT)
+                            && handle_global_player_key(&mut app, key.code));
+                    if !handled && handle_key(&mut app, key.code)? {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if last_tick.elapsed() >= tick_rate {
+            last_tick = Instant::now();
+        }
+
+        while let Ok(TabMsg { tab, msg }) = app.rx.try_recv() {
+            // Results for a background tab are applied with that tab swapped
+            // in, then the visible tab is restored.
+            let search_done = matches!(msg, AppMsg::Search { .. });
+            // Player and download events aren't tied to the tab that started them.
+            let global = matches!(
+                msg,
+                AppMsg::Player { .. }
+                    | AppMsg::Download { .. }
+                    | AppMsg::LoginCode(..)
+                    | AppMsg::Account(_)
+            );
+            let swapped = if tab != app.screen.id && !global {
+                let Some(slot) = find_tab(&app, tab) else {
+                    continue;
+                };
+                swap_tab(&mut app, slot);
+                app.toasts.set_quiet(true);
+                Some(slot)
+            } else {
+                None
+            };
+            match msg {
+                AppMsg::Player { id, event } => {
+                    if matches!(event, PlayerEvent::Exited) {
+                        app.detached_players.retain(|player| player.id() != id);
+                    }
+                    if app.player.as_ref().map(Player::id) == Some(id) {
+                        match event {
+                            PlayerEvent::Property(property) => {
+                                match property {
+                                    PlayerProperty::Position(position) => {
+                                        skip_sponsor_segment(&mut app, position);
+                                    }
+                                    PlayerProperty::PlaylistPos(pos) => {
+                                        follow_handoff(&mut app, pos);
+                                    }
+                                    _ => {}
+                                }
+                                app.playback
+                                    .get_or_insert_with(Default::default)
+                                    .apply(property);
+                            }
+                            PlayerEvent::EndFile { reason } => {
+                                if reason == "error" {
+                                    app.toasts.push(
+                                        Level::Error,
+                                        "mpv failed to play the video.".to_string(),
+                                    );
+                                }
+                                app.ended_at_eof = reason == "eof";
+                            }
+                            PlayerEvent::Failed { status, stderr } => {
+                                let message = match stderr.last() {
+                                    Some(line) => format!(
+                                        "Player exited with {status}: {line} (E for details)"
+                                    ),
+                                    None => format!("Player exited with {status}."),
+                                };
+                                app.toasts.push(Level::Error, message);
+                                for line in &stderr {
+                                    log::debug!("player: {line}");
+                                }
+                                app.player_log = stderr;
+                            }
+                            PlayerEvent::Exited => {
+                                let finished = app.playing_video.clone();
+                                finish_current_video(&mut app);
+                                app.player = None;
+                                app.playback = None;
+                                app.handoff = None;
+                                // mpv quits after the last file (or playlist entry), so
+                                // only move on when it wasn't closed by the user.
+                                if std::mem::take(&mut app.ended_at_eof) {
+                                    play_after(&mut app, finished);
+                                }
+                            }
+                        }
+                    }
+                }
+                AppMsg::Download { id, event } => {
+                    if let Some(item) = app.downloads.iter_mut().find(|item| item.id == id) {
+                        match event {
+                            DownloadEvent::Progress(percent) => item.progress = percent,
+                            DownloadEvent::Finished(result) => {
+                                item.handle = None;
+                                match result {
+                                    Ok(()) => {
+                                        item.progress = 100.0;
+                                        item.state = DownloadState::Done;
+                                        app.toasts.push(
+                                            Level::Info,
+                                            format!("Downloaded: {}", item.title),
+                                        );
+                                    }
+                                    // A cancelled item was already marked by the key handler.
+                                    Err(_) if item.state == DownloadState::Cancelled => {}
+                                    Err(err) => {
+                                        let title = format!("Download failed: {}", item.title);
+                                        item.state = DownloadState::Failed(err.clone());
+                                        show_error(&mut app, title, err, Retry::Download(id));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                AppMsg::Search { generation, .. } | AppMsg::MoreResults { generation, .. }
+                    if generation != app.screen.search_generation => {}
+                AppMsg::Search { source, result, .. } => {
+                    app.screen.searching = false;
+                    let live = std::mem::take(&mut app.live_search);
+                    match result {
+                        Ok(results) => {
+                            // Switching tabs within a channel replaces its results in place.
+                            let same_channel = matches!(
+                                (&app.screen.source, &source),
+                                (
+                                    Some(ResultsSource::Channel { id: current, .. }),
+                                    ResultsSource::Channel { id: next, .. },
+                                ) if current == next
+                            );
+                            if !source.stacks() {
+                                app.screen.back_stack.clear();
+                            } else if !app.screen.results.is_empty() && !same_channel {
+                                let snapshot = take_results_snapshot(&mut app);
+                                app.screen.back_stack.push(snapshot);
+                            }
+                            app.screen.results = results.results;
+                            app.screen.filter.clear();
+                            app.filter_editing = false;
+                            app.screen.source = Some(source);
+                            refresh_visible(&mut app);
+                            app.screen.channel_header = results.channel;
+                            queue_channel_avatar(&mut app);
+                            app.screen.selected = 0;
+                            app.chapter_selected = 0;
+                            app.screen.selected_row = 0;
+                            app.screen.results_state = ListState::default();
+                            app.screen.search_ctoken = results.ctoken;
+                            app.screen.search_visitor_data = results.visitor_data;
+                            app.screen.search_endpoint = Some(results.endpoint);
+                            app.screen.pages_loaded = 1;
+                            app.screen.loading_more = false;
+                            app.screen.pending_next_target = None;
+                            // Don't steal focus from a query typed while trending was
+                            // loading, or from the query a live search is for.
+                            let typing =
+                                app.screen.focus == Focus::Search && !app.screen.query.is_empty();
+                            let background = typing
+                                && (live
+                                    || matches!(
+                                        app.screen.source,
+                                        Some(ResultsSource::Trending(_))
+                                    ));
+                            if !app.screen.results.is_empty() && !background {
+                                app.screen.focus = Focus::Results;
+                            }
+                            if !app.screen.results.is_empty() {
+                                let selected = app.screen.selected;
+                                queue_thumbnail(&mut app, selected);
+                            }
+                            let found = app.screen.results.len();
+                            let message = match results.cache {
+                                None => format!("Found {found} results."),
+                                Some(CacheHit::Fresh { age }) => {
+                                    format!("Found {found} results (cached {}).", format_age(age))
+                                }
+                                Some(CacheHit::Offline { age, error }) => {
+                                    app.toasts.push(
+                                        Level::Warning,
+                                        format!(
+                                            "{error}. Showing results cached {}.",
+                                            format_age(age)
+                                        ),
+                                    );
+                                    format!("Found {found} results (offline).")
+                                }
+                            };
+                            app.toasts.push(Level::Info, message);
+                            if swapped.is_none() && std::mem::take(&mut app.lucky_pending) {
+                                play_random_result(&mut app);
+                            }
+                        }
+                        Err(err) if live => app.toasts.push(Level::Error, err),
+                        Err(err) => {
+                            app.lucky_pending = false;
+                            let title = format!("Loading {} failed", tab_label("", Some(&source)));
+                            show_error(&mut app, title, err, Retry::Source(source));
+                        }
+                    }
+                }
+                AppMsg::MoreResults { result, .. } => {
+                    app.screen.loading_more = false;
+                    match result {
+                        Ok(results) => {
+                            if results.results.is_empty() {
+                                app.screen.pending_next_target = None;
+                                app.toasts.push(Level::Info, "No more results.".to_string());
+                            } else {
+                                app.screen.results.extend(results.results);
+                                refresh_visible(&mut app);
+                                app.screen.search_ctoken = results.ctoken;
+                                app.screen.search_visitor_data = results.visitor_data;
+                                app.screen.search_endpoint = Some(results.endpoint);
+                                app.screen.pages_loaded += 1;
+                                if app.screen.pending_next_target.is_some() {
+                                    select_pending_row(&mut app);
+                                } else {
+                                    app.toasts.push(
+                                        Level::Info,
+                                        format!("Found {} results.", app.screen.results.len()),
+                                    );
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            app.screen.pending_next_target = None;
+                            app.toasts.push(Level::Error, err);
+                        }
+                    }
+                }
+                AppMsg::Imported(imported) => finish_import(&mut app, imported),
+                AppMsg::LoginCode(user_code, url) => {
+                    if let Some(Popup::Login(view)) = app.popup.as_mut() {
+                        view.code = Some((user_code, url));
+                    }
+                }
+                AppMsg::Account(result) => {
+                    if matches!(app.popup, Some(Popup::Login(_))) {
+                        close_popup(&mut app);
+                    }
+                    match result {
+                        Ok(message) => app.toasts.push(Level::Info, message),
+                        Err(err) => app.toasts.push(Level::Error, err),
+                    }
+                }
+                AppMsg::Resolved(result) => {
+                    app.screen.searching = false;
+                    match result {
+                        Ok(target) => open_url_target(&mut app, target),
+                        Err(err) => {
+                            app.toasts.push(Level::Error, err);
+                        }
+                    }
+                }
+                AppMsg::Comments { video_id, result } => {
+                    if let Some(Popup::Comments(view)) = app.popup.as_mut() {
+                        if view.video_id == video_id {
+                            view.loading = false;
+                            match result {
+                                Ok(page) => {
+                                    view.comments.extend(page.comments);
+                                    view.total = page.total.or(view.total);
+                                    view.ctoken = page.ctoken;
+                                    view.visitor_data = page.visitor_data;
+                                }
+                                Err(err) => {
+                                    view.error = Some(err);
+                                }
+                            }
+                        }
+                    }
+                }
+                AppMsg::Description { video_id, result } => {
+                    if let Some(Popup::Description(view)) = app.popup.as_mut() {
+                        if view.video_id == video_id {
+                            view.loading = false;
+                            match result {
+                                Ok(page) => {
+                                    view.segments = page.segments;
+                                    view.selected_link =
+                                        if page.links.is_empty() { None } else { Some(0) };
+                                    view.links = page.links;
+                                }
+                                Err(err) => {
+                                    view.error = Some(err);
+                                }
+                            }
+                        }
+                    }
+                }
+                AppMsg::Streams {
+                    request,
+                    url,
+                    start,
+                    result,
+                } => {
+                    if request == app.stream_request {
+                        let started = match result {
+                            Ok(streams) => launch_direct(&mut app, streams, start),
+                            Err(err) => {
+                                app.toasts
+                                    .push(Level::Warning, format!("{err} Falling back to yt-dlp."));
+                                let args = vec![app.config.ytdl_format()];
+                                launch_player(&mut app, &url, start, args)
+                            }
+                        };
+                        if let Err(err) = started {
+                            app.playback = None;
+                            app.toasts.push(Level::Error, err);
+                        }
+                    }
+                }
+                AppMsg::Prefetched { video_id, result } => {
+                    let pending = app
+                        .prefetch
+                        .as_ref()
+                        .is_some_and(|p| p.video_id == video_id && p.streams.is_none());
+                    if pending {
+                        match result {
+                            Ok(streams) => {
+                                if let Some(prefetch) = app.prefetch.as_mut() {
+                                    prefetch.streams = Some(streams);
+                                }
+                            }
+                            // Playback resolves the streams again when it gets there.
+                            Err(_) => app.prefetch = None,
+                        }
+                    }
+                }
+                AppMsg::Formats { video_id, result } => {
+                    let tallest = result.iter().flatten().filter_map(|quality| match quality {
+                        Quality::Height { height, .. } => Some(*height),
+                        Quality::AudioOnly => None,
+                    });
+                    if let Some(height) = tallest.max() {
+                        app.max_heights.insert(video_id.clone(), height);
+                    }
+                    if let Some(Popup::Quality(view)) = app.popup.as_mut() {
+                        if view.video_id == video_id {
+                            view.loading = false;
+                            match result {
+                                Ok(options) => view.options = options,
+                                Err(err) => view.error = Some(err),
+                            }
+                        }
+                    }
+                }
+                AppMsg::CaptionTracks { video_id, result } => {
+                    let mut load_track = None;
+                    match app.popup.as_mut() {
+                        Some(Popup::Transcript(view)) if view.video_id == video_id => {
+                            view.loading = false;
+                            match result {
+                                Ok(tracks) if tracks.is_empty() => {
+                                    view.error = Some("No captions for this video.".to_string());
+                                }
+                                Ok(tracks) => {
+                                    // Prefer a manual track over auto-generated ones.
+                                    view.picker_selected = tracks
+                                        .iter()
+                                        .position(|track| !track.auto_generated)
+                                        .unwrap_or(0);
+                                    if tracks.len() == 1 {
+                                        load_track = Some(0);
+                                    }
+                                    view.tracks = tracks;
+                                }
+                                Err(err) => {
+                                    view.error = Some(err);
+                                }
+                            }
+                        }
+                        Some(Popup::Subtitles(view)) if view.video_id == video_id => {
+                            view.loading = false;
+                            match result {
+                                Ok(tracks) => {
+                                    view.selected = default_subtitle_track(
+                                        &tracks,
+                                        app.config.subtitle_language.as_deref(),
+                                    )
+                                    .map_or(0, |index| index + 1);
+                                    view.tracks = tracks;
+                                }
+                                Err(err) => view.error = Some(err),
+                            }
+                        }
+                        _ => {}
+                    }
+                    if let Some(index) = load_track {
+                        load_transcript_track(&mut app, index);
+                    }
+                }
+                AppMsg::Transcript {
+                    video_id,
+                    lang,
+                    result,
+                } => {
+                    if let Some(Popup::Transcript(view)) = app.popup.as_mut() {
+                        let current = view.track.and_then(|i| view.tracks.get(i));
+                        if view.video_id == video_id && current.is_some_and(|t| t.lang == lang) {
+                            view.loading = false;
+                            match result {
+                                Ok(cues) => {
+                                    view.cues = cues;
+                                    view.cue_selected = 0;
+                                }
+                                Err(err) => {
+                                    view.error = Some(err);
+                                }
+                            }
+                        }
+                    }
+                }
+                AppMsg::Chapters { video_id, result } => {
+                    let chapters = match result {
+                        Ok(chapters) => chapters,
+                        Err(err) => {
+                            app.toasts.push(Level::Error, err);
+                            Vec::new()
+                        }
+                    };
+                    for item in &mut app.screen.results {
+                        if let SearchResultItem::Video(video) = item {
+                            if video.id == video_id {
+                                video.chapters_loading = false;
+                                video.chapters = Some(chapters.clone());
+                            }
+                        }
+                    }
+                }
+                AppMsg::CastDevices(result) => {
+                    if let Some(Popup::Cast(view)) = app.popup.as_mut() {
+                        view.loading = false;
+                        match result {
+                            Ok(renderers) if renderers.is_empty() => {
+                                view.error =
+                                    Some("No Chromecasts or DLNA renderers found.".to_string());
+                            }
+                            Ok(renderers) => view.renderers = renderers,
+                            Err(err) => view.error = Some(err),
+                        }
+                    }
+                }
+                AppMsg::StreamUrl(result) => match result {
+                    Ok(url) => copy_to_clipboard(&mut app, &url, "stream URL"),
+                    Err(err) => app.toasts.push(Level::Error, err),
+                },
+                AppMsg::CastStatus(result) => match result {
+                    Ok(message) => app.toasts.push(Level::Info, message),
+                    Err(err) => app.toasts.push(Level::Error, err),
+                },
+                AppMsg::Branding { video_id, branding } => {
+                    let replaced = branding.thumbnail_url.is_some() && !app.original_branding;
+                    app.branding.insert(video_id.clone(), Some(branding));
+                    // The original thumbnail may already be showing.
+                    if replaced {
+                        for (index, item) in app.screen.results.iter_mut().enumerate() {
+                            match item {
+                                SearchResultItem::Video(video)
+                                    if video.id == video_id && !video.thumbnail_loading =>
+                                {
+                                    video.thumbnail_path = None;
+                                    video.thumbnail_size = None;
+                                    if index == app.screen.selected {
+                                        app.last_thumb = None;
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                        let selected = app.screen.selected;
+                        queue_thumbnail(&mut app, selected);
+                    }
+                }
+                AppMsg::Votes { video_id, result } => {
+                    app.votes.insert(video_id, result.ok());
+                }
+                AppMsg::VideoInfo { video_id, result } => {
+                    app.video_info.insert(video_id, result.ok());
+                }
+                AppMsg::Preview { video_id, result } => {
+                    if let Some(preview) = app.preview.as_mut() {
+                        if preview.video_id == video_id {
+                            match result {
+                                Ok(frames) => {
+                                    preview.frames = frames;
+                                    preview.since = Instant::now();
+                                }
+                                Err(err) => log::warn!("No preview of {video_id}: {err}"),
+                            }
+                        }
+                    }
+                }
+                AppMsg::SponsorSegments { video_id, result } => {
+                    let segments = match result {
+                        Ok(segments) => segments,
+                        Err(err) => {
+                            app.toasts.push(Level::Error, err);
+                            Vec::new()
+                        }
+                    };
+                    for item in &mut app.screen.results {
+                        if let SearchResultItem::Video(video) = item {
+                            if video.id == video_id {
+                                video.sponsor_loading = false;
+                                video.sponsor_segments = Some(segments.clone());
+                            }
+                        }
+                    }
+                    if app.config.sponsorblock_skip
+                        && app.playing_video.as_ref().map(|item| item.id.as_str())
+                            == Some(video_id.as_str())
+                    {
+                        app.skip_segments = segments;
+                    }
+                }
+                AppMsg::ChannelAvatar { channel_id, result } => {
+                    if let Some(header) = app.screen.channel_header.as_mut() {
+                        if header.id == channel_id {
+                            match result {
+                                Ok(path) => {
+                                    header.avatar_size = thumbnail_size_from_path(&path);
+                                    header.avatar_path = Some(path);
+                                }
+                                Err(err) => log::warn!("Channel avatar not loaded: {err}"),
+                            }
+                        }
+                    }
+                }
+                AppMsg::Thumbnail { index, url, result } => {
+                    app.thumbnail_downloads = app.thumbnail_downloads.saturating_sub(1);
+                    let selected = index == app.screen.selected;
+                    let width = thumbnail_pixels(&app);
+                    if let Some(item) = app.screen.results.get_mut(index) {
+                        match item {
+                            SearchResultItem::Video(video)
+                                if video.display_thumbnail_url(
+                                    active_branding(
+                                        &app.branding,
+                                        app.original_branding,
+                                        &video.id,
+                                    ),
+                                    width,
+                                ) == Some(url.as_str()) =>
+                            {
+                                video.thumbnail_loading = false;
+                                match result {
+                                    Ok(path) => {
+                                        video.thumbnail_size = thumbnail_size_from_path(&path);
+                                        video.thumbnail_path = Some(path);
+                                        if selected {
+                                            app.toasts
+                                                .push(Level::Info, "Thumbnail ready.".to_string());
+                                        }
+                                    }
+                                    Err(err) if selected => {
+                                        app.toasts.push(Level::Error, err);
+                                    }
+                                    Err(err) => log::warn!("Thumbnail {url} not loaded: {err}"),
+                                }
+                            }
+                            // DeArrow swapped the thumbnail while this one loaded.
+                            SearchResultItem::Video(video) if video.thumbnail_loading => {
+                                video.thumbnail_loading = false;
+                                if selected {
+                                    queue_thumbnail(&mut app, index);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            if let Some(slot) = swapped {
+                app.toasts.set_quiet(false);
+                swap_tab(&mut app, slot);
+            }
+            if search_done {
+                start_next_batch_search(&mut app);
+            }
+        }
+    }
+
+    if app.config.quit_stops_players {
+        stop_player(&mut app);
+        for player in app.detached_players.drain(..) {
+            player.quit();
+        }
+    }
+
+    disable_raw_mode()?;
+    crossterm::execute!(
+        terminal.backend_mut(),
+        crossterm::event::DisableBracketedPaste,
+        crossterm::terminal::LeaveAlternateScreen
+    )?;
+    terminal.show_cursor()?;
+
+    Ok(())
+}
+
+/// Handles Ctrl shortcuts for search tabs; returns false for other keys.
+fn handle_tab_key(app: &mut App, key: KeyCode) -> bool {
+    if app.popup.is_some()
+        || app.filter_editing
+        || app.command_editing
+        || app.views.current != View::Search
+    {
+        return false;
+    }
+    let count = app.tabs.len() + 1;
+    match key {
+        KeyCode::Char('t') => {
+            let id = app.next_tab_id;
+            app.next_tab_id += 1;
+            let tab = show_screen(app, ResultsScreen::new(TabId::Search(id)));
+            app.tabs.insert(app.active_tab, tab);
+            app.active_tab += 1;
+            app.needs_full_redraw = true;
+            app.toasts
+                .push(Level::Info, "Opened a new tab.".to_string());
+        }
+        KeyCode::Char('w') => {
+            if app.tabs.is_empty() {
+                app.toasts
+                    .push(Level::Info, "Can't close the last tab.".to_string());
+                return true;
+            }
+            // Prefer the tab to the right, like most tabbed UIs.
+            let next = if app.active_tab < app.tabs.len() {
+                app.tabs.remove(app.active_tab)
+            } else {
+                app.active_tab -= 1;
+                app.tabs.remove(app.active_tab)
+            };
+            show_screen(app, next);
+            app.needs_full_redraw = true;
+            app.toasts.push(Level::Info, "Closed tab.".to_string());
+        }
+        KeyCode::Right | KeyCode::PageDown => {
+            switch_tab(app, (app.active_tab + 1) % count);
+            app.needs_full_redraw = true;
+        }
+        KeyCode::Left | KeyCode::PageUp => {
+            switch_tab(app, (app.active_tab + count - 1) % count);
+            app.needs_full_redraw = true;
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Finds where the inactive results screen with tab id `id` is kept.
+fn find_tab(app: &App, id: TabId) -> Option<TabSlot> {
+    if app.views.parked.as_ref().is_some_and(|tab| tab.id == id) {
+        return Some(TabSlot::Parked);
+    }
+    app.tabs
+        .iter()
+        .position(|tab| tab.id == id)
+        .map(TabSlot::Search)
+}
+
+/// Exchanges `App::screen` with the results screen in `slot`; calling it
+/// again swaps them back.
+fn swap_tab(app: &mut App, slot: TabSlot) {
+    let other = match slot {
+        TabSlot::Search(index) => &mut app.tabs[index],
+        TabSlot::Parked => app
+            .views
+            .parked
+            .get_or_insert_with(|| ResultsScreen::new(TabId::Feed)),
+    };
+    std::mem::swap(&mut app.screen, other);
+    screen_shown(app);
+}
+
+/// Shows `screen` and returns the one it replaces.
+fn show_screen(app: &mut App, screen: ResultsScreen) -> ResultsScreen {
+    let shown = std::mem::replace(&mut app.screen, screen);
+    screen_shown(app);
+    shown
+}
+
+/// Resets what belongs to the screen on display after `App::screen` changed.
+fn screen_shown(app: &mut App) {
+    app.tx.tab = app.screen.id;
+    app.filter_editing = false;
+    app.chapter_selected = 0;
+    refresh_visible(app);
+}
+
+/// Shows `view`. The Search and Feed views each have their own results
+/// screen; the hidden one waits in `Views::parked`.
+fn switch_view(app: &mut App, view: View) {
+    if view == app.views.current {
+        return;
+    }
+    let feed_shown = app.screen.id == TabId::Feed;
+    let wants_feed = match view {
+        View::Search => Some(false),
+        View::Feed => Some(true),
+        View::History
+        | View::Queue
+        | View::Downloads
+        | View::Favorites
+        | View::Playlists
+        | View::WatchLater
+        | View::Stats => None,
+    };
+    if wants_feed.is_some_and(|wants_feed| wants_feed != feed_shown) {
+        swap_tab(app, TabSlot::Parked);
+    }
+    app.views.current = view;
+    app.filter_editing = false;
+    app.needs_full_redraw = true;
+    if view == View::Feed && app.screen.results.is_empty() && !app.screen.searching {
+        start_feed(app);
+    }
+}
+
+/// Plays a video that isn't in the results, resuming where it stopped.
+fn replay(app: &mut App, id: &str, title: &str) {
+    let url = format!("https://www.youtube.com/watch?v={id}");
+    let start = app.progress.get(id).map(|saved| saved.position as u32);
+    match play_url(app, &url, start) {
+        Ok(()) => {
+            record_watch(app, id, title);
+            app.toasts.push(Level::Info, format!("Playing: {title}"));
+        }
+        Err(err) => app.toasts.push(Level::Error, err),
+    }
+}
+
+fn add_to_watch_later(app: &mut App, id: String, title: String, channel: Option<String>) {
+    if app.watch_later.iter().any(|entry| entry.id == id) {
+        app.toasts
+            .push(Level::Info, format!("{title} is already in Watch later."));
+        return;
+    }
+    let status = format!("Added {title} to Watch later.");
+    app.watch_later.push(WatchLaterEntry {
+        id,
+        title,
+        channel,
+        added_at: OffsetDateTime::now_utc().unix_timestamp(),
+    });
+    save_watch_later(app, status);
+}
+
+/// Saves the watch later list, showing `status` when that worked.
+fn save_watch_later(app: &mut App, status: String) {
+    match storage::save_watch_later(&app.watch_later) {
+        Ok(()) => app.toasts.push(Level::Info, status),
+        Err(err) => app
+            .toasts
+            .push(Level::Error, format!("Failed to save watch later: {err}")),
+    }
+}
+
+/// Offers the marked videos, or else the selected one, for a local playlist.
+fn add_to_local_playlist(app: &mut App) {
+    let items = if app.marked.is_empty() {
+        match app.screen.results.get(app.screen.selected) {
+            Some(SearchResultItem::Video(video)) => vec![QueueItem {
+                id: video.id.clone(),
+                title: video.title.clone(),
+                url: video.url.clone(),
+            }],
+            _ => {
+                app.toasts.push(
+                    Level::Info,
+                    "Select or mark videos to add them to a playlist.".to_string(),
+                );
+                return;
+            }
+        }
+    } else {
+        std::mem::take(&mut app.marked)
+    };
+    open_playlist_picker(app, items);
+}
+
+fn open_playlist_picker(app: &mut App, items: Vec<QueueItem>) {
+    // With no playlists yet, go straight to naming the first one.
+    let new_name = app.playlists.is_empty().then(String::new);
+    app.popup = Some(Popup::AddToPlaylist(PlaylistPickerView {
+        items,
+        selected: 0,
+        new_name,
+    }));
+    app.needs_full_redraw = true;
+}
+
+fn add_to_playlist_at(app: &mut App, index: usize, items: Vec<QueueItem>) {
+    let Some(playlist) = app.playlists.get_mut(index) else {
+        return;
+    };
+    let count = items.len();
+    playlist
+        .videos
+        .extend(items.into_iter().map(|item| PlaylistVideo {
+            id: item.id,
+            title: item.title,
+            url: item.url,
+        }));
+    let status = if count == 0 {
+        format!("Created playlist {}.", playlist.name)
+    } else {
+        format!("Added {count} videos to {}.", playlist.name)
+    };
+    save_playlists(app, status);
+}
+
+/// Queues the videos of playlist `index` from `from` on.
+fn play_local_playlist(app: &mut App, index: usize, from: usize) {
+    let Some(playlist) = app.playlists.get(index) else {
+        return;
+    };
+    let items: Vec<QueueItem> = playlist
+        .videos
+        .iter()
+        .skip(from)
+        .map(|video| QueueItem {
+            id: video.id.clone(),
+            title: video.title.clone(),
+            url: video.url.clone(),
+        })
+        .collect();
+    if items.is_empty() {
+        app.toasts
+            .push(Level::Info, format!("{} is empty.", playlist.name));
+        return;
+    }
+    enqueue_items(app, items);
+}
+
+/// Saves the local playlists, showing `status` when that worked.
+fn save_playlists(app: &mut App, status: String) {
+    match storage::save_playlists(&app.playlists) {
+        Ok(()) => app.toasts.push(Level::Info, status),
+        Err(err) => app
+            .toasts
+            .push(Level::Error, format!("Failed to save playlists: {err}")),
+    }
+}
+
+/// Bookmarks the selected video, or removes its bookmark.
+fn toggle_bookmark(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        app.toasts
+            .push(Level::Info, "Select a video to bookmark it.".to_string());
+        return;
+    };
+    let status = match app
+        .bookmarks
+        .iter()
+        .position(|bookmark| bookmark.id == video.id)
+    {
+        Some(index) => {
+            let bookmark = app.bookmarks.remove(index);
+            format!("Removed {} from Favorites.", bookmark.title)
+        }
+        None => {
+            let status = format!("Bookmarked {}.", video.title);
+            app.bookmarks.push(Bookmark {
+                id: video.id.clone(),
+                title: video.title.clone(),
+                channel: video.channel.clone(),
+                tags: Vec::new(),
+                added_at: OffsetDateTime::now_utc().unix_timestamp(),
+            });
+            status
+        }
+    };
+    save_bookmarks(app, status);
+}
+
+/// Saves the bookmarks, showing `status` when that worked.
+fn save_bookmarks(app: &mut App, status: String) {
+    match storage::save_bookmarks(&app.bookmarks) {
+        Ok(()) => app.toasts.push(Level::Info, status),
+        Err(err) => app
+            .toasts
+            .push(Level::Error, format!("Failed to save bookmarks: {err}")),
+    }
+}
+
+/// Makes the tab at display position `index` the active one.
+fn switch_tab(app: &mut App, index: usize) {
+    if index == app.active_tab || index > app.tabs.len() {
+        return;
+    }
+    // `tabs` leaves out the active tab, which sits at `active_tab`.
+    let (next, current) = if index < app.active_tab {
+        (index, app.active_tab - 1)
+    } else {
+        (index - 1, app.active_tab)
+    };
+    let next = app.tabs.remove(next);
+    let shown = show_screen(app, next);
+    app.tabs.insert(current, shown);
+    app.active_tab = index;
+}
+
+/// Unlocks a saved YouTube login with the passphrase in `YTBV_PASSPHRASE`,
+/// or else asks for it before the TUI starts; an empty answer goes on
+/// without the login. Returns why it stayed locked, if it did.
+fn unlock_credentials() -> io::Result<Option<String>> {
+    if let Ok(passphrase) = env::var("YTBV_PASSPHRASE") {
+        return Ok(credentials::unlock(&passphrase, &rustypipe_cache_file()).err());
+    }
+    if !credentials::stored(&rustypipe_cache_file()) {
+        return Ok(None);
+    }
+    let mut error = None;
+    for _ in 0..3 {
+        let passphrase =
+            read_passphrase("Passphrase for the saved YouTube login (Enter to skip): ")?;
+        if passphrase.is_empty() {
+            return Ok(None);
+        }
+        match credentials::unlock(&passphrase, &rustypipe_cache_file()) {
+            Ok(()) => return Ok(None),
+            Err(err) => {
+                eprintln!("{err}");
+                error = Some(err);
+            }
+        }
+    }
+    Ok(error)
+}
+
+/// Reads a line from the terminal without echoing it.
+fn read_passphrase(prompt: &str) -> io::Result<String> {
+    eprint!("{prompt}");
+    enable_raw_mode()?;
+    let mut passphrase = String::new();
+    let result = loop {
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => key,
+            Ok(_) => continue,
+            Err(err) => break Err(err),
+        };
+        match key.code {
+            KeyCode::Enter => break Ok(()),
+            KeyCode::Esc => {
+                passphrase.clear();
+                break Ok(());
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                break Err(io::Error::new(io::ErrorKind::Interrupted, "interrupted"));
+            }
+            KeyCode::Backspace => {
+                passphrase.pop();
+            }
+            KeyCode::Char(c) => passphrase.push(c),
+            _ => {}
+        }
+    };
+    disable_raw_mode()?;
+    eprintln!();
+    result.map(|()| passphrase)
+}
+
+/// Reads one query per line from `path`, or from stdin when it is `-`.
+/// Blank lines and lines starting with `#` are skipped.
+fn read_batch_queries(path: &str) -> io::Result<Vec<String>> {
+    let data = if path == "-" {
+        let mut data = String::new();
+        io::stdin().read_to_string(&mut data)?;
+        data
+    } else {
+        fs::read_to_string(path)?
+    };
+    Ok(data
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Opens one search tab per batch query, starting with the current tab.
+fn open_batch_tabs(app: &mut App, queries: Vec<String>) {
+    let count = queries.len();
+    for (index, query) in queries.into_iter().enumerate() {
+        let id = if index == 0 {
+            app.screen.cursor = query.len();
+            app.screen.query = query;
+            app.screen.id
+        } else {
+            let id = TabId::Search(app.next_tab_id);
+            app.next_tab_id += 1;
+            let mut tab = ResultsScreen::new(id);
+            tab.cursor = query.len();
+            tab.query = query;
+            app.tabs.push(tab);
+            id
+        };
+        app.batch_queue.push_back(id);
+    }
+    start_next_batch_search(app);
+    app.toasts.push(
+        Level::Info,
+        format!("Batch: {count} queries. Ctrl+Left/Right steps through the results."),
+    );
+}
+
+fn start_next_batch_search(app: &mut App) {
+    while let Some(id) = app.batch_queue.pop_front() {
+        if id == app.screen.id {
+            let query = app.screen.query.clone();
+            start_search(app, query);
+            return;
+        }
+        // Closed tabs are simply skipped.
+        let Some(slot) = find_tab(app, id) else {
+            continue;
+        };
+        swap_tab(app, slot);
+        app.toasts.set_quiet(true);
+        let query = app.screen.query.clone();
+        start_search(app, query);
+        app.toasts.set_quiet(false);
+        swap_tab(app, slot);
+        return;
+    }
+}
+
+fn tab_label(query: &str, source: Option<&ResultsSource>) -> String {
+    if !query.is_empty() {
+        return query.to_string();
+    }
+    match source {
+        Some(ResultsSource::Channel { name, .. }) => name.clone(),
+        Some(ResultsSource::Playlist { name, .. }) => name.clone(),
+        Some(ResultsSource::Trending(_)) => "Trending".to_string(),
+        Some(ResultsSource::Related { title, .. }) => title.clone(),
+        Some(ResultsSource::Feed) => "Feed".to_string(),
+        Some(ResultsSource::Search(query)) => query.clone(),
+        None => "New tab".to_string(),
+    }
+}
+
+/// Routes a key to the popup, prompt or focused pane that takes it, and
+/// else to the keys that work everywhere. Returns true when the key quits.
+fn handle_key(app: &mut App, key: KeyCode) -> io::Result<bool> {
+    if app.popup.is_some() {
+        handle_popup_key(app, key);
+        return Ok(false);
+    }
+    if app.filter_editing {
+        handle_filter_key(app, key);
+        return Ok(false);
+    }
+    if app.command_editing {
+        return Ok(handle_command_key(app, key));
+    }
+    if let Some(play) = app.pending_label.take() {
+        if let KeyCode::Char(label) = key {
+            if select_labelled_row(app, label) && play {
+                return handle_key(app, KeyCode::Enter);
+            }
+        }
+        return Ok(false);
+    }
+
+    let typing = (app.views.current == View::Search && app.screen.focus == Focus::Search)
+        || app.views.history.search_editing;
+    // Vim-style motions, outside the search box.
+    let key = if typing {
+        key
+    } else {
+        let pending_g = std::mem::take(&mut app.pending_g);
+        match key {
+            KeyCode::Char('j') => KeyCode::Down,
+            KeyCode::Char('k') => KeyCode::Up,
+            KeyCode::Char('g') if pending_g => KeyCode::Home,
+            KeyCode::Char('G') => KeyCode::End,
+            key => key,
+        }
+    };
+    if key == KeyCode::Char(':') && !typing {
+        app.command.clear();
+        app.command_editing = true;
+        return Ok(false);
+    }
+    if let KeyCode::Char(digit @ '1'..='9') = key {
+        if !typing {
+            switch_view(app, View::ALL[digit as usize - '1' as usize]);
+            return Ok(false);
+        }
+    }
+    if !matches!(app.views.current, View::Search | View::Feed) {
+        return views::handle_key(app, key);
+    }
+
+    let handled = match app.screen.focus {
+        Focus::Search => handle_search_key(app, key),
+        Focus::Results => handle_results_key(app, key) || handle_selection_key(app, key),
+        Focus::Details => handle_details_key(app, key) || handle_selection_key(app, key),
+        Focus::Queue | Focus::Downloads => views::handle_pane_key(app, key),
+    };
+    Ok(!handled && handle_global_key(app, key))
+}
+
+/// Keys that work whatever pane has focus: moving focus, playback, and
+/// switching what is listed and how. Returns true when the key quits.
+fn handle_global_key(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char('q') => return true,
+        KeyCode::Tab => cycle_focus(app, true),
+        KeyCode::BackTab => cycle_focus(app, false),
+        KeyCode::Backspace => go_back(app),
+        KeyCode::Char('/') => {
+            switch_view(app, View::Search);
+            app.screen.focus = Focus::Search;
+            app.screen.cursor = app.screen.query.len();
+        }
+        KeyCode::Char(c) if handle_player_key(app, c) => {}
+        KeyCode::Char('g') => app.pending_g = true,
+        KeyCode::Char(c) => match app.keys.action(c) {
+            Some(Action::Trending) => start_trending(app),
+            Some(Action::Feed) => {
+                if app.views.current == View::Feed {
+                    start_feed(app);
+                } else {
+                    switch_view(app, View::Feed);
+                }
+            }
+            Some(Action::ShowQueue) => switch_view(app, View::Queue),
+            Some(Action::HoverPreview) => toggle_hover_preview(app),
+            Some(Action::Region) => open_locale_picker(app),
+            Some(Action::OriginalBranding) => toggle_original_branding(app),
+            Some(Action::NextTheme) => cycle_theme(app),
+            Some(Action::NextLayout) => cycle_layout(app),
+            Some(Action::ResultsStyle) => {
+                // List, then wrapped list, then grid.
+                (app.grid_view, app.wrap_rows) = match (app.grid_view, app.wrap_rows) {
+                    (false, false) => (false, true),
+                    (false, true) => (true, false),
+                    (true, _) => (false, false),
+                };
+                app.needs_full_redraw = true;
+                app.toasts.push(
+                    Level::Info,
+                    if app.grid_view {
+                        "Grid view.".to_string()
+                    } else if app.wrap_rows {
+                        "Wrapped list view.".to_string()
+                    } else {
+                        "List view.".to_string()
+                    },
+                );
+            }
+            Some(Action::Zen) => {
+                app.zen = app.zen.next();
+                app.needs_full_redraw = true;
+                app.toasts
+                    .push(Level::Info, format!("{} mode.", app.zen.label()));
+            }
+            Some(Action::PlaybackOptions) => {
+                app.popup = Some(Popup::PlaybackOptions(0));
+                app.needs_full_redraw = true;
+            }
+            Some(Action::PlayerLog) => {
+                if app.player_log.is_empty() {
+                    app.toasts
+                        .push(Level::Info, "No player errors.".to_string());
+                } else {
+                    app.popup = Some(Popup::PlayerLog(0));
+                    app.needs_full_redraw = true;
+                }
+            }
+            Some(Action::Shuffle) => {
+                app.shuffle = !app.shuffle;
+                app.toasts.push(
+                    Level::Info,
+                    if app.shuffle {
+                        "Shuffle on.".to_string()
+                    } else {
+                        "Shuffle off.".to_string()
+                    },
+                );
+            }
+            Some(Action::HideShorts) => {
+                app.hide_shorts = !app.hide_shorts;
+                let filter = app.screen.filter.clone();
+                set_filter(app, filter);
+                app.toasts.push(
+                    Level::Info,
+                    if app.hide_shorts {
+                        "Hiding Shorts.".to_string()
+                    } else {
+                        "Showing Shorts.".to_string()
+                    },
+                );
+            }
+            Some(Action::HideWatched) => {
+                app.hide_watched = !app.hide_watched;
+                let filter = app.screen.filter.clone();
+                set_filter(app, filter);
+                app.toasts.push(
+                    Level::Info,
+                    if app.hide_watched {
+                        "Hiding watched videos.".to_string()
+                    } else {
+                        "Showing watched videos.".to_string()
+                    },
+                );
+            }
+            Some(Action::LiveFilter) => {
+                app.live_filter = app.live_filter.next();
+                let filter = app.screen.filter.clone();
+                set_filter(app, filter);
+                app.toasts
+                    .push(Level::Info, format!("Showing {}.", app.live_filter.label()));
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+    false
+}
+
+/// Keys acting on the selected result (or the marked ones), taken while the
+/// results or the Details pane showing that result have focus.
+fn handle_selection_key(app: &mut App, key: KeyCode) -> bool {
+    let KeyCode::Char(c) = key else {
+        return false;
+    };
+    let Some(action) = app.keys.action(c) else {
+        return false;
+    };
+    match action {
+        Action::Related => match app.screen.results.get(app.screen.selected) {
+            Some(SearchResultItem::Video(video)) => {
+                let id = video.id.clone();
+                let title = video.title.clone();
+                start_related_videos(app, id, title);
+            }
+            _ => {
+                app.toasts.push(
+                    Level::Info,
+                    "Select a video to load related videos.".to_string(),
+                );
+            }
+        },
+        Action::Comments => open_comments(app),
+        Action::Description => open_description(app),
+        Action::Transcript => open_transcript(app),
+        Action::Sort if !app.screen.results.is_empty() => {
+            let selected = SortOrder::ALL.iter().position(|sort| *sort == app.sort);
+            app.popup = Some(Popup::Sort(selected.unwrap_or(0)));
+            app.needs_full_redraw = true;
+        }
+        Action::OpenInBrowser => open_selected_in_browser(app),
+        Action::Bookmark => toggle_bookmark(app),
+        Action::AddToPlaylist => add_to_local_playlist(app),
+        Action::WatchLater => match app.screen.results.get(app.screen.selected) {
+            Some(SearchResultItem::Video(video)) => {
+                let (id, title) = (video.id.clone(), video.title.clone());
+                let channel = video.channel.clone();
+                add_to_watch_later(app, id, title, channel);
+            }
+            _ => app
+                .toasts
+                .push(Level::Info, "Select a video to watch it later.".to_string()),
+        },
+        Action::Lucky => {
+            if app.screen.searching {
+                app.lucky_pending = true;
+                app.toasts.push(
+                    Level::Info,
+                    "Will play a random result once the search completes.".to_string(),
+                );
+            } else {
+                play_random_result(app);
+            }
+        }
+        Action::Enqueue if !app.marked.is_empty() => enqueue_marked(app),
+        Action::Enqueue => enqueue_selected(app),
+        Action::Download if !app.marked.is_empty() => download_marked(app),
+        Action::Download => download_selected(app),
+        Action::Subtitles => open_subtitle_picker(app),
+        Action::PlayAll => play_all_results(app),
+        Action::Cast => open_cast_picker(app),
+        Action::Clip => open_clip_prompt(app),
+        Action::Label | Action::LabelAndPlay if !app.screen.visible.is_empty() => {
+            app.pending_label = Some(action == Action::LabelAndPlay);
+        }
+        Action::Jump => open_jump_prompt(app),
+        Action::CopyUrl if !app.marked.is_empty() => yank_marked_urls(app),
+        Action::CopyUrl => yank_selected_url(app),
+        Action::CopyStreamUrl => yank_stream_url(app),
+        Action::Subscribe => toggle_subscription(app),
+        Action::PlayPlaylist => {
+            if let Some((playlist_id, playlist_name)) = current_playlist_info(app) {
+                let url = playlist_url(&playlist_id);
+                let mut args = vec![app.config.ytdl_format()];
+                if app.shuffle {
+                    args.push("--shuffle".to_string());
+                }
+                match spawn_player(app, &url, None, args) {
+                    Ok(()) => {
+                        app.toasts
+                            .push(Level::Info, format!("Playing playlist: {playlist_name}"));
+                    }
+                    Err(err) => {
+                        app.toasts.push(Level::Error, err);
+                    }
+                }
+            } else {
+                app.toasts
+                    .push(Level::Info, "No playlist selected.".to_string());
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Keys of the search box: editing the query and Enter to search.
+fn handle_search_key(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Enter => {
+            let query = app.screen.query.trim().to_string();
+            if query.is_empty() {
+                start_trending(app);
+            } else if looks_like_youtube_link(&query) {
+                start_resolve(app, query);
+            } else {
+                start_search(app, query);
+            }
+        }
+        KeyCode::Backspace => {
+            if app.screen.cursor > 0 {
+                let start = previous_grapheme(&app.screen.query, app.screen.cursor);
+                app.screen.query.replace_range(start..app.screen.cursor, "");
+                app.screen.cursor = start;
+                query_edited(app);
+            }
+        }
+        KeyCode::Left if app.screen.cursor > 0 => {
+            app.screen.cursor = previous_grapheme(&app.screen.query, app.screen.cursor);
+        }
+        KeyCode::Right if app.screen.cursor < app.screen.query.len() => {
+            app.screen.cursor = next_grapheme(&app.screen.query, app.screen.cursor);
+        }
+        KeyCode::Delete if app.screen.cursor < app.screen.query.len() => {
+            let end = next_grapheme(&app.screen.query, app.screen.cursor);
+            app.screen.query.replace_range(app.screen.cursor..end, "");
+            query_edited(app);
+        }
+        KeyCode::Left | KeyCode::Right | KeyCode::Delete => {}
+        KeyCode::Home => app.screen.cursor = 0,
+        KeyCode::End => app.screen.cursor = app.screen.query.len(),
+        KeyCode::Char(c) => {
+            app.screen.query.insert(app.screen.cursor, c);
+            app.screen.cursor += c.len_utf8();
+            query_edited(app);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Keys of the results list: moving, opening and marking results.
+fn handle_results_key(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Enter => {
+            if app.screen.visible.is_empty() {
+                return true;
+            }
+            app.screen.selected_row = app.screen.selected_row.min(app.screen.visible.len() - 1);
+            if let Some(item) = app
+                .screen
+                .results
+                .get(app.screen.visible[app.screen.selected_row])
+            {
+                match item {
+                    SearchResultItem::Video(video) => {
+                        let (id, title) = (video.id.clone(), video.title.clone());
+                        let url = video.url.clone();
+                        if let Some(saved) = app.progress.get(&id) {
+                            app.popup = Some(Popup::Resume(ResumeView {
+                                video_id: id,
+                                title,
+                                url,
+                                position: saved.position,
+                                selected: 0,
+                            }));
+                            app.needs_full_redraw = true;
+                            return true;
+                        }
+                        match play_url(app, &url, None) {
+                            Ok(()) => {
+                                app.toasts.push(Level::Info, format!("Playing: {title}"));
+                                record_watch(app, &id, &title);
+                            }
+                            Err(err) => {
+                                app.toasts.push(Level::Error, err);
+                            }
+                        }
+                    }
+                    SearchResultItem::Channel(channel) => {
+                        app.screen.query = channel.name.clone();
+                        app.screen.cursor = app.screen.query.len();
+                        start_channel_videos(app, channel.id.clone(), channel.name.clone());
+                    }
+                    SearchResultItem::Playlist(playlist) => {
+                        start_playlist_videos(app, playlist.id.clone(), playlist.name.clone());
+                    }
+                }
+            }
+        }
+        KeyCode::Up => move_selection(app, -results_step(app)),
+        KeyCode::Down => move_selection(app, results_step(app)),
+        KeyCode::Left if app.grid_view => move_selection(app, -1),
+        KeyCode::Right if app.grid_view => move_selection(app, 1),
+        KeyCode::PageUp => move_selection(app, -(app.results_view_rows.max(1) as isize)),
+        KeyCode::PageDown => move_selection(app, app.results_view_rows.max(1) as isize),
+        KeyCode::Home => {
+            app.screen.selected_row = 0;
+            sync_selected_result(app);
+        }
+        KeyCode::End => {
+            app.screen.selected_row = app.screen.visible.len().saturating_sub(1);
+            sync_selected_result(app);
+        }
+        KeyCode::Esc if !app.screen.filter.is_empty() => {
+            set_filter(app, String::new());
+            app.toasts.push(Level::Info, "Filter cleared.".to_string());
+        }
+        KeyCode::Esc if !app.marked.is_empty() => {
+            app.marked.clear();
+            app.toasts.push(Level::Info, "Marks cleared.".to_string());
+        }
+        KeyCode::Char(' ') => toggle_mark(app),
+        KeyCode::Char('F') if !app.screen.results.is_empty() => app.filter_editing = true,
+        KeyCode::Char('n') => repeat_filter(app, true),
+        KeyCode::Char('N') => repeat_filter(app, false),
+        KeyCode::Left => {
+            cycle_channel_tab(app, false);
+            cycle_trending_category(app, false);
+        }
+        KeyCode::Right => {
+            cycle_channel_tab(app, true);
+            cycle_trending_category(app, true);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Keys of the Details pane: picking a chapter and opening the channel.
+fn handle_details_key(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Enter => play_selected_chapter(app),
+        KeyCode::Up => app.chapter_selected = app.chapter_selected.saturating_sub(1),
+        KeyCode::Down => {
+            if let Some(SearchResultItem::Video(video)) =
+                app.screen.results.get(app.screen.selected)
+            {
+                let count = video.chapters.as_ref().map_or(0, Vec::len);
+                if app.chapter_selected + 1 < count {
+                    app.chapter_selected += 1;
+                }
+            }
+        }
+        KeyCode::Char('c') => {
+            if let Some((channel_id, channel_name)) = selected_channel_info(app) {
+                app.screen.query = channel_name.clone();
+                app.screen.cursor = app.screen.query.len();
+                start_channel_videos(app, channel_id, channel_name);
+            } else {
+                app.toasts
+                    .push(Level::Info, "No channel info for this result.".to_string());
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Moves the focus to the next pane that can take it, in `Focus::ALL`
+/// order or backwards.
+fn cycle_focus(app: &mut App, forward: bool) {
+    let count = Focus::ALL.len();
+    let current = Focus::ALL
+        .iter()
+        .position(|&focus| focus == app.screen.focus);
+    let current = current.unwrap_or(0);
+    let next = (1..count)
+        .map(|step| {
+            if forward {
+                Focus::ALL[(current + step) % count]
+            } else {
+                Focus::ALL[(current + count - step) % count]
+            }
+        })
+        .find(|&focus| can_focus(app, focus));
+    if let Some(focus) = next {
+        set_focus(app, focus);
+    }
+}
+
+fn can_focus(app: &App, focus: Focus) -> bool {
+    // The Feed has no search box, and shows its list even while empty.
+    let has_results = app.views.current == View::Feed || !app.screen.results.is_empty();
+    match focus {
+        Focus::Search => app.views.current != View::Feed,
+        Focus::Results => has_results,
+        Focus::Details => has_results && details_shown(app),
+        Focus::Queue | Focus::Downloads => app.side_panes.contains(&focus),
+    }
+}
+
+fn set_focus(app: &mut App, focus: Focus) {
+    match focus {
+        Focus::Search | Focus::Queue | Focus::Downloads => {}
+        Focus::Results => sync_selected_result(app),
+        Focus::Details => {
+            queue_chapters(app);
+            queue_sponsor_segments(app);
+        }
+    }
+    app.screen.focus = focus;
+}
+
+/// Width of the column of side panes beside the results.
+const SIDE_PANE_WIDTH: u16 = 36;
+
+/// The side panes to draw beside results `width` columns wide, top to
+/// bottom: the queue while it has videos and the downloads while one runs.
+fn side_panes(app: &App, width: u16) -> Vec<Focus> {
+    if app.zen == ZenMode::Compact || width < SIDE_PANE_WIDTH * 3 {
+        return Vec::new();
+    }
+    let mut panes = Vec::new();
+    if !app.queue.is_empty() {
+        panes.push(Focus::Queue);
+    }
+    if app
+        .downloads
+        .iter()
+        .any(|item| item.state == DownloadState::Running)
+    {
+        panes.push(Focus::Downloads);
+    }
+    panes
+}
+
+fn handle_filter_key(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc => {
+            app.filter_editing = false;
+            set_filter(app, String::new());
+        }
+        KeyCode::Enter => {
+            app.filter_editing = false;
+            if !app.screen.filter.is_empty() {
+                app.last_filter = app.screen.filter.clone();
+            }
+            app.toasts.push(
+                Level::Info,
+                format!(
+                    "{} of {} results match.",
+                    app.screen.visible.len(),
+                    app.screen.results.len()
+                ),
+            );
+        }
+        KeyCode::Backspace => {
+            let mut filter = app.screen.filter.clone();
+            filter.pop();
+            set_filter(app, filter);
+        }
+        KeyCode::Char(c) => {
+            let mut filter = app.screen.filter.clone();
+            filter.push(c);
+            set_filter(app, filter);
+        }
+        _ => {}
+    }
+}
+
+fn set_filter(app: &mut App, filter: String) {
+    app.screen.filter = filter;
+    refresh_visible(app);
+    app.screen.selected_row = 0;
+    app.screen.results_state = ListState::default();
+    sync_selected_result(app);
+}
+
+/// Recomputes which results are shown after the results or the filter change.
+fn refresh_visible(app: &mut App) {
+    let terms = filter_terms(&app.screen.filter);
+    let live_filter = app.live_filter;
+    let blocklist = &app.blocklist;
+    let (hide_watched, watched) = (app.hide_watched, &app.watched);
+    // The Shorts tab of a channel is shown as requested even when hiding Shorts.
+    let hide_shorts = app.hide_shorts
+        && !matches!(
+            app.screen.source,
+            Some(ResultsSource::Channel {
+                tab: ChannelTab::Shorts,
+                ..
+            })
+        );
+    app.screen.visible = app
+        .screen
+        .results
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| !is_blocked(blocklist, item))
+        .filter(|(_, item)| live_filter.allows(item))
+        .filter(|(_, item)| !hide_shorts || !is_short(item))
+        .filter(|(_, item)| match item {
+            SearchResultItem::Video(video) => !hide_watched || !watched.contains(&video.id),
+            SearchResultItem::Channel(_) | SearchResultItem::Playlist(_) => true,
+        })
+        .filter(|(_, item)| matches_filter(item, &terms))
+        .map(|(index, _)| index)
+        .collect();
+    app.sort.apply(&app.screen.results, &mut app.screen.visible);
+}
+
+/// Switches to the next built-in theme and saves it to the config. Color
+/// overrides from the config still apply.
+fn cycle_theme(app: &mut App) {
+    let current = app
+        .config
+        .theme
+        .name
+        .as_deref()
+        .unwrap_or(theme::BUILTIN[0]);
+    let next = theme::BUILTIN
+        .iter()
+        .position(|name| *name == current)
+        .map_or(0, |index| (index + 1) % theme::BUILTIN.len());
+    set_theme(app, theme::BUILTIN[next]);
+}
+
+/// Switches to the next layout preset and saves it to the config.
+fn cycle_layout(app: &mut App) {
+    let layout = app.config.layout.next();
+    app.config.layout = layout;
+    if app.screen.focus == Focus::Details && !details_shown(app) {
+        app.screen.focus = Focus::Results;
+    }
+    app.needs_full_redraw = true;
+    match save_config(app) {
+        Ok(()) => app
+            .toasts
+            .push(Level::Info, format!("Layout: {}.", layout.label())),
+        Err(err) => app
+            .toasts
+            .push(Level::Error, format!("Failed to save config: {err}")),
+    }
+}
+
+fn details_shown(app: &App) -> bool {
+    app.zen == ZenMode::Off
+        && PaneLayout::for_preset(app.config.layout).details != DetailsPlacement::Hidden
+}
+
+/// Reloads the config once its file has changed on disk.
+fn watch_config(app: &mut App) {
+    if app.config_checked.elapsed() < CONFIG_POLL {
+        return;
+    }
+    app.config_checked = Instant::now();
+    let modified = config::config_modified();
+    if modified == app.config_modified {
+        return;
+    }
+    app.config_modified = modified;
+    // ytbv's own saves change the file too, but not what it holds.
+    let unchanged = config::load_config().is_ok_and(|config| {
+        serde_json::to_value(&config).ok() == serde_json::to_value(&app.config).ok()
+    });
+    if !unchanged {
+        reload_config(app);
+    }
+}
+
+/// Reads the config file again and applies it: theme, keys, blocklist,
+/// locale and player settings. A broken file leaves the current settings in
+/// place.
+fn reload_config(app: &mut App) {
+    app.config_modified = config::config_modified();
+    let loaded = config::load_config().and_then(|config| {
+        let blocklist = config.blocklist_regexes()?;
+        let keys = Keymap::new(&config.keys)?;
+        let theme = Theme::from_config(&config.theme)?;
+        Ok((config, blocklist, keys, theme))
+    });
+    let (config, blocklist, keys, theme) = match loaded {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            app.toasts
+                .push(Level::Error, format!("Failed to reload config: {err}"));
+            return;
+        }
+    };
+    let locale_changed =
+        (config.language, config.country) != (app.config.language, app.config.country);
+    app.config = config;
+    app.config_broken = false;
+    app.blocklist = blocklist;
+    app.keys = keys;
+    app.theme = theme;
+    set_locale(&app.config);
+    let filter = app.screen.filter.clone();
+    set_filter(app, filter);
+    app.needs_full_redraw = true;
+    app.toasts.push(
+        Level::Info,
+        if locale_changed {
+            "Config reloaded. The new language and region apply to the next search.".to_string()
+        } else {
+            "Config reloaded.".to_string()
+        },
+    );
+}
+
+/// Saves `app.config`, unless the config file didn't load: then the user's
+/// file is kept as it is until it is fixed and reloaded.
+fn save_config(app: &App) -> Result<(), String> {
+    if app.config_broken {
+        return Err("the config file didn't load; fix it and run :reload".to_string());
+    }
+    config::save_config(&app.config)
+}
+
+/// Writes the config file on the first run, with the answers to the setup
+/// when `answered` and as defaults when it was skipped.
+fn finish_setup(app: &mut App, answered: bool) {
+    let Some(Popup::Setup(setup)) = app.popup.take() else {
+        return;
+    };
+    close_popup(app);
+    if answered {
+        setup.apply(&mut app.config);
+    }
+    app.theme = Theme::from_config(&app.config.theme).unwrap_or_default();
+    match save_config(app) {
+        Ok(()) => {
+            app.config_modified = config::config_modified();
+            app.toasts.push(
+                Level::Info,
+                if answered {
+                    "Setup saved to the config file.".to_string()
+                } else {
+                    "Setup skipped; the defaults were saved to the config file.".to_string()
+                },
+            );
+        }
+        Err(err) => app
+            .toasts
+            .push(Level::Error, format!("Failed to save config: {err}")),
+    }
+}
+
+/// Switches to the built-in theme `name` and saves it to the config.
+fn set_theme(app: &mut App, name: &str) {
+    app.config.theme.name = Some(name.to_string());
+    match Theme::from_config(&app.config.theme) {
+        Ok(theme) => app.theme = theme,
+        Err(err) => {
+            app.toasts.push(Level::Error, err);
+            return;
+        }
+    }
+    app.needs_full_redraw = true;
+    match save_config(app) {
+        Ok(()) => app.toasts.push(Level::Info, format!("Theme: {name}.")),
+        Err(err) => app
+            .toasts
+            .push(Level::Error, format!("Failed to save config: {err}")),
+    }
+}
+
+/// Keys of the `:` command line. Returns true when the command quits.
+fn handle_command_key(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Esc => app.command_editing = false,
+        KeyCode::Enter => {
+            app.command_editing = false;
+            let line = std::mem::take(&mut app.command);
+            match command::parse(&line) {
+                Ok(command) => return run_command(app, command),
+                Err(err) => app.toasts.push(Level::Error, err),
+            }
+        }
+        KeyCode::Tab => match command::complete(&app.command) {
+            Some(completed) => app.command = completed,
+            None => app
+                .toasts
+                .push(Level::Info, format!("No completion for '{}'.", app.command)),
+        },
+        KeyCode::Backspace if app.command.is_empty() => app.command_editing = false,
+        KeyCode::Backspace => {
+            app.command.pop();
+        }
+        KeyCode::Char(c) => app.command.push(c),
+        _ => {}
+    }
+    false
+}
+
+fn run_command(app: &mut App, command: Command) -> bool {
+    match command {
+        Command::Quit => return true,
+        Command::Search(query) => {
+            switch_view(app, View::Search);
+            app.screen.query = query.clone();
+            app.screen.cursor = app.screen.query.len();
+            start_search(app, query);
+        }
+        Command::Trending => {
+            switch_view(app, View::Search);
+            start_trending(app);
+        }
+        Command::Feed => {
+            if app.views.current == View::Feed {
+                start_feed(app);
+            } else {
+                switch_view(app, View::Feed);
+            }
+        }
+        Command::Download => download_selected(app),
+        Command::Queue { all: false } => enqueue_selected(app),
+        Command::Queue { all: true } => enqueue_all(app),
+        Command::Filter(filter) => {
+            set_filter(app, filter.clone());
+            if !filter.is_empty() {
+                app.last_filter = filter;
+            }
+            app.toasts.push(
+                Level::Info,
+                format!(
+                    "{} of {} results match.",
+                    app.screen.visible.len(),
+                    app.screen.results.len()
+                ),
+            );
+        }
+        Command::Sort(index) => {
+            app.sort = SortOrder::ALL[index];
+            let filter = app.screen.filter.clone();
+            set_filter(app, filter);
+            app.toasts
+                .push(Level::Info, format!("Sorted by: {}.", app.sort.label()));
+        }
+        Command::Theme(name) => set_theme(app, name),
+        Command::View(index) => switch_view(app, View::ALL[index]),
+        Command::Clear(index) => {
+            let (question, action) = match command::CLEAR_TARGETS[index] {
+                "history" => (
+                    "Clear the whole watch history?",
+                    ConfirmAction::ClearHistory,
+                ),
+                "searches" => ("Delete every cached search?", ConfirmAction::PurgeSearches),
+                _ => (
+                    "Delete every cached thumbnail?",
+                    ConfirmAction::PurgeThumbnails,
+                ),
+            };
+            ask_confirmation(app, question.to_string(), action);
+        }
+        Command::Import(path) => import_subscriptions(app, &path),
+        Command::Export { csv, dir } => export_data(app, csv, &dir),
+        Command::Cache => show_cache_stats(app),
+        Command::ReloadConfig => reload_config(app),
+        Command::Logs => match logging::tail(LOG_LINES) {
+            Ok(lines) if lines.is_empty() => app
+                .toasts
+                .push(Level::Info, "The log is empty.".to_string()),
+            Ok(lines) => {
+                app.popup = Some(Popup::Logs(LogsView { lines, scroll: 0 }));
+                app.needs_full_redraw = true;
+            }
+            Err(err) => app.toasts.push(Level::Error, err),
+        },
+        Command::Login(cookies) => start_login(app, cookies),
+        Command::Logout => {
+            app.toasts.push(Level::Info, "Logging out...".to_string());
+            let tx = app.tx.clone();
+            thread::spawn(move || {
+                let _ = tx.send(AppMsg::Account(youtube_logout()));
+            });
+        }
+    }
+    false
+}
+
+/// Queues every listed video, starting the first one if nothing plays.
+fn enqueue_all(app: &mut App) {
+    let items: Vec<QueueItem> = app
+        .screen
+        .visible
+        .iter()
+        .filter_map(|&index| match &app.screen.results[index] {
+            SearchResultItem::Video(video) => Some(QueueItem {
+                id: video.id.clone(),
+                title: video.title.clone(),
+                url: video.url.clone(),
+            }),
+            _ => None,
+        })
+        .collect();
+    let count = items.len();
+    let mut items = items.into_iter();
+    if app.player.is_none() {
+        let Some(first) = items.next() else {
+            app.toasts
+                .push(Level::Info, "No videos to queue.".to_string());
+            return;
+        };
+        play_queue_item(app, first);
+    }
+    for item in items {
+        if app.handoff.is_some() {
+            append_to_handoff(app, item.clone());
+        }
+        app.queue.push_back(item);
+    }
+    prefetch_next(app);
+    app.toasts.push(
+        Level::Info,
+        format!("Queued {count} videos ({} in queue).", app.queue.len()),
+    );
+}
+
+/// Marks or unmarks the selected video for a batch action and moves on to
+/// the next row.
+fn toggle_mark(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        app.toasts
+            .push(Level::Info, "Select a video to mark it.".to_string());
+        return;
+    };
+    match app.marked.iter().position(|item| item.id == video.id) {
+        Some(position) => {
+            app.marked.remove(position);
+        }
+        None => app.marked.push(QueueItem {
+            id: video.id.clone(),
+            title: video.title.clone(),
+            url: video.url.clone(),
+        }),
+    }
+    app.toasts
+        .push(Level::Info, format!("{} marked.", app.marked.len()));
+    if app.screen.selected_row + 1 < app.screen.visible.len() {
+        move_selection(app, 1);
+    }
+}
+
+/// Queues the marked videos, starting the first one if nothing plays.
+fn enqueue_marked(app: &mut App) {
+    let marked = std::mem::take(&mut app.marked);
+    enqueue_items(app, marked);
+}
+
+/// Queues `items` in order, starting the first when nothing is playing.
+fn enqueue_items(app: &mut App, items: Vec<QueueItem>) {
+    let count = items.len();
+    let mut items = items.into_iter();
+    if app.player.is_none() {
+        if let Some(first) = items.next() {
+            play_queue_item(app, first);
+        }
+    }
+    for item in items {
+        if app.handoff.is_some() {
+            append_to_handoff(app, item.clone());
+        }
+        app.queue.push_back(item);
+    }
+    prefetch_next(app);
+    app.toasts.push(
+        Level::Info,
+        format!("Queued {count} videos ({} in queue).", app.queue.len()),
+    );
+}
+
+/// Downloads the marked videos and opens the downloads list.
+fn download_marked(app: &mut App) {
+    for item in std::mem::take(&mut app.marked) {
+        app.views.downloads.selected = add_download(app, item);
+    }
+    switch_view(app, View::Downloads);
+}
+
+fn yank_marked_urls(app: &mut App) {
+    let urls: Vec<String> = std::mem::take(&mut app.marked)
+        .into_iter()
+        .map(|item| item.url)
+        .collect();
+    copy_to_clipboard(app, &urls.join("\n"), &format!("{} URLs", urls.len()));
+}
+
+fn filter_terms(filter: &str) -> Vec<String> {
+    filter.split_whitespace().map(str::to_lowercase).collect()
+}
+
+fn matches_filter(item: &SearchResultItem, terms: &[String]) -> bool {
+    let haystack = match item {
+        SearchResultItem::Video(video) => {
+            format!("{} {}", video.title, video.channel.as_deref().unwrap_or(""))
+        }
+        SearchResultItem::Channel(channel) => channel.name.clone(),
+        SearchResultItem::Playlist(playlist) => format!(
+            "{} {}",
+            playlist.name,
+            playlist.channel.as_deref().unwrap_or("")
+        ),
+    }
+    .to_lowercase();
+    terms.iter().all(|term| match FilterCondition::parse(term) {
+        Some(condition) => condition.matches(item),
+        None => fuzzy_matches(&haystack, term),
+    })
+}
+
+/// A filter term comparing a number instead of matching text, like
+/// `duration>10m` or `views<1k`. Only videos can match one.
+struct FilterCondition {
+    views: bool,
+    greater: bool,
+    value: u64,
+}
+
+impl FilterCondition {
+    fn parse(term: &str) -> Option<FilterCondition> {
+        let (views, rest) = match term.strip_prefix("duration") {
+            Some(rest) => (false, rest),
+            None => (true, term.strip_prefix("views")?),
+        };
+        let greater = match rest.chars().next()? {
+            '>' => true,
+            '<' => false,
+            _ => return None,
+        };
+        let value = &rest[1..];
+        // Lengths are in minutes unless they end in s or h; counts take a
+        // k, m or b suffix.
+        let (number, scale) = match (views, value.chars().last()?) {
+            (false, 's') => (&value[..value.len() - 1], 1.0),
+            (false, 'm') => (&value[..value.len() - 1], 60.0),
+            (false, 'h') => (&value[..value.len() - 1], 3600.0),
+            (false, _) => (value, 60.0),
+            (true, 'k') => (&value[..value.len() - 1], 1e3),
+            (true, 'm') => (&value[..value.len() - 1], 1e6),
+            (true, 'b') => (&value[..value.len() - 1], 1e9),
+            (true, _) => (value, 1.0),
+        };
+        let number: f64 = number.parse().ok()?;
+        Some(FilterCondition {
+            views,
+            greater,
+            value: (number * scale) as u64,
+        })
+    }
+
+    fn matches(&self, item: &SearchResultItem) -> bool {
+        let SearchResultItem::Video(video) = item else {
+            return false;
+        };
+        let actual = if self.views {
+            video.view_count
+        } else {
+            video.duration
+        };
+        actual.is_some_and(|actual| {
+            if self.greater {
+                actual > self.value
+            } else {
+                actual < self.value
+            }
+        })
+    }
+}
+
+/// Selects the next (or previous) listed result matching the last filter,
+/// wrapping around the list.
+fn repeat_filter(app: &mut App, forward: bool) {
+    let terms = filter_terms(&app.last_filter);
+    if terms.is_empty() {
+        app.toasts
+            .push(Level::Info, "No filter to repeat.".to_string());
+        return;
+    }
+    let len = app.screen.visible.len();
+    let found = (1..=len)
+        .map(|step| {
+            if forward {
+                (app.screen.selected_row + step) % len
+            } else {
+                (app.screen.selected_row + len - step) % len
+            }
+        })
+        .find(|&row| matches_filter(&app.screen.results[app.screen.visible[row]], &terms));
+    match found {
+        Some(row) => {
+            app.screen.selected_row = row;
+            sync_selected_result(app);
+        }
+        None => app.toasts.push(
+            Level::Info,
+            format!("No results match \"{}\".", app.last_filter),
+        ),
+    }
+}
+
+fn is_blocked(blocklist: &[regex::Regex], item: &SearchResultItem) -> bool {
+    let title = match item {
+        SearchResultItem::Video(video) => &video.title,
+        SearchResultItem::Channel(channel) => &channel.name,
+        SearchResultItem::Playlist(playlist) => &playlist.name,
+    };
+    blocklist.iter().any(|regex| regex.is_match(title))
+}
+
+fn is_short(item: &SearchResultItem) -> bool {
+    match item {
+        SearchResultItem::Video(video) => {
+            video.is_short || video.duration.is_some_and(|secs| secs <= 60)
+        }
+        SearchResultItem::Channel(_) | SearchResultItem::Playlist(_) => false,
+    }
+}
+
+/// True if all chars of `needle` appear in `haystack` in order.
+fn fuzzy_matches(haystack: &str, needle: &str) -> bool {
+    let mut chars = haystack.chars();
+    needle.chars().all(|c| chars.any(|h| h == c))
+}
+
+fn handle_popup_key(app: &mut App, key: KeyCode) {
+    let Some(popup) = app.popup.as_mut() else {
+        return;
+    };
+    match popup {
+        Popup::Locale(_) => handle_locale_key(app, key),
+        Popup::Quality(view) => match key {
+            KeyCode::Esc | KeyCode::Char('q') => close_popup(app),
+            KeyCode::Up | KeyCode::Char('k') => view.selected = view.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                view.selected = (view.selected + 1).min(view.options.len().saturating_sub(1));
+            }
+            KeyCode::Enter => {
+                if let Some(quality) = view.options.get(view.selected).cloned() {
+                    let (id, title, url) = (
+                        view.video_id.clone(),
+                        view.video_title.clone(),
+                        view.url.clone(),
+                    );
+                    close_popup(app);
+                    match spawn_player(app, &url, None, quality.mpv_args()) {
+                        Ok(()) => {
+                            record_watch(app, &id, &title);
+                            app.toasts.push(
+                                Level::Info,
+                                format!("Playing: {title} ({})", quality.label()),
+                            );
+                        }
+                        Err(err) => app.toasts.push(Level::Error, err),
+                    }
+                }
+            }
+            _ => {}
+        },
+        Popup::Confirm(_) => match key {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                if let Some(Popup::Confirm(view)) = app.popup.take() {
+                    close_popup(app);
+                    run_confirmed(app, view.action);
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('q') => close_popup(app),
+            _ => {}
+        },
+        Popup::Error(view) => match key {
+            KeyCode::Char('r') | KeyCode::Enter => {
+                if let Some(Popup::Error(view)) = app.popup.take() {
+                    close_popup(app);
+                    retry(app, view.retry);
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => close_popup(app),
+            KeyCode::Up | KeyCode::Char('k') => view.scroll = view.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => view.scroll = view.scroll.saturating_add(1),
+            _ => {}
+        },
+        Popup::Login(LoginView {
+            passphrase: Some(passphrase),
+            ..
+        }) => match key {
+            KeyCode::Esc => close_popup(app),
+            KeyCode::Backspace => {
+                passphrase.pop();
+            }
+            KeyCode::Char(c) => passphrase.push(c),
+            KeyCode::Enter if passphrase.is_empty() => {}
+            KeyCode::Enter => match credentials::unlock(passphrase, &rustypipe_cache_file()) {
+                Ok(()) => {
+                    if let Some(Popup::Login(view)) = app.popup.take() {
+                        close_popup(app);
+                        begin_login(app, view.cookies);
+                    }
+                }
+                Err(err) => {
+                    passphrase.clear();
+                    app.toasts.push(Level::Error, err);
+                }
+            },
+            _ => {}
+        },
+        Popup::Login(view) => match key {
+            KeyCode::Esc | KeyCode::Char('q') => close_popup(app),
+            KeyCode::Char('o') => {
+                if let Some((_, url)) = &view.code {
+                    match browser::open(url) {
+                        Ok(_) => app.toasts.push(Level::Info, format!("Opened {url}.")),
+                        Err(err) => app.toasts.push(Level::Error, err),
+                    }
+                }
+            }
+            _ => {}
+        },
+        Popup::CacheStats(_) => match key {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => close_popup(app),
+            KeyCode::Char('c') => ask_confirmation(
+                app,
+                "Delete every cached thumbnail?".to_string(),
+                ConfirmAction::PurgeThumbnails,
+            ),
+            _ => {}
+        },
+        Popup::Setup(setup) => {
+            let step = &mut setup.steps[setup.step];
+            match key {
+                KeyCode::Esc => finish_setup(app, false),
+                KeyCode::Up | KeyCode::Char('k') => step.selected = step.selected.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    step.selected = (step.selected + 1).min(step.choices.len() - 1);
+                }
+                KeyCode::Left | KeyCode::Backspace | KeyCode::Char('h') => {
+                    setup.step = setup.step.saturating_sub(1);
+                }
+                KeyCode::Enter if setup.step + 1 == setup.steps.len() => finish_setup(app, true),
+                KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+                    setup.step = (setup.step + 1).min(setup.steps.len() - 1);
+                }
+                _ => {}
+            }
+            if let Some(Popup::Setup(setup)) = app.popup.as_ref() {
+                if setup.step == setup::THEME {
+                    if let Some(theme) = Theme::builtin(setup.theme()) {
+                        app.theme = theme;
+                        app.needs_full_redraw = true;
+                    }
+                }
+            }
+        }
+        Popup::Logs(view) => match key {
+            KeyCode::Esc | KeyCode::Char('q') => close_popup(app),
+            KeyCode::Up | KeyCode::Char('k') => {
+                view.scroll = (view.scroll + 1).min(view.lines.len().saturating_sub(1));
+            }
+            KeyCode::Down | KeyCode::Char('j') => view.scroll = view.scroll.saturating_sub(1),
+            KeyCode::PageUp => {
+                view.scroll = (view.scroll + 10).min(view.lines.len().saturating_sub(1));
+            }
+            KeyCode::PageDown => view.scroll = view.scroll.saturating_sub(10),
+            KeyCode::Char('g') => view.scroll = view.lines.len().saturating_sub(1),
+            KeyCode::Char('G') => view.scroll = 0,
+            _ => {}
+        },
+        Popup::PlayerLog(scroll) => match key {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('E') => close_popup(app),
+            KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => *scroll = scroll.saturating_add(1),
+            _ => {}
+        },
+        Popup::Clip(view) => match key {
+            KeyCode::Esc => close_popup(app),
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+                view.editing_end = !view.editing_end;
+            }
+            KeyCode::Backspace => {
+                let field = if view.editing_end {
+                    &mut view.end
+                } else {
+                    &mut view.start
+                };
+                field.pop();
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() || c == ':' => {
+                let field = if view.editing_end {
+                    &mut view.end
+                } else {
+                    &mut view.start
+                };
+                field.push(c);
+            }
+            KeyCode::Enter => play_clip(app),
+            _ => {}
+        },
+        Popup::AddToPlaylist(view) if view.new_name.is_some() => match key {
+            KeyCode::Esc => close_popup(app),
+            KeyCode::Backspace => {
+                if let Some(name) = view.new_name.as_mut() {
+                    name.pop();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(name) = view.new_name.as_mut() {
+                    name.push(c);
+                }
+            }
+            KeyCode::Enter => {
+                let name = view
+                    .new_name
+                    .as_deref()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+                if name.is_empty() {
+                    return;
+                }
+                if let Some(Popup::AddToPlaylist(view)) = app.popup.take() {
+                    close_popup(app);
+                    let index = match app.playlists.iter().position(|p| p.name == name) {
+                        Some(index) => index,
+                        None => {
+                            app.playlists.push(LocalPlaylist {
+                                name,
+                                videos: Vec::new(),
+                            });
+                            app.playlists.len() - 1
+                        }
+                    };
+                    add_to_playlist_at(app, index, view.items);
+                }
+            }
+            _ => {}
+        },
+        Popup::AddToPlaylist(view) => match key {
+            KeyCode::Esc | KeyCode::Char('q') => close_popup(app),
+            KeyCode::Up | KeyCode::Char('k') => view.selected = view.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                view.selected = (view.selected + 1).min(app.playlists.len());
+            }
+            KeyCode::Char('n') => view.new_name = Some(String::new()),
+            KeyCode::Enter if view.selected == app.playlists.len() => {
+                view.new_name = Some(String::new());
+            }
+            KeyCode::Enter => {
+                let index = view.selected;
+                if let Some(Popup::AddToPlaylist(view)) = app.popup.take() {
+                    close_popup(app);
+                    add_to_playlist_at(app, index, view.items);
+                }
+            }
+            _ => {}
+        },
+        Popup::Tags(view) => match key {
+            KeyCode::Esc => close_popup(app),
+            KeyCode::Backspace => {
+                view.input.pop();
+            }
+            KeyCode::Char(c) => view.input.push(c),
+            KeyCode::Enter => {
+                let tags: Vec<String> = view
+                    .input
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                let video_id = view.video_id.clone();
+                close_popup(app);
+                if let Some(bookmark) = app.bookmarks.iter_mut().find(|b| b.id == video_id) {
+                    bookmark.tags = tags;
+                    let status = format!("Tagged {}.", bookmark.title);
+                    save_bookmarks(app, status);
+                }
+            }
+            _ => {}
+        },
+        Popup::Jump(view) => match key {
+            KeyCode::Esc => close_popup(app),
+            KeyCode::Backspace => {
+                view.input.pop();
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() || c == ':' => view.input.push(c),
+            KeyCode::Enter => match parse_timestamp(&view.input) {
+                Some(start) => {
+                    let (id, title) = (view.video_id.clone(), view.title.clone());
+                    close_popup(app);
+                    jump_to(app, &id, &title, start);
+                }
+                None => view.error = Some("Enter a time like hh:mm:ss.".to_string()),
+            },
+            _ => {}
+        },
+        Popup::Cast(view) => match key {
+            KeyCode::Esc | KeyCode::Char('q') => close_popup(app),
+            KeyCode::Up | KeyCode::Char('k') => view.selected = view.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                view.selected = (view.selected + 1).min(view.renderers.len().saturating_sub(1));
+            }
+            KeyCode::Char('r') if !view.loading => {
+                view.renderers.clear();
+                view.error = None;
+                view.loading = true;
+                discover_renderers(app);
+            }
+            KeyCode::Enter => {
+                if let Some(renderer) = view.renderers.get(view.selected).cloned() {
+                    let (video_id, title) = (view.video_id.clone(), view.video_title.clone());
+                    app.toasts.push(
+                        Level::Info,
+                        format!("Sending {title} to {}...", renderer.name),
+                    );
+                    app.cast = Some(renderer.clone());
+                    app.cast_paused = false;
+                    let tx = app.tx.clone();
+                    thread::spawn(move || {
+                        let result = cast_stream(&video_id).and_then(|url| {
+                            renderer.play_url(&url, &title)?;
+                            Ok(format!("Casting {title} to {}.", renderer.name))
+                        });
+                        let _ = tx.send(AppMsg::CastStatus(result));
+                    });
+                }
+            }
+            KeyCode::Char(' ') => {
+                if let Some(renderer) = app.cast.clone() {
+                    let pause = !app.cast_paused;
+                    app.cast_paused = pause;
+                    send_cast_command(app, move || {
+                        if pause {
+                            renderer.pause().map(|()| "Cast paused.".to_string())
+                        } else {
+                            renderer.resume().map(|()| "Cast resumed.".to_string())
+                        }
+                    });
+                }
+            }
+            KeyCode::Char('x') => {
+                if let Some(renderer) = app.cast.take() {
+                    send_cast_command(app, move || {
+                        renderer.stop().map(|()| "Cast stopped.".to_string())
+                    });
+                }
+            }
+            _ => {}
+        },
+        Popup::Subtitles(view) => match key {
+            KeyCode::Esc | KeyCode::Char('q') => close_popup(app),
+            KeyCode::Up | KeyCode::Char('k') => view.selected = view.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                view.selected = (view.selected + 1).min(view.tracks.len());
+            }
+            KeyCode::Enter if !view.loading => {
+                let (args, label) = match view.selected.checked_sub(1) {
+                    Some(index) => {
+                        let track = &view.tracks[index];
+                        (
+                            vec![format!("--sub-file={}&fmt=vtt", track.url)],
+                            caption_track_label(track),
+                        )
+                    }
+                    None => (vec!["--sid=no".to_string()], "no subtitles".to_string()),
+                };
+                let mut mpv_args = vec![app.config.ytdl_format()];
+                mpv_args.extend(args);
+                let (id, title, url) = (
+                    view.video_id.clone(),
+                    view.video_title.clone(),
+                    view.url.clone(),
+                );
+                close_popup(app);
+                match spawn_player(app, &url, None, mpv_args) {
+                    Ok(()) => {
+                        record_watch(app, &id, &title);
+                        app.toasts
+                            .push(Level::Info, format!("Playing: {title} ({label})"));
+                    }
+                    Err(err) => app.toasts.push(Level::Error, err),
+                }
+            }
+            _ => {}
+        },
+        Popup::Resume(view) => match key {
+            KeyCode::Esc | KeyCode::Char('q') => close_popup(app),
+            KeyCode::Up | KeyCode::Char('k') => view.selected = 0,
+            KeyCode::Down | KeyCode::Char('j') => view.selected = 1,
+            KeyCode::Enter => {
+                let start = (view.selected == 0).then_some(view.position as u32);
+                let (id, title, url) =
+                    (view.video_id.clone(), view.title.clone(), view.url.clone());
+                close_popup(app);
+                match play_url(app, &url, start) {
+                    Ok(()) => {
+                        record_watch(app, &id, &title);
+                        app.toasts.push(Level::Info, format!("Playing: {title}"));
+                    }
+                    Err(err) => app.toasts.push(Level::Error, err),
+                }
+            }
+            _ => {}
+        },
+        Popup::PlaybackOptions(selected) => match key {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('O') => close_popup(app),
+            KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                *selected = (*selected + 1).min(PlaybackOption::ALL.len() - 1);
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                let option = PlaybackOption::ALL[*selected];
+                cycle_playback_option(app, option, false);
+            }
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter | KeyCode::Char(' ') => {
+                let option = PlaybackOption::ALL[*selected];
+                cycle_playback_option(app, option, true);
+            }
+            _ => {}
+        },
+        Popup::Sort(selected) => match key {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('e') => close_popup(app),
+            KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                *selected = (*selected + 1).min(SortOrder::ALL.len() - 1);
+            }
+            KeyCode::Enter => {
+                app.sort = SortOrder::ALL[*selected];
+                close_popup(app);
+                let filter = app.screen.filter.clone();
+                set_filter(app, filter);
+                app.toasts
+                    .push(Level::Info, format!("Sorted by: {}.", app.sort.label()));
+            }
+            _ => {}
+        },
+        Popup::Comments(view) => {
+            let page = view.view_height.max(1);
+            match key {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('C') => close_popup(app),
+                KeyCode::Up | KeyCode::Char('k') => {
+                    view.scroll = view.scroll.saturating_sub(1);
+                }
+                KeyCode::PageUp => view.scroll = view.scroll.saturating_sub(page),
+                KeyCode::Home => view.scroll = 0,
+                // Scrolling past the last comment fetches the next batch.
+                KeyCode::Down | KeyCode::Char('j') | KeyCode::PageDown | KeyCode::End
+                    if view.scroll >= view.max_scroll =>
+                {
+                    load_more_comments(app);
+                }
+                KeyCode::Down | KeyCode::Char('j') => view.scroll += 1,
+                KeyCode::PageDown => view.scroll += page,
+                KeyCode::End => view.scroll = view.max_scroll,
+                KeyCode::Tab | KeyCode::BackTab => {
+                    let count = view
+                        .comments
+                        .iter()
+                        .flat_map(|comment| &comment.text)
+                        .filter(|(_, start)| start.is_some())
+                        .count();
+                    if count > 0 {
+                        view.selected_timestamp = Some(match (key, view.selected_timestamp) {
+                            (KeyCode::Tab, Some(i)) => (i + 1) % count,
+                            (KeyCode::Tab, None) => 0,
+                            (_, Some(i)) => (i + count - 1) % count,
+                            (_, None) => count - 1,
+                        });
+                        view.follow_selection = true;
+                    }
+                }
+                KeyCode::Enter if view.selected_timestamp.is_some() => {
+                    let start = view
+                        .comments
+                        .iter()
+                        .flat_map(|comment| &comment.text)
+                        .filter_map(|(_, start)| *start)
+                        .nth(view.selected_timestamp.unwrap_or_default());
+                    if let Some(start) = start {
+                        let (id, title) = (view.video_id.clone(), view.video_title.clone());
+                        close_popup(app);
+                        play_video_at(app, &id, &title, start);
+                    }
+                }
+                KeyCode::Enter => load_more_comments(app),
+                _ => {}
+            }
+        }
+        Popup::Description(view) => {
+            let page = view.view_height.max(1);
+            match key {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('d') => close_popup(app),
+                KeyCode::Up | KeyCode::Char('k') => {
+                    view.scroll = view.scroll.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    view.scroll = (view.scroll + 1).min(view.max_scroll);
+                }
+                KeyCode::PageUp => view.scroll = view.scroll.saturating_sub(page),
+                KeyCode::PageDown => view.scroll = (view.scroll + page).min(view.max_scroll),
+                KeyCode::Home => view.scroll = 0,
+                KeyCode::End => view.scroll = view.max_scroll,
+                KeyCode::Tab if !view.links.is_empty() => {
+                    let next = view.selected_link.map_or(0, |i| (i + 1) % view.links.len());
+                    view.selected_link = Some(next);
+                }
+                KeyCode::BackTab if !view.links.is_empty() => {
+                    let len = view.links.len();
+                    let prev = view.selected_link.map_or(len - 1, |i| (i + len - 1) % len);
+                    view.selected_link = Some(prev);
+                }
+                KeyCode::Enter => open_description_link(app),
+                _ => {}
+            }
+        }
+        Popup::Transcript(view) => {
+            let page = view.view_height.max(1);
+            if view.track.is_none() {
+                match key {
+                    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('T') => close_popup(app),
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        view.picker_selected = view.picker_selected.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        view.picker_selected =
+                            (view.picker_selected + 1).min(view.tracks.len().saturating_sub(1));
+                    }
+                    KeyCode::Enter if !view.tracks.is_empty() => {
+                        let index = view.picker_selected;
+                        load_transcript_track(app, index);
+                    }
+                    _ => {}
+                }
+                return;
+            }
+            let last = view.cues.len().saturating_sub(1);
+            match key {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('T') => close_popup(app),
+                KeyCode::Char('l') if view.tracks.len() > 1 => {
+                    view.track = None;
+                    view.error = None;
+                    view.loading = false;
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    view.cue_selected = view.cue_selected.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    view.cue_selected = (view.cue_selected + 1).min(last);
+                }
+                KeyCode::PageUp => view.cue_selected = view.cue_selected.saturating_sub(page),
+                KeyCode::PageDown => view.cue_selected = (view.cue_selected + page).min(last),
+                KeyCode::Home => view.cue_selected = 0,
+                KeyCode::End => view.cue_selected = last,
+                KeyCode::Enter => {
+                    if let Some(cue) = view.cues.get(view.cue_selected) {
+                        let url = format!("https://www.youtube.com/watch?v={}", view.video_id);
+                        let start = (cue.start_ms / 1000) as u32;
+                        let video_id = view.video_id.clone();
+                        let title = view.video_title.clone();
+                        close_popup(app);
+                        match play_url(app, &url, Some(start)) {
+                            Ok(()) => {
+                                record_watch(app, &video_id, &title);
+                                app.toasts.push(
+                                    Level::Info,
+                                    format!(
+                                        "Playing: {title} from {}",
+                                        format_duration(u64::from(start))
+                                    ),
+                                );
+                            }
+                            Err(err) => app.toasts.push(Level::Error, err),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn handle_locale_key(app: &mut App, key: KeyCode) {
+    let Some(Popup::Locale(view)) = app.popup.as_mut() else {
+        return;
+    };
+    let (selected, len) = if view.country_focus {
+        (&mut view.country, COUNTRIES.len())
+    } else {
+        (&mut view.language, LANGUAGES.len())
+    };
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('R') => close_popup(app),
+        KeyCode::Tab | KeyCode::BackTab | KeyCode::Left | KeyCode::Right => {
+            view.country_focus = !view.country_focus;
+        }
+        KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(len - 1),
+        KeyCode::PageUp => *selected = selected.saturating_sub(10),
+        KeyCode::PageDown => *selected = (*selected + 10).min(len - 1),
+        KeyCode::Enter => {
+            let language = LANGUAGES[view.language];
+            let country = COUNTRIES[view.country];
+            close_popup(app);
+            app.config.language = Some(language);
+            app.config.country = Some(country);
+            set_locale(&app.config);
+            let saved = save_config(app);
+            match &app.screen.source {
+                Some(ResultsSource::Search(query)) => {
+                    let query = query.clone();
+                    start_search(app, query);
+                }
+                Some(ResultsSource::Trending(category)) => {
+                    let category = *category;
+                    start_trending_category(app, category);
+                }
+                _ => {}
+            }
+            match saved {
+                Ok(()) => app.toasts.push(
+                    Level::Info,
+                    format!("Locale set to {} / {}.", language.name(), country.name()),
+                ),
+                Err(err) => app
+                    .toasts
+                    .push(Level::Error, format!("Failed to save config: {err}")),
+            }
+        }
+        _ => {}
+    }
+}
+
+fn open_locale_picker(app: &mut App) {
+    let (language, country) = current_locale();
+    app.popup = Some(Popup::Locale(LocaleView {
+        country_focus: false,
+        language: LANGUAGES.iter().position(|l| *l == language).unwrap_or(0),
+        country: COUNTRIES.iter().position(|c| *c == country).unwrap_or(0),
+    }));
+    app.needs_full_redraw = true;
+}
+
+fn set_locale(config: &Config) {
+    let mut locale = LOCALE.write().unwrap_or_else(|err| err.into_inner());
+    *locale = (
+        config.language.unwrap_or(Language::En),
+        config.country.unwrap_or(Country::Us),
+    );
+}
+
+fn current_locale() -> (Language, Country) {
+    *LOCALE.read().unwrap_or_else(|err| err.into_inner())
+}
+
+fn open_transcript(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        app.toasts.push(
+            Level::Info,
+            "Select a video to show its transcript.".to_string(),
+        );
+        return;
+    };
+    let video_id = video.id.clone();
+    app.popup = Some(Popup::Transcript(TranscriptView {
+        video_id: video_id.clone(),
+        video_title: video.title.clone(),
+        tracks: Vec::new(),
+        track: None,
+        picker_selected: 0,
+        cues: Vec::new(),
+        cue_selected: 0,
+        loading: true,
+        error: None,
+        view_height: 0,
+    }));
+    app.needs_full_redraw = true;
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = caption_tracks(&video_id);
+        let _ = tx.send(AppMsg::CaptionTracks { video_id, result });
+    });
+}
+
+/// Copies the YouTube URL of the selected video or playlist.
+fn yank_selected_url(app: &mut App) {
+    let url = match app.screen.results.get(app.screen.selected) {
+        Some(SearchResultItem::Video(video)) => video.url.clone(),
+        Some(SearchResultItem::Playlist(playlist)) => playlist_url(&playlist.id),
+        _ => {
+            app.toasts.push(
+                Level::Info,
+                "Select a video or playlist to copy its URL.".to_string(),
+            );
+            return;
+        }
+    };
+    copy_to_clipboard(app, &url, "URL");
+}
+
+/// Opens the selected video, channel or playlist on YouTube in the browser.
+fn open_selected_in_browser(app: &mut App) {
+    let url = match app.screen.results.get(app.screen.selected) {
+        Some(SearchResultItem::Video(video)) => video.url.clone(),
+        Some(SearchResultItem::Channel(channel)) => channel_url(&channel.id),
+        Some(SearchResultItem::Playlist(playlist)) => playlist_url(&playlist.id),
+        None => match &app.screen.channel_header {
+            Some(header) => channel_url(&header.id),
+            None => {
+                app.toasts.push(
+                    Level::Info,
+                    "Select something to open it in the browser.".to_string(),
+                );
+                return;
+            }
+        },
+    };
+    match browser::open(&url) {
+        Ok(program) => app
+            .toasts
+            .push(Level::Info, format!("Opened {url} ({program}).")),
+        Err(err) => app.toasts.push(Level::Error, err),
+    }
+}
+
+/// Resolves a directly playable stream URL for the selected video and
+/// copies it once it arrives.
+fn yank_stream_url(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        app.toasts.push(
+            Level::Info,
+            "Select a video to copy its stream URL.".to_string(),
+        );
+        return;
+    };
+    let video_id = video.id.clone();
+    app.toasts
+        .push(Level::Info, "Resolving stream URL...".to_string());
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let _ = tx.send(AppMsg::StreamUrl(cast_stream(&video_id)));
+    });
+}
+
+/// Copies `text` and reports how it went.
+fn copy_to_clipboard(app: &mut App, text: &str, what: &str) {
+    match clipboard::copy(text) {
+        Ok(method) => app
+            .toasts
+            .push(Level::Info, format!("Copied {what} ({method}).")),
+        Err(err) => app.toasts.push(Level::Error, err),
+    }
+}
+
+fn open_cast_picker(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        app.toasts
+            .push(Level::Info, "Select a video to cast it.".to_string());
+        return;
+    };
+    app.popup = Some(Popup::Cast(CastView {
+        video_id: video.id.clone(),
+        video_title: video.title.clone(),
+        renderers: Vec::new(),
+        selected: 0,
+        loading: true,
+        error: None,
+    }));
+    app.needs_full_redraw = true;
+    discover_renderers(app);
+}
+
+fn discover_renderers(app: &App) {
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = cast::discover(Duration::from_secs(3));
+        let _ = tx.send(AppMsg::CastDevices(result));
+    });
+}
+
+/// Runs a renderer command off the UI thread and reports how it went.
+fn send_cast_command<F>(app: &App, command: F)
+where
+    F: FnOnce() -> Result<String, String> + Send + 'static,
+{
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let _ = tx.send(AppMsg::CastStatus(command()));
+    });
+}
+
+/// Opens the subtitle picker for the selected video; Enter plays it with
+/// the chosen caption track.
+fn open_subtitle_picker(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        app.toasts.push(
+            Level::Info,
+            "Select a video to pick its subtitles.".to_string(),
+        );
+        return;
+    };
+    let video_id = video.id.clone();
+    app.popup = Some(Popup::Subtitles(SubtitleView {
+        video_id: video_id.clone(),
+        video_title: video.title.clone(),
+        url: video.url.clone(),
+        tracks: Vec::new(),
+        selected: 0,
+        loading: true,
+        error: None,
+    }));
+    app.needs_full_redraw = true;
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = caption_tracks(&video_id);
+        let _ = tx.send(AppMsg::CaptionTracks { video_id, result });
+    });
+}
+
+/// Index of the track matching the configured subtitle language (`"de"`
+/// matches `de` and `de-DE`), preferring manual captions.
+fn default_subtitle_track(tracks: &[CaptionTrack], language: Option<&str>) -> Option<usize> {
+    let language = language?;
+    let matches = |track: &CaptionTrack| {
+        track.lang == language
+            || track
+                .lang
+                .split('-')
+                .next()
+                .is_some_and(|base| base == language)
+    };
+    tracks
+        .iter()
+        .position(|track| matches(track) && !track.auto_generated)
+        .or_else(|| tracks.iter().position(matches))
+}
+
+fn load_transcript_track(app: &mut App, index: usize) {
+    let Some(Popup::Transcript(view)) = app.popup.as_mut() else {
+        return;
+    };
+    let Some(track) = view.tracks.get(index).cloned() else {
+        return;
+    };
+    view.track = Some(index);
+    view.cues.clear();
+    view.loading = true;
+    view.error = None;
+    let video_id = view.video_id.clone();
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = download_captions(&track.url);
+        let _ = tx.send(AppMsg::Transcript {
+            video_id,
+            lang: track.lang,
+            result,
+        });
+    });
+}
+
+fn open_description(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        app.toasts.push(
+            Level::Info,
+            "Select a video to show its description.".to_string(),
+        );
+        return;
+    };
+    let video_id = video.id.clone();
+    app.popup = Some(Popup::Description(DescriptionView {
+        video_id: video_id.clone(),
+        video_title: video.title.clone(),
+        segments: Vec::new(),
+        links: Vec::new(),
+        selected_link: None,
+        loading: true,
+        error: None,
+        scroll: 0,
+        max_scroll: 0,
+        view_height: 0,
+    }));
+    app.needs_full_redraw = true;
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = video_description(&video_id);
+        let _ = tx.send(AppMsg::Description { video_id, result });
+    });
+}
+
+fn open_description_link(app: &mut App) {
+    let Some(Popup::Description(view)) = app.popup.as_ref() else {
+        return;
+    };
+    let Some(link) = view.selected_link.and_then(|i| view.links.get(i)) else {
+        return;
+    };
+    match &link.target {
+        LinkTarget::Timestamp(start) => {
+            let video_id = view.video_id.clone();
+            let title = view.video_title.clone();
+            let start = *start;
+            close_popup(app);
+            play_video_at(app, &video_id, &title, start);
+        }
+        LinkTarget::YouTube(target) => {
+            let target = target.clone();
+            close_popup(app);
+            open_url_target(app, target);
+        }
+        LinkTarget::Web(url) => {
+            app.toasts.push(Level::Info, format!("Link: {url}"));
+        }
+    }
+}
+
+fn play_video_at(app: &mut App, video_id: &str, title: &str, start: u32) {
+    let url = format!("https://www.youtube.com/watch?v={video_id}");
+    match play_url(app, &url, Some(start)) {
+        Ok(()) => {
+            record_watch(app, video_id, title);
+            app.toasts.push(
+                Level::Info,
+                format!(
+                    "Playing: {title} from {}",
+                    format_duration(u64::from(start))
+                ),
+            );
+        }
+        Err(err) => app.toasts.push(Level::Error, err),
+    }
+}
+
+fn open_jump_prompt(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        app.toasts.push(
+            Level::Info,
+            "Select a video to play it from a timestamp.".to_string(),
+        );
+        return;
+    };
+    app.popup = Some(Popup::Jump(JumpView {
+        video_id: video.id.clone(),
+        title: video.title.clone(),
+        input: String::new(),
+        error: None,
+    }));
+    app.needs_full_redraw = true;
+}
+
+/// Seeks when the video is already playing in mpv, otherwise starts it there.
+fn jump_to(app: &mut App, video_id: &str, title: &str, start: u32) {
+    let playing = app
+        .playing_video
+        .as_ref()
+        .is_some_and(|item| item.id == video_id);
+    if let Some(player) = app.player.as_ref().filter(|_| playing) {
+        if player.seek_to(f64::from(start)).is_ok() {
+            app.toasts.push(
+                Level::Info,
+                format!("Jumped to {}.", format_duration(u64::from(start))),
+            );
+            return;
+        }
+    }
+    play_video_at(app, video_id, title, start);
+}
+
+fn toggle_original_branding(app: &mut App) {
+    if !app.config.dearrow {
+        app.toasts.push(
+            Level::Info,
+            "Set \"dearrow\": true in the config to use DeArrow.".to_string(),
+        );
+        return;
+    }
+    app.original_branding = !app.original_branding;
+    // Thumbnails that DeArrow replaced have to be loaded again.
+    for item in &mut app.screen.results {
+        if let SearchResultItem::Video(video) = item {
+            let replaced = app
+                .branding
+                .get(&video.id)
+                .and_then(Option::as_ref)
+                .is_some_and(|branding| branding.thumbnail_url.is_some());
+            if replaced && !video.thumbnail_loading {
+                video.thumbnail_path = None;
+                video.thumbnail_size = None;
+            }
+        }
+    }
+    app.last_thumb = None;
+    let selected = app.screen.selected;
+    queue_thumbnail(app, selected);
+    app.toasts.push(
+        Level::Info,
+        if app.original_branding {
+            "Showing original titles and thumbnails.".to_string()
+        } else {
+            "Showing DeArrow titles and thumbnails.".to_string()
+        },
+    );
+}
+
+fn cycle_playback_option(app: &mut App, option: PlaybackOption, forward: bool) {
+    match option {
+        PlaybackOption::Window => {
+            let presets = app.config.window_presets();
+            let len = presets.len().max(1);
+            app.window_preset = if forward {
+                (app.window_preset + 1) % len
+            } else {
+                (app.window_preset + len - 1) % len
+            };
+            let Some(preset) = presets.get(app.window_preset) else {
+                return;
+            };
+            // mpv applies both at runtime, so a running window moves too.
+            if let Some(player) = app.player.as_ref() {
+                if let Err(err) = player.set_property("ontop", serde_json::json!(preset.ontop)) {
+                    log::warn!("Could not set the player on top: {err}");
+                }
+                if let Some(geometry) = &preset.geometry {
+                    if let Err(err) = player.set_property("geometry", serde_json::json!(geometry)) {
+                        log::warn!("Could not move the player window: {err}");
+                    }
+                }
+            }
+            app.toasts
+                .push(Level::Info, format!("Window: {}.", preset.name));
+        }
+        PlaybackOption::Repeat => {
+            app.repeat = if forward {
+                app.repeat.next()
+            } else {
+                app.repeat.next().next()
+            };
+            if let Some(player) = app.player.as_ref() {
+                let mode = if app.repeat == Repeat::One {
+                    "inf"
+                } else {
+                    "no"
+                };
+                if let Err(err) = player.set_property("loop-file", serde_json::json!(mode)) {
+                    log::warn!("Could not set the player to repeat: {err}");
+                }
+            }
+            app.toasts
+                .push(Level::Info, format!("Repeat: {}.", app.repeat.label()));
+        }
+        PlaybackOption::Speed => change_speed(app, forward),
+    }
+}
+
+/// Steps the speed up or down, changing the running player too.
+fn change_speed(app: &mut App, faster: bool) {
+    let next = if faster {
+        SPEED_STEPS
+            .iter()
+            .copied()
+            .find(|step| *step > app.speed + 0.01)
+    } else {
+        SPEED_STEPS
+            .iter()
+            .copied()
+            .rev()
+            .find(|step| *step < app.speed - 0.01)
+    };
+    let Some(speed) = next else {
+        return;
+    };
+    app.speed = speed;
+    match app.player.as_ref() {
+        Some(player) => match player.set_property("speed", serde_json::json!(speed)) {
+            Ok(()) => app
+                .toasts
+                .push(Level::Info, format!("Speed {}.", format_speed(speed))),
+            Err(err) => app.toasts.push(Level::Error, err),
+        },
+        None => app.toasts.push(
+            Level::Info,
+            format!("New videos play at {}.", format_speed(speed)),
+        ),
+    }
+}
+
+fn format_speed(speed: f64) -> String {
+    format!("{speed}×")
+}
+
+fn playback_option_value(app: &App, option: PlaybackOption) -> String {
+    match option {
+        PlaybackOption::Window => app
+            .config
+            .window_presets()
+            .get(app.window_preset)
+            .map(|preset| preset.name.clone())
+            .unwrap_or_default(),
+        PlaybackOption::Repeat => app.repeat.label().to_string(),
+        PlaybackOption::Speed => format_speed(app.speed),
+    }
+}
+
+fn close_popup(app: &mut App) {
+    app.popup = None;
+    app.needs_full_redraw = true;
+}
+
+fn ask_confirmation(app: &mut App, question: String, action: ConfirmAction) {
+    app.popup = Some(Popup::Confirm(ConfirmView { question, action }));
+    app.needs_full_redraw = true;
+}
+
+/// Shows a failure in a modal, or as a toast when another popup is open.
+fn show_error(app: &mut App, title: String, text: String, retry: Retry) {
+    if app.popup.is_some() {
+        app.toasts.push(Level::Error, format!("{title}: {text}"));
+        return;
+    }
+    app.popup = Some(Popup::Error(ErrorView {
+        title,
+        text,
+        retry,
+        scroll: 0,
+    }));
+    app.needs_full_redraw = true;
+}
+
+fn retry(app: &mut App, retry: Retry) {
+    match retry {
+        Retry::Source(source) => start_source(app, source),
+        Retry::Download(id) => {
+            let index = app.downloads.iter().position(|item| {
+                item.id == id
+                    && matches!(
+                        item.state,
+                        DownloadState::Failed(_) | DownloadState::Cancelled
+                    )
+            });
+            if let Some(index) = index {
+                start_download(app, index);
+            }
+        }
+    }
+}
+
+/// Loads `source` again from scratch.
+fn start_source(app: &mut App, source: ResultsSource) {
+    match source {
+        ResultsSource::Search(query) => start_search(app, query),
+        ResultsSource::Channel { id, name, tab } => start_channel_tab(app, id, name, tab),
+        ResultsSource::Playlist { id, name } => start_playlist_videos(app, id, name),
+        ResultsSource::Trending(category) => start_trending_category(app, category),
+        ResultsSource::Related { id, title } => start_related_videos(app, id, title),
+        ResultsSource::Feed => start_feed(app),
+    }
+}
+
+fn run_confirmed(app: &mut App, action: ConfirmAction) {
+    match action {
+        ConfirmAction::ClearHistory => {
+            app.history.clear();
+            app.watched.clear();
+            app.views.history.selected = 0;
+            match storage::save_history(&app.history) {
+                Ok(()) => app.toasts.push(Level::Info, "History cleared.".to_string()),
+                Err(err) => app
+                    .toasts
+                    .push(Level::Error, format!("Failed to save watch history: {err}")),
+            }
+        }
+        ConfirmAction::CancelDownload(id) => {
+            if let Some(item) = app.downloads.iter_mut().find(|item| item.id == id) {
+                if let Some(handle) = item.handle.take() {
+                    handle.cancel();
+                    item.state = DownloadState::Cancelled;
+                }
+            }
+        }
+        ConfirmAction::DeleteLocalPlaylist(name) => {
+            app.playlists.retain(|playlist| playlist.name != name);
+            app.views.playlists.selected = app
+                .views
+                .playlists
+                .selected
+                .min(app.playlists.len().saturating_sub(1));
+            save_playlists(app, format!("Deleted playlist {name}."));
+        }
+        ConfirmAction::PurgeThumbnails => match purge_thumbnail_cache() {
+            Ok(()) => {
+                for item in &mut app.screen.results {
+                    if let SearchResultItem::Video(video) = item {
+                        video.thumbnail_path = None;
+                    }
+                }
+                app.last_thumb = None;
+                app.grid_drawn.clear();
+                queue_thumbnail(app, app.screen.selected);
+                app.toasts
+                    .push(Level::Info, "Thumbnail cache cleared.".to_string());
+            }
+            Err(err) => app.toasts.push(Level::Error, err),
+        },
+        ConfirmAction::PurgeSearches => match search_cache::purge() {
+            Ok(()) => app
+                .toasts
+                .push(Level::Info, "Search cache cleared.".to_string()),
+            Err(err) => app.toasts.push(Level::Error, err),
+        },
+    }
+}
+
+fn open_comments(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        app.toasts.push(
+            Level::Info,
+            "Select a video to show its comments.".to_string(),
+        );
+        return;
+    };
+    let video_id = video.id.clone();
+    app.popup = Some(Popup::Comments(CommentsView {
+        video_id: video_id.clone(),
+        video_title: video.title.clone(),
+        comments: Vec::new(),
+        total: None,
+        ctoken: None,
+        visitor_data: None,
+        loading: true,
+        error: None,
+        selected_timestamp: None,
+        follow_selection: false,
+        scroll: 0,
+        max_scroll: 0,
+        view_height: 0,
+    }));
+    app.needs_full_redraw = true;
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = video_comments_first(&video_id);
+        let _ = tx.send(AppMsg::Comments { video_id, result });
+    });
+}
+
+fn load_more_comments(app: &mut App) {
+    let Some(Popup::Comments(view)) = app.popup.as_mut() else {
+        return;
+    };
+    if view.loading {
+        return;
+    }
+    let Some(ctoken) = view.ctoken.clone() else {
+        return;
+    };
+    view.loading = true;
+    view.error = None;
+    let video_id = view.video_id.clone();
+    let visitor = view.visitor_data.clone();
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = video_comments_continuation(&video_id, &ctoken, visitor.as_deref());
+        let _ = tx.send(AppMsg::Comments { video_id, result });
+    });
+}
+
+fn queue_chapters(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get_mut(app.screen.selected)
+    else {
+        return;
+    };
+    if video.chapters.is_some() || video.chapters_loading {
+        return;
+    }
+    video.chapters_loading = true;
+    let video_id = video.id.clone();
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = video_chapters(&video_id);
+        let _ = tx.send(AppMsg::Chapters { video_id, result });
+    });
+}
+
+/// The DeArrow branding to show for `video_id`, if any.
+fn active_branding<'a>(
+    branding: &'a HashMap<String, Option<Branding>>,
+    original: bool,
+    video_id: &str,
+) -> Option<&'a Branding> {
+    if original {
+        return None;
+    }
+    branding.get(video_id).and_then(Option::as_ref)
+}
+
+/// Looks up DeArrow branding for loaded videos that don't have it yet,
+/// one request after another on a single thread.
+fn queue_branding(app: &mut App) {
+    if !app.config.dearrow {
+        return;
+    }
+    let mut video_ids = Vec::new();
+    for item in &app.screen.results {
+        if let SearchResultItem::Video(video) = item {
+            if !app.branding.contains_key(&video.id) {
+                app.branding.insert(video.id.clone(), None);
+                video_ids.push(video.id.clone());
+            }
+        }
+    }
+    if video_ids.is_empty() {
+        return;
+    }
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        for video_id in video_ids {
+            // Failed lookups just leave the original title in place.
+            let branding = dearrow_branding(&video_id).unwrap_or_default();
+            if tx.send(AppMsg::Branding { video_id, branding }).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Fetches the dislike estimate for the selected video.
+fn queue_votes(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        return;
+    };
+    if video.is_live || app.votes.contains_key(&video.id) {
+        return;
+    }
+    let video_id = video.id.clone();
+    app.votes.insert(video_id.clone(), None);
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = dislike_votes(&video_id);
+        let _ = tx.send(AppMsg::Votes { video_id, result });
+    });
+}
+
+fn queue_video_info(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        app.info_candidate = None;
+        return;
+    };
+    if app.video_info.contains_key(&video.id) {
+        return;
+    }
+    match &app.info_candidate {
+        Some((id, since)) if *id == video.id => {
+            if since.elapsed() < INFO_DELAY {
+                return;
+            }
+        }
+        _ => {
+            app.info_candidate = Some((video.id.clone(), Instant::now()));
+            return;
+        }
+    }
+    app.info_candidate = None;
+    let video_id = video.id.clone();
+    app.video_info.insert(video_id.clone(), None);
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = video_info(&video_id);
+        let _ = tx.send(AppMsg::VideoInfo { video_id, result });
+    });
+}
+
+/// Fetches the storyboard frames of the selected video once it stays
+/// selected for `INFO_DELAY`, while hover previews are on.
+fn queue_preview(app: &mut App) {
+    let video_id = match app.screen.results.get(app.screen.selected) {
+        Some(SearchResultItem::Video(video))
+            if app.hover_preview && app.config.graphics != Graphics::Off =>
+        {
+            video.id.clone()
+        }
+        _ => {
+            app.preview = None;
+            return;
+        }
+    };
+    let preview = match app.preview.as_mut() {
+        Some(preview) if preview.video_id == video_id => preview,
+        _ => {
+            app.preview = Some(Preview {
+                video_id,
+                since: Instant::now(),
+                requested: false,
+                frames: Vec::new(),
+            });
+            return;
+        }
+    };
+    if preview.requested || preview.since.elapsed() < INFO_DELAY {
+        return;
+    }
+    preview.requested = true;
+    let tx = app.tx.clone();
+    let width = thumbnail_pixels(app);
+    let limit = app.config.thumbnail_cache_bytes();
+    app.thumbnail_pool.spawn(move |client| {
+        let result = storyboard_frames(client, &video_id, width, limit);
+        let _ = tx.send(AppMsg::Preview { video_id, result });
+    });
+}
+
+fn toggle_hover_preview(app: &mut App) {
+    if app.config.graphics == Graphics::Off {
+        app.toasts.push(
+            Level::Info,
+            "Thumbnails are off (\"graphics\": \"off\").".to_string(),
+        );
+        return;
+    }
+    app.hover_preview = !app.hover_preview;
+    app.toasts.push(
+        Level::Info,
+        if app.hover_preview {
+            "Previewing the selected video.".to_string()
+        } else {
+            "Preview off.".to_string()
+        },
+    );
+}
+
+fn queue_sponsor_segments(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get_mut(app.screen.selected)
+    else {
+        return;
+    };
+    if video.sponsor_segments.is_some() || video.sponsor_loading {
+        return;
+    }
+    video.sponsor_loading = true;
+    let video_id = video.id.clone();
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = sponsor_segments(&video_id);
+        let _ = tx.send(AppMsg::SponsorSegments { video_id, result });
+    });
+}
+
+/// Seeks past a sponsor segment when playback enters one.
+fn skip_sponsor_segment(app: &mut App, position: f64) {
+    let Some(&(_, end)) = app
+        .skip_segments
+        .iter()
+        .find(|(start, end)| position >= *start && position < end - 1.0)
+    else {
+        return;
+    };
+    if let Some(player) = app.player.as_ref() {
+        match player.seek_to(end) {
+            Ok(()) => {
+                app.toasts.push(
+                    Level::Info,
+                    format!(
+                        "Skipped sponsor segment ({}).",
+                        format_duration((end - position) as u64)
+                    ),
+                );
+            }
+            Err(err) => app.toasts.push(Level::Error, err),
+        }
+    }
+}
+
+/// Opens the clip prompt, starting from the selected chapter when there is one.
+fn open_clip_prompt(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        app.toasts.push(
+            Level::Info,
+            "Select a video to play a clip of it.".to_string(),
+        );
+        return;
+    };
+    let chapters = video.chapters.as_deref().unwrap_or_default();
+    let (start, end) = match chapters.get(app.chapter_selected) {
+        Some(chapter) => (
+            format_duration(chapter.position as u64),
+            chapters
+                .get(app.chapter_selected + 1)
+                .map(|next| next.position as u64)
+                .or(video.duration)
+                .map(format_duration)
+                .unwrap_or_default(),
+        ),
+        None => (String::new(), String::new()),
+    };
+    app.popup = Some(Popup::Clip(ClipView {
+        video_id: video.id.clone(),
+        title: video.title.clone(),
+        url: video.url.clone(),
+        start,
+        end,
+        editing_end: false,
+        error: None,
+    }));
+    app.needs_full_redraw = true;
+}
+
+fn play_clip(app: &mut App) {
+    let Some(Popup::Clip(view)) = app.popup.as_mut() else {
+        return;
+    };
+    let start = if view.start.is_empty() {
+        Some(0)
+    } else {
+        parse_timestamp(&view.start)
+    };
+    let end = if view.end.is_empty() {
+        None
+    } else {
+        match parse_timestamp(&view.end) {
+            Some(end) => Some(end),
+            None => {
+                view.error = Some("End must look like hh:mm:ss.".to_string());
+                return;
+            }
+        }
+    };
+    let Some(start) = start else {
+        view.error = Some("Start must look like hh:mm:ss.".to_string());
+        return;
+    };
+    if end.is_some_and(|end| end <= start) {
+        view.error = Some("End must come after the start.".to_string());
+        return;
+    }
+    let (id, title, url) = (view.video_id.clone(), view.title.clone(), view.url.clone());
+    close_popup(app);
+    let mut args = vec![app.config.ytdl_format()];
+    if let Some(end) = end {
+        args.push(format!("--end={end}"));
+    }
+    let range = match end {
+        Some(end) => format!(
+            "{}-{}",
+            format_duration(start as u64),
+            format_duration(end as u64)
+        ),
+        None => format!("from {}", format_duration(start as u64)),
+    };
+    match spawn_player(app, &url, Some(start), args) {
+        Ok(()) => {
+            record_watch(app, &id, &title);
+            app.toasts
+                .push(Level::Info, format!("Playing: {title} ({range})"));
+        }
+        Err(err) => app.toasts.push(Level::Error, err),
+    }
+}
+
+/// Parses `hh:mm:ss`, `mm:ss` or plain seconds.
+fn parse_timestamp(text: &str) -> Option<u32> {
+    let parts: Vec<&str> = text.trim().split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    let mut total = 0u32;
+    for (idx, part) in parts.iter().enumerate() {
+        let value: u32 = part.parse().ok()?;
+        if idx > 0 && value >= 60 {
+            return None;
+        }
+        total = total.checked_mul(60)?.checked_add(value)?;
+    }
+    Some(total)
+}
+
+fn play_selected_chapter(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        return;
+    };
+    let Some(chapter) = video
+        .chapters
+        .as_ref()
+        .and_then(|chapters| chapters.get(app.chapter_selected))
+    else {
+        app.toasts
+            .push(Level::Info, "No chapters for this video.".to_string());
+        return;
+    };
+    let (id, title, url) = (video.id.clone(), video.title.clone(), video.url.clone());
+    let (position, status) = (
+        chapter.position,
+        format!("Playing: {} from '{}'", video.title, chapter.name),
+    );
+    match play_url(app, &url, Some(position)) {
+        Ok(()) => {
+            record_watch(app, &id, &title);
+            app.toasts.push(Level::Info, status);
+        }
+        Err(err) => app.toasts.push(Level::Error, err),
+    }
+}
+
+/// Ctrl+D / Ctrl+U move the results selection by half a screen.
+/// Readline-style editing in the search box: Ctrl+W deletes the word
+/// before the cursor, Ctrl+U clears the line, Ctrl+A/E and Alt+B/F move
+/// to the line ends and by word.
+fn handle_line_edit_key(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> bool {
+    if app.popup.is_some()
+        || app.filter_editing
+        || app.command_editing
+        || app.views.current != View::Search
+        || app.screen.focus != Focus::Search
+    {
+        return false;
+    }
+    let KeyCode::Char(c) = key else {
+        return false;
+    };
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        match c {
+            'w' => {
+                let start = previous_word(&app.screen.query, app.screen.cursor);
+                app.screen.query.replace_range(start..app.screen.cursor, "");
+                app.screen.cursor = start;
+                query_edited(app);
+            }
+            'u' => {
+                app.screen.query.clear();
+                app.screen.cursor = 0;
+                query_edited(app);
+            }
+            'a' => app.screen.cursor = 0,
+            'e' => app.screen.cursor = app.screen.query.len(),
+            _ => return false,
+        }
+    } else if modifiers.contains(KeyModifiers::ALT) {
+        match c {
+            'b' => app.screen.cursor = previous_word(&app.screen.query, app.screen.cursor),
+            'f' => app.screen.cursor = next_word(&app.screen.query, app.screen.cursor),
+            _ => return false,
+        }
+    } else {
+        return false;
+    }
+    true
+}
+
+/// Byte offset of the start of the word before `cursor` in `text`.
+fn previous_word(text: &str, cursor: usize) -> usize {
+    let before = text[..cursor].trim_end();
+    before
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace())
+        .map_or(0, |(index, c)| index + c.len_utf8())
+}
+
+/// Byte offset of the end of the word after `cursor` in `text`.
+fn next_word(text: &str, cursor: usize) -> usize {
+    let after = &text[cursor..];
+    let start = after.len() - after.trim_start().len();
+    after[start..]
+        .char_indices()
+        .find(|(_, c)| c.is_whitespace())
+        .map_or(text.len(), |(index, _)| cursor + start + index)
+}
+
+/// Inserts pasted text into the filter prompt or the search box as one
+/// edit, so it isn't replayed as key presses.
+fn paste(app: &mut App, text: &str) {
+    if app.popup.is_some() {
+        return;
+    }
+    let text: String = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if app.filter_editing {
+        let filter = format!("{}{text}", app.screen.filter);
+        set_filter(app, filter);
+        return;
+    }
+    if app.command_editing {
+        app.command.push_str(&text);
+        return;
+    }
+    switch_view(app, View::Search);
+    app.screen.focus = Focus::Search;
+    app.screen.query.insert_str(app.screen.cursor, &text);
+    app.screen.cursor += text.len();
+    query_edited(app);
+}
+
+fn handle_half_page_key(app: &mut App, key: KeyCode) -> bool {
+    if app.popup.is_some()
+        || app.filter_editing
+        || app.command_editing
+        || app.screen.focus != Focus::Results
+    {
+        return false;
+    }
+    let half = (app.results_view_rows / 2).max(1) as isize;
+    match key {
+        KeyCode::Char('d') => move_selection(app, half),
+        KeyCode::Char('u') => move_selection(app, -half),
+        _ => return false,
+    }
+    true
+}
+
+/// Ctrl+Up/Down resize the Details pane and Ctrl+Left/Right its thumbnail.
+/// The sizes are saved to the config.
+fn handle_resize_key(app: &mut App, key: KeyCode) -> bool {
+    if app.popup.is_some()
+        || app.filter_editing
+        || app.command_editing
+        || !matches!(app.views.current, View::Search | View::Feed)
+    {
+        return false;
+    }
+    match key {
+        KeyCode::Up | KeyCode::Down => {
+            let height = if key == KeyCode::Up {
+                (app.details_height + 1).min(app.max_details_height)
+            } else {
+                app.details_height.saturating_sub(1).max(MIN_DETAILS_HEIGHT)
+            };
+            app.config.details_height = Some(height);
+            app.toasts
+                .push(Level::Info, format!("Details pane: {height} rows."));
+        }
+        KeyCode::Left | KeyCode::Right => {
+            let current = app
+                .thumb_area
+                .map(|area| area.width)
+                .or(app.config.thumbnail_width)
+                .unwrap_or(app.max_thumb_width);
+            let width = if key == KeyCode::Right {
+                (current + THUMB_WIDTH_STEP).min(app.max_thumb_width.max(current))
+            } else {
+                current
+                    .saturating_sub(THUMB_WIDTH_STEP)
+                    .max(MIN_THUMB_WIDTH)
+            };
+            app.config.thumbnail_width = Some(width);
+            app.toasts
+                .push(Level::Info, format!("Thumbnail: up to {width} columns."));
+        }
+        _ => return false,
+    }
+    app.needs_full_redraw = true;
+    if let Err(err) = save_config(app) {
+        app.toasts
+            .push(Level::Error, format!("Failed to save config: {err}"));
+    }
+    true
+}
+
+/// Rows Up/Down move by: a whole line of the grid in grid view.
+fn results_step(app: &App) -> isize {
+    if app.grid_view {
+        app.grid_columns.max(1) as isize
+    } else {
+        1
+    }
+}
+
+/// Moves the selection by `delta` rows. Moving down past the last result
+/// loads more when the source has them.
+fn move_selection(app: &mut App, delta: isize) {
+    let last = app.screen.visible.len().saturating_sub(1);
+    if delta > 0 && app.screen.selected_row >= last {
+        load_more_results(app);
+        return;
+    }
+    app.screen.selected_row = app
+        .screen
+        .selected_row
+        .saturating_add_signed(delta)
+        .min(last);
+    sync_selected_result(app);
+}
+
+fn load_more_results(app: &mut App) {
+    if app.screen.search_ctoken.is_some() && !app.screen.loading_more {
+        app.screen.pending_next_target = Some(app.screen.visible.len());
+        let target = app.screen.visible.len() + app.results_view_rows.max(1);
+        request_more_results(app, target);
+    } else if !app.screen.loading_more {
+        app.toasts.push(Level::Info, "No more results.".to_string());
+    }
+}
+
+fn request_more_results(app: &mut App, target: usize) {
+    app.screen.loading_more = true;
+    app.toasts
+        .push(Level::Info, "Loading more results...".to_string());
+    let tx = app.tx.clone();
+    let ctoken = app.screen.search_ctoken.clone().unwrap_or_default();
+    let visitor = app.screen.search_visitor_data.clone();
+    let endpoint = app
+        .screen
+        .search_endpoint
+        .unwrap_or(ContinuationEndpoint::Search);
+    let target_count = target.saturating_sub(app.screen.visible.len());
+    let generation = app.screen.search_generation;
+    thread::spawn(move || {
+        let result = if ctoken.is_empty() {
+            Err("No more results.".to_string())
+        } else {
+            search_rustypipe_continuation(&ctoken, visitor.as_deref(), endpoint, target_count)
+        };
+        let _ = tx.send(AppMsg::MoreResults { generation, result });
+    });
+}
+
+/// Selects the row on screen showing `label`, if there is one.
+fn select_labelled_row(app: &mut App, label: char) -> bool {
+    let Some(offset) = ROW_LABELS.iter().position(|&c| c == label) else {
+        return false;
+    };
+    let row = first_row_on_screen(app) + offset;
+    if row >= app.screen.visible.len() {
+        return false;
+    }
+    app.screen.selected_row = row;
+    sync_selected_result(app);
+    true
+}
+
+fn first_row_on_screen(app: &App) -> usize {
+    if app.grid_view {
+        app.grid_scroll * app.grid_columns
+    } else {
+        app.screen.results_state.offset()
+    }
+}
+
+/// The label drawn in front of `row` while one is being picked.
+fn row_label(app: &App, row: usize) -> Option<char> {
+    app.pending_label?;
+    let offset = row.checked_sub(first_row_on_screen(app))?;
+    ROW_LABELS.get(offset).copied()
+}
+
+fn sync_selected_result(app: &mut App) {
+    if app.screen.visible.is_empty() {
+        return;
+    }
+    app.screen.selected_row = app.screen.selected_row.min(app.screen.visible.len() - 1);
+    let index = app.screen.visible[app.screen.selected_row];
+    if app.screen.selected != index {
+        app.screen.selected = index;
+        app.chapter_selected = 0;
+        queue_thumbnail(app, index);
+    }
+}
+
+/// Selects the row that was asked for before more results were loaded,
+/// loading again when the filters hid everything that came in.
+fn select_pending_row(app: &mut App) {
+    let Some(target) = app.screen.pending_next_target else {
+        return;
+    };
+
+    if app.screen.visible.len() > target {
+        app.screen.selected_row = target;
+        sync_selected_result(app);
+        app.screen.pending_next_target = None;
+        app.toasts.push(
+            Level::Info,
+            format!("Found {} results.", app.screen.results.len()),
+        );
+        return;
+    }
+
+    if app.screen.search_ctoken.is_some() && !app.screen.loading_more {
+        request_more_results(app, target + app.results_view_rows.max(1));
+    } else {
+        app.screen.pending_next_target = None;
+        app.toasts.push(Level::Info, "No more results.".to_string());
+    }
+}
+
+fn start_search(app: &mut App, query: String) {
+    // A search started by typing is superseded rather than waited for.
+    if query.is_empty() || (app.screen.searching && !app.live_search) {
+        return;
+    }
+    app.screen.searching = true;
+    app.live_search = false;
+    app.query_edited = None;
+    app.toasts
+        .push(Level::Info, format!("Searching for '{query}'..."));
+    let tx = app.tx.clone();
+    let generation = next_search_generation(app);
+    let cache_ttl = app.config.search_cache_secs();
+    thread::spawn(move || {
+        let result = search_rustypipe(&query, cache_ttl);
+        let source = ResultsSource::Search(query);
+        let _ = tx.send(AppMsg::Search {
+            generation,
+            source,
+            result,
+        });
+    });
+}
+
+/// Starts a new generation of results requests, so responses to the
+/// earlier ones are ignored.
+fn next_search_generation(app: &mut App) -> u64 {
+    app.screen.search_started = Instant::now();
+    app.screen.search_generation += 1;
+    app.screen.search_generation
+}
+
+/// Notes a change to the query for search-as-you-type.
+fn query_edited(app: &mut App) {
+    if app.config.search_as_you_type {
+        app.query_edited = Some(Instant::now());
+    }
+}
+
+/// Searches for the query once typing has paused for `LIVE_SEARCH_DELAY`.
+fn run_live_search(app: &mut App) {
+    let Some(edited) = app.query_edited else {
+        return;
+    };
+    if edited.elapsed() < LIVE_SEARCH_DELAY {
+        return;
+    }
+    app.query_edited = None;
+    let query = app.screen.query.trim().to_string();
+    let current =
+        matches!(&app.screen.source, Some(ResultsSource::Search(shown)) if *shown == query);
+    if query.is_empty() || current || looks_like_youtube_link(&query) {
+        return;
+    }
+    // Whatever is still loading was asked for before this query; its
+    // results are dropped when they arrive.
+    app.screen.searching = false;
+    start_search(app, query);
+    app.live_search = true;
+}
+
+fn start_related_videos(app: &mut App, video_id: String, title: String) {
+    if app.screen.searching {
+        return;
+    }
+    app.screen.searching = true;
+    app.toasts.push(
+        Level::Info,
+        format!("Loading videos related to '{title}'..."),
+    );
+    let tx = app.tx.clone();
+    let generation = next_search_generation(app);
+    thread::spawn(move || {
+        let result = related_videos(&video_id);
+        let source = ResultsSource::Related {
+            id: video_id,
+            title,
+        };
+        let _ = tx.send(AppMsg::Search {
+            generation,
+            source,
+            result,
+        });
+    });
+}
+
+fn take_results_snapshot(app: &mut App) -> ResultsSnapshot {
+    ResultsSnapshot {
+        results: std::mem::take(&mut app.screen.results),
+        source: app.screen.source.take(),
+        channel_header: app.screen.channel_header.take(),
+        selected_row: app.screen.selected_row,
+        selected: app.screen.selected,
+        search_ctoken: app.screen.search_ctoken.take(),
+        search_visitor_data: app.screen.search_visitor_data.take(),
+        search_endpoint: app.screen.search_endpoint.take(),
+        pages_loaded: std::mem::take(&mut app.screen.pages_loaded),
+    }
+}
+
+fn go_back(app: &mut App) {
+    if app.screen.searching {
+        return;
+    }
+    let Some(snapshot) = app.screen.back_stack.pop() else {
+        app.toasts
+            .push(Level::Info, "Nothing to go back to.".to_string());
+        return;
+    };
+    app.screen.results = snapshot.results;
+    app.screen.filter.clear();
+    app.filter_editing = false;
+    app.screen.source = snapshot.source;
+    refresh_visible(app);
+    app.screen.channel_header = snapshot.channel_header;
+    app.screen.selected_row = snapshot.selected_row;
+    app.screen.selected = snapshot.selected;
+    app.chapter_selected = 0;
+    app.screen.search_ctoken = snapshot.search_ctoken;
+    app.screen.search_visitor_data = snapshot.search_visitor_data;
+    app.screen.search_endpoint = snapshot.search_endpoint;
+    app.screen.pages_loaded = snapshot.pages_loaded;
+    app.screen.results_state = ListState::default();
+    app.screen.loading_more = false;
+    app.screen.pending_next_target = None;
+    // Downloads that were in flight when the snapshot was taken were dropped.
+    for item in &mut app.screen.results {
+        if let SearchResultItem::Video(video) = item {
+            video.thumbnail_loading = false;
+        }
+    }
+    let selected = app.screen.selected;
+    queue_thumbnail(app, selected);
+    app.toasts
+        .push(Level::Info, format!("Back to {}.", results_title(app)));
+}
+
+fn start_resolve(app: &mut App, input: String) {
+    if app.screen.searching {
+        return;
+    }
+    app.screen.searching = true;
+    app.screen.search_started = Instant::now();
+    app.toasts
+        .push(Level::Info, format!("Opening '{input}'..."));
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = resolve_youtube_link(&input);
+        let _ = tx.send(AppMsg::Resolved(result));
+    });
+}
+
+fn open_url_target(app: &mut App, target: UrlTarget) {
+    match target {
+        UrlTarget::Video { id, start_time } => {
+            let url = format!("https://www.youtube.com/watch?v={id}");
+            match play_url(app, &url, Some(start_time)) {
+                Ok(()) => {
+                    app.toasts.push(Level::Info, format!("Playing: {url}"));
+                    record_watch(app, &id, &id);
+                }
+                Err(err) => {
+                    app.toasts.push(Level::Error, err);
+                }
+            }
+        }
+        UrlTarget::Channel { id } => {
+            let name = id.clone();
+            start_channel_videos(app, id, name);
+        }
+        UrlTarget::Playlist { id } => {
+            let name = id.clone();
+            start_playlist_videos(app, id, name);
+        }
+        UrlTarget::Album { .. } => {
+            app.toasts.push(
+                Level::Warning,
+                "YouTube Music albums are not supported.".to_string(),
+            );
+        }
+    }
+}
+
+/// Hands every listed video (after filters and sorting) to one player
+/// instance through a temporary M3U playlist.
+fn play_all_results(app: &mut App) {
+    let mut videos: Vec<&Video> = app
+        .screen
+        .visible
+        .iter()
+        .filter_map(|index| match app.screen.results.get(*index) {
+            Some(SearchResultItem::Video(video)) => Some(video),
+            _ => None,
+        })
+        .collect();
+    if videos.is_empty() {
+        app.toasts
+            .push(Level::Info, "No videos to play.".to_string());
+        return;
+    }
+    if app.shuffle {
+        fastrand::shuffle(&mut videos);
+    }
+    let count = videos.len();
+    let path = match write_m3u(
+        "results",
+        videos
+            .iter()
+            .map(|video| (video.duration, video.title.as_str(), video.url.as_str())),
+    ) {
+        Ok(path) => path,
+        Err(err) => {
+            app.toasts.push(Level::Error, err);
+            return;
+        }
+    };
+    match play_url(app, &path.to_string_lossy(), None) {
+        Ok(()) => app
+            .toasts
+            .push(Level::Info, format!("Playing {count} results.")),
+        Err(err) => app.toasts.push(Level::Error, err),
+    }
+}
+
+/// Writes `(duration, title, url)` entries to a temporary M3U playlist
+/// `ytbv-<name>-<pid>.m3u`.
+fn write_m3u<'a>(
+    name: &str,
+    entries: impl Iterator<Item = (Option<u64>, &'a str, &'a str)>,
+) -> Result<PathBuf, String> {
+    let path = env::temp_dir().join(format!("ytbv-{name}-{}.m3u", std::process::id()));
+    let mut playlist = String::from("#EXTM3U\n");
+    for (duration, title, url) in entries {
+        let duration = duration.map_or(-1, |secs| secs as i64);
+        let title = title.replace(['\r', '\n'], " ");
+        playlist.push_str(&format!("#EXTINF:{duration},{title}\n{url}\n"));
+    }
+    fs::write(&path, playlist).map_err(|err| format!("Failed to write playlist: {err}"))?;
+    Ok(path)
+}
+
+/// Plays a random video from the visible results, weighted by view count when
+/// `lucky_weighted` is set in the config.
+fn play_random_result(app: &mut App) {
+    let candidates: Vec<&Video> = app
+        .screen
+        .visible
+        .iter()
+        .filter_map(|index| match app.screen.results.get(*index) {
+            Some(SearchResultItem::Video(video)) if !video.is_live => Some(video),
+            _ => None,
+        })
+        .collect();
+    if candidates.is_empty() {
+        app.toasts
+            .push(Level::Info, "No videos to pick from.".to_string());
+        return;
+    }
+    let pick = if app.config.lucky_weighted {
+        let weights: Vec<u64> = candidates
+            .iter()
+            .map(|video| video.view_count.unwrap_or(0).saturating_add(1))
+            .collect();
+        let total = weights.iter().fold(0u64, |sum, w| sum.saturating_add(*w));
+        let mut target = fastrand::u64(..total);
+        weights
+            .iter()
+            .position(|w| {
+                if target < *w {
+                    true
+                } else {
+                    target -= w;
+                    false
+                }
+            })
+            .unwrap_or(0)
+    } else {
+        fastrand::usize(..candidates.len())
+    };
+    let video = candidates[pick];
+    let (id, title, url) = (video.id.clone(), video.title.clone(), video.url.clone());
+    match play_url(app, &url, None) {
+        Ok(()) => {
+            record_watch(app, &id, &title);
+            app.toasts
+                .push(Level::Info, format!("Feeling lucky: {title}"));
+        }
+        Err(err) => app.toasts.push(Level::Error, err),
+    }
+}
+
+/// Adds the selected video to the queue, or plays it right away when
+/// nothing is playing.
+fn enqueue_selected(app: &mut App) {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        app.toasts
+            .push(Level::Info, "Select a video to queue it.".to_string());
+        return;
+    };
+    let item = QueueItem {
+        id: video.id.clone(),
+        title: video.title.clone(),
+        url: video.url.clone(),
+    };
+    if app.player.is_none() {
+        play_queue_item(app, item);
+        return;
+    }
+    app.toasts.push(
+        Level::Info,
+        format!("Queued: {} ({} in queue)", item.title, app.queue.len() + 1),
+    );
+    if app.handoff.is_some() {
+        append_to_handoff(app, item.clone());
+    }
+    app.queue.push_back(item);
+    prefetch_next(app);
+}
+
+/// Whether the queue can go to the player as one playlist.
+fn can_hand_off(app: &App) -> bool {
+    let mpv = app
+        .config
+        .players()
+        .first()
+        .is_none_or(PlayerCommand::is_mpv);
+    mpv && !app.shuffle && !app.queue.is_empty()
+}
+
+/// Plays `item` followed by the whole queue from a generated playlist, so
+/// mpv goes from one video to the next without being restarted.
+fn hand_off_queue(app: &mut App, item: QueueItem) {
+    let mut entries = vec![item];
+    entries.extend(app.queue.iter().cloned());
+    let path = match write_m3u(
+        "queue",
+        entries
+            .iter()
+            .map(|entry| (None, entry.title.as_str(), entry.url.as_str())),
+    ) {
+        Ok(path) => path,
+        Err(err) => {
+            app.toasts.push(Level::Error, err);
+            return;
+        }
+    };
+    let args = vec![
+        app.config.ytdl_format(),
+        "--prefetch-playlist=yes".to_string(),
+    ];
+    match spawn_player(app, &path.to_string_lossy(), None, args) {
+        Ok(()) => {
+            let first = entries[0].clone();
+            record_watch(app, &first.id, &first.title);
+            app.handoff = Some(Handoff { entries, pos: 0 });
+            app.toasts.push(
+                Level::Info,
+                format!("Playing: {} ({} queued)", first.title, app.queue.len()),
+            );
+        }
+        Err(err) => app.toasts.push(Level::Error, err),
+    }
+}
+
+fn append_to_handoff(app: &mut App, item: QueueItem) {
+    let (Some(handoff), Some(player)) = (app.handoff.as_mut(), app.player.as_ref()) else {
+        return;
+    };
+    match player.append(&item.url) {
+        Ok(()) => handoff.entries.push(item),
+        Err(err) => app.toasts.push(Level::Error, err),
+    }
+}
+
+/// Keeps the now-playing video, history and queue in step when mpv moves
+/// to another entry of a handed-off queue.
+fn follow_handoff(app: &mut App, pos: i64) {
+    let Some(handoff) = app.handoff.as_ref() else {
+        return;
+    };
+    // -1 once the playlist is done; the player exits right after.
+    let Ok(pos) = usize::try_from(pos) else {
+        return;
+    };
+    if pos == handoff.pos || pos >= handoff.entries.len() {
+        return;
+    }
+    let forward = pos > handoff.pos;
+    let finished = app.playing_video.clone();
+    if forward {
+        finish_current_video(app);
+    } else {
+        // Going back: the entry being returned to is the last one played.
+        save_watch_progress(app);
+        app.playing_video = None;
+        app.played.pop();
+    }
+    app.ended_at_eof = false;
+    app.skip_segments.clear();
+    let Some(handoff) = app.handoff.as_mut() else {
+        return;
+    };
+    handoff.pos = pos;
+    let item = handoff.entries[pos].clone();
+    app.queue = handoff.entries[pos + 1..].iter().cloned().collect();
+    if let Some(finished) = finished.filter(|_| forward && app.repeat == Repeat::All) {
+        append_to_handoff(app, finished.clone());
+        app.queue.push_back(finished);
+    }
+    record_watch(app, &item.id, &item.title);
+    app.toasts
+        .push(Level::Info, format!("Playing: {}", item.title));
+}
+
+/// Drops queue entry `index`, from mpv's playlist too when it has the queue.
+fn remove_queued(app: &mut App, index: usize) {
+    if app.queue.remove(index).is_none() {
+        return;
+    }
+    let (Some(handoff), Some(player)) = (app.handoff.as_mut(), app.player.as_ref()) else {
+        return;
+    };
+    let entry = handoff.pos + 1 + index;
+    if entry < handoff.entries.len() {
+        handoff.entries.remove(entry);
+        if let Err(err) = player.playlist_remove(entry) {
+            app.toasts.push(Level::Error, err);
+        }
+    }
+}
+
+/// Moves on after `finished` played to the end, honouring the repeat mode.
+/// mpv loops a single video itself, so "repeat one" only gets here for
+/// players without IPC.
+fn play_after(app: &mut App, finished: Option<QueueItem>) {
+    match (app.repeat, finished) {
+        (Repeat::One, Some(item)) => play_queue_item(app, item),
+        (Repeat::All, Some(item)) => {
+            app.queue.push_back(item);
+            play_next_queued(app);
+        }
+        _ => play_next_queued(app),
+    }
+}
+
+fn play_next_queued(app: &mut App) {
+    let next = if app.shuffle && !app.queue.is_empty() {
+        fastrand::usize(..app.queue.len())
+    } else {
+        0
+    };
+    if let Some(item) = app.queue.remove(next) {
+        play_queue_item(app, item);
+    }
+}
+
+/// Queues a download of the selected video and opens the downloads list.
+/// A video that is already listed isn't downloaded twice.
+fn download_selected(app: &mut App) {
+    app.views.downloads.selected = match app.screen.results.get(app.screen.selected) {
+        Some(SearchResultItem::Video(video)) => {
+            let item = QueueItem {
+                id: video.id.clone(),
+                title: video.title.clone(),
+                url: video.url.clone(),
+            };
+            add_download(app, item)
+        }
+        _ => app.downloads.len().saturating_sub(1),
+    };
+    switch_view(app, View::Downloads);
+}
+
+/// Adds `video` to the downloads list and starts it, unless it's already
+/// listed. Returns its index in the list.
+fn add_download(app: &mut App, video: QueueItem) -> usize {
+    if let Some(existing) = app
+        .downloads
+        .iter()
+        .position(|item| item.video_id == video.id)
+    {
+        return existing;
+    }
+    app.downloads.push(DownloadItem {
+        id: 0,
+        video_id: video.id,
+        title: video.title,
+        url: video.url,
+        progress: 0.0,
+        state: DownloadState::Running,
+        handle: None,
+    });
+    let index = app.downloads.len() - 1;
+    start_download(app, index);
+    index
+}
+
+/// Starts (or restarts) yt-dlp for `app.downloads[index]`.
+fn start_download(app: &mut App, index: usize) {
+    let id = app.next_download_id;
+    app.next_download_id += 1;
+    let ytdlp_bin = env::var("YTBV_YTDLP").unwrap_or_else(|_| "yt-dlp".to_string());
+    let dir = app.config.download_dir();
+    let tx = app.tx.clone();
+    let Some(item) = app.downloads.get_mut(index) else {
+        return;
+    };
+    item.id = id;
+    item.progress = 0.0;
+    let started = dir.and_then(|dir| {
+        Download::spawn(&ytdlp_bin, &item.url, &dir, move |event| {
+            let _ = tx.send(AppMsg::Download { id, event });
+        })
+    });
+    match started {
+        Ok(handle) => {
+            item.handle = Some(handle);
+            item.state = DownloadState::Running;
+        }
+        Err(err) => {
+            let title = format!("Download failed: {}", item.title);
+            item.state = DownloadState::Failed(err.clone());
+            show_error(app, title, err, Retry::Download(id));
+        }
+    }
+}
+
+/// Skips to the next queued video, or to the next entry of a playlist
+/// that mpv is playing.
+fn play_next(app: &mut App) {
+    if let Some(player) = app.player.as_ref().filter(|_| app.handoff.is_some()) {
+        if !app.queue.is_empty() {
+            if let Err(err) = player.playlist_next() {
+                app.toasts.push(Level::Error, err);
+            }
+            return;
+        }
+    }
+    if !app.queue.is_empty() {
+        play_next_queued(app);
+        return;
+    }
+    match app.player.as_ref() {
+        Some(player) if app.playing_video.is_none() => {
+            if let Err(err) = player.playlist_next() {
+                app.toasts.push(Level::Error, err);
+            }
+        }
+        _ => app
+            .toasts
+            .push(Level::Info, "The queue is empty.".to_string()),
+    }
+}
+
+/// Goes back to the previously played video; the current one is put back
+/// at the front of the queue.
+fn play_previous(app: &mut App) {
+    let handoff_back = app.handoff.as_ref().is_some_and(|handoff| handoff.pos > 0);
+    if let Some(player) = app
+        .player
+        .as_ref()
+        .filter(|_| app.playing_video.is_none() || handoff_back)
+    {
+        if let Err(err) = player.playlist_prev() {
+            app.toasts.push(Level::Error, err);
+        }
+        return;
+    }
+    let playing = app.playing_video.is_some();
+    stop_player(app);
+    if playing {
+        if let Some(current) = app.played.pop() {
+            app.queue.push_front(current);
+        }
+    }
+    match app.played.pop() {
+        Some(item) => play_queue_item(app, item),
+        None => app
+            .toasts
+            .push(Level::Info, "Nothing was played before this.".to_string()),
+    }
+}
+
+fn play_queue_item(app: &mut App, item: QueueItem) {
+    if can_hand_off(app) {
+        hand_off_queue(app, item);
+        return;
+    }
+    match play_url(app, &item.url, None) {
+        Ok(()) => {
+            record_watch(app, &item.id, &item.title);
+            app.toasts
+                .push(Level::Info, format!("Playing: {}", item.title));
+        }
+        Err(err) => app.toasts.push(Level::Error, err),
+    }
+}
+
+/// Appends a played video to the watch history. Title and channel are taken
+/// from the loaded results when the video is listed there.
+fn record_watch(app: &mut App, video_id: &str, title: &str) {
+    let listed = app.screen.results.iter().find_map(|item| match item {
+        SearchResultItem::Video(video) if video.id == video_id => Some(video),
+        _ => None,
+    });
+    app.history.push(HistoryEntry {
+        id: video_id.to_string(),
+        title: listed.map_or_else(|| title.to_string(), |video| video.title.clone()),
+        channel: listed.and_then(|video| video.channel.clone()),
+        watched_at: OffsetDateTime::now_utc().unix_timestamp(),
+        watched_secs: None,
+    });
+    app.watched.insert(video_id.to_string());
+    app.playing_video = Some(QueueItem {
+        id: video_id.to_string(),
+        title: title.to_string(),
+        url: format!("https://www.youtube.com/watch?v={video_id}"),
+    });
+    if app.config.sponsorblock_skip {
+        match listed.and_then(|video| video.sponsor_segments.clone()) {
+            Some(segments) => app.skip_segments = segments,
+            None => {
+                let video_id = video_id.to_string();
+                let tx = app.tx.clone();
+                thread::spawn(move || {
+                    let result = sponsor_segments(&video_id);
+                    let _ = tx.send(AppMsg::SponsorSegments { video_id, result });
+                });
+            }
+        }
+    }
+    if let Err(err) = storage::save_history(&app.history) {
+        app.toasts
+            .push(Level::Error, format!("Failed to save watch history: {err}"));
+    }
+    if app.config.watch_later_auto_remove {
+        let before = app.watch_later.len();
+        app.watch_later.retain(|entry| entry.id != video_id);
+        if app.watch_later.len() != before {
+            if let Err(err) = storage::save_watch_later(&app.watch_later) {
+                app.toasts
+                    .push(Level::Error, format!("Failed to save watch later: {err}"));
+            }
+        }
+    }
+}
+
+/// Saves the progress of the current video and moves it to `played`.
+fn finish_current_video(app: &mut App) {
+    save_watch_progress(app);
+    if let Some(item) = app.playing_video.take() {
+        app.played.push(item);
+    }
+}
+
+/// Stores where the current video stopped so it can be resumed later.
+/// Finished or barely started videos are forgotten instead.
+fn save_watch_progress(app: &mut App) {
+    const MIN_SECS: f64 = 30.0;
+    let Some(video_id) = app.playing_video.as_ref().map(|item| item.id.clone()) else {
+        return;
+    };
+    let reached = match app.playback.as_ref() {
+        Some(playback) if app.ended_at_eof => playback.duration,
+        Some(playback) => playback.position,
+        None => None,
+    };
+    if let Some(reached) = reached {
+        record_watched_secs(app, &video_id, reached as u64);
+    }
+    let playback = app.playback.as_ref();
+    let unfinished = match (
+        playback.and_then(|p| p.position),
+        playback.and_then(|p| p.duration),
+    ) {
+        (Some(position), Some(duration))
+            if !app.ended_at_eof && position >= MIN_SECS && duration - position >= MIN_SECS =>
+        {
+            Some(WatchProgress { position, duration })
+        }
+        _ => None,
+    };
+    match unfinished {
+        Some(progress) => {
+            app.progress.insert(video_id, progress);
+        }
+        None => {
+            if app.progress.remove(&video_id).is_none() {
+                return;
+            }
+        }
+    }
+    if let Err(err) = storage::save_progress(&app.progress) {
+        app.toasts.push(
+            Level::Error,
+            format!("Failed to save watch progress: {err}"),
+        );
+    }
+}
+
+/// Notes in the newest History entry of `video_id` how far it was watched.
+fn record_watched_secs(app: &mut App, video_id: &str, secs: u64) {
+    let Some(entry) = app
+        .history
+        .iter_mut()
+        .rev()
+        .find(|entry| entry.id == video_id)
+    else {
+        return;
+    };
+    if entry.watched_secs == Some(secs) {
+        return;
+    }
+    entry.watched_secs = Some(secs);
+    if let Err(err) = storage::save_history(&app.history) {
+        app.toasts
+            .push(Level::Error, format!("Failed to save watch history: {err}"));
+    }
+}
+
+fn toggle_subscription(app: &mut App) {
+    let Some((channel_id, channel_name)) = selected_channel_info(app) else {
+        app.toasts
+            .push(Level::Info, "No channel info for this result.".to_string());
+        return;
+    };
+    let subscribed = if let Some(pos) = app.subscriptions.iter().position(|s| s.id == channel_id) {
+        app.subscriptions.remove(pos);
+        false
+    } else {
+        app.subscriptions.push(Subscription {
+            id: channel_id,
+            name: channel_name.clone(),
+        });
+        true
+    };
+    match storage::save_subscriptions(&app.subscriptions) {
+        Ok(()) if subscribed => app
+            .toasts
+            .push(Level::Info, format!("Subscribed to {channel_name}.")),
+        Ok(()) => app
+            .toasts
+            .push(Level::Info, format!("Unsubscribed from {channel_name}.")),
+        Err(err) => app
+            .toasts
+            .push(Level::Error, format!("Failed to save subscriptions: {err}")),
+    }
+}
+
+/// Writes the history, bookmarks, subscriptions, watch later list and
+/// local playlists into `dir`, or the current directory.
+fn export_data(app: &mut App, csv: bool, dir: &str) {
+    let data = export::Data {
+        history: &app.history,
+        bookmarks: &app.bookmarks,
+        subscriptions: &app.subscriptions,
+        watch_later: &app.watch_later,
+        playlists: &app.playlists,
+    };
+    let dir = if dir.is_empty() { "." } else { dir };
+    match export::write(&data, csv, Path::new(dir)) {
+        Ok(paths) => {
+            let names: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+            app.toasts
+                .push(Level::Info, format!("Exported to {}.", names.join(", ")));
+        }
+        Err(err) => app
+            .toasts
+            .push(Level::Error, format!("Failed to export: {err}")),
+    }
+}
+
+/// `ytbv export [json|csv] [dir]`: the same as `:export`, without the TUI.
+fn export_from_cli(format: &str, dir: &str) -> io::Result<()> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+    let csv = match format {
+        "json" => false,
+        "csv" => true,
+        other => return Err(invalid(format!("unknown export format: {other}"))),
+    };
+    let history = storage::load_history().map_err(invalid)?;
+    let bookmarks = storage::load_bookmarks().map_err(invalid)?;
+    let subscriptions = storage::load_subscriptions().map_err(invalid)?;
+    let watch_later = storage::load_watch_later().map_err(invalid)?;
+    let playlists = storage::load_playlists().map_err(invalid)?;
+    let data = export::Data {
+        history: &history,
+        bookmarks: &bookmarks,
+        subscriptions: &subscriptions,
+        watch_later: &watch_later,
+        playlists: &playlists,
+    };
+    let paths = export::write(&data, csv, Path::new(dir)).map_err(io::Error::other)?;
+    for path in paths {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+/// Adds the channels in an exported subscription list to the
+/// subscriptions. Channels given only by a handle or custom URL are
+/// looked up in the background first.
+fn import_subscriptions(app: &mut App, path: &str) {
+    let imported = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {path}: {err}"))
+        .and_then(|text| import::parse(&text));
+    let imported = match imported {
+        Ok(imported) => imported,
+        Err(err) => {
+            app.toasts.push(Level::Error, err);
+            return;
+        }
+    };
+    if imported.unresolved.is_empty() {
+        finish_import(app, imported);
+        return;
+    }
+    app.toasts.push(
+        Level::Info,
+        format!("Looking up {} channels...", imported.unresolved.len()),
+    );
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let imported = resolve_imported(imported);
+        let _ = tx.send(AppMsg::Imported(imported));
+    });
+}
+
+/// Merges imported channels into the subscriptions, skipping those already
+/// subscribed to.
+fn finish_import(app: &mut App, imported: import::Imported) {
+    let total = imported.subscriptions.len();
+    let mut added = 0;
+    for subscription in imported.subscriptions {
+        if !app.subscriptions.iter().any(|s| s.id == subscription.id) {
+            app.subscriptions.push(subscription);
+            added += 1;
+        }
+    }
+    let mut status = format!(
+        "Imported {added} subscriptions ({} already subscribed).",
+        total - added
+    );
+    if imported.skipped > 0 {
+        status = format!(
+            "{status} Skipped {} entries that aren't YouTube channels.",
+            imported.skipped
+        );
+    }
+    match storage::save_subscriptions(&app.subscriptions) {
+        Ok(()) => app.toasts.push(Level::Info, status),
+        Err(err) => app
+            .toasts
+            .push(Level::Error, format!("Failed to save subscriptions: {err}")),
+    }
+}
+
+fn start_feed(app: &mut App) {
+    if app.screen.searching {
+        return;
+    }
+    if app.subscriptions.is_empty() {
+        app.toasts.push(
+            Level::Info,
+            "No subscriptions yet. Press s on a channel or video to subscribe.".to_string(),
+        );
+        return;
+    }
+    app.screen.searching = true;
+    app.toasts.push(
+        Level::Info,
+        format!(
+            "Loading feed from {} subscriptions...",
+            app.subscriptions.len()
+        ),
+    );
+    let tx = app.tx.clone();
+    let subscriptions = app.subscriptions.clone();
+    let generation = next_search_generation(app);
+    thread::spawn(move || {
+        let result = subscription_feed(subscriptions);
+        let _ = tx.send(AppMsg::Search {
+            generation,
+            source: ResultsSource::Feed,
+            result,
+        });
+    });
+}
+
+fn start_trending(app: &mut App) {
+    start_trending_category(app, TrendingCategory::Now);
+}
+
+fn start_trending_category(app: &mut App, category: TrendingCategory) {
+    if app.screen.searching {
+        return;
+    }
+    app.screen.searching = true;
+    app.toasts.push(
+        Level::Info,
+        format!("Loading trending {} videos...", category.label()),
+    );
+    let tx = app.tx.clone();
+    let generation = next_search_generation(app);
+    thread::spawn(move || {
+        let result = trending_videos(category);
+        let _ = tx.send(AppMsg::Search {
+            generation,
+            source: ResultsSource::Trending(category),
+            result,
+        });
+    });
+}
+
+fn cycle_trending_category(app: &mut App, forward: bool) {
+    let Some(ResultsSource::Trending(category)) = app.screen.source else {
+        return;
+    };
+    let all = TrendingCategory::ALL;
+    let current = all.iter().position(|c| *c == category).unwrap_or(0);
+    let next = if forward {
+        (current + 1) % all.len()
+    } else {
+        (current + all.len() - 1) % all.len()
+    };
+    start_trending_category(app, all[next]);
+}
+
+fn start_channel_videos(app: &mut App, channel_id: String, channel_name: String) {
+    start_channel_tab(app, channel_id, channel_name, ChannelTab::Videos);
+}
+
+fn start_channel_tab(app: &mut App, channel_id: String, channel_name: String, tab: ChannelTab) {
+    if app.screen.searching {
+        return;
+    }
+    app.screen.searching = true;
+    app.toasts.push(
+        Level::Info,
+        format!(
+            "Loading {} for '{channel_name}'...",
+            tab.label().to_lowercase()
+        ),
+    );
+    let tx = app.tx.clone();
+    let generation = next_search_generation(app);
+    thread::spawn(move || {
+        let (name, result) = match channel_tab_items(&channel_id, tab) {
+            Ok(page) => {
+                let name = page
+                    .channel
+                    .as_ref()
+                    .map(|header| header.name.clone())
+                    .unwrap_or(channel_name);
+                (name, Ok(page))
+            }
+            Err(err) => (channel_name, Err(err)),
+        };
+        let source = ResultsSource::Channel {
+            id: channel_id,
+            name,
+            tab,
+        };
+        let _ = tx.send(AppMsg::Search {
+            generation,
+            source,
+            result,
+        });
+    });
+}
+
+/// Moves to the next/previous tab of the channel being browsed, skipping
+/// tabs the channel doesn't have.
+fn cycle_channel_tab(app: &mut App, forward: bool) {
+    let Some(ResultsSource::Channel { id, name, tab }) = app.screen.source.clone() else {
+        return;
+    };
+    let tabs: Vec<ChannelTab> = ChannelTab::ALL
+        .into_iter()
+        .filter(|tab| match (tab, app.screen.channel_header.as_ref()) {
+            (ChannelTab::Live, Some(header)) => header.has_live,
+            (ChannelTab::Shorts, Some(header)) => header.has_shorts,
+            _ => true,
+        })
+        .collect();
+    let current = tabs.iter().position(|t| *t == tab).unwrap_or(0);
+    let next = if forward {
+        (current + 1) % tabs.len()
+    } else {
+        (current + tabs.len() - 1) % tabs.len()
+    };
+    start_channel_tab(app, id, name, tabs[next]);
+}
+
+fn queue_channel_avatar(app: &mut App) {
+    let Some(header) = app.screen.channel_header.as_ref() else {
+        return;
+    };
+    if header.avatar_path.is_some() {
+        return;
+    }
+    let Some(url) = header.avatar_url.clone() else {
+        return;
+    };
+    let channel_id = header.id.clone();
+    let limit = app.config.thumbnail_cache_bytes();
+    let tx = app.tx.clone();
+    app.thumbnail_pool.spawn(move |client| {
+        let result = download_thumbnail(client, &url, limit);
+        let _ = tx.send(AppMsg::ChannelAvatar { channel_id, result });
+    });
+}
+
+fn start_playlist_videos(app: &mut App, playlist_id: String, playlist_name: String) {
+    if app.screen.searching {
+        return;
+    }
+    app.screen.searching = true;
+    app.toasts.push(
+        Level::Info,
+        format!("Loading playlist '{playlist_name}'..."),
+    );
+    let tx = app.tx.clone();
+    let generation = next_search_generation(app);
+    thread::spawn(move || {
+        let (name, result) = match playlist_videos(&playlist_id) {
+            Ok((page, name)) => (name, Ok(page)),
+            Err(err) => (playlist_name, Err(err)),
+        };
+        let source = ResultsSource::Playlist {
+            id: playlist_id,
+            name,
+        };
+        let _ = tx.send(AppMsg::Search {
+            generation,
+            source,
+            result,
+        });
+    });
+}
+
+fn ui(f: &mut Frame<'_>, app: &mut App) {
+    let theme = app.theme;
+    let size = f.size();
+
+    if app.zen == ZenMode::Zen {
+        hide_images(f, app);
+        if size.height > 2 {
+            let area = ratatui::layout::Rect::new(size.x, size.y + size.height / 2, size.width, 1);
+            let hint = Span::styled("Zen mode — Z to leave", Style::default().fg(theme.muted));
+            f.render_widget(Paragraph::new(hint).alignment(Alignment::Center), area);
+        }
+        render_overlays(f, app, size);
+        return;
+    }
+
+    let mut spec = PaneLayout::for_preset(app.config.layout);
+    let compact = app.zen == ZenMode::Compact;
+    if compact {
+        spec.details = DetailsPlacement::Hidden;
+    }
+    let inner_height = size.height.saturating_sub(2);
+    let mut preview_height = app
+        .config
+        .details_height
+        .unwrap_or((inner_height / 3).clamp(4, 14));
+    let max_preview_height = inner_height
+        .saturating_sub(4)
+        .min(spec.max_details_height.unwrap_or(u16::MAX));
+    if preview_height > max_preview_height {
+        preview_height = max_preview_height;
+    }
+    app.details_height = preview_height;
+    app.max_details_height = max_preview_height;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(if compact && app.screen.focus != Focus::Search {
+                0
+            } else {
+                3
+            }),
+            Constraint::Min(0),
+            Constraint::Length(if spec.details == DetailsPlacement::Bottom {
+                preview_height
+            } else {
+                0
+            }),
+            Constraint::Length(1),
+        ])
+        .split(size);
+
+    if size.height > 0 && size.width > 2 {
+        // The view bar uses the top margin row above the Search block.
+        let titles: Vec<String> = View::ALL
+            .iter()
+            .enumerate()
+            .map(|(index, view)| format!("{} {}", index + 1, view.label()))
+            .collect();
+        // Each title is padded by a space on both sides, with a divider between.
+        let views_width = titles.iter().map(|title| title.width() + 3).sum::<usize>() as u16;
+        let selected = View::ALL.iter().position(|&view| view == app.views.current);
+        let views = Tabs::new(titles)
+            .select(selected.unwrap_or(0))
+            .highlight_style(
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            );
+        let area = ratatui::layout::Rect::new(size.x + 1, size.y, size.width - 2, 1);
+        f.render_widget(views, area);
+        let right = area.right();
+        let tabs_x = (area.x + views_width + 2).min(right);
+        let area = ratatui::layout::Rect::new(tabs_x, area.y, right - tabs_x, 1);
+        if !app.tabs.is_empty() && app.views.current == View::Search {
+            render_search_tabs(f, app, area);
+        }
+    }
+
+    if !matches!(app.views.current, View::Search | View::Feed) {
+        // Inline images would be printed over these screens.
+        hide_images(f, app);
+        let area = chunks[0].union(chunks[2]);
+        views::render(f, app, area);
+        render_status_bar(f, app, chunks[3]);
+        render_overlays(f, app, size);
+        return;
+    }
+
+    if app.views.current == View::Feed {
+        let block = Block::default().borders(Borders::ALL).title("Feed");
+        let text = format!(
+            "Latest uploads from {} subscribed channels. Press f to refresh.",
+            app.subscriptions.len()
+        );
+        f.render_widget(
+            Paragraph::new(Span::styled(text, Style::default().fg(theme.muted))).block(block),
+            chunks[0],
+        );
+    } else {
+        render_search_box(f, app, chunks[0]);
+    }
+
+    app.side_panes = side_panes(app, chunks[1].width);
+    if matches!(app.screen.focus, Focus::Queue | Focus::Downloads)
+        && !app.side_panes.contains(&app.screen.focus)
+    {
+        // The pane went away, like the queue once its last video started.
+        app.screen.focus = Focus::Results;
+    }
+    let main_chunk = if app.side_panes.is_empty() {
+        chunks[1]
+    } else {
+        let parts = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(SIDE_PANE_WIDTH)])
+            .split(chunks[1]);
+        let panes = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![
+                Constraint::Ratio(1, app.side_panes.len() as u32);
+                app.side_panes.len()
+            ])
+            .split(parts[1]);
+        for (&pane, &area) in app.side_panes.iter().zip(panes.iter()) {
+            views::render_pane(f, app, pane, area);
+        }
+        parts[0]
+    };
+
+    let (results_chunk, details_area) = match spec.details {
+        DetailsPlacement::Bottom => (main_chunk, Some(chunks[2])),
+        DetailsPlacement::Right => {
+            let parts = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(main_chunk);
+            (parts[0], Some(parts[1]))
+        }
+        DetailsPlacement::Hidden => (main_chunk, None),
+    };
+
+    let results_title = results_title(app);
+    let results_block = Block::default()
+        .borders(Borders::ALL)
+        .title(results_title)
+        .border_style(match app.screen.focus {
+            Focus::Results => Style::default().fg(theme.accent),
+            Focus::Search | Focus::Details | Focus::Queue | Focus::Downloads => Style::default(),
+        });
+    let (header_area, results_area) = match (&app.screen.source, &app.screen.channel_header) {
+        (Some(ResultsSource::Channel { .. }), Some(_)) if results_chunk.height >= 14 => {
+            let parts = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(6), Constraint::Min(0)])
+                .split(results_chunk);
+            (Some(parts[0]), parts[1])
+        }
+        _ => (None, results_chunk),
+    };
+    app.avatar_area = None;
+    if let Some(header_area) = header_area {
+        render_channel_header(f, app, header_area);
+    }
+    if app.avatar_area.is_none() {
+        if let Some(last) = app.last_avatar.as_ref() {
+            f.render_widget(Clear, last.area);
+        }
+    }
+
+    f.render_widget(results_block.clone(), results_area);
+    let inner = results_block.inner(results_area);
+    app.results_view_rows = inner.height as usize;
+
+    if app.grid_view {
+        render_results_grid(f, app, inner);
+    } else {
+        app.grid_cells.clear();
+        render_results_list(f, app, inner);
+    }
+
+    match details_area {
+        Some(area) => render_details(f, app, area, spec.thumbnail),
+        None => {
+            app.thumb_area = None;
+            if let Some(last) = app.last_thumb.as_ref() {
+                f.render_widget(Clear, last.area);
+            }
+        }
+    }
+
+    render_status_bar(f, app, chunks[3]);
+    render_overlays(f, app, size);
+    if blocks::wanted(app.config.graphics) {
+        draw_half_blocks(f, app);
+    }
+}
+
+/// Draws the thumbnails and avatar laid out in this frame into its cells,
+/// where they would otherwise be printed after it.
+fn draw_half_blocks(f: &mut Frame<'_>, app: &mut App) {
+    let video_thumb = |index: usize| match app.screen.results.get(index) {
+        Some(SearchResultItem::Video(video)) => video.thumbnail_path.clone(),
+        _ => None,
+    };
+    let mut images: Vec<(PathBuf, ratatui::layout::Rect)> = app
+        .grid_cells
+        .iter()
+        .filter_map(|&(index, area)| Some((video_thumb(index)?, area)))
+        .collect();
+    images.extend(
+        app.thumb_area
+            .and_then(|area| Some((selected_thumbnail(app)?.clone(), area))),
+    );
+    images.extend(app.avatar_area.and_then(|area| {
+        let path = app.screen.channel_header.as_ref()?.avatar_path.clone()?;
+        Some((path, area))
+    }));
+    for (path, area) in images {
+        app.half_blocks.draw(&path, area, f.buffer_mut());
+    }
+}
+
+/// Stops drawing inline images and blanks the ones on screen.
+fn hide_images(f: &mut Frame<'_>, app: &mut App) {
+    app.thumb_area = None;
+    app.avatar_area = None;
+    app.grid_cells.clear();
+    for area in [
+        app.last_thumb.as_ref().map(|last| last.area),
+        app.last_avatar.as_ref().map(|last| last.area),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        f.render_widget(Clear, area);
+    }
+}
+
+/// Draws the Details pane for the selected result into `area`, with the
+/// thumbnail on `thumbnail`'s side of the text.
+fn render_details(
+    f: &mut Frame<'_>,
+    app: &mut App,
+    area: ratatui::layout::Rect,
+    thumbnail: Option<ThumbSide>,
+) {
+    let theme = app.theme;
+    let preview_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Details")
+        .border_style(match app.screen.focus {
+            Focus::Details => Style::default().fg(theme.accent),
+            Focus::Search | Focus::Results | Focus::Queue | Focus::Downloads => Style::default(),
+        });
+    let preview_inner = preview_block.inner(area);
+    f.render_widget(preview_block, area);
+
+    let (preview, preview_lines) = match app.screen.results.get(app.screen.selected) {
+        Some(SearchResultItem::Video(video)) => {
+            let views = video
+                .view_count
+                .map(format_views)
+                .unwrap_or_else(|| "- views".to_string());
+            let duration = video
+                .duration
+                .map(format_duration)
+                .unwrap_or_else(|| "-".to_string());
+            let uploader = video.channel.clone().unwrap_or_else(|| "-".to_string());
+            let published = format_published(video.publish_date_txt.as_deref(), video.publish_date);
+            let mut lines = vec![
+                Line::from(Span::styled(
+                    video.display_title(active_branding(
+                        &app.branding,
+                        app.original_branding,
+                        &video.id,
+                    )),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                Line::from(Span::styled(views, Style::default().fg(theme.stats))),
+                if video.is_live {
+                    Line::from(live_badge(&theme))
+                } else {
+                    Line::from(Span::styled(
+                        format!("Length: {duration}"),
+                        Style::default().fg(theme.positive),
+                    ))
+                },
+                Line::from(Span::styled(
+                    format!("Uploaded by {uploader}"),
+                    Style::default().fg(theme.channel),
+                )),
+                Line::from(Span::styled(published, Style::default().fg(theme.date))),
+            ];
+            if let Some(votes) = app.votes.get(&video.id).copied().flatten() {
+                lines.push(votes_line(votes, &theme));
+            }
+            if let Some(info) = app.video_info.get(&video.id).and_then(Option::as_ref) {
+                lines.extend(video_info_lines(info, &theme));
+            }
+            if let Some(segments) = video.sponsor_segments.as_ref().filter(|s| !s.is_empty()) {
+                let total: f64 = segments.iter().map(|(start, end)| end - start).sum();
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "Sponsored: {} ({} segments)",
+                        format_duration(total as u64),
+                        segments.len()
+                    ),
+                    Style::default().fg(theme.alert),
+                )));
+            }
+            (Paragraph::new(lines.clone()), lines.len())
+        }
+        Some(SearchResultItem::Channel(channel)) => {
+            let subs = channel
+                .subscriber_count
+                .map(format_subscribers)
+                .unwrap_or_else(|| "- subscribers".to_string());
+            let handle = channel.handle.clone().unwrap_or_else(|| "-".to_string());
+            let desc = channel
+                .description
+                .clone()
+                .unwrap_or_else(|| "-".to_string());
+            let lines = vec![
+                Line::from(Span::styled(
+                    &channel.name,
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                Line::from(Span::styled(
+                    format!("Handle: {handle}"),
+                    Style::default().fg(theme.channel),
+                )),
+                Line::from(Span::styled(subs, Style::default().fg(theme.stats))),
+                Line::from(Span::styled(desc, Style::default().fg(theme.date))),
+            ];
+            (Paragraph::new(lines.clone()), lines.len())
+        }
+        Some(SearchResultItem::Playlist(playlist)) => {
+            let channel = playlist.channel.clone().unwrap_or_else(|| "-".to_string());
+            let count = playlist
+                .video_count
+                .map(|count| format!("{count} videos"))
+                .unwrap_or_else(|| "- videos".to_string());
+            let lines = vec![
+                Line::from(Span::styled(
+                    &playlist.name,
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                Line::from(Span::styled(count, Style::default().fg(theme.stats))),
+                Line::from(Span::styled(
+                    format!("Created by {channel}"),
+                    Style::default().fg(theme.channel),
+                )),
+            ];
+            (Paragraph::new(lines.clone()), lines.len())
+        }
+        None => {
+            let lines = vec![Line::from("No results yet.")];
+            (Paragraph::new(lines.clone()), lines.len())
+        }
+    };
+
+    if let Some(side) = thumbnail {
+        app.thumb_bounds = thumb_bounds(preview_inner, side);
+    }
+    let (text_area, thumb_area) = match (app.screen.results.get(app.screen.selected), thumbnail) {
+        (Some(SearchResultItem::Video(video)), Some(side))
+            if preview_inner.width >= if side == ThumbSide::Top { 20 } else { 50 }
+                && preview_inner.height >= 8
+                && video.thumbnail_path.is_some() =>
+        {
+            let (max_thumb_width, max_thumb_height) = thumb_bounds(preview_inner, side);
+            app.max_thumb_width = max_thumb_width;
+            let max_thumb_width = app
+                .config
+                .thumbnail_width
+                .map_or(max_thumb_width, |width| width.min(max_thumb_width));
+            if max_thumb_width < MIN_THUMB_WIDTH {
+                (preview_inner, None)
+            } else {
+                let (img_w, img_h) = video.thumbnail_size.unwrap_or((160, 90));
+                let (thumb_w, thumb_h) =
+                    fit_dimensions_cells(img_w, img_h, max_thumb_width, max_thumb_height);
+                if thumb_w == 0 || thumb_h == 0 {
+                    (preview_inner, None)
+                } else {
+                    let (thumb_rect, text_rect) =
+                        place_thumbnail(preview_inner, side, thumb_w, thumb_h);
+                    (text_rect, Some(thumb_rect))
+                }
+            }
+        }
+        _ => (preview_inner, None),
+    };
+
+    app.thumb_area = thumb_area;
+    let chapters = match app.screen.results.get(app.screen.selected) {
+        Some(SearchResultItem::Video(video)) => video.chapters.as_deref().unwrap_or(&[]),
+        _ => &[],
+    };
+    let description = match app.screen.results.get(app.screen.selected) {
+        Some(SearchResultItem::Video(video)) => app
+            .video_info
+            .get(&video.id)
+            .and_then(Option::as_ref)
+            .map_or(&[][..], |info| &info.description[..]),
+        _ => &[],
+    };
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(preview_lines as u16), Constraint::Min(0)])
+        .split(text_area);
+    f.render_widget(preview, parts[0]);
+    let mut below = parts[1];
+    // The description and chapters share what's left, each under a
+    // one-line heading.
+    if !description.is_empty() && below.height > 1 {
+        let height = if chapters.is_empty() || below.height < 4 {
+            below.height
+        } else {
+            below.height / 2
+        };
+        let area = ratatui::layout::Rect { height, ..below };
+        below.y += height;
+        below.height -= height;
+        render_details_description(f, app, description, area);
+    }
+    if !chapters.is_empty() && below.height > 1 {
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(below);
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                format!("Chapters ({})", chapters.len()),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            parts[0],
+        );
+        let items: Vec<ListItem> = chapters
+            .iter()
+            .map(|chapter| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", format_duration(u64::from(chapter.position))),
+                        Style::default().fg(theme.accent),
+                    ),
+                    Span::raw(chapter.name.clone()),
+                ]))
+            })
+            .collect();
+        let mut state = ListState::default();
+        state.select(Some(app.chapter_selected.min(chapters.len() - 1)));
+        let highlight = if app.screen.focus == Focus::Details {
+            Style::default()
+                .fg(theme.selection)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let list = List::new(items).highlight_style(highlight);
+        f.render_stateful_widget(list, parts[1], &mut state);
+    }
+    if app.thumb_area.is_none() {
+        if let Some(last) = app.last_thumb.as_ref() {
+            f.render_widget(Clear, last.area);
+        }
+    } else if app.thumb_area.is_some() && !selected_has_thumbnail(app) {
+        if let Some(area) = app.thumb_area {
+            f.render_widget(Clear, area);
+        }
+    }
+}
+
+/// The most cells a thumbnail can take in the Details pane's `inner` area.
+/// Beside the text, it leaves it 20 columns; on top, it takes at most half
+/// the height.
+fn thumb_bounds(inner: ratatui::layout::Rect, side: ThumbSide) -> (u16, u16) {
+    if side == ThumbSide::Top {
+        (inner.width, inner.height / 2)
+    } else {
+        (inner.width.saturating_sub(20), inner.height)
+    }
+}
+
+/// Splits the Details pane into the thumbnail's `width` x `height` cells
+/// and the rest, for the text.
+fn place_thumbnail(
+    inner: ratatui::layout::Rect,
+    side: ThumbSide,
+    width: u16,
+    height: u16,
+) -> (ratatui::layout::Rect, ratatui::layout::Rect) {
+    use ratatui::layout::Rect;
+    let text_width = inner.width.saturating_sub(width);
+    match side {
+        ThumbSide::Right => (
+            Rect::new(
+                inner.x + text_width,
+                inner.y + inner.height.saturating_sub(height),
+                width,
+                height,
+            ),
+            Rect::new(inner.x, inner.y, text_width, inner.height),
+        ),
+        ThumbSide::Left => (
+            Rect::new(inner.x, inner.y, width, height),
+            Rect::new(inner.x + width, inner.y, text_width, inner.height),
+        ),
+        ThumbSide::Top => (
+            Rect::new(inner.x + text_width / 2, inner.y, width, height),
+            Rect::new(
+                inner.x,
+                inner.y + height,
+                inner.width,
+                inner.height.saturating_sub(height),
+            ),
+        ),
+    }
+}
+
+/// Label shown at the left of the status bar.
+fn status_mode(app: &App) -> &'static str {
+    if app.filter_editing {
+        return "FILTER";
+    }
+    if app.command_editing {
+        return "COMMAND";
+    }
+    if app.popup.is_some() {
+        return "POPUP";
+    }
+    match (app.views.current, app.screen.focus) {
+        (View::Search | View::Feed, Focus::Search) => "SEARCH",
+        (View::Search | View::Feed, Focus::Results) if app.grid_view => "GRID",
+        (View::Search | View::Feed, Focus::Results) => "RESULTS",
+        (View::Search | View::Feed, Focus::Details) => "DETAILS",
+        (View::Search | View::Feed, Focus::Queue) => "QUEUE",
+        (View::Search | View::Feed, Focus::Downloads) => "DOWNLOADS",
+        (View::History, _) => "HISTORY",
+        (View::Queue, _) => "QUEUE",
+        (View::Downloads, _) => "DOWNLOADS",
+        (View::Favorites, _) => "FAVORITES",
+        (View::Playlists, _) => "PLAYLISTS",
+        (View::WatchLater, _) => "LATER",
+        (View::Stats, _) => "STATS",
+    }
+}
+
+/// Keys that matter for the focused pane, most relevant first. The status
+/// bar shows as many as fit.
+fn key_hints(app: &App) -> Vec<(&str, &'static str)> {
+    let mut hints = Vec::new();
+    if app.filter_editing {
+        hints.push(("⏎", "Apply"));
+        hints.push(("Esc", "Clear"));
+        return hints;
+    }
+    if app.command_editing {
+        hints.push(("↹", "Complete"));
+        hints.push(("⏎", "Run"));
+        hints.push(("Esc", "Cancel"));
+        let candidates: Vec<_> = command::candidates(&app.command).collect();
+        match candidates[..] {
+            [(name, description)] => hints.push((name, description)),
+            _ => hints.extend(candidates.iter().map(|(name, _)| (*name, ""))),
+        }
+        return hints;
+    }
+    if app.popup.is_some() {
+        hints.push(("Esc", "Close"));
+        return hints;
+    }
+    if app.pending_label.is_some() {
+        hints.push(("1-9 a-z", "Pick row"));
+        hints.push(("Esc", "Cancel"));
+        return hints;
+    }
+    match app.views.current {
+        View::History => {
+            hints.push(("⏎", "Play"));
+            hints.push(("/", "Search"));
+            hints.push(("d", "Remove"));
+            hints.push(("D", "Clear all"));
+        }
+        View::Queue => {
+            hints.push(("⏎", "Play now"));
+            hints.push(("d", "Remove"));
+        }
+        View::Downloads => {
+            hints.push(("c", "Cancel"));
+            hints.push(("r", "Retry"));
+            hints.push(("d", "Remove"));
+        }
+        View::Favorites => {
+            hints.push(("⏎", "Play"));
+            hints.push(("t", "Tags"));
+            hints.push(("d", "Remove"));
+        }
+        View::Playlists if app.views.playlists.open.is_some() => {
+            hints.push(("⏎", "Play from here"));
+            hints.push(("P", "Play all"));
+            hints.push(("J/K", "Move"));
+            hints.push(("d", "Remove"));
+        }
+        View::Playlists => {
+            hints.push(("⏎", "Open"));
+            hints.push(("P", "Play"));
+            hints.push(("n", "New"));
+            hints.push(("d", "Delete"));
+        }
+        View::WatchLater => {
+            hints.push(("⏎", "Play"));
+            hints.push(("P", "Play all"));
+            hints.push(("d", "Remove"));
+        }
+        View::Search | View::Feed | View::Stats => {}
+    }
+    if !matches!(app.views.current, View::Search | View::Feed) {
+        hints.push(("Esc", "Back"));
+        hints.push(("1-9", "Views"));
+        hints.push((":", "Command"));
+        hints.push(("q", "Quit"));
+        return hints;
+    }
+    if app.screen.focus == Focus::Search {
+        hints.push(("⏎", "Search"));
+        hints.push(("↹", "Results"));
+        hints.push(("Ctrl+T", "New tab"));
+        return hints;
+    }
+    if app.screen.focus == Focus::Queue {
+        hints.push(("⏎", "Play now"));
+        hints.push(("d", "Remove"));
+        hints.push(("Esc", "Results"));
+    }
+    if app.screen.focus == Focus::Downloads {
+        hints.push(("c", "Cancel"));
+        hints.push(("d", "Remove"));
+        hints.push(("Esc", "Results"));
+    }
+
+    let video_selected = matches!(
+        app.screen.results.get(app.screen.selected),
+        Some(SearchResultItem::Video(_))
+    );
+    if app.screen.focus == Focus::Results {
+        hints.push(("↑/↓ j/k", "Navigate"));
+        hints.push(("⏎", "Select/Play"));
+        if matches!(app.screen.source, Some(ResultsSource::Channel { .. })) {
+            hints.push(("←/→", "Tabs"));
+        } else if matches!(app.screen.source, Some(ResultsSource::Trending(_))) {
+            hints.push(("←/→", "Category"));
+        }
+        if !app.marked.is_empty() {
+            push_hint(&mut hints, app, Action::Enqueue, "Enqueue marked");
+            push_hint(&mut hints, app, Action::Download, "Download marked");
+            push_hint(&mut hints, app, Action::CopyUrl, "Copy URLs");
+            push_hint(
+                &mut hints,
+                app,
+                Action::AddToPlaylist,
+                "Add marked to playlist",
+            );
+            hints.push(("Esc", "Unmark"));
+        } else if video_selected {
+            hints.push(("Space", "Mark"));
+        }
+        push_hint(&mut hints, app, Action::Label, "Jump");
+        if video_selected {
+            push_hint(&mut hints, app, Action::Enqueue, "Enqueue");
+            push_hint(&mut hints, app, Action::Related, "Related");
+        }
+        if let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) {
+            let info = app.video_info.get(&video.id).and_then(Option::as_ref);
+            if info.is_some_and(|info| info.description.len() > 1) {
+                hints.push(("⇧↑/↓", "Description"));
+            }
+        }
+        hints.push(("F", "Filter"));
+        hints.push(("/", "Search"));
+    }
+    if app.screen.focus == Focus::Details {
+        if let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) {
+            if video.chapters.as_ref().is_some_and(|c| !c.is_empty()) {
+                hints.push(("↑/↓", "Chapters"));
+                hints.push(("⏎", "Play from chapter"));
+            }
+        }
+        if selected_channel_info(app).is_some() {
+            hints.push(("c", "Channel videos"));
+        }
+    }
+    if !app.screen.back_stack.is_empty() {
+        hints.push(("⌫", "Back"));
+    }
+    if current_playlist_info(app).is_some() {
+        push_hint(&mut hints, app, Action::PlayPlaylist, "Play playlist");
+    }
+    if let Some((channel_id, _)) = selected_channel_info(app) {
+        let label = if app.subscriptions.iter().any(|s| s.id == channel_id) {
+            "Unsubscribe"
+        } else {
+            "Subscribe"
+        };
+        push_hint(&mut hints, app, Action::Subscribe, label);
+    }
+    hints.push(("↹", "Nav"));
+    hints.push(("1-9", "Views"));
+    hints.push((":", "Command"));
+    hints.push(("q", "Quit"));
+    if app.screen.focus == Focus::Results {
+        if video_selected {
+            push_hint(&mut hints, app, Action::Comments, "Comments");
+            push_hint(&mut hints, app, Action::Description, "Description");
+            push_hint(&mut hints, app, Action::Transcript, "Transcript");
+            push_hint(&mut hints, app, Action::WatchLater, "Watch later");
+        }
+        push_hint(&mut hints, app, Action::ShowQueue, "Queue");
+        push_hint(&mut hints, app, Action::Lucky, "Lucky");
+        push_hint(&mut hints, app, Action::Sort, "Sort");
+        let next_view = if app.grid_view {
+            "List"
+        } else if app.wrap_rows {
+            "Grid"
+        } else {
+            "Wrap"
+        };
+        push_hint(&mut hints, app, Action::ResultsStyle, next_view);
+        push_hint(&mut hints, app, Action::Zen, app.zen.next().label());
+        push_hint(&mut hints, app, Action::Region, "Region");
+        push_hint(&mut hints, app, Action::LiveFilter, "Live");
+        push_hint(
+            &mut hints,
+            app,
+            Action::HideWatched,
+            if app.hide_watched {
+                "Show watched"
+            } else {
+                "Hide watched"
+            },
+        );
+        push_hint(
+            &mut hints,
+            app,
+            Action::HideShorts,
+            if app.hide_shorts {
+                "Show Shorts"
+            } else {
+                "Hide Shorts"
+            },
+        );
+    }
+    push_hint(&mut hints, app, Action::Trending, "Trending");
+    push_hint(&mut hints, app, Action::Feed, "Feed");
+    hints
+}
+
+/// Adds the hint for `action` under whatever key it is bound to.
+fn push_hint<'a>(
+    hints: &mut Vec<(&'a str, &'static str)>,
+    app: &'a App,
+    action: Action,
+    label: &'static str,
+) {
+    if let Some(key) = app.keys.label(action) {
+        hints.push((key, label));
+    }
+}
+
+/// The bottom bar: the current mode, the filter or command prompt while one
+/// is open, and as many key hints as fit after it.
+fn render_status_bar(f: &mut Frame<'_>, app: &App, area: ratatui::layout::Rect) {
+    let theme = app.theme;
+    let mode = format!(" {} ", status_mode(app));
+    let mut used = mode.width();
+    let mut spans = vec![
+        Span::styled(
+            mode,
+            Style::default()
+                .fg(theme.badge_text)
+                .bg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" "),
+    ];
+    used += 1;
+    if app.filter_editing {
+        spans.push(Span::styled("/", Style::default().fg(theme.accent)));
+        spans.push(Span::raw(app.screen.filter.clone()));
+        let cursor_x = area.x + (used + 1 + app.screen.filter.width()) as u16;
+        f.set_cursor(cursor_x.min(area.right().saturating_sub(1)), area.y);
+        used += 1 + app.screen.filter.width();
+    } else if app.views.history.search_editing {
+        spans.push(Span::styled("/", Style::default().fg(theme.accent)));
+        spans.push(Span::raw(app.views.history.search.clone()));
+        let cursor_x = area.x + (used + 1 + app.views.history.search.width()) as u16;
+        f.set_cursor(cursor_x.min(area.right().saturating_sub(1)), area.y);
+        used += 1 + app.views.history.search.width();
+    } else if app.command_editing {
+        spans.push(Span::styled(":", Style::default().fg(theme.accent)));
+        spans.push(Span::raw(app.command.clone()));
+        let cursor_x = area.x + (used + 1 + app.command.width()) as u16;
+        f.set_cursor(cursor_x.min(area.right().saturating_sub(1)), area.y);
+        used += 1 + app.command.width();
+    }
+    let mut hints = Vec::new();
+    for (key, label) in key_hints(app) {
+        let width = key.width() + label.width() + 3;
+        if used + width > usize::from(area.width) {
+            break;
+        }
+        used += width;
+        hints.push(Span::styled(
+            format!("  {key} "),
+            Style::default().fg(theme.accent),
+        ));
+        hints.push(Span::raw(label));
+    }
+    if !hints.is_empty() {
+        let padding = usize::from(area.width).saturating_sub(used);
+        spans.push(Span::raw(" ".repeat(padding)));
+        spans.extend(hints);
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Draws what sits on top of every view: the now-playing line, popups and
+/// toasts.
+fn render_overlays(f: &mut Frame<'_>, app: &mut App, size: ratatui::layout::Rect) {
+    app.image_areas = app
+        .thumb_area
+        .into_iter()
+        .chain(app.avatar_area)
+        .chain(app.grid_cells.iter().map(|&(_, area)| area))
+        .collect();
+    if size.height > 1 && size.width > 2 {
+        // Like the view bar, the now-playing line uses the bottom margin row.
+        let area = ratatui::layout::Rect::new(size.x + 1, size.bottom() - 1, size.width - 2, 1);
+        f.render_widget(
+            Paragraph::new(now_playing_line(app)).alignment(Alignment::Center),
+            area,
+        );
+    }
+
+    if app.popup.is_some() {
+        // Inline images would be printed on top of the overlay.
+        app.thumb_area = None;
+        app.avatar_area = None;
+        app.grid_cells.clear();
+        render_popup(f, app);
+    }
+    render_toasts(f, app, size);
+}
+
+/// Stacks the toasts in the top-right corner, newest at the top.
+fn render_toasts(f: &mut Frame<'_>, app: &mut App, size: ratatui::layout::Rect) {
+    let theme = app.theme;
+    let width = (size.width / 2).max(24).min(size.width);
+    let mut y = size.y + 1;
+    for toast in app.toasts.iter().rev() {
+        if y + 3 > size.bottom() {
+            break;
+        }
+        let (color, title) = match toast.level {
+            Level::Info => (theme.accent, ""),
+            Level::Warning => (theme.selection, " Warning "),
+            Level::Error => (theme.alert, " Error "),
+        };
+        let text = fit_to_width(&toast.text, usize::from(width.saturating_sub(4)), false);
+        let text = text.trim_end();
+        let toast_width = (text.width() as u16 + 4)
+            .max(title.width() as u16 + 2)
+            .min(width);
+        let area = ratatui::layout::Rect::new(size.right() - toast_width, y, toast_width, 3);
+        y += 3;
+        // Inline images would be printed over the toast.
+        app.grid_cells.retain(|(_, cell)| !cell.intersects(area));
+        if app.thumb_area.is_some_and(|thumb| thumb.intersects(area)) {
+            app.thumb_area = None;
+        }
+        if app
+            .avatar_area
+            .is_some_and(|avatar| avatar.intersects(area))
+        {
+            app.avatar_area = None;
+        }
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(format!(" {text}")).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .border_style(Style::default().fg(color)),
+            ),
+            area,
+        );
+    }
+}
+
+fn render_search_tabs(f: &mut Frame<'_>, app: &App, area: ratatui::layout::Rect) {
+    let theme = app.theme;
+    let mut titles = Vec::with_capacity(app.tabs.len() + 1);
+    for (index, tab) in app.tabs.iter().enumerate() {
+        if index == app.active_tab {
+            titles.push(tab_label(&app.screen.query, app.screen.source.as_ref()));
+        }
+        titles.push(tab_label(&tab.query, tab.source.as_ref()));
+    }
+    if app.active_tab == app.tabs.len() {
+        titles.push(tab_label(&app.screen.query, app.screen.source.as_ref()));
+    }
+    let tabs = Tabs::new(titles).select(app.active_tab).highlight_style(
+        Style::default()
+            .fg(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_widget(tabs, area);
+}
+
+fn render_search_box(f: &mut Frame<'_>, app: &mut App, area: ratatui::layout::Rect) {
+    let theme = app.theme;
+    let search_title = "Search";
+    let search_block = Block::default().borders(Borders::ALL).title(search_title);
+    let search_block = search_block.border_style(match app.screen.focus {
+        Focus::Search => Style::default().fg(theme.accent),
+        Focus::Results | Focus::Details | Focus::Queue | Focus::Downloads => Style::default(),
+    });
+    let inner = search_block.inner(area);
+    f.render_widget(search_block, area);
+    let width = usize::from(inner.width);
+    if width == 0 {
+        return;
+    }
+
+    // Scroll horizontally just enough to keep the cursor in the box.
+    let cursor_column = app.screen.query[..app.screen.cursor].width();
+    let end_scroll = (app.screen.query.width() + 1).saturating_sub(width);
+    app.query_scroll = app.query_scroll.min(end_scroll);
+    if cursor_column < app.query_scroll {
+        app.query_scroll = cursor_column;
+    } else if cursor_column >= app.query_scroll + width {
+        app.query_scroll = cursor_column + 1 - width;
+    }
+    let mut column = 0;
+    let mut shown = String::new();
+    for grapheme in app.screen.query.graphemes(true) {
+        let grapheme_width = grapheme.width();
+        // A wide character cut by either edge is left out.
+        if column >= app.query_scroll && column + grapheme_width <= app.query_scroll + width {
+            shown.push_str(grapheme);
+        }
+        column += grapheme_width;
+    }
+    f.render_widget(Paragraph::new(shown), inner);
+    if app.screen.focus == Focus::Search {
+        let cursor_x = inner.x + (cursor_column - app.query_scroll) as u16;
+        f.set_cursor(cursor_x, inner.y);
+    }
+}
+
+/// Byte offset of the grapheme boundary before `cursor` in `text`.
+fn previous_grapheme(text: &str, cursor: usize) -> usize {
+    text[..cursor]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(0, |(index, _)| index)
+}
+
+/// Byte offset of the grapheme boundary after `cursor` in `text`.
+fn next_grapheme(text: &str, cursor: usize) -> usize {
+    text[cursor..]
+        .graphemes(true)
+        .next()
+        .map_or(cursor, |grapheme| cursor + grapheme.len())
+}
+
+/// One row of the results list, without its label.
+fn result_row_line(
+    app: &App,
+    index: usize,
+    columns: &ResultColumns,
+    row_width: usize,
+    labels: bool,
+) -> Line<'static> {
+    let theme = app.theme;
+    match app.screen.results.get(index) {
+        Some(SearchResultItem::Video(video)) => {
+            let mut spans = Vec::new();
+            let mut title_width = columns.title_width(row_width);
+            if labels {
+                title_width = title_width.saturating_sub(2);
+            }
+            if app.marked.iter().any(|item| item.id == video.id) {
+                spans.push(Span::styled("✓ ", Style::default().fg(theme.positive)));
+                title_width = title_width.saturating_sub(2);
+            }
+            for badge in video_badges(app, video) {
+                title_width = title_width.saturating_sub(badge.width() + 1);
+                spans.push(badge);
+                spans.push(Span::raw(" "));
+            }
+            let progress = app.progress.get(&video.id).map(|progress| {
+                title_width = title_width.saturating_sub(9);
+                format!(" {}", progress_bar(progress.fraction(), 8))
+            });
+            let branding = active_branding(&app.branding, app.original_branding, &video.id);
+            let title = fit_to_width(video.display_title(branding), title_width, false);
+            if app.watched.contains(&video.id) {
+                spans.push(Span::styled(title, Style::default().fg(theme.muted)));
+            } else {
+                spans.push(Span::raw(title));
+            }
+            if let Some(progress) = progress {
+                spans.push(Span::styled(progress, Style::default().fg(theme.alert)));
+            }
+            columns.push_video_cells(&mut spans, video, &theme);
+            Line::from(spans)
+        }
+        Some(SearchResultItem::Channel(channel)) => {
+            let mut spans = vec![Span::raw(format!("[Channel] {}", channel.name))];
+            if let Some(count) = channel.subscriber_count {
+                spans.push(Span::styled(
+                    format!(" · {}", format_subscribers(count)),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+            Line::from(spans)
+        }
+        Some(SearchResultItem::Playlist(playlist)) => {
+            let mut spans = vec![Span::raw(format!("[Playlist] {}", playlist.name))];
+            if let Some(count) = playlist.video_count {
+                spans.push(Span::styled(
+                    format!(" · {count} videos"),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+            Line::from(spans)
+        }
+        None => Line::from(Span::raw("-")),
+    }
+}
+
+/// A video of the wrapped list: the title over as many lines as it needs
+/// (up to `WRAPPED_TITLE_LINES`), then the channel and stats.
+fn result_row_lines(app: &App, index: usize, row_width: usize) -> Vec<Line<'static>> {
+    let theme = app.theme;
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(index) else {
+        let columns = ResultColumns::for_width(row_width);
+        return vec![result_row_line(app, index, &columns, row_width, false)];
+    };
+    let mut prefix = Vec::new();
+    if app.marked.iter().any(|item| item.id == video.id) {
+        prefix.push(Span::styled("✓ ", Style::default().fg(theme.positive)));
+    }
+    for badge in video_badges(app, video) {
+        prefix.push(badge);
+        prefix.push(Span::raw(" "));
+    }
+    let indent: usize = prefix.iter().map(Span::width).sum();
+    let title_width = row_width.saturating_sub(indent).max(1);
+    let title_style = if app.watched.contains(&video.id) {
+        Style::default().fg(theme.muted)
+    } else {
+        Style::default()
+    };
+    let branding = active_branding(&app.branding, app.original_branding, &video.id);
+    let title = video.display_title(branding);
+    let mut title_lines = wrap_styled(&[(title, title_style)], title_width);
+    if title_lines.len() > WRAPPED_TITLE_LINES {
+        title_lines.truncate(WRAPPED_TITLE_LINES);
+        // Mark the cut with an ellipsis on the last line kept.
+        if let Some(last) = title_lines.last_mut() {
+            let text: String = last
+                .spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect();
+            let text = fit_to_width(&format!("{text} …"), title_width, false);
+            *last = Line::from(Span::styled(text.trim_end().to_string(), title_style));
+        }
+    }
+    let mut lines = Vec::with_capacity(title_lines.len() + 1);
+    for (number, mut line) in title_lines.into_iter().enumerate() {
+        if number == 0 {
+            line.spans.splice(0..0, prefix.drain(..));
+        } else if indent > 0 {
+            line.spans.insert(0, Span::raw(" ".repeat(indent)));
+        }
+        lines.push(line);
+    }
+
+    let mut details = vec![Span::raw(" ".repeat(indent))];
+    let mut push_detail = |text: String, color| {
+        if text.is_empty() {
+            return;
+        }
+        if details.len() > 1 {
+            details.push(Span::styled(" · ", Style::default().fg(theme.muted)));
+        }
+        details.push(Span::styled(text, Style::default().fg(color)));
+    };
+    push_detail(video.channel.clone().unwrap_or_default(), theme.channel);
+    let views = video.view_count.map(format_count).unwrap_or_default();
+    push_detail(views, theme.stats);
+    push_detail(upload_age(video), theme.date);
+    if let Some(duration) = video.duration.filter(|_| !video.is_live) {
+        details.push(Span::raw(" "));
+        details.push(badge(
+            format!(" {} ", format_duration(duration)),
+            theme.positive,
+            &theme,
+        ));
+    }
+    if let Some(progress) = app.progress.get(&video.id) {
+        details.push(Span::styled(
+            format!(" {}", progress_bar(progress.fraction(), 8)),
+            Style::default().fg(theme.alert),
+        ));
+    }
+    lines.push(Line::from(details));
+    lines
+}
+
+fn render_results_list(f: &mut Frame<'_>, app: &mut App, inner: ratatui::layout::Rect) {
+    let theme = app.theme;
+    // One column is kept free for the scrollbar.
+    let row_width = usize::from(inner.width.saturating_sub(1));
+    let columns = ResultColumns::for_width(row_width);
+    let labels = app.pending_label.is_some();
+    let label_width = if labels { 2 } else { 0 };
+    let mut items: Vec<ListItem> = app
+        .screen
+        .visible
+        .iter()
+        .enumerate()
+        .map(|(row, &index)| {
+            let mut lines = if app.wrap_rows {
+                result_row_lines(app, index, row_width.saturating_sub(label_width))
+            } else {
+                vec![result_row_line(app, index, &columns, row_width, labels)]
+            };
+            if labels {
+                let label =
+                    row_label(app, row).map_or("  ".to_string(), |label| format!("{label} "));
+                lines[0].spans.insert(
+                    0,
+                    Span::styled(
+                        label,
+                        Style::default()
+                            .fg(theme.accent)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                );
+                for line in &mut lines[1..] {
+                    line.spans.insert(0, Span::raw("  "));
+                }
+            }
+            ListItem::new(lines)
+        })
+        .collect();
+    let rows = items.len();
+    if app.screen.loading_more {
+        items.push(ListItem::new(Line::from(Span::styled(
+            "Loading more...",
+            Style::default().fg(theme.accent),
+        ))));
+    }
+    let heights: Vec<usize> = items.iter().map(ListItem::height).collect();
+
+    if rows == 0 {
+        app.screen.results_state.select(None);
+        *app.screen.results_state.offset_mut() = 0;
+    } else {
+        app.screen.selected_row = app.screen.selected_row.min(rows - 1);
+        app.screen
+            .results_state
+            .select(Some(app.screen.selected_row));
+    }
+
+    let show_scrollbar = inner.height > 0 && heights.iter().sum::<usize>() > inner.height as usize;
+    let (list_area, scrollbar_area) = if show_scrollbar && inner.width > 1 {
+        (
+            ratatui::layout::Rect::new(inner.x, inner.y, inner.width - 1, inner.height),
+            Some(ratatui::layout::Rect::new(
+                inner.x + inner.width - 1,
+                inner.y,
+                1,
+                inner.height,
+            )),
+        )
+    } else {
+        (inner, None)
+    };
+
+    // Rows can be taller than one line, so the offset is worked out from
+    // their heights: far enough down to show the selected row whole, and no
+    // further than needed to fill the viewport.
+    let viewport_len = list_area.height as usize;
+    let mut rows_on_screen = viewport_len;
+    if viewport_len > 0 && !heights.is_empty() {
+        let selected = app.screen.selected_row.min(heights.len() - 1);
+        let mut offset = app.screen.results_state.offset().min(selected);
+        let mut shown: usize = heights[offset..=selected].iter().sum();
+        while offset < selected && shown > viewport_len {
+            shown -= heights[offset];
+            offset += 1;
+        }
+        let mut below: usize = heights[offset..].iter().sum();
+        while offset > 0 && below + heights[offset - 1] <= viewport_len {
+            offset -= 1;
+            below += heights[offset];
+        }
+        *app.screen.results_state.offset_mut() = offset;
+        if app.wrap_rows {
+            let mut used = 0;
+            rows_on_screen = heights[offset..]
+                .iter()
+                .take_while(|height| {
+                    used += **height;
+                    used <= viewport_len
+                })
+                .count()
+                .max(1);
+            app.results_view_rows = rows_on_screen;
+        }
+    }
+
+    let highlight_style = if app.screen.focus == Focus::Results {
+        Style::default()
+            .fg(theme.selection)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    let results = List::new(items).highlight_style(highlight_style);
+    f.render_stateful_widget(results, list_area, &mut app.screen.results_state);
+
+    if let Some(scrollbar_area) = scrollbar_area {
+        let scroll_pos = app.screen.selected_row.min(rows.saturating_sub(1));
+        let mut scrollbar_state = ScrollbarState::new(rows)
+            .position(scroll_pos)
+            .viewport_content_length(rows_on_screen);
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
+        f.render_stateful_widget(scrollbar, scrollbar_area, &mut scrollbar_state);
+    }
+    prefetch_thumbnails(app, app.screen.results_state.offset(), rows_on_screen);
+}
+
+/// Where `ui` puts the Details pane and its thumbnail, built from the
+/// configured `LayoutPreset`.
+#[derive(Clone, Copy)]
+struct PaneLayout {
+    details: DetailsPlacement,
+    /// `None` leaves the Details pane text-only.
+    thumbnail: Option<ThumbSide>,
+    /// Caps the configured `details_height`.
+    max_details_height: Option<u16>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DetailsPlacement {
+    Bottom,
+    Right,
+    Hidden,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ThumbSide {
+    Left,
+    Right,
+    Top,
+}
+
+impl PaneLayout {
+    fn for_preset(preset: LayoutPreset) -> PaneLayout {
+        let (details, thumbnail, max_details_height) = match preset {
+            LayoutPreset::Default => (DetailsPlacement::Bottom, Some(ThumbSide::Right), None),
+            LayoutPreset::DetailsRight => (DetailsPlacement::Right, Some(ThumbSide::Top), None),
+            LayoutPreset::ThumbnailLeft => (DetailsPlacement::Bottom, Some(ThumbSide::Left), None),
+            LayoutPreset::NoDetails => (DetailsPlacement::Hidden, None, None),
+            LayoutPreset::WideList => (DetailsPlacement::Bottom, None, Some(6)),
+        };
+        PaneLayout {
+            details,
+            thumbnail,
+            max_details_height,
+        }
+    }
+}
+
+/// Width of a grid cell, borders included.
+const GRID_CELL_WIDTH: u16 = 30;
+
+/// Lays the results out as a grid of thumbnails with the title underneath.
+/// The thumbnails themselves are printed after the frame, by
+/// `render_grid_thumbnails`.
+fn render_results_grid(f: &mut Frame<'_>, app: &mut App, inner: ratatui::layout::Rect) {
+    let theme = app.theme;
+    let columns = (inner.width / GRID_CELL_WIDTH).max(1);
+    let cell_width = inner.width / columns;
+    // A 16:9 thumbnail in cells about twice as tall as wide, plus borders
+    // and two lines of title.
+    let thumb_height = (cell_width.saturating_sub(2) * 9 / 32).max(1);
+    let cell_height = thumb_height + 4;
+    let grid_rows = (inner.height / cell_height).max(1);
+    let (columns, grid_rows) = (usize::from(columns), usize::from(grid_rows));
+    app.grid_columns = columns;
+    app.grid_cell_width = cell_width;
+    app.results_view_rows = grid_rows * columns;
+    app.grid_cells.clear();
+    if app.screen.visible.is_empty() {
+        return;
+    }
+    app.screen.selected_row = app.screen.selected_row.min(app.screen.visible.len() - 1);
+    let selected_line = app.screen.selected_row / columns;
+    if selected_line < app.grid_scroll {
+        app.grid_scroll = selected_line;
+    } else if selected_line >= app.grid_scroll + grid_rows {
+        app.grid_scroll = selected_line + 1 - grid_rows;
+    }
+
+    let first = app.grid_scroll * columns;
+    let last = (first + grid_rows * columns).min(app.screen.visible.len());
+    for (slot, row) in (first..last).enumerate() {
+        let index = app.screen.visible[row];
+        let x = inner.x + (slot % columns) as u16 * cell_width;
+        let y = inner.y + (slot / columns) as u16 * cell_height;
+        let area = ratatui::layout::Rect::new(
+            x,
+            y,
+            cell_width,
+            cell_height.min(inner.bottom().saturating_sub(y)),
+        );
+        let selected = row == app.screen.selected_row && app.screen.focus == Focus::Results;
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(if selected { theme.accent } else { theme.muted }));
+        let cell = block.inner(area);
+        f.render_widget(block, area);
+
+        let (title, thumb_size, watched) = match app.screen.results.get(index) {
+            Some(SearchResultItem::Video(video)) => (
+                video
+                    .display_title(active_branding(
+                        &app.branding,
+                        app.original_branding,
+                        &video.id,
+                    ))
+                    .to_string(),
+                video
+                    .thumbnail_path
+                    .as_ref()
+                    .map(|_| video.thumbnail_size.unwrap_or((160, 90))),
+                app.watched.contains(&video.id),
+            ),
+            Some(SearchResultItem::Channel(channel)) => {
+                (format!("[Channel] {}", channel.name), None, false)
+            }
+            Some(SearchResultItem::Playlist(playlist)) => {
+                (format!("[Playlist] {}", playlist.name), None, false)
+            }
+            None => ("-".to_string(), None, false),
+        };
+        if let Some((img_w, img_h)) = thumb_size.filter(|_| cell.height > thumb_height) {
+            let (thumb_w, thumb_h) = fit_dimensions_cells(img_w, img_h, cell.width, thumb_height);
+            if thumb_w > 0 && thumb_h > 0 {
+                let thumb_x = cell.x + (cell.width - thumb_w) / 2;
+                app.grid_cells.push((
+                    index,
+                    ratatui::layout::Rect::new(thumb_x, cell.y, thumb_w, thumb_h),
+                ));
+            }
+        }
+        let style = if selected {
+            Style::default()
+                .fg(theme.selection)
+                .add_modifier(Modifier::BOLD)
+        } else if watched {
+            Style::default().fg(theme.muted)
+        } else {
+            Style::default()
+        };
+        let title_area = ratatui::layout::Rect::new(
+            cell.x,
+            cell.y + thumb_height.min(cell.height),
+            cell.width,
+            cell.height.saturating_sub(thumb_height),
+        );
+        let marked = match app.screen.results.get(index) {
+            Some(SearchResultItem::Video(video)) => {
+                app.marked.iter().any(|item| item.id == video.id)
+            }
+            _ => false,
+        };
+        let mut spans = Vec::new();
+        if let Some(label) = row_label(app, row) {
+            spans.push(Span::styled(
+                format!("{label} "),
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        if marked {
+            spans.push(Span::styled("✓ ", Style::default().fg(theme.positive)));
+        }
+        spans.push(Span::styled(title, style));
+        f.render_widget(
+            Paragraph::new(Line::from(spans)).wrap(Wrap { trim: true }),
+            title_area,
+        );
+    }
+    prefetch_thumbnails(app, first, last - first);
+}
+
+/// Queues the thumbnails of the `rows` results from `first` on, then of as
+/// many again below them, so scrolling a page finds them loaded.
+fn prefetch_thumbnails(app: &mut App, first: usize, rows: usize) {
+    let last = (first + 2 * rows).min(app.screen.visible.len());
+    for row in first..last {
+        let index = app.screen.visible[row];
+        queue_thumbnail(app, index);
+    }
+}
+
+fn render_popup(f: &mut Frame<'_>, app: &mut App) {
+    let theme = app.theme;
+    if let Some(Popup::Sort(selected)) = app.popup {
+        render_sort_menu(f, selected, &theme);
+        return;
+    }
+    if let Some(Popup::Quality(view)) = app.popup.as_ref() {
+        render_quality_menu(f, view, &theme);
+        return;
+    }
+    if let Some(Popup::Resume(view)) = app.popup.as_ref() {
+        render_resume_prompt(f, view, &theme);
+        return;
+    }
+    if let Some(Popup::Clip(view)) = app.popup.as_ref() {
+        render_clip_prompt(f, view, &theme);
+        return;
+    }
+    if let Some(Popup::Jump(view)) = app.popup.as_ref() {
+        render_jump_prompt(f, view, &theme);
+        return;
+    }
+    if let Some(Popup::PlaybackOptions(selected)) = app.popup {
+        render_playback_options(f, app, selected);
+        return;
+    }
+    if let Some(Popup::Confirm(view)) = app.popup.as_ref() {
+        render_confirm_prompt(f, view, &theme);
+        return;
+    }
+    if let Some(Popup::Tags(view)) = app.popup.as_ref() {
+        render_tags_prompt(f, view, &theme);
+        return;
+    }
+    if let Some(Popup::AddToPlaylist(view)) = app.popup.as_ref() {
+        render_playlist_picker(f, app, view);
+        return;
+    }
+    if let Some(Popup::Login(view)) = app.popup.as_ref() {
+        render_login(f, view, &theme);
+        return;
+    }
+    if let Some(Popup::Setup(setup)) = app.popup.as_ref() {
+        render_setup(f, setup, &theme);
+        return;
+    }
+    if let Some(Popup::CacheStats(stats)) = app.popup.as_ref() {
+        let area = centered_rect(f.size(), 60, 30);
+        f.render_widget(Clear, area);
+        render_cache_stats(f, stats, area, &theme);
+        return;
+    }
+    if let Some(Popup::Cast(view)) = app.popup.as_ref() {
+        let area = centered_rect(f.size(), 50, 50);
+        f.render_widget(Clear, area);
+        render_cast_picker(f, app, view, area);
+        return;
+    }
+    if let Some(Popup::Subtitles(view)) = app.popup.as_ref() {
+        let area = centered_rect(f.size(), 50, 60);
+        f.render_widget(Clear, area);
+        render_subtitle_picker(f, view, area, &theme);
+        return;
+    }
+    let area = centered_rect(f.size(), 80, 80);
+    f.render_widget(Clear, area);
+    let Some(popup) = app.popup.as_mut() else {
+        return;
+    };
+    match popup {
+        Popup::Comments(view) => render_comments(f, view, area, &theme),
+        Popup::Description(view) => render_description(f, view, area, &theme),
+        Popup::Transcript(view) => render_transcript(f, view, area, &theme),
+        Popup::Locale(view) => render_locale(f, view, area, &theme),
+        Popup::Error(view) => {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(format!("{} — r retry, Esc close", view.title))
+                .border_style(Style::default().fg(theme.alert));
+            f.render_widget(
+                Paragraph::new(view.text.as_str())
+                    .block(block)
+                    .wrap(Wrap { trim: false })
+                    .scroll((view.scroll, 0)),
+                area,
+            );
+        }
+        Popup::PlayerLog(scroll) => {
+            let lines: Vec<Line> = app
+                .player_log
+                .iter()
+                .map(|line| Line::from(line.clone()))
+                .collect();
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title("Player output — Esc close")
+                .border_style(Style::default().fg(theme.alert));
+            f.render_widget(
+                Paragraph::new(lines)
+                    .block(block)
+                    .wrap(Wrap { trim: false })
+                    .scroll((*scroll, 0)),
+                area,
+            );
+        }
+        Popup::Sort(_)
+        | Popup::Quality(_)
+        | Popup::Resume(_)
+        | Popup::Subtitles(_)
+        | Popup::Cast(_)
+        | Popup::Clip(_)
+        | Popup::Jump(_)
+        | Popup::PlaybackOptions(_)
+        | Popup::Confirm(_)
+        | Popup::Tags(_)
+        | Popup::AddToPlaylist(_)
+        | Popup::CacheStats(_)
+        | Popup::Login(_)
+        | Popup::Setup(_) => {}
+        Popup::Logs(view) => render_logs(f, view, area, &theme),
+    }
+}
+
+fn render_setup(f: &mut Frame<'_>, setup: &setup::Setup, theme: &Theme) {
+    let step = &setup.steps[setup.step];
+    let size = f.size();
+    let width = 60.min(size.width);
+    let height = (step.choices.len() as u16 + 6).min(size.height);
+    let area = ratatui::layout::Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "Setup {}/{}: {} — ⏎ next, ← back, Esc skip",
+            setup.step + 1,
+            setup.steps.len(),
+            step.title
+        ))
+        .border_style(Style::default().fg(theme.accent));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(inner);
+    f.render_widget(
+        Paragraph::new(step.hint)
+            .style(Style::default().fg(theme.muted))
+            .wrap(Wrap { trim: true }),
+        rows[0],
+    );
+    let items: Vec<ListItem> = step
+        .choices
+        .iter()
+        .map(|choice| ListItem::new(choice.as_str()))
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(step.selected));
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .fg(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, rows[1], &mut state);
+}
+
+fn render_logs(f: &mut Frame<'_>, view: &LogsView, area: ratatui::layout::Rect, theme: &Theme) {
+    let height = usize::from(area.height.saturating_sub(2));
+    let end = view.lines.len() - view.scroll.min(view.lines.len());
+    let start = end.saturating_sub(height);
+    let lines: Vec<Line> = view.lines[start..end]
+        .iter()
+        .map(|line| {
+            let color = match logging::level(line) {
+                Some(log::Level::Error) => theme.alert,
+                Some(log::Level::Warn) => theme.selection,
+                Some(log::Level::Info) => Color::Reset,
+                _ => theme.muted,
+            };
+            Line::from(Span::styled(line.clone(), Style::default().fg(color)))
+        })
+        .collect();
+    let title = match logging::path() {
+        Ok(path) => format!("{} — j/k scroll, Esc close", path.display()),
+        Err(_) => "Log — j/k scroll, Esc close".to_string(),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(theme.accent));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_login(f: &mut Frame<'_>, view: &LoginView, theme: &Theme) {
+    let size = f.size();
+    let width = 56.min(size.width);
+    let height = 8.min(size.height);
+    let area = ratatui::layout::Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+    let muted = Style::default().fg(theme.muted);
+    let mut title = "Log in to YouTube — o open in browser, Esc close";
+    let text = match &view.code {
+        None if view.passphrase.is_some() => {
+            title = "Passphrase for the saved login";
+            let typed = view.passphrase.as_deref().unwrap_or_default();
+            vec![
+                Line::from(Span::styled(
+                    "The login is stored encrypted with this passphrase.",
+                    muted,
+                )),
+                Line::from(""),
+                Line::from(Span::styled(
+                    format!("{}_", "*".repeat(typed.chars().count())),
+                    Style::default()
+                        .fg(theme.selection)
+                        .add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                Line::from(Span::styled("Enter continues, Esc cancels", muted)),
+            ]
+        }
+        None => vec![Line::from(Span::styled("Getting a login code...", muted))],
+        Some((user_code, url)) => vec![
+            Line::from(vec![
+                Span::raw("Open "),
+                Span::styled(url.clone(), Style::default().fg(theme.channel)),
+                Span::raw(" and enter"),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                user_code.clone(),
+                Style::default()
+                    .fg(theme.selection)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .alignment(Alignment::Center),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Waiting for the login to be confirmed...",
+                muted,
+            )),
+        ],
+    };
+    f.render_widget(
+        Paragraph::new(text).wrap(Wrap { trim: true }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(theme.accent)),
+        ),
+        area,
+    );
+}
+
+fn render_cache_stats(
+    f: &mut Frame<'_>,
+    stats: &CacheStats,
+    area: ratatui::layout::Rect,
+    theme: &Theme,
+) {
+    let percent = (stats.bytes.saturating_mul(100))
+        .checked_div(stats.limit)
+        .unwrap_or(100);
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{label:<12}"), Style::default().fg(theme.muted)),
+            Span::raw(value),
+        ])
+    };
+    let lines = vec![
+        field("Thumbnails", format!("{} files", stats.files)),
+        field(
+            "Size",
+            format!(
+                "{} of {} ({percent}%)",
+                format_megabytes(stats.bytes),
+                format_megabytes(stats.limit)
+            ),
+        ),
+        field("Location", stats.dir.display().to_string()),
+        field(
+            "Searches",
+            format!(
+                "{} cached, {} (reused for {} minutes)",
+                stats.searches,
+                format_megabytes(stats.search_bytes),
+                stats.search_ttl / 60
+            ),
+        ),
+        Line::from(""),
+        Line::from(Span::styled(
+            "The least recently shown thumbnails go first once the limit is reached.",
+            Style::default().fg(theme.muted),
+        )),
+    ];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Cache — c clear thumbnails, Esc close")
+        .border_style(Style::default().fg(theme.accent));
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+fn render_playlist_picker(f: &mut Frame<'_>, app: &App, view: &PlaylistPickerView) {
+    let theme = app.theme;
+    let size = f.size();
+    let width = 50.min(size.width);
+    if let Some(name) = &view.new_name {
+        let height = 4.min(size.height);
+        let area = ratatui::layout::Rect::new(
+            size.x + (size.width - width) / 2,
+            size.y + (size.height - height) / 2,
+            width,
+            height,
+        );
+        f.render_widget(Clear, area);
+        let lines = vec![
+            Line::from(Span::styled(
+                format!("{name}_"),
+                Style::default()
+                    .fg(theme.selection)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled(
+                "Enter creates it, Esc cancels",
+                Style::default().fg(theme.muted),
+            )),
+        ];
+        f.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("New playlist")
+                    .border_style(Style::default().fg(theme.accent)),
+            ),
+            area,
+        );
+        return;
+    }
+    let height = (app.playlists.len() as u16 + 3).min(size.height);
+    let area = ratatui::layout::Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+    let items: Vec<ListItem> = app
+        .playlists
+        .iter()
+        .map(|playlist| ListItem::new(playlist.name.clone()))
+        .chain(std::iter::once(ListItem::new(Span::styled(
+            "New playlist…",
+            Style::default().fg(theme.muted),
+        ))))
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(view.selected));
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Add {} videos to", view.items.len()))
+                .border_style(Style::default().fg(theme.accent)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_cast_picker(f: &mut Frame<'_>, app: &App, view: &CastView, area: ratatui::layout::Rect) {
+    let theme = app.theme;
+    let title = match &app.cast {
+        Some(renderer) => format!(
+            "Cast — on {}: ␣ {}, x stop, r rescan",
+            renderer.name,
+            if app.cast_paused { "resume" } else { "pause" }
+        ),
+        None => format!("Cast {} — ⏎ send, r rescan", view.video_title),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(theme.accent));
+    if view.loading || view.error.is_some() {
+        let message = view
+            .error
+            .clone()
+            .unwrap_or_else(|| "Searching for Chromecasts and DLNA renderers...".to_string());
+        f.render_widget(Paragraph::new(message).block(block), area);
+        return;
+    }
+    let items: Vec<ListItem> = view
+        .renderers
+        .iter()
+        .map(|renderer| {
+            ListItem::new(Line::from(vec![
+                Span::raw(renderer.name.clone()),
+                Span::styled(
+                    format!(" · {}", renderer.kind()),
+                    Style::default().fg(theme.muted),
+                ),
+            ]))
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(view.selected));
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .fg(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_subtitle_picker(
+    f: &mut Frame<'_>,
+    view: &SubtitleView,
+    area: ratatui::layout::Rect,
+    theme: &Theme,
+) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Subtitles — {}", view.video_title))
+        .border_style(Style::default().fg(theme.accent));
+    if view.loading || view.error.is_some() {
+        let message = view
+            .error
+            .clone()
+            .unwrap_or_else(|| "Loading caption tracks...".to_string());
+        f.render_widget(Paragraph::new(message).block(block), area);
+        return;
+    }
+    let items: Vec<ListItem> = std::iter::once(ListItem::new("No subtitles"))
+        .chain(
+            view.tracks
+                .iter()
+                .map(|track| ListItem::new(caption_track_label(track))),
+        )
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(view.selected));
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .fg(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_resume_prompt(f: &mut Frame<'_>, view: &ResumeView, theme: &Theme) {
+    let size = f.size();
+    let width = 40.min(size.width);
+    let height = 4.min(size.height);
+    let area = ratatui::layout::Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+    let items = vec![
+        ListItem::new(format!(
+            "Resume from {}",
+            format_duration(view.position as u64)
+        )),
+        ListItem::new("Start over"),
+    ];
+    let mut state = ListState::default();
+    state.select(Some(view.selected));
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Continue watching?")
+                .border_style(Style::default().fg(theme.accent)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_confirm_prompt(f: &mut Frame<'_>, view: &ConfirmView, theme: &Theme) {
+    let size = f.size();
+    let width = (view.question.width() as u16 + 4)
+        .clamp(30, 60)
+        .min(size.width);
+    let inner_width = usize::from(width.saturating_sub(2)).max(1);
+    let lines = view.question.width().div_ceil(inner_width).max(1) as u16;
+    let height = (lines + 4).min(size.height);
+    let area = ratatui::layout::Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+    let text = vec![
+        Line::from(view.question.clone()),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y", Style::default().fg(theme.accent)),
+            Span::raw(" Yes  "),
+            Span::styled("n", Style::default().fg(theme.accent)),
+            Span::raw(" No"),
+        ]),
+    ];
+    f.render_widget(
+        Paragraph::new(text).wrap(Wrap { trim: true }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Are you sure?")
+                .border_style(Style::default().fg(theme.alert)),
+        ),
+        area,
+    );
+}
+
+fn render_clip_prompt(f: &mut Frame<'_>, view: &ClipView, theme: &Theme) {
+    let size = f.size();
+    let width = 40.min(size.width);
+    let height = 5.min(size.height);
+    let area = ratatui::layout::Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+    let field = |label: &str, value: &str, focused: bool| {
+        let style = if focused {
+            Style::default()
+                .fg(theme.selection)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let cursor = if focused { "_" } else { "" };
+        Line::from(vec![
+            Span::styled(format!("{label:<7}"), Style::default().fg(theme.muted)),
+            Span::styled(format!("{value}{cursor}"), style),
+        ])
+    };
+    let mut lines = vec![
+        field("Start", &view.start, !view.editing_end),
+        field("End", &view.end, view.editing_end),
+    ];
+    lines.push(match &view.error {
+        Some(err) => Line::from(Span::styled(err.clone(), Style::default().fg(theme.alert))),
+        None => Line::from(Span::styled(
+            "Tab switches, empty end plays to the end",
+            Style::default().fg(theme.muted),
+        )),
+    });
+    f.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Play clip")
+                .border_style(Style::default().fg(theme.accent)),
+        ),
+        area,
+    );
+}
+
+fn render_tags_prompt(f: &mut Frame<'_>, view: &TagsView, theme: &Theme) {
+    let size = f.size();
+    let width = 50.min(size.width);
+    let height = 4.min(size.height);
+    let area = ratatui::layout::Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("{}_", view.input),
+            Style::default()
+                .fg(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            "Separate tags with commas",
+            Style::default().fg(theme.muted),
+        )),
+    ];
+    f.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Tags")
+                .border_style(Style::default().fg(theme.accent)),
+        ),
+        area,
+    );
+}
+
+fn render_jump_prompt(f: &mut Frame<'_>, view: &JumpView, theme: &Theme) {
+    let size = f.size();
+    let width = 40.min(size.width);
+    let height = 4.min(size.height);
+    let area = ratatui::layout::Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+    let hint = match &view.error {
+        Some(err) => Span::styled(err.clone(), Style::default().fg(theme.alert)),
+        None => Span::styled(
+            "hh:mm:ss, mm:ss or seconds",
+            Style::default().fg(theme.muted),
+        ),
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("{}_", view.input),
+            Style::default()
+                .fg(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(hint),
+    ];
+    f.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Play from")
+                .border_style(Style::default().fg(theme.accent)),
+        ),
+        area,
+    );
+}
+
+fn render_quality_menu(f: &mut Frame<'_>, view: &QualityView, theme: &Theme) {
+    let size = f.size();
+    let width = 32.min(size.width);
+    let rows = if view.options.is_empty() {
+        1
+    } else {
+        view.options.len() as u16
+    };
+    let height = (rows + 2).min(size.height);
+    let area = ratatui::layout::Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Quality")
+        .border_style(Style::default().fg(theme.accent));
+    if view.loading || view.error.is_some() || view.options.is_empty() {
+        let message = match &view.error {
+            Some(err) => err.as_str(),
+            None if view.loading => "Loading formats...",
+            None => "No formats available.",
+        };
+        f.render_widget(Paragraph::new(message.to_string()).block(block), area);
+        return;
+    }
+    let items: Vec<ListItem> = view
+        .options
+        .iter()
+        .map(|quality| ListItem::new(quality.label().to_string()))
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(view.selected));
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .fg(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_sort_menu(f: &mut Frame<'_>, selected: usize, theme: &Theme) {
+    let size = f.size();
+    let width = 24.min(size.width);
+    let height = (SortOrder::ALL.len() as u16 + 2).min(size.height);
+    let area = ratatui::layout::Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+    let items: Vec<ListItem> = SortOrder::ALL
+        .iter()
+        .map(|sort| ListItem::new(sort.label()))
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(selected));
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Sort by")
+                .border_style(Style::default().fg(theme.accent)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_playback_options(f: &mut Frame<'_>, app: &App, selected: usize) {
+    let theme = app.theme;
+    let size = f.size();
+    let width = 44.min(size.width);
+    let height = (PlaybackOption::ALL.len() as u16 + 2).min(size.height);
+    let area = ratatui::layout::Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+    f.render_widget(Clear, area);
+    let items: Vec<ListItem> = PlaybackOption::ALL
+        .iter()
+        .map(|option| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<8}", option.label()),
+                    Style::default().fg(theme.muted),
+                ),
+                Span::raw(format!("◂ {} ▸", playback_option_value(app, *option))),
+            ]))
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(selected));
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Playback options (←/→ change)")
+                .border_style(Style::default().fg(theme.accent)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_locale(f: &mut Frame<'_>, view: &LocaleView, area: ratatui::layout::Rect, theme: &Theme) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Region & language (⏎ apply, Esc cancel)")
+        .border_style(Style::default().fg(theme.accent));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+
+    let highlight = Style::default()
+        .fg(theme.selection)
+        .add_modifier(Modifier::BOLD);
+    let column_block = |title: &'static str, focused: bool| {
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(if focused {
+                Style::default().fg(theme.accent)
+            } else {
+                Style::default()
+            })
+    };
+
+    let languages: Vec<ListItem> = LANGUAGES
+        .iter()
+        .map(|lang| ListItem::new(format!("{} ({lang})", lang.name())))
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(view.language));
+    f.render_stateful_widget(
+        List::new(languages)
+            .block(column_block("Language", !view.country_focus))
+            .highlight_style(highlight),
+        columns[0],
+        &mut state,
+    );
+
+    let countries: Vec<ListItem> = COUNTRIES
+        .iter()
+        .map(|country| ListItem::new(format!("{} ({country})", country.name())))
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(view.country));
+    f.render_stateful_widget(
+        List::new(countries)
+            .block(column_block("Region", view.country_focus))
+            .highlight_style(highlight),
+        columns[1],
+        &mut state,
+    );
+}
+
+fn render_transcript(
+    f: &mut Frame<'_>,
+    view: &mut TranscriptView,
+    area: ratatui::layout::Rect,
+    theme: &Theme,
+) {
+    let lang = view
+        .track
+        .and_then(|i| view.tracks.get(i))
+        .map(|track| format!(" [{}]", caption_track_label(track)))
+        .unwrap_or_default();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Transcript{lang} — {}", view.video_title))
+        .border_style(Style::default().fg(theme.accent));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.height == 0 {
+        return;
+    }
+    view.view_height = usize::from(inner.height);
+
+    if view.loading || view.error.is_some() {
+        let message = view.error.as_deref().unwrap_or("Loading captions...");
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                message.to_string(),
+                Style::default().fg(theme.accent),
+            )),
+            inner,
+        );
+        return;
+    }
+
+    let highlight = Style::default()
+        .fg(theme.selection)
+        .add_modifier(Modifier::BOLD);
+    let mut state = ListState::default();
+    let items: Vec<ListItem> = if view.track.is_none() {
+        state.select(Some(view.picker_selected));
+        view.tracks
+            .iter()
+            .map(|track| ListItem::new(caption_track_label(track)))
+            .collect()
+    } else {
+        state.select(Some(view.cue_selected));
+        view.cues
+            .iter()
+            .map(|cue| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", format_duration(cue.start_ms / 1000)),
+                        Style::default().fg(theme.accent),
+                    ),
+                    Span::raw(cue.text.clone()),
+                ]))
+            })
+            .collect()
+    };
+    let list = List::new(items).highlight_style(highlight);
+    f.render_stateful_widget(list, inner, &mut state);
+}
+
+fn caption_track_label(track: &CaptionTrack) -> String {
+    if track.auto_generated {
+        format!("{} (auto-generated)", track.lang_name)
+    } else {
+        track.lang_name.clone()
+    }
+}
+
+fn render_description(
+    f: &mut Frame<'_>,
+    view: &mut DescriptionView,
+    area: ratatui::layout::Rect,
+    theme: &Theme,
+) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Description — {}", view.video_title))
+        .border_style(Style::default().fg(theme.accent));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.width < 4 || inner.height < 3 {
+        return;
+    }
+
+    if view.loading || view.error.is_some() {
+        let message = view.error.as_deref().unwrap_or("Loading description...");
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                message.to_string(),
+                Style::default().fg(theme.accent),
+            )),
+            inner,
+        );
+        return;
+    }
+
+    let links_height = if view.links.is_empty() {
+        0
+    } else {
+        (view.links.len() as u16 + 2).min(inner.height / 3)
+    };
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(links_height)])
+        .split(inner);
+
+    let segments: Vec<(&str, Style)> = view
+        .segments
+        .iter()
+        .map(|segment| {
+            let style = match segment.link.and_then(|i| view.links.get(i).map(|l| (i, l))) {
+                Some((i, link)) => {
+                    let color = match link.target {
+                        LinkTarget::Timestamp(_) => theme.accent,
+                        LinkTarget::YouTube(_) | LinkTarget::Web(_) => theme.channel,
+                    };
+                    let style = Style::default()
+                        .fg(color)
+                        .add_modifier(Modifier::UNDERLINED);
+                    if view.selected_link == Some(i) {
+                        style.add_modifier(Modifier::REVERSED)
+                    } else {
+                        style
+                    }
+                }
+                None => Style::default(),
+            };
+            (segment.text.as_str(), style)
+        })
+        .collect();
+    let lines = wrap_styled(&segments, usize::from(parts[0].width).max(1));
+    view.view_height = usize::from(parts[0].height);
+    view.max_scroll = lines.len().saturating_sub(view.view_height);
+    view.scroll = view.scroll.min(view.max_scroll);
+    let visible: Vec<Line> = lines
+        .into_iter()
+        .skip(view.scroll)
+        .take(view.view_height)
+        .collect();
+    f.render_widget(Paragraph::new(visible), parts[0]);
+
+    if links_height > 0 {
+        let items: Vec<ListItem> = view
+            .links
+            .iter()
+            .map(|link| {
+                let (kind, color) = match link.target {
+                    LinkTarget::Timestamp(_) => ("⏱", theme.accent),
+                    LinkTarget::YouTube(_) => ("▶", theme.alert),
+                    LinkTarget::Web(_) => ("🔗", theme.channel),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{kind} "), Style::default().fg(color)),
+                    Span::raw(link.label.clone()),
+                ]))
+            })
+            .collect();
+        let mut state = ListState::default();
+        state.select(view.selected_link);
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::TOP)
+                    .title("Links (Tab to select, ⏎ to open)"),
+            )
+            .highlight_style(
+                Style::default()
+                    .fg(theme.selection)
+                    .add_modifier(Modifier::BOLD),
+            );
+        f.render_stateful_widget(list, parts[1], &mut state);
+    }
+}
+
+fn render_comments(
+    f: &mut Frame<'_>,
+    view: &mut CommentsView,
+    area: ratatui::layout::Rect,
+    theme: &Theme,
+) {
+    let count = match view.total {
+        Some(total) => format!(" ({total})"),
+        None => String::new(),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Comments{count} — {}", view.video_title))
+        .border_style(Style::default().fg(theme.accent));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.width < 4 || inner.height == 0 {
+        return;
+    }
+
+    let text_width = usize::from(inner.width).saturating_sub(2).max(1);
+    let mut lines: Vec<Line> = Vec::new();
+    let mut timestamp_index = 0;
+    let mut selected_line = None;
+    for comment in &view.comments {
+        let mut header = vec![Span::styled(
+            comment.author.clone(),
+            Style::default()
+                .fg(if comment.by_owner {
+                    theme.selection
+                } else {
+                    theme.channel
+                })
+                .add_modifier(Modifier::BOLD),
+        )];
+        header.push(Span::styled(
+            format!(" · {}", comment.published),
+            Style::default().fg(theme.muted),
+        ));
+        if let Some(likes) = comment.like_count {
+            header.push(Span::styled(
+                format!(" · {likes} likes"),
+                Style::default().fg(theme.positive),
+            ));
+        }
+        if comment.reply_count > 0 {
+            header.push(Span::styled(
+                format!(" · {} replies", comment.reply_count),
+                Style::default().fg(theme.muted),
+            ));
+        }
+        if comment.pinned {
+            header.push(Span::styled(" · pinned", Style::default().fg(theme.date)));
+        }
+        lines.push(Line::from(header));
+        let segments: Vec<(&str, Style)> = comment
+            .text
+            .iter()
+            .map(|(text, start)| {
+                if start.is_none() {
+                    return (text.as_str(), Style::default());
+                }
+                let style = Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::UNDERLINED);
+                let selected = view.selected_timestamp == Some(timestamp_index);
+                timestamp_index += 1;
+                if selected {
+                    selected_line = Some(lines.len());
+                    (text.as_str(), style.add_modifier(Modifier::REVERSED))
+                } else {
+                    (text.as_str(), style)
+                }
+            })
+            .collect();
+        for mut wrapped in wrap_styled(&segments, text_width) {
+            wrapped.spans.insert(0, Span::raw("  "));
+            lines.push(wrapped);
+        }
+        lines.push(Line::from(""));
+    }
+
+    let footer = if view.loading {
+        "Loading comments..."
+    } else if let Some(err) = view.error.as_deref() {
+        err
+    } else if view.ctoken.is_some() {
+        "⏎ Load more comments"
+    } else if view.comments.is_empty() {
+        "No comments."
+    } else {
+        "End of comments."
+    };
+    lines.push(Line::from(Span::styled(
+        footer.to_string(),
+        Style::default().fg(theme.accent),
+    )));
+
+    view.view_height = usize::from(inner.height);
+    view.max_scroll = lines.len().saturating_sub(view.view_height);
+    if let Some(line) = selected_line.filter(|_| view.follow_selection) {
+        // The selected comment's header line is kept in view too.
+        let top = line.saturating_sub(1);
+        if top < view.scroll || line >= view.scroll + view.view_height {
+            view.scroll = top;
+        }
+        view.follow_selection = false;
+    }
+    view.scroll = view.scroll.min(view.max_scroll);
+    let visible: Vec<Line> = lines
+        .into_iter()
+        .skip(view.scroll)
+        .take(view.view_height)
+        .collect();
+    f.render_widget(Paragraph::new(visible), inner);
+}
+
+fn now_playing_line(app: &App) -> Line<'static> {
+    let theme = app.theme;
+    let queued = (!app.queue.is_empty()).then(|| {
+        Span::styled(
+            format!("  {} queued", app.queue.len()),
+            Style::default().fg(theme.accent),
+        )
+    });
+    let Some(playback) = app.playback.as_ref() else {
+        let mut spans = vec![match &app.cast {
+            Some(renderer) => Span::styled(
+                format!("⇱ Casting to {}", renderer.name),
+                Style::default().fg(theme.positive),
+            ),
+            None => Span::styled("■ Nothing playing", Style::default().fg(theme.muted)),
+        }];
+        spans.extend(queued);
+        if !app.queue.is_empty() {
+            spans.push(Span::styled(
+                "  > play next",
+                Style::default().fg(theme.muted),
+            ));
+        }
+        return Line::from(spans);
+    };
+    let icon = if playback.paused { "⏸ " } else { "▶ " };
+    let title = match (&playback.title, &playback.player) {
+        (Some(title), _) => title.clone(),
+        (None, Some(player)) => format!("Playing in {player}"),
+        (None, None) => "Starting mpv...".to_string(),
+    };
+    let mut spans = vec![
+        Span::styled(icon, Style::default().fg(theme.positive)),
+        Span::styled(title, Style::default().add_modifier(Modifier::BOLD)),
+    ];
+    if let Some(position) = playback.position {
+        let mut time = format!("  {}", format_duration(position as u64));
+        if let Some(duration) = playback.duration {
+            time.push_str(&format!(" / {}", format_duration(duration as u64)));
+        }
+        spans.push(Span::styled(time, Style::default().fg(theme.stats)));
+    }
+    if let (Some(player), Some(_)) = (&playback.player, &playback.title) {
+        spans.push(Span::styled(
+            format!("  via {player}"),
+            Style::default().fg(theme.muted),
+        ));
+    }
+    if let Some(volume) = playback.volume {
+        spans.push(Span::styled(
+            format!("  vol {volume:.0}%"),
+            Style::default().fg(theme.muted),
+        ));
+    }
+    if app.speed != 1.0 {
+        spans.push(Span::styled(
+            format!("  {}", format_speed(app.speed)),
+            Style::default().fg(theme.mode),
+        ));
+    }
+    spans.extend(queued);
+    if app.shuffle {
+        spans.push(Span::styled("  ⤮ shuffle", Style::default().fg(theme.mode)));
+    }
+    match app.repeat {
+        Repeat::Off => {}
+        Repeat::One => spans.push(Span::styled(
+            "  ⟳ repeat one",
+            Style::default().fg(theme.mode),
+        )),
+        Repeat::All => spans.push(Span::styled(
+            "  ⟳ repeat all",
+            Style::default().fg(theme.mode),
+        )),
+    }
+    spans.push(Span::styled(
+        "  ␣ pause  [/] seek  </> prev/next  -/+ volume  {/} speed  x stop",
+        Style::default().fg(theme.muted),
+    ));
+    Line::from(spans)
+}
+
+/// Likes, dislikes and a ratio bar, e.g. `👍 12K  👎 450  ▰▰▰▰▰▰▰▰▰▱ 96%`.
+fn render_details_description(
+    f: &mut Frame<'_>,
+    app: &App,
+    description: &[String],
+    area: ratatui::layout::Rect,
+) {
+    let theme = app.theme;
+    let scroll = description_scroll(app).min(description.len() - 1);
+    let heading = if scroll > 0 {
+        format!("Description ({}/{})", scroll + 1, description.len())
+    } else {
+        "Description".to_string()
+    };
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            heading,
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        parts[0],
+    );
+    let lines: Vec<Line> = description[scroll..]
+        .iter()
+        .map(|line| Line::from(Span::styled(line.clone(), Style::default().fg(theme.date))))
+        .collect();
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), parts[1]);
+}
+
+/// Description line the Details pane starts at for the selected video.
+fn description_scroll(app: &App) -> usize {
+    match (
+        &app.description_scroll,
+        app.screen.results.get(app.screen.selected),
+    ) {
+        (Some((id, scroll)), Some(SearchResultItem::Video(video))) if *id == video.id => *scroll,
+        _ => 0,
+    }
+}
+
+/// Shift+Up/Down scroll the description in the Details pane while Results
+/// are focused.
+fn handle_description_scroll_key(app: &mut App, key: KeyCode) -> bool {
+    if app.popup.is_some()
+        || app.filter_editing
+        || app.command_editing
+        || app.screen.focus != Focus::Results
+    {
+        return false;
+    }
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        return false;
+    };
+    let Some(Some(info)) = app.video_info.get(&video.id) else {
+        return false;
+    };
+    let last = info.description.len().saturating_sub(1);
+    let scroll = description_scroll(app);
+    let scroll = match key {
+        KeyCode::Up => scroll.saturating_sub(1),
+        KeyCode::Down => (scroll + 1).min(last),
+        _ => return false,
+    };
+    app.description_scroll = Some((video.id.clone(), scroll));
+    true
+}
+
+fn video_info_lines(info: &VideoInfo, theme: &Theme) -> Vec<Line<'static>> {
+    let mut counts = format!("{} views", group_digits(info.views));
+    if let Some(likes) = info.likes {
+        counts.push_str(&format!(" · {} likes", group_digits(u64::from(likes))));
+    }
+    let mut lines = vec![Line::from(Span::styled(
+        counts,
+        Style::default().fg(theme.stats),
+    ))];
+    if let Some(subscribers) = info.subscribers {
+        lines.push(Line::from(Span::styled(
+            format!("Channel: {}", format_subscribers(subscribers)),
+            Style::default().fg(theme.channel),
+        )));
+    }
+    let captions = if info.captions.is_empty() {
+        "No captions".to_string()
+    } else {
+        format!("Captions: {}", info.captions.join(", "))
+    };
+    lines.push(Line::from(Span::styled(
+        captions,
+        Style::default().fg(theme.muted),
+    )));
+    if !info.tags.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("Tags: {}", info.tags.join(", ")),
+            Style::default().fg(theme.muted),
+        )));
+    }
+    lines
+}
+
+fn votes_line(votes: Votes, theme: &Theme) -> Line<'static> {
+    const WIDTH: usize = 10;
+    let total = votes.likes + votes.dislikes;
+    let mut spans = vec![
+        Span::styled(
+            format!("👍 {}", format_count(votes.likes)),
+            Style::default().fg(theme.positive),
+        ),
+        Span::styled(
+            format!("  👎 {}", format_count(votes.dislikes)),
+            Style::default().fg(theme.alert),
+        ),
+    ];
+    if total > 0 {
+        let ratio = votes.likes as f64 / total as f64;
+        let filled = ((ratio * WIDTH as f64).round() as usize).min(WIDTH);
+        spans.push(Span::styled(
+            format!("  {}", "▰".repeat(filled)),
+            Style::default().fg(theme.positive),
+        ));
+        spans.push(Span::styled(
+            "▰".repeat(WIDTH - filled),
+            Style::default().fg(theme.alert),
+        ));
+        spans.push(Span::styled(
+            format!(" {:.0}%", ratio * 100.0),
+            Style::default().fg(theme.muted),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Renders `fraction` as a bar of `width` cells, e.g. `▰▰▰▱▱▱▱▱`.
+fn progress_bar(fraction: f64, width: usize) -> String {
+    let filled = ((fraction * width as f64).round() as usize).min(width);
+    format!("{}{}", "▰".repeat(filled), "▱".repeat(width - filled))
+}
+
+/// Widths of the columns after a video's title in the results list; 0
+/// hides a column. Narrow terminals drop the less useful ones first.
+struct ResultColumns {
+    duration: usize,
+    channel: usize,
+    views: usize,
+    age: usize,
+}
+
+impl ResultColumns {
+    fn for_width(width: usize) -> ResultColumns {
+        ResultColumns {
+            duration: if width >= 40 { 9 } else { 0 },
+            views: if width >= 60 { 7 } else { 0 },
+            age: if width >= 80 { 14 } else { 0 },
+            channel: if width >= 100 { (width / 5).min(24) } else { 0 },
+        }
+    }
+
+    fn title_width(&self, width: usize) -> usize {
+        let columns = [self.duration, self.channel, self.views, self.age];
+        let used: usize = columns
+            .iter()
+            .filter(|&&column| column > 0)
+            .map(|column| column + 1)
+            .sum();
+        width.saturating_sub(used)
+    }
+
+    fn push_video_cells(&self, spans: &mut Vec<Span<'static>>, video: &Video, theme: &Theme) {
+        if self.duration > 0 {
+            // A pill, right-aligned in the column.
+            let duration = video.duration.filter(|_| !video.is_live);
+            let text = duration.map_or(String::new(), |d| format!(" {} ", format_duration(d)));
+            let padding = self.duration.saturating_sub(text.width());
+            spans.push(Span::raw(" ".repeat(padding + 1)));
+            if !text.is_empty() {
+                spans.push(badge(text, theme.positive, theme));
+            }
+        }
+        let mut cell = |text: &str, width: usize, align_right: bool, color| {
+            if width > 0 {
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled(
+                    fit_to_width(text, width, align_right),
+                    Style::default().fg(color),
+                ));
+            }
+        };
+        let channel = video.channel.as_deref().unwrap_or_default();
+        cell(channel, self.channel, false, theme.channel);
+        let views = video.view_count.map(format_count).unwrap_or_default();
+        cell(&views, self.views, true, theme.stats);
+        cell(&upload_age(video), self.age, true, theme.date);
+    }
+}
+
+/// `text` cut to `width` columns (ending in `…` when cut) and padded to
+/// exactly that width.
+fn fit_to_width(text: &str, width: usize, align_right: bool) -> String {
+    let mut fitted = String::new();
+    let mut used = 0;
+    if text.width() > width {
+        for c in text.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if used + char_width + 1 > width {
+                break;
+            }
+            fitted.push(c);
+            used += char_width;
+        }
+        if width > 0 {
+            fitted.push('…');
+            used += 1;
+        }
+    } else {
+        fitted.push_str(text);
+        used = text.width();
+    }
+    let padding = " ".repeat(width.saturating_sub(used));
+    if align_right {
+        format!("{padding}{fitted}")
+    } else {
+        fitted + &padding
+    }
+}
+
+/// How long ago a video was uploaded, like `3 weeks ago`.
+fn upload_age(video: &Video) -> String {
+    if let Some(text) = video.publish_date_txt.as_deref() {
+        return text.to_string();
+    }
+    let Some(date) = video.publish_date else {
+        return String::new();
+    };
+    format_age((OffsetDateTime::now_utc() - date).whole_seconds().max(0) as u64)
+}
+
+/// Formats an age in seconds like `3 weeks ago`.
+fn format_age(secs: u64) -> String {
+    let (count, unit) = match secs {
+        s if s < 3600 => (s / 60, "minute"),
+        s if s < 86_400 => (s / 3600, "hour"),
+        s if s < 7 * 86_400 => (s / 86_400, "day"),
+        s if s < 30 * 86_400 => (s / (7 * 86_400), "week"),
+        s if s < 365 * 86_400 => (s / (30 * 86_400), "month"),
+        s => (s / (365 * 86_400), "year"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{count} {unit}{plural} ago")
+}
+
+fn live_badge(theme: &Theme) -> Span<'static> {
+    badge(" LIVE ".to_string(), theme.alert, theme)
+}
+
+/// `text` in bold on a `color` background.
+fn badge(text: String, color: Color, theme: &Theme) -> Span<'static> {
+    Span::styled(
+        text,
+        Style::default()
+            .fg(theme.badge_text)
+            .bg(color)
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+/// The badges shown in front of a video's title: LIVE, NEW for uploads of
+/// the last day, and 4K or HD once its qualities have been looked up.
+fn video_badges(app: &App, video: &Video) -> Vec<Span<'static>> {
+    let theme = &app.theme;
+    let mut badges = Vec::new();
+    if video.is_live {
+        badges.push(live_badge(theme));
+    }
+    let recent = video
+        .publish_date
+        .is_some_and(|date| OffsetDateTime::now_utc() - date < time::Duration::DAY);
+    if recent && !video.is_live {
+        badges.push(badge(" NEW ".to_string(), theme.accent, theme));
+    }
+    match app.max_heights.get(&video.id) {
+        Some(height) if *height >= 2160 => {
+            badges.push(badge(" 4K ".to_string(), theme.stats, theme))
+        }
+        Some(height) if *height >= 720 => {
+            badges.push(badge(" HD ".to_string(), theme.stats, theme))
+        }
+        _ => {}
+    }
+    badges
+}
+
+fn centered_rect(
+    area: ratatui::layout::Rect,
+    percent_x: u16,
+    percent_y: u16,
+) -> ratatui::layout::Rect {
+    let width = area.width * percent_x / 100;
+    let height = area.height * percent_y / 100;
+    ratatui::layout::Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Greedy word wrap of styled text by display width. Newlines in the text
+/// start a new line; words longer than `width` are split.
+fn wrap_styled(segments: &[(&str, Style)], width: usize) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut line_width = 0;
+    // Whether the text before the current word ended in whitespace.
+    let mut pending_space = false;
+
+    for (text, style) in segments {
+        let mut rest = *text;
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('\n') {
+                lines.push(Line::from(std::mem::take(&mut spans)));
+                line_width = 0;
+                pending_space = false;
+                rest = after;
+                continue;
+            }
+            if rest.starts_with(char::is_whitespace) {
+                pending_space = true;
+                rest = rest.trim_start_matches(|c: char| c.is_whitespace() && c != '\n');
+                continue;
+            }
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let word = &rest[..end];
+            rest = &rest[end..];
+
+            let word_width: usize = word.chars().map(|c| c.width().unwrap_or(0)).sum();
+            let space = usize::from(pending_space && line_width > 0);
+            if line_width > 0 && line_width + space + word_width > width {
+                lines.push(Line::from(std::mem::take(&mut spans)));
+                line_width = 0;
+            } else if space > 0 {
+                spans.push(Span::raw(" "));
+                line_width += 1;
+            }
+            pending_space = false;
+
+            let mut chunk = String::new();
+            for c in word.chars() {
+                let char_width = c.width().unwrap_or(0);
+                if line_width + char_width > width && line_width > 0 {
+                    spans.push(Span::styled(std::mem::take(&mut chunk), *style));
+                    lines.push(Line::from(std::mem::take(&mut spans)));
+                    line_width = 0;
+                }
+                chunk.push(c);
+                line_width += char_width;
+            }
+            spans.push(Span::styled(chunk, *style));
+        }
+    }
+    lines.push(Line::from(spans));
+    lines
+}
+
+fn render_channel_header(f: &mut Frame<'_>, app: &mut App, area: ratatui::layout::Rect) {
+    let theme = app.theme;
+    let Some(header) = app.screen.channel_header.as_ref() else {
         return;
     };
+    let current_tab = match &app.screen.source {
+        Some(ResultsSource::Channel { tab, .. }) => *tab,
+        _ => ChannelTab::Videos,
+    };
+
+    let block = Block::default().borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let mut text_area = inner;
+    if header.avatar_path.is_some() && inner.width >= 40 {
+        let (img_w, img_h) = header.avatar_size.unwrap_or((88, 88));
+        let (avatar_w, avatar_h) = fit_dimensions_cells(img_w, img_h, 12, inner.height);
+        if avatar_w > 0 && avatar_h > 0 {
+            app.avatar_area = Some(ratatui::layout::Rect::new(
+                inner.x, inner.y, avatar_w, avatar_h,
+            ));
+            text_area = ratatui::layout::Rect::new(
+                inner.x + avatar_w + 1,
+                inner.y,
+                inner.width.saturating_sub(avatar_w + 1),
+                inner.height,
+            );
+        }
+    }
+
+    let mut title = vec![Span::styled(
+        header.name.clone(),
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    if let Some(handle) = &header.handle {
+        title.push(Span::styled(
+            format!("  {handle}"),
+            Style::default().fg(theme.channel),
+        ));
+    }
+    let subs = header
+        .subscriber_count
+        .map(format_subscribers)
+        .unwrap_or_else(|| "- subscribers".to_string());
+    let stats = match header.video_count {
+        Some(count) => format!("{subs} · {count} videos"),
+        None => subs,
+    };
+    let description = header
+        .description
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("-")
+        .to_string();
+
+    let mut tabs = Vec::new();
+    for tab in ChannelTab::ALL {
+        let available = match tab {
+            ChannelTab::Live => header.has_live,
+            ChannelTab::Shorts => header.has_shorts,
+            ChannelTab::Videos | ChannelTab::Playlists => true,
+        };
+        let style = if tab == current_tab {
+            Style::default()
+                .fg(theme.selection)
+                .add_modifier(Modifier::BOLD)
+        } else if available {
+            Style::default().fg(theme.accent)
+        } else {
+            Style::default().fg(theme.muted)
+        };
+        if !tabs.is_empty() {
+            tabs.push(Span::raw(" │ "));
+        }
+        tabs.push(Span::styled(tab.label(), style));
+    }
+
+    let lines = vec![
+        Line::from(title),
+        Line::from(Span::styled(stats, Style::default().fg(theme.stats))),
+        Line::from(Span::styled(description, Style::default().fg(theme.date))),
+        Line::from(tabs),
+    ];
+    f.render_widget(Paragraph::new(lines), text_area);
+}
+
+/// Searches YouTube, answering from the search cache while its entry is
+/// younger than `cache_ttl` seconds, or at any age when the search fails.
+/// A TTL of 0 leaves the cache alone.
+fn search_rustypipe(query: &str, cache_ttl: u64) -> Result<SearchPage, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
+
+    let (language, country) = current_locale();
+    let key = format!("{language:?}/{country:?}/{query}");
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let cached = search_cache::load(&key).filter(|_| cache_ttl > 0);
+    if let Some(entry) = cached.clone() {
+        let age = (now - entry.fetched_at).max(0) as u64;
+        if age < cache_ttl {
+            return Ok(cached_search_page(entry, Some(CacheHit::Fresh { age })));
+        }
+    }
+
+    let result = runtime.block_on(
+        rustypipe_query().search_filter::<YouTubeItem, _>(query.to_string(), &SearchFilter::new()),
+    );
+
+    let response = match result {
+        Ok(response) => response,
+        Err(err) => {
+            let error = format!("RustyPipe search failed: {err}");
+            return match cached {
+                Some(entry) => {
+                    let age = (now - entry.fetched_at).max(0) as u64;
+                    Ok(cached_search_page(
+                        entry,
+                        Some(CacheHit::Offline { age, error }),
+                    ))
+                }
+                None => Err(error),
+            };
+        }
+    };
 
-    if app.results.len() >= target {
-        app.page += 1;
-        app.selected_row = first_result_row(app);
-        app.results_state = ListState::default();
-        sync_selected_result(app);
-        app.pending_next_target = None;
-        app.status = format!("Found {} results.", app.results.len());
-        return;
+    let paginator = response.items;
+    let entry = CachedSearch {
+        key,
+        fetched_at: now,
+        items: paginator.items,
+        ctoken: paginator.ctoken,
+        visitor_data: paginator.visitor_data,
+        endpoint: paginator.endpoint,
+    };
+    if cache_ttl > 0 {
+        // A search that can't be cached still shows its results.
+        if let Err(err) = search_cache::save(&entry) {
+            log::warn!("Search not cached: {err}");
+        }
     }
+    Ok(cached_search_page(entry, None))
+}
 
-    if app.search_ctoken.is_some() && !app.loading_more {
-        app.loading_more = true;
-        app.status = "Loading more results...".to_string();
-        let tx = app.tx.clone();
-        let ctoken = app.search_ctoken.clone().unwrap_or_default();
-        let visitor = app.search_visitor_data.clone();
-        let endpoint = app.search_endpoint.unwrap_or(ContinuationEndpoint::Search);
-        let target_count = target.saturating_sub(app.results.len());
-        thread::spawn(move || {
-            let result = if ctoken.is_empty() {
-                Err("No more results.".to_string())
-            } else {
-                search_rustypipe_continuation(&ctoken, visitor.as_deref(), endpoint, target_count)
-            };
-            let _ = tx.send(AppMsg::MoreResults(result));
-        });
-    } else {
-        app.pending_next_target = None;
-        app.status = "No more results.".to_string();
+fn cached_search_page(entry: CachedSearch, cache: Option<CacheHit>) -> SearchPage {
+    SearchPage {
+        results: entry
+            .items
+            .into_iter()
+            .filter_map(youtube_item_to_result)
+            .collect(),
+        channel: None,
+        ctoken: entry.ctoken,
+        visitor_data: entry.visitor_data,
+        endpoint: entry.endpoint,
+        cache,
     }
 }
 
-fn start_search(app: &mut App, query: String) {
-    if query.is_empty() || app.searching {
-        return;
+fn search_rustypipe_continuation(
+    ctoken: &str,
+    visitor_data: Option<&str>,
+    endpoint: ContinuationEndpoint,
+    target_count: usize,
+) -> Result<SearchPage, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
+
+    let mut results = Vec::new();
+    let mut next_ctoken = if ctoken.is_empty() {
+        None
+    } else {
+        Some(ctoken.to_string())
+    };
+    let mut next_visitor = visitor_data.map(str::to_string);
+    let mut next_endpoint = endpoint;
+    let mut pages_fetched = 0usize;
+
+    while target_count > 0 && results.len() < target_count {
+        let Some(token) = next_ctoken.clone() else {
+            break;
+        };
+        let result = runtime.block_on(rustypipe_query().continuation::<YouTubeItem, _>(
+            token,
+            next_endpoint,
+            next_visitor.as_deref(),
+        ));
+
+        let paginator = match result {
+            Ok(paginator) => paginator,
+            Err(err) => return Err(format!("RustyPipe continuation failed: {err}")),
+        };
+
+        for item in paginator.items {
+            if let Some(result) = youtube_item_to_result(item) {
+                results.push(result);
+            }
+        }
+
+        next_ctoken = paginator.ctoken;
+        next_visitor = paginator.visitor_data;
+        next_endpoint = paginator.endpoint;
+        pages_fetched += 1;
+        if pages_fetched >= 3 {
+            break;
+        }
     }
-    app.searching = true;
-    app.status = format!("Searching for '{query}'...");
-    let tx = app.tx.clone();
-    thread::spawn(move || {
-        let result = search_rustypipe(&query);
-        let _ = tx.send(AppMsg::Search(result));
+
+    Ok(SearchPage {
+        results,
+        channel: None,
+        ctoken: next_ctoken,
+        visitor_data: next_visitor,
+        endpoint: next_endpoint,
+        cache: None,
+    })
+}
+
+fn channel_tab_items(channel_id: &str, tab: ChannelTab) -> Result<SearchPage, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
     });
+
+    let video_tab = match tab {
+        ChannelTab::Videos => ChannelVideoTab::Videos,
+        ChannelTab::Live => ChannelVideoTab::Live,
+        ChannelTab::Shorts => ChannelVideoTab::Shorts,
+        ChannelTab::Playlists => {
+            let result = runtime.block_on(rustypipe_query().channel_playlists(channel_id));
+            let channel = match result {
+                Ok(channel) => channel,
+                Err(err) => return Err(format!("RustyPipe channel playlists failed: {err}")),
+            };
+            let (header, paginator) = split_channel(channel);
+            let results = paginator
+                .items
+                .into_iter()
+                .map(|item| SearchResultItem::Playlist(playlist_item_to_playlist(item)))
+                .collect();
+            return Ok(SearchPage {
+                results,
+                channel: Some(header),
+                ctoken: paginator.ctoken,
+                visitor_data: paginator.visitor_data,
+                endpoint: paginator.endpoint,
+                cache: None,
+            });
+        }
+    };
+
+    // Channel tabs are sorted by upload date, newest first.
+    let result = runtime.block_on(rustypipe_query().channel_videos_tab(channel_id, video_tab));
+
+    let channel = match result {
+        Ok(channel) => channel,
+        Err(err) => return Err(format!("RustyPipe channel videos failed: {err}")),
+    };
+
+    let (header, paginator) = split_channel(channel);
+    let mut results = Vec::new();
+    for item in paginator.items {
+        results.push(SearchResultItem::Video(video_item_to_video(item)));
+    }
+
+    Ok(SearchPage {
+        results,
+        channel: Some(header),
+        ctoken: paginator.ctoken,
+        visitor_data: paginator.visitor_data,
+        endpoint: paginator.endpoint,
+        cache: None,
+    })
 }
 
-fn start_channel_videos(app: &mut App, channel_id: String, channel_name: String) {
-    if app.searching {
-        return;
+fn split_channel<T>(
+    channel: rustypipe::model::Channel<Paginator<T>>,
+) -> (ChannelHeader, Paginator<T>) {
+    // Prefer a mid-sized avatar; the list is ordered smallest first.
+    let avatar_url = channel
+        .avatar
+        .iter()
+        .find(|thumb| thumb.width >= 88)
+        .or(channel.avatar.last())
+        .map(|thumb| thumb.url.clone());
+    let mut paginator = channel.content;
+    if paginator.visitor_data.is_none() {
+        paginator.visitor_data = channel.visitor_data;
     }
-    app.searching = true;
-    app.status = format!("Loading channel videos for '{channel_name}'...");
-    let tx = app.tx.clone();
-    thread::spawn(move || {
-        let result = channel_videos_latest(&channel_id);
-        let _ = tx.send(AppMsg::Search(result));
+    let header = ChannelHeader {
+        id: channel.id,
+        name: channel.name,
+        handle: channel.handle,
+        subscriber_count: channel.subscriber_count,
+        video_count: channel.video_count,
+        description: channel.description,
+        has_live: channel.has_live,
+        has_shorts: channel.has_shorts,
+        avatar_url,
+        avatar_path: None,
+        avatar_size: None,
+    };
+    (header, paginator)
+}
+
+fn related_videos(video_id: &str) -> Result<SearchPage, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
     });
+
+    let result = runtime.block_on(rustypipe_query().video_details(video_id));
+
+    let details = match result {
+        Ok(details) => details,
+        Err(err) => return Err(format!("RustyPipe video details failed: {err}")),
+    };
+
+    let paginator = details.recommended;
+    let mut results = Vec::new();
+    for item in paginator.items {
+        results.push(SearchResultItem::Video(video_item_to_video(item)));
+    }
+
+    Ok(SearchPage {
+        results,
+        channel: None,
+        ctoken: paginator.ctoken,
+        visitor_data: paginator.visitor_data.or(details.visitor_data),
+        endpoint: paginator.endpoint,
+        cache: None,
+    })
 }
 
-fn ui(f: &mut Frame<'_>, app: &mut App) {
-    let size = f.size();
+/// Fetches the latest uploads of every subscribed channel, a few channels at
+/// a time, and merges them newest first.
+fn subscription_feed(subscriptions: Vec<Subscription>) -> Result<SearchPage, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
 
-    let inner_height = size.height.saturating_sub(2);
-    let mut preview_height = (inner_height / 3).clamp(4, 14);
-    let max_preview_height = inner_height.saturating_sub(4);
-    if preview_height > max_preview_height {
-        preview_height = max_preview_height;
+    let total = subscriptions.len();
+    let (mut videos, failures) = runtime.block_on(async {
+        let mut pending = subscriptions.into_iter();
+        let mut tasks = tokio::task::JoinSet::new();
+        let mut videos = Vec::new();
+        let mut failures = 0usize;
+        loop {
+            while tasks.len() < FEED_CONCURRENCY {
+                let Some(subscription) = pending.next() else {
+                    break;
+                };
+                let query = rustypipe_query();
+                tasks.spawn(async move { query.channel_videos(subscription.id).await });
+            }
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+            match joined {
+                Ok(Ok(channel)) => videos.extend(channel.content.items),
+                _ => failures += 1,
+            }
+        }
+        (videos, failures)
+    });
+
+    if videos.is_empty() && failures > 0 {
+        return Err(format!(
+            "RustyPipe feed failed for {failures} of {total} channels"
+        ));
     }
 
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(1)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(0),
-            Constraint::Length(preview_height),
-            Constraint::Length(1),
-        ])
-        .split(size);
+    videos.sort_by_key(|video| Reverse(video.publish_date));
+    let results = videos
+        .into_iter()
+        .map(|item| SearchResultItem::Video(video_item_to_video(item)))
+        .collect();
 
-    let search_title = "Search";
-    let search_block = Block::default().borders(Borders::ALL).title(search_title);
-    let search_block = search_block.border_style(match app.focus {
-        Focus::Search => Style::default().fg(Color::Cyan),
-        Focus::Results | Focus::Details => Style::default(),
+    Ok(SearchPage {
+        results,
+        channel: None,
+        ctoken: None,
+        visitor_data: None,
+        endpoint: ContinuationEndpoint::Browse,
+        cache: None,
+    })
+}
+
+fn video_chapters(video_id: &str) -> Result<Vec<VideoChapter>, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
+
+    let details = runtime
+        .block_on(rustypipe_query().video_details(video_id))
+        .map_err(|err| format!("RustyPipe video details failed: {err}"))?;
+
+    Ok(details
+        .chapters
+        .into_iter()
+        .map(|chapter| VideoChapter {
+            name: chapter.name,
+            position: chapter.position,
+        })
+        .collect())
+}
+
+/// Lists the distinct video heights available for a video, best first,
+/// followed by an audio-only option.
+fn video_qualities(video_id: &str) -> Result<Vec<Quality>, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
     });
-    let search = Paragraph::new(app.query.as_str()).block(search_block.clone());
-    f.render_widget(search, chunks[0]);
-    if app.focus == Focus::Search {
-        let inner = search_block.inner(chunks[0]);
-        if inner.width > 0 {
-            let cursor_x = inner.x + app.cursor as u16;
-            let cursor_x = cursor_x.min(inner.x + inner.width.saturating_sub(1));
-            f.set_cursor(cursor_x, inner.y);
+
+    let player = runtime
+        .block_on(rustypipe_query().player(video_id))
+        .map_err(|err| format!("RustyPipe player failed: {err}"))?;
+
+    let mut streams: Vec<_> = player
+        .video_only_streams
+        .iter()
+        .chain(player.video_streams.iter())
+        .collect();
+    streams.sort_by_key(|stream| Reverse((stream.height, stream.fps)));
+    let mut options = Vec::new();
+    for stream in streams {
+        let seen = options
+            .iter()
+            .any(|q| matches!(q, Quality::Height { height, .. } if *height == stream.height));
+        if !seen {
+            options.push(Quality::Height {
+                height: stream.height,
+                label: stream.quality.clone(),
+            });
         }
     }
+    if !player.audio_streams.is_empty() {
+        options.push(Quality::AudioOnly);
+    }
+    Ok(options)
+}
 
-    let results_title = "Results";
-    let results_block = Block::default()
-        .borders(Borders::ALL)
-        .title(results_title)
-        .border_style(match app.focus {
-            Focus::Results => Style::default().fg(Color::Cyan),
-            Focus::Search | Focus::Details => Style::default(),
+/// Picks a stream that plays on its own (on a renderer, or when copied): a
+/// combined audio+video stream, or the HLS manifest for livestreams.
+fn cast_stream(video_id: &str) -> Result<String, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
+
+    let player = runtime
+        .block_on(rustypipe_query().player(video_id))
+        .map_err(|err| format!("RustyPipe player failed: {err}"))?;
+    if let Some(hls) = player.hls_manifest_url.clone() {
+        return Ok(hls);
+    }
+    player
+        .select_video_stream(&StreamFilter::new())
+        .map(|stream| stream.url.clone())
+        .ok_or_else(|| "No stream with both audio and video to cast.".to_string())
+}
+
+fn direct_streams(video_id: &str, max_height: u32) -> Result<DirectStreams, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
+
+    let player = runtime
+        .block_on(rustypipe_query().player(video_id))
+        .map_err(|err| format!("RustyPipe player failed: {err}"))?;
+    let title = player
+        .details
+        .name
+        .clone()
+        .unwrap_or_else(|| video_id.to_string());
+    if let Some(hls) = player.hls_manifest_url.clone() {
+        return Ok(DirectStreams {
+            title,
+            video: hls,
+            audio: None,
+            valid_until: player.valid_until,
         });
-    f.render_widget(results_block.clone(), chunks[1]);
-    let inner = results_block.inner(chunks[1]);
-    update_results_layout(app, inner.height as usize);
+    }
+    let filter = StreamFilter::new().video_max_res(max_height);
+    let (video, audio) = player.select_video_audio_stream(&filter);
+    let video = video.ok_or_else(|| "No playable stream found.".to_string())?;
+    Ok(DirectStreams {
+        title,
+        video: video.url.clone(),
+        audio: audio.map(|audio| audio.url.clone()),
+        valid_until: player.valid_until,
+    })
+}
+
+fn caption_tracks(video_id: &str) -> Result<Vec<CaptionTrack>, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
+
+    let player = runtime
+        .block_on(rustypipe_query().player(video_id))
+        .map_err(|err| format!("RustyPipe player failed: {err}"))?;
 
-    let entries = results_entries(app);
-    let items: Vec<ListItem> = entries
+    Ok(player
+        .subtitles
+        .into_iter()
+        .map(|subtitle| CaptionTrack {
+            url: subtitle.url,
+            lang: subtitle.lang,
+            lang_name: subtitle.lang_name,
+            auto_generated: subtitle.auto_generated,
+        })
+        .collect())
+}
+
+/// Fetches the sponsor segments SponsorBlock's community submitted for a
+/// video. Videos without any return an empty list.
+fn sponsor_segments(video_id: &str) -> Result<Vec<(f64, f64)>, String> {
+    let url =
+        format!("https://sponsor.ajay.app/api/skipSegments?videoID={video_id}&category=sponsor");
+    let response = reqwest::blocking::get(&url).map_err(|e| format!("SponsorBlock error: {e}"))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    let bytes = response.bytes().map_err(|e| format!("Read error: {e}"))?;
+    let value: serde_json::Value =
+        serde_json::from_slice(&bytes).map_err(|e| format!("SponsorBlock parse error: {e}"))?;
+    let mut segments: Vec<(f64, f64)> = value
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
         .iter()
-        .map(|entry| match *entry {
-            ResultsEntry::PreviousPage { enabled } => {
-                let style = if enabled {
-                    Style::default().fg(Color::Cyan)
+        .filter_map(|entry| {
+            let segment = entry.get("segment")?.as_array()?;
+            Some((segment.first()?.as_f64()?, segment.get(1)?.as_f64()?))
+        })
+        .collect();
+    segments.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Ok(segments)
+}
+
+fn dislike_votes(video_id: &str) -> Result<Votes, String> {
+    let url = format!("https://returnyoutubedislikeapi.com/votes?videoId={video_id}");
+    let response = reqwest::blocking::get(&url).map_err(|e| format!("RYD error: {e}"))?;
+    let bytes = response.bytes().map_err(|e| format!("Read error: {e}"))?;
+    let value: serde_json::Value =
+        serde_json::from_slice(&bytes).map_err(|e| format!("RYD parse error: {e}"))?;
+    let count = |key: &str| {
+        value
+            .get(key)
+            .and_then(serde_json::Value::as_u64)
+            .ok_or_else(|| format!("RYD response has no {key}."))
+    };
+    Ok(Votes {
+        likes: count("likes")?,
+        dislikes: count("dislikes")?,
+    })
+}
+
+fn dearrow_branding(video_id: &str) -> Result<Branding, String> {
+    let url = format!("https://sponsor.ajay.app/api/branding?videoID={video_id}");
+    let response = reqwest::blocking::get(&url).map_err(|e| format!("DeArrow error: {e}"))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Branding::default());
+    }
+    let bytes = response.bytes().map_err(|e| format!("Read error: {e}"))?;
+    let value: serde_json::Value =
+        serde_json::from_slice(&bytes).map_err(|e| format!("DeArrow parse error: {e}"))?;
+    // Entries come best first; downvoted ones and the originals are skipped.
+    let trusted = |key: &str| {
+        value
+            .get(key)
+            .and_then(serde_json::Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .find(|entry| {
+                let original = entry
+                    .get("original")
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or(false);
+                let locked = entry
+                    .get("locked")
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or(false);
+                let votes = entry
+                    .get("votes")
+                    .and_then(serde_json::Value::as_i64)
+                    .unwrap_or(0);
+                !original && (locked || votes >= 0)
+            })
+            .cloned()
+    };
+    let title = trusted("titles").and_then(|entry| entry.get("title")?.as_str().map(dearrow_title));
+    let thumbnail_url = trusted("thumbnails").and_then(|entry| {
+        let time = entry.get("timestamp")?.as_f64()?;
+        Some(format!(
+            "https://dearrow-thumb.ajay.app/api/v1/getThumbnail?videoID={video_id}&time={time}"
+        ))
+    });
+    Ok(Branding {
+        title,
+        thumbnail_url,
+    })
+}
+
+/// Drops the `>` DeArrow puts before words that shouldn't be recased.
+fn dearrow_title(title: &str) -> String {
+    let mut out = String::with_capacity(title.len());
+    let mut word_start = true;
+    let mut chars = title.chars().peekable();
+    while let Some(c) = chars.next() {
+        let marker =
+            c == '>' && word_start && chars.peek().is_some_and(|next| !next.is_whitespace());
+        if !marker {
+            out.push(c);
+        }
+        word_start = c.is_whitespace();
+    }
+    out
+}
+
+/// Downloads a caption track in YouTube's `json3` timed-text format.
+fn download_captions(url: &str) -> Result<Vec<Cue>, String> {
+    let url = format!("{url}&fmt=json3");
+    let response = reqwest::blocking::get(&url).map_err(|e| format!("Download error: {e}"))?;
+    let bytes = response.bytes().map_err(|e| format!("Read error: {e}"))?;
+    let value: serde_json::Value =
+        serde_json::from_slice(&bytes).map_err(|e| format!("Caption parse error: {e}"))?;
+
+    let mut cues = Vec::new();
+    let events = value
+        .get("events")
+        .and_then(serde_json::Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for event in events {
+        let Some(start_ms) = event.get("tStartMs").and_then(serde_json::Value::as_u64) else {
+            continue;
+        };
+        let segs = event
+            .get("segs")
+            .and_then(serde_json::Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let text: String = segs
+            .iter()
+            .filter_map(|seg| seg.get("utf8").and_then(serde_json::Value::as_str))
+            .collect();
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            cues.push(Cue { start_ms, text });
+        }
+    }
+    Ok(cues)
+}
+
+/// Exact counts from the video page, plus tags and caption tracks from the
+/// player, which are left out when the player can't be fetched.
+fn video_info(video_id: &str) -> Result<VideoInfo, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
+
+    let details = runtime
+        .block_on(rustypipe_query().video_details(video_id))
+        .map_err(|err| format!("RustyPipe video details failed: {err}"))?;
+    let description: String = description_page(video_id, details.description)
+        .segments
+        .into_iter()
+        .map(|segment| segment.text)
+        .collect();
+    let player = runtime.block_on(rustypipe_query().player(video_id)).ok();
+    let (tags, captions) = match player {
+        Some(player) => (
+            player.details.keywords,
+            player
+                .subtitles
+                .iter()
+                .map(|subtitle| {
+                    if subtitle.auto_generated {
+                        format!("{} (auto)", subtitle.lang_name)
+                    } else {
+                        subtitle.lang_name.clone()
+                    }
+                })
+                .collect(),
+        ),
+        None => (Vec::new(), Vec::new()),
+    };
+    Ok(VideoInfo {
+        views: details.view_count,
+        likes: details.like_count,
+        subscribers: details.channel.subscriber_count,
+        tags,
+        captions,
+        description: description.trim_end().lines().map(str::to_string).collect(),
+    })
+}
+
+fn video_description(video_id: &str) -> Result<DescriptionPage, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
+
+    let details = runtime
+        .block_on(rustypipe_query().video_details(video_id))
+        .map_err(|err| format!("RustyPipe video details failed: {err}"))?;
+
+    Ok(description_page(video_id, details.description))
+}
+
+/// Splits a rich-text description into display segments and the list of
+/// links (timestamps into this video, other YouTube targets, web URLs).
+fn description_page(video_id: &str, description: RichText) -> DescriptionPage {
+    let mut segments = Vec::new();
+    let mut links = Vec::new();
+    for component in description.0 {
+        match component {
+            TextComponent::Text { text, .. } => {
+                segments.push(DescriptionSegment { text, link: None });
+            }
+            TextComponent::Web { text, url } => {
+                let label = if text == url {
+                    url.clone()
                 } else {
-                    Style::default().fg(Color::DarkGray)
+                    format!("{text} ({url})")
+                };
+                links.push(DescriptionLink {
+                    label,
+                    target: LinkTarget::Web(url),
+                });
+                segments.push(DescriptionSegment {
+                    text,
+                    link: Some(links.len() - 1),
+                });
+            }
+            TextComponent::YouTube { text, target } => {
+                let link_target = match target {
+                    UrlTarget::Video { ref id, start_time } if id == video_id => {
+                        LinkTarget::Timestamp(start_time)
+                    }
+                    target => LinkTarget::YouTube(target),
                 };
-                ListItem::new(Line::from(Span::styled("Previous Page", style)))
-            }
-            ResultsEntry::NextPage { enabled } => {
-                let style = if enabled {
-                    Style::default().fg(Color::Cyan)
-                } else {
-                    Style::default().fg(Color::DarkGray)
+                let label = match &link_target {
+                    LinkTarget::Timestamp(start) => {
+                        format!("{} {text}", format_duration(u64::from(*start)))
+                    }
+                    LinkTarget::YouTube(target) => format!("{text} ({target})"),
+                    LinkTarget::Web(url) => url.clone(),
                 };
-                ListItem::new(Line::from(Span::styled("Next Page", style)))
+                links.push(DescriptionLink {
+                    label,
+                    target: link_target,
+                });
+                segments.push(DescriptionSegment {
+                    text,
+                    link: Some(links.len() - 1),
+                });
             }
-            ResultsEntry::Result(index) => match app.results.get(index) {
-                Some(SearchResultItem::Video(video)) => {
-                    ListItem::new(Line::from(Span::raw(video.title.clone())))
-                }
-                Some(SearchResultItem::Channel(channel)) => {
-                    ListItem::new(Line::from(Span::raw(format!(
-                        "[Channel] {}",
-                        channel.name
-                    ))))
-                }
-                None => ListItem::new(Line::from(Span::raw("-"))),
-            },
-        })
-        .collect();
-
-    if entries.is_empty() {
-        app.results_state.select(None);
-        *app.results_state.offset_mut() = 0;
-    } else {
-        app.selected_row = app.selected_row.min(entries.len().saturating_sub(1));
-        app.results_state.select(Some(app.selected_row));
+            other => segments.push(DescriptionSegment {
+                text: other.get_text().to_string(),
+                link: None,
+            }),
+        }
+    }
+    if segments.is_empty() {
+        segments.push(DescriptionSegment {
+            text: "No description.".to_string(),
+            link: None,
+        });
     }
+    DescriptionPage { segments, links }
+}
 
-    let show_scrollbar = inner.height > 0 && items.len() > inner.height as usize;
-    let (list_area, scrollbar_area) = if show_scrollbar && inner.width > 1 {
-        (
-            ratatui::layout::Rect::new(inner.x, inner.y, inner.width - 1, inner.height),
-            Some(ratatui::layout::Rect::new(
-                inner.x + inner.width - 1,
-                inner.y,
-                1,
-                inner.height,
-            )),
-        )
-    } else {
-        (inner, None)
-    };
+fn video_comments_first(video_id: &str) -> Result<CommentsPage, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
 
-    let viewport_len = list_area.height as usize;
-    if viewport_len > 0 {
-        let mut offset = app.results_state.offset();
-        if app.selected_row < offset {
-            offset = app.selected_row;
-        } else if app.selected_row >= offset + viewport_len {
-            offset = app.selected_row + 1 - viewport_len;
-        }
-        let max_offset = entries.len().saturating_sub(viewport_len);
-        *app.results_state.offset_mut() = offset.min(max_offset);
-    }
+    let details = runtime
+        .block_on(rustypipe_query().video_details(video_id))
+        .map_err(|err| format!("RustyPipe video details failed: {err}"))?;
 
-    let highlight_style = if app.focus == Focus::Results {
-        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
-    } else {
-        Style::default()
+    let total = details.top_comments.count;
+    let Some(ctoken) = details.top_comments.ctoken else {
+        return Ok(CommentsPage {
+            comments: Vec::new(),
+            total,
+            ctoken: None,
+            visitor_data: None,
+        });
     };
-    let results = List::new(items).highlight_style(highlight_style);
-    f.render_stateful_widget(results, list_area, &mut app.results_state);
+    let mut page = video_comments_continuation(video_id, &ctoken, details.visitor_data.as_deref())?;
+    page.total = page.total.or(total);
+    Ok(page)
+}
 
-    if let Some(scrollbar_area) = scrollbar_area {
-        let scroll_pos = app.selected_row.min(entries.len().saturating_sub(1));
-        let mut scrollbar_state = ScrollbarState::new(entries.len())
-            .position(scroll_pos)
-            .viewport_content_length(list_area.height as usize);
-        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
-        f.render_stateful_widget(scrollbar, scrollbar_area, &mut scrollbar_state);
-    }
+fn video_comments_continuation(
+    video_id: &str,
+    ctoken: &str,
+    visitor_data: Option<&str>,
+) -> Result<CommentsPage, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
 
-    let preview_block = Block::default()
-        .borders(Borders::ALL)
-        .title("Details")
-        .border_style(match app.focus {
-            Focus::Details => Style::default().fg(Color::Cyan),
-            Focus::Search | Focus::Results => Style::default(),
-        });
-    let preview_inner = preview_block.inner(chunks[2]);
-    f.render_widget(preview_block, chunks[2]);
+    let paginator = runtime
+        .block_on(rustypipe_query().video_comments(ctoken, visitor_data))
+        .map_err(|err| format!("RustyPipe comments failed: {err}"))?;
 
-    let (preview, _) = match app.results.get(app.selected) {
-        Some(SearchResultItem::Video(video)) => {
-            let views = video
-                .view_count
-                .map(format_views)
-                .unwrap_or_else(|| "- views".to_string());
-            let duration = video
-                .duration
-                .map(format_duration)
-                .unwrap_or_else(|| "-".to_string());
-            let uploader = video.channel.clone().unwrap_or_else(|| "-".to_string());
-            let published = format_published(video.publish_date_txt.as_deref(), video.publish_date);
-            let lines = vec![
-                Line::from(Span::styled(
-                    &video.title,
-                    Style::default().add_modifier(Modifier::BOLD),
-                )),
-                Line::from(Span::styled(views, Style::default().fg(Color::Yellow))),
-                Line::from(Span::styled(
-                    format!("Length: {duration}"),
-                    Style::default().fg(Color::Green),
-                )),
-                Line::from(Span::styled(
-                    format!("Uploaded by {uploader}"),
-                    Style::default().fg(Color::Blue),
-                )),
-                Line::from(Span::styled(
-                    published,
-                    Style::default().fg(Color::LightMagenta),
-                )),
-            ];
-            (Paragraph::new(lines.clone()), lines.len())
-        }
-        Some(SearchResultItem::Channel(channel)) => {
-            let subs = channel
-                .subscriber_count
-                .map(format_subscribers)
-                .unwrap_or_else(|| "- subscribers".to_string());
-            let handle = channel.handle.clone().unwrap_or_else(|| "-".to_string());
-            let desc = channel
-                .description
-                .clone()
-                .unwrap_or_else(|| "-".to_string());
-            let lines = vec![
-                Line::from(Span::styled(
-                    &channel.name,
-                    Style::default().add_modifier(Modifier::BOLD),
-                )),
-                Line::from(Span::styled(
-                    format!("Handle: {handle}"),
-                    Style::default().fg(Color::Blue),
-                )),
-                Line::from(Span::styled(subs, Style::default().fg(Color::Yellow))),
-                Line::from(Span::styled(
-                    desc,
-                    Style::default().fg(Color::LightMagenta),
-                )),
-            ];
-            (Paragraph::new(lines.clone()), lines.len())
-        }
-        None => {
-            let lines = vec![Line::from("No results yet.")];
-            (Paragraph::new(lines.clone()), lines.len())
-        }
+    Ok(CommentsPage {
+        comments: paginator
+            .items
+            .into_iter()
+            .map(|comment| comment_to_entry(video_id, comment))
+            .collect(),
+        total: paginator.count,
+        ctoken: paginator.ctoken,
+        visitor_data: paginator.visitor_data,
+    })
+}
+
+fn trending_videos(category: TrendingCategory) -> Result<SearchPage, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
+
+    let results = if category == TrendingCategory::Now {
+        let result = runtime.block_on(rustypipe_query().trending());
+
+        let videos = match result {
+            Ok(videos) => videos,
+            Err(err) => return Err(format!("RustyPipe trending failed: {err}")),
+        };
+
+        videos
+            .into_iter()
+            .map(|item| SearchResultItem::Video(video_item_to_video(item)))
+            .collect()
+    } else {
+        // RustyPipe only requests the default tab, so fetch the category page
+        // through the raw browse endpoint and extract its video renderers.
+        let body = serde_json::json!({
+            "browseId": "FEtrending",
+            "params": category.browse_params(),
+        });
+        let response = runtime
+            .block_on(rustypipe_query().raw(ClientType::Desktop, "browse", &body))
+            .map_err(|err| format!("RustyPipe trending failed: {err}"))?;
+        let value: serde_json::Value = serde_json::from_str(&response)
+            .map_err(|err| format!("Trending parse error: {err}"))?;
+        let mut videos = Vec::new();
+        collect_video_renderers(&value, &mut videos);
+        let mut seen = HashSet::new();
+        videos.retain(|video| seen.insert(video.id.clone()));
+        videos.into_iter().map(SearchResultItem::Video).collect()
     };
 
-    let (text_area, thumb_area) = match app.results.get(app.selected) {
-        Some(SearchResultItem::Video(video))
-            if preview_inner.width >= 50
-                && preview_inner.height >= 8
-                && video.thumbnail_path.is_some() =>
-        {
-            let min_text_width = 20;
-            let max_thumb_width = preview_inner.width.saturating_sub(min_text_width);
-            if max_thumb_width < 10 {
-                (preview_inner, None)
-            } else {
-                let (img_w, img_h) = video.thumbnail_size.unwrap_or((160, 90));
-                let (thumb_w, thumb_h) =
-                    fit_dimensions_cells(img_w, img_h, max_thumb_width, preview_inner.height);
-                if thumb_w == 0 || thumb_h == 0 {
-                    (preview_inner, None)
+    Ok(SearchPage {
+        results,
+        channel: None,
+        ctoken: None,
+        visitor_data: None,
+        endpoint: ContinuationEndpoint::Browse,
+        cache: None,
+    })
+}
+
+/// Walks a raw browse response and converts every `videoRenderer` found.
+fn collect_video_renderers(value: &serde_json::Value, videos: &mut Vec<Video>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                if key == "videoRenderer" {
+                    if let Some(video) = video_renderer_to_video(child) {
+                        videos.push(video);
+                    }
                 } else {
-                    let x = preview_inner.x + preview_inner.width.saturating_sub(thumb_w);
-                    let y = preview_inner.y + preview_inner.height.saturating_sub(thumb_h);
-                    let thumb_rect = ratatui::layout::Rect::new(x, y, thumb_w, thumb_h);
-                    let text_rect = ratatui::layout::Rect::new(
-                        preview_inner.x,
-                        preview_inner.y,
-                        preview_inner.width.saturating_sub(thumb_w),
-                        preview_inner.height,
-                    );
-                    (text_rect, Some(thumb_rect))
+                    collect_video_renderers(child, videos);
                 }
             }
         }
-        _ => (preview_inner, None),
-    };
-
-    app.thumb_area = thumb_area;
-    f.render_widget(preview, text_area);
-    if app.thumb_area.is_none() {
-        if let Some(last) = app.last_thumb.as_ref() {
-            f.render_widget(Clear, last.area);
-        }
-    } else if app.thumb_area.is_some() && !selected_has_thumbnail(app) {
-        if let Some(area) = app.thumb_area {
-            f.render_widget(Clear, area);
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_video_renderers(item, videos);
+            }
         }
+        _ => {}
     }
+}
 
-    if app.focus == Focus::Search {
-        f.render_widget(Paragraph::new(""), chunks[3]);
-    } else {
-        let mut controls = vec![];
-        if app.focus == Focus::Results {
-            controls.push(Span::styled(" ↑/↓ ", Style::default().fg(Color::Cyan)));
-            controls.push(Span::raw("Navigate "));
-            controls.push(Span::styled(" ⏎ ", Style::default().fg(Color::Cyan)));
-            controls.push(Span::raw("Select/Play "));
-        }
-        if app.focus == Focus::Details && selected_channel_info(app).is_some() {
-            controls.push(Span::styled(" c ", Style::default().fg(Color::Cyan)));
-            controls.push(Span::raw("Channel videos "));
+fn video_renderer_to_video(renderer: &serde_json::Value) -> Option<Video> {
+    // Text fields are either `{"simpleText": ..}` or `{"runs": [{"text": ..}]}`.
+    let text = |field: &str| -> Option<String> {
+        let value = renderer.get(field)?;
+        if let Some(text) = value.get("simpleText").and_then(serde_json::Value::as_str) {
+            return Some(text.to_string());
         }
-        controls.push(Span::styled(" ↹ ", Style::default().fg(Color::Cyan)));
-        controls.push(Span::raw("Nav "));
-        controls.push(Span::styled(" q ", Style::default().fg(Color::Cyan)));
-        controls.push(Span::raw("Quit"));
-        let controls = Line::from(controls);
-        let controls_bar = Paragraph::new(controls).alignment(Alignment::Center);
-        f.render_widget(controls_bar, chunks[3]);
-    }
+        let runs = value.get("runs")?.as_array()?;
+        Some(
+            runs.iter()
+                .filter_map(|run| run.get("text").and_then(serde_json::Value::as_str))
+                .collect(),
+        )
+    };
+    let id = renderer.get("videoId")?.as_str()?.to_string();
+    let channel_id = renderer
+        .pointer("/ownerText/runs/0/navigationEndpoint/browseEndpoint/browseId")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string);
+    let thumbnails = renderer
+        .pointer("/thumbnail/thumbnails")
+        .and_then(serde_json::Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|thumb| {
+            let width = thumb.get("width").and_then(serde_json::Value::as_u64);
+            let url = thumb.get("url")?.as_str()?.to_string();
+            Some((width.unwrap_or(0) as u32, url))
+        })
+        .collect();
+    let duration = text("lengthText").and_then(|length| {
+        length.split(':').try_fold(0u64, |secs, part| {
+            part.trim().parse::<u64>().ok().map(|n| secs * 60 + n)
+        })
+    });
+    let view_count = text("viewCountText").and_then(|views| {
+        let digits: String = views.chars().filter(char::is_ascii_digit).collect();
+        digits.parse().ok()
+    });
+    Some(Video {
+        url: format!("https://www.youtube.com/watch?v={id}"),
+        title: text("title").unwrap_or_else(|| id.clone()),
+        id,
+        channel: text("ownerText"),
+        channel_id,
+        is_live: duration.is_none(),
+        duration,
+        view_count,
+        publish_date: None,
+        publish_date_txt: text("publishedTimeText"),
+        thumbnails,
+        thumbnail_path: None,
+        thumbnail_size: None,
+        thumbnail_loading: false,
+        is_short: false,
+        chapters: None,
+        chapters_loading: false,
+        sponsor_segments: None,
+        sponsor_loading: false,
+    })
 }
 
-fn search_rustypipe(query: &str) -> Result<SearchPage, String> {
-    let client = rustypipe_client();
+fn playlist_videos(playlist_id: &str) -> Result<(SearchPage, String), String> {
     let runtime = RUNTIME.get_or_init(|| {
         tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
     });
 
-    let result = runtime.block_on(client.query().search_filter::<YouTubeItem, _>(
-        query.to_string(),
-        &SearchFilter::new(),
-    ));
+    let result = runtime.block_on(rustypipe_query().playlist(playlist_id));
 
-    let response = match result {
-        Ok(response) => response,
-        Err(err) => return Err(format!("RustyPipe search failed: {err}")),
+    let playlist = match result {
+        Ok(playlist) => playlist,
+        Err(err) => return Err(format!("RustyPipe playlist failed: {err}")),
     };
 
-    let paginator = response.items;
+    let paginator = playlist.videos;
     let mut results = Vec::new();
     for item in paginator.items {
-        if let Some(result) = youtube_item_to_result(item) {
-            results.push(result);
-        }
+        results.push(SearchResultItem::Video(video_item_to_video(item)));
     }
 
-    Ok(SearchPage {
+    let page = SearchPage {
         results,
+        channel: None,
         ctoken: paginator.ctoken,
-        visitor_data: paginator.visitor_data,
+        visitor_data: paginator.visitor_data.or(playlist.visitor_data),
         endpoint: paginator.endpoint,
-    })
+        cache: None,
+    };
+    Ok((page, playlist.name))
 }
 
-fn search_rustypipe_continuation(
-    ctoken: &str,
-    visitor_data: Option<&str>,
-    endpoint: ContinuationEndpoint,
-    target_count: usize,
-) -> Result<SearchPage, String> {
-    let client = rustypipe_client();
+/// Logs in with the cookies in the cookies.txt file at `cookies`, or else
+/// through a device code entered on YouTube's login page. The passphrase
+/// the login is saved with is asked for first unless it is known already.
+fn start_login(app: &mut App, cookies: String) {
+    if youtube_logged_in() {
+        app.toasts.push(
+            Level::Info,
+            "Already logged in; :logout first to switch accounts.".to_string(),
+        );
+        return;
+    }
+    if !credentials::unlocked() {
+        app.popup = Some(Popup::Login(LoginView {
+            cookies,
+            passphrase: Some(String::new()),
+            code: None,
+        }));
+        app.needs_full_redraw = true;
+        return;
+    }
+    begin_login(app, cookies);
+}
+
+/// RustyPipe keeps the session in its cache, which `credentials::Storage`
+/// encrypts, and every later request uses it.
+fn begin_login(app: &mut App, cookies: String) {
+    let tx = app.tx.clone();
+    if !cookies.is_empty() {
+        app.toasts
+            .push(Level::Info, "Checking the cookies...".to_string());
+        thread::spawn(move || {
+            let _ = tx.send(AppMsg::Account(login_with_cookies(&cookies)));
+        });
+        return;
+    }
+    app.popup = Some(Popup::Login(LoginView {
+        cookies: String::new(),
+        passphrase: None,
+        code: None,
+    }));
+    app.needs_full_redraw = true;
+    thread::spawn(move || {
+        let runtime = RUNTIME.get_or_init(|| {
+            tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+        });
+
+        let client = rustypipe_client();
+        let code = match runtime.block_on(client.user_auth_get_code()) {
+            Ok(code) => code,
+            Err(err) => {
+                let _ = tx.send(AppMsg::Account(Err(format!(
+                    "Could not start the login: {err}"
+                ))));
+                return;
+            }
+        };
+        let _ = tx.send(AppMsg::LoginCode(
+            code.user_code.clone(),
+            code.verification_url.clone(),
+        ));
+        let result = runtime
+            .block_on(client.user_auth_wait_for_login(&code))
+            .map(|()| "Logged in to YouTube.".to_string())
+            .map_err(|err| format!("Login failed: {err}"));
+        let _ = tx.send(AppMsg::Account(result));
+    });
+}
+
+fn login_with_cookies(path: &str) -> Result<String, String> {
     let runtime = RUNTIME.get_or_init(|| {
         tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
     });
 
-    let mut results = Vec::new();
-    let mut next_ctoken = if ctoken.is_empty() {
-        None
-    } else {
-        Some(ctoken.to_string())
+    let cookies =
+        fs::read_to_string(path).map_err(|err| format!("Failed to read {path}: {err}"))?;
+    runtime
+        .block_on(rustypipe_client().user_auth_set_cookie_txt(&cookies))
+        .map_err(|err| format!("Login with cookies failed: {err}"))?;
+    Ok("Logged in to YouTube with cookies.".to_string())
+}
+
+/// Revokes the OAuth login and drops the cookies, whichever are stored.
+fn youtube_logout() -> Result<String, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
+
+    if !credentials::unlocked() {
+        // The client never read the locked login, so only the file is left.
+        match credentials::forget() {
+            Ok(true) => return Ok("Deleted the saved login.".to_string()),
+            Ok(false) => {}
+            Err(err) => return Err(format!("Logout failed: {err}")),
+        }
+    }
+    let client = rustypipe_client();
+    // Both fail with `NoLogin` when there is nothing of their kind to drop.
+    let no_login = |err: &rustypipe::error::Error| {
+        matches!(
+            err,
+            rustypipe::error::Error::Auth(rustypipe::error::AuthError::NoLogin)
+        )
     };
-    let mut next_visitor = visitor_data.map(str::to_string);
-    let mut next_endpoint = endpoint;
-    let mut pages_fetched = 0usize;
+    let oauth = runtime.block_on(client.user_auth_logout());
+    let cookie = runtime.block_on(client.user_auth_remove_cookie());
+    match (oauth, cookie) {
+        (Err(a), Err(b)) if no_login(&a) && no_login(&b) => Err("Not logged in.".to_string()),
+        (Err(err), _) | (_, Err(err)) if !no_login(&err) => Err(format!("Logout failed: {err}")),
+        _ => Ok("Logged out of YouTube.".to_string()),
+    }
+}
 
-    while target_count > 0 && results.len() < target_count {
-        let Some(token) = next_ctoken.clone() else {
-            break;
-        };
-        let result = runtime.block_on(
-            client
-                .query()
-                .continuation::<YouTubeItem, _>(token, next_endpoint, next_visitor.as_deref()),
-        );
+/// Whether RustyPipe holds an OAuth login or cookies.
+fn youtube_logged_in() -> bool {
+    let query = rustypipe_client().query();
+    query.auth_enabled(ClientType::Desktop) || query.auth_enabled(ClientType::Tv)
+}
 
-        let paginator = match result {
-            Ok(paginator) => paginator,
-            Err(err) => return Err(format!("RustyPipe continuation failed: {err}")),
-        };
+/// Moves the channels of `imported` that have no ID yet to its
+/// subscriptions, or counts them as skipped when they can't be resolved.
+fn resolve_imported(mut imported: import::Imported) -> import::Imported {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
 
-        for item in paginator.items {
-            if let Some(result) = youtube_item_to_result(item) {
-                results.push(result);
-            }
+    for (url, name) in std::mem::take(&mut imported.unresolved) {
+        match runtime.block_on(rustypipe_query().resolve_url(&url, false)) {
+            Ok(UrlTarget::Channel { id }) => imported.subscriptions.push(Subscription {
+                name: if name.is_empty() { id.clone() } else { name },
+                id,
+            }),
+            _ => imported.skipped += 1,
+        }
+    }
+    imported
+}
+
+fn resolve_youtube_link(input: &str) -> Result<UrlTarget, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
+
+    runtime
+        .block_on(rustypipe_query().resolve_string(input, false))
+        .map_err(|err| format!("Could not open '{input}': {err}"))
+}
+
+/// Starts mpv for `url`, replacing any video that is already playing.
+fn play_url(app: &mut App, url: &str, start: Option<u32>) -> Result<(), String> {
+    let mpv = app
+        .config
+        .players()
+        .first()
+        .is_none_or(PlayerCommand::is_mpv);
+    let video_id = url
+        .strip_prefix("https://www.youtube.com/watch?v=")
+        .filter(|_| app.config.direct_streams && mpv);
+    match video_id {
+        Some(video_id) => {
+            let prefetched = app
+                .prefetch
+                .take_if(|p| p.video_id == video_id)
+                .and_then(|p| p.streams)
+                .filter(|streams| {
+                    streams.valid_until > OffsetDateTime::now_utc() + Duration::from_secs(60)
+                });
+            let result = match prefetched {
+                Some(streams) => {
+                    release_player(app);
+                    launch_direct(app, streams, start)
+                }
+                None => {
+                    resolve_streams(app, video_id.to_string(), url.to_string(), start);
+                    Ok(())
+                }
+            };
+            prefetch_next(app);
+            result
+        }
+        None => {
+            let args = vec![app.config.ytdl_format()];
+            spawn_player(app, url, start, args)
         }
+    }
+}
 
-        next_ctoken = paginator.ctoken;
-        next_visitor = paginator.visitor_data;
-        next_endpoint = paginator.endpoint;
-        pages_fetched += 1;
-        if pages_fetched >= 3 {
-            break;
-        }
+/// Starts mpv on streams resolved by RustyPipe.
+fn launch_direct(app: &mut App, streams: DirectStreams, start: Option<u32>) -> Result<(), String> {
+    let mut args = vec![
+        "--ytdl=no".to_string(),
+        format!("--force-media-title={}", streams.title),
+    ];
+    if let Some(audio) = streams.audio {
+        args.push(format!("--audio-file={audio}"));
     }
+    launch_player(app, &streams.video, start, args)
+}
 
-    Ok(SearchPage {
-        results,
-        ctoken: next_ctoken,
-        visitor_data: next_visitor,
-        endpoint: next_endpoint,
-    })
+/// Resolves the streams of the next queued video while the current one
+/// plays, so moving on doesn't wait for the lookup. Only used with
+/// `direct_streams`, since otherwise mpv does the extraction itself.
+fn prefetch_next(app: &mut App) {
+    if !app.config.direct_streams || app.shuffle {
+        return;
+    }
+    let Some(next) = app.queue.front() else {
+        return;
+    };
+    if app.prefetch.as_ref().is_some_and(|p| p.video_id == next.id) {
+        return;
+    }
+    let video_id = next.id.clone();
+    let max_height = app.config.max_height();
+    app.prefetch = Some(Prefetch {
+        video_id: video_id.clone(),
+        streams: None,
+    });
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = direct_streams(&video_id, max_height);
+        let _ = tx.send(AppMsg::Prefetched { video_id, result });
+    });
 }
 
-fn channel_videos_latest(channel_id: &str) -> Result<SearchPage, String> {
-    let client = rustypipe_client();
-    let runtime = RUNTIME.get_or_init(|| {
-        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+/// Looks up the video's stream URLs in the background and starts mpv on
+/// them once they arrive.
+fn resolve_streams(app: &mut App, video_id: String, url: String, start: Option<u32>) {
+    release_player(app);
+    let request = app.stream_request;
+    app.playback = Some(PlaybackState::default());
+    let max_height = app.config.max_height();
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = direct_streams(&video_id, max_height);
+        let _ = tx.send(AppMsg::Streams {
+            request,
+            url,
+            start,
+            result,
+        });
     });
+}
 
-    let result = runtime.block_on(
-        client
-            .query()
-            .channel_videos_order(channel_id, ChannelOrder::Latest),
-    );
+/// Makes way for a new video: the current player is stopped, or left
+/// running on its own when `keep_players` is set.
+fn release_player(app: &mut App) {
+    if !app.config.keep_players {
+        stop_player(app);
+        return;
+    }
+    if let Some(player) = app.player.take() {
+        app.detached_players.push(player);
+    }
+    app.handoff = None;
+    finish_current_video(app);
+    app.playback = None;
+    app.skip_segments.clear();
+    app.stream_request += 1;
+}
 
-    let paginator = match result {
-        Ok(paginator) => paginator,
-        Err(err) => return Err(format!("RustyPipe channel videos failed: {err}")),
-    };
+/// Stops the running player, keeping its watch progress.
+fn stop_player(app: &mut App) {
+    if let Some(player) = app.player.take() {
+        player.quit();
+    }
+    app.handoff = None;
+    finish_current_video(app);
+    app.playback = None;
+    app.skip_segments.clear();
+    app.stream_request += 1;
+}
 
-    let mut results = Vec::new();
-    for item in paginator.items {
-        results.push(SearchResultItem::Video(video_item_to_video(item)));
+/// Starts mpv with extra `args` (such as the format selection) for `url`.
+fn spawn_player(
+    app: &mut App,
+    url: &str,
+    start: Option<u32>,
+    args: Vec<String>,
+) -> Result<(), String> {
+    release_player(app);
+    launch_player(app, url, start, args)
+}
+
+/// Starts the configured player without touching the current one.
+fn launch_player(
+    app: &mut App,
+    url: &str,
+    start: Option<u32>,
+    mut args: Vec<String>,
+) -> Result<(), String> {
+    if let Some(start) = start.filter(|start| *start > 0) {
+        args.push(format!("--start={start}"));
+    }
+    // An explicit pick from the subtitle picker wins over the default.
+    let picked = args
+        .iter()
+        .any(|arg| arg.starts_with("--sub-file=") || arg.starts_with("--sid="));
+    if let Some(language) = app.config.subtitle_language.as_deref().filter(|_| !picked) {
+        args.push(format!("--slang={language}"));
+    }
+    if let Some(preset) = app.config.window_presets().get(app.window_preset) {
+        args.extend(preset.mpv_args());
+    }
+    if app.repeat == Repeat::One {
+        args.push("--loop-file=inf".to_string());
+    }
+    if app.speed != 1.0 {
+        args.push(format!("--speed={}", app.speed));
     }
 
-    Ok(SearchPage {
-        results,
-        ctoken: paginator.ctoken,
-        visitor_data: paginator.visitor_data,
-        endpoint: paginator.endpoint,
-    })
+    // Without a configured player, mpv (or `YTBV_MPV`) is always driven over IPC.
+    let default = [PlayerCommand {
+        program: env::var("YTBV_MPV").unwrap_or_else(|_| "mpv".to_string()),
+        args: Vec::new(),
+    }];
+    let configured = app.config.players();
+    let players = if configured.is_empty() {
+        &default[..]
+    } else {
+        configured
+    };
+    let mut errors = Vec::new();
+    for (index, command) in players.iter().enumerate() {
+        let tx = app.tx.clone();
+        let on_event = move |id, event| {
+            let _ = tx.send(AppMsg::Player { id, event });
+        };
+        let ipc = command.is_mpv() || configured.is_empty();
+        let command_args = command.expand_args(url, start.unwrap_or(0));
+        let spawned = if ipc {
+            let mut mpv_args = args.clone();
+            mpv_args.extend(command_args);
+            Player::spawn(&command.program, &mpv_args, on_event)
+        } else {
+            Player::spawn_plain(&command.program, &command_args, on_event)
+        };
+        match spawned {
+            Ok(player) => {
+                app.player = Some(player);
+                app.playback = Some(PlaybackState {
+                    player: (index > 0 || !ipc).then(|| command.name()),
+                    ..PlaybackState::default()
+                });
+                app.ended_at_eof = false;
+                return Ok(());
+            }
+            Err(err) => errors.push(err),
+        }
+    }
+    Err(errors.join("; "))
 }
 
-fn play_video(video: &Video) -> Result<(), String> {
-    let mpv_bin = env::var("YTBV_MPV").unwrap_or_else(|_| "mpv".to_string());
+/// Opens the quality menu for the selected video; returns false when no
+/// video is selected so the key falls through to the normal handler.
+fn open_quality_menu(app: &mut App) -> bool {
+    if app.popup.is_some() || app.screen.focus != Focus::Results {
+        return false;
+    }
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        return false;
+    };
+    let video_id = video.id.clone();
+    app.popup = Some(Popup::Quality(QualityView {
+        video_id: video_id.clone(),
+        video_title: video.title.clone(),
+        url: video.url.clone(),
+        options: Vec::new(),
+        selected: 0,
+        loading: true,
+        error: None,
+    }));
+    app.needs_full_redraw = true;
+    let tx = app.tx.clone();
+    thread::spawn(move || {
+        let result = video_qualities(&video_id);
+        let _ = tx.send(AppMsg::Formats { video_id, result });
+    });
+    true
+}
 
-    Command::new(&mpv_bin)
-        .args([
-            "--ytdl-format=bestvideo[height<=1080]+bestaudio/best",
-            &video.url,
-        ])
-        .stdin(Stdio::null())
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-        .map(|_| ())
-        .map_err(|err| format!("Failed to start mpv ('{mpv_bin}'): {err}"))
+/// Alt plus a playback key works from any focus, including the search box;
+/// Alt+Left/Right seek as well.
+fn handle_global_player_key(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char(c) => handle_player_key(app, c),
+        KeyCode::Left => handle_player_key(app, '['),
+        KeyCode::Right => handle_player_key(app, ']'),
+        _ => false,
+    }
+}
+
+/// Handles the playback keys; returns false when nothing is playing or the
+/// key isn't one of them.
+fn handle_player_key(app: &mut App, c: char) -> bool {
+    match c {
+        '>' => {
+            play_next(app);
+            return true;
+        }
+        '<' => {
+            play_previous(app);
+            return true;
+        }
+        '{' | '}' => {
+            change_speed(app, c == '}');
+            return true;
+        }
+        _ => {}
+    }
+    let Some(player) = app.player.as_ref() else {
+        return false;
+    };
+    let result = match c {
+        ' ' => player.toggle_pause(),
+        '[' => player.seek(-10.0),
+        ']' => player.seek(10.0),
+        '-' => player.add_volume(-5.0),
+        '+' | '=' => player.add_volume(5.0),
+        'x' => {
+            stop_player(app);
+            app.toasts
+                .push(Level::Info, "Playback stopped.".to_string());
+            return true;
+        }
+        _ => return false,
+    };
+    if let Err(err) = result {
+        app.toasts.push(Level::Error, err);
+    }
+    true
 }
 
 fn queue_thumbnail(app: &mut App, index: usize) {
+    if app.config.graphics == Graphics::Off
+        || index != app.screen.selected && app.thumbnail_downloads >= THUMBNAIL_PREFETCHES
+    {
+        return;
+    }
     let tx = app.tx.clone();
-    let maybe_url = app.results.get_mut(index).and_then(|item| match item {
-        SearchResultItem::Video(video) => {
-            if video.thumbnail_path.is_none() && !video.thumbnail_loading {
-                if let Some(url) = video.thumbnail_url.clone() {
-                    video.thumbnail_loading = true;
-                    return Some(url);
+    let limit = app.config.thumbnail_cache_bytes();
+    let width = thumbnail_pixels(app);
+    let (branding, original) = (&app.branding, app.original_branding);
+    let maybe_url = app
+        .screen
+        .results
+        .get_mut(index)
+        .and_then(|item| match item {
+            SearchResultItem::Video(video) => {
+                if video.thumbnail_path.is_none() && !video.thumbnail_loading {
+                    let branding = active_branding(branding, original, &video.id);
+                    if let Some(url) = video
+                        .display_thumbnail_url(branding, width)
+                        .map(str::to_string)
+                    {
+                        video.thumbnail_loading = true;
+                        return Some(url);
+                    }
                 }
+                None
             }
-            None
-        }
-        SearchResultItem::Channel(_) => None,
-    });
+            SearchResultItem::Channel(_) | SearchResultItem::Playlist(_) => None,
+        });
 
     if let Some(url) = maybe_url {
-        thread::spawn(move || {
-            let result = download_thumbnail(&url);
-            let _ = tx.send(AppMsg::Thumbnail { index, result });
+        app.thumbnail_downloads += 1;
+        app.thumbnail_pool.spawn(move |client| {
+            let result = download_thumbnail(client, &url, limit);
+            let _ = tx.send(AppMsg::Thumbnail { index, url, result });
         });
     }
 }
 
+/// How many pixels wide thumbnails are drawn: the grid cells, or a 16:9
+/// thumbnail in the Details pane, at the terminal's cell width. Half blocks take
+/// two pixels a cell, one per row, but only one across.
+fn thumbnail_pixels(app: &App) -> u32 {
+    let columns = if app.grid_view {
+        app.grid_cell_width
+    } else {
+        let (width, height) = app.thumb_bounds;
+        let width = app
+            .config
+            .thumbnail_width
+            .map_or(width, |thumb_width| thumb_width.min(width));
+        fit_dimensions_cells(16, 9, width, height).0
+    };
+    let per_column = if blocks::wanted(app.config.graphics) {
+        1
+    } else {
+        cell_pixel_width()
+    };
+    u32::from(columns.max(MIN_THUMB_WIDTH)) * per_column
+}
+
+/// Pixels per terminal column, when the terminal tells; 10 otherwise.
+fn cell_pixel_width() -> u32 {
+    static WIDTH: OnceLock<u32> = OnceLock::new();
+    *WIDTH.get_or_init(|| match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.columns > 0 => {
+            u32::from(size.width).div_ceil(u32::from(size.columns))
+        }
+        _ => 10,
+    })
+}
+
+/// The image shown as the selected video's thumbnail: the current preview
+/// frame, else the thumbnail itself.
+fn selected_thumbnail(app: &App) -> Option<&PathBuf> {
+    let Some(SearchResultItem::Video(video)) = app.screen.results.get(app.screen.selected) else {
+        return None;
+    };
+    let frame = app
+        .preview
+        .as_ref()
+        .filter(|preview| preview.video_id == video.id && !preview.frames.is_empty())
+        .map(|preview| {
+            let shown = preview.since.elapsed().as_millis() / PREVIEW_FRAME.as_millis();
+            &preview.frames[shown as usize % preview.frames.len()]
+        });
+    frame.or(video.thumbnail_path.as_ref())
+}
+
 fn render_thumbnail(app: &mut App) -> io::Result<()> {
     let area = match app.thumb_area {
         Some(area) => area,
@@ -1029,71 +11138,147 @@ fn render_thumbnail(app: &mut App) -> io::Result<()> {
         }
     };
 
-    let item = match app.results.get(app.selected) {
-        Some(item) => item,
+    let path = match selected_thumbnail(app) {
+        Some(path) => path.clone(),
         None => {
             app.last_thumb = None;
             return Ok(());
         }
     };
 
-    let path = match item {
-        SearchResultItem::Video(video) => match &video.thumbnail_path {
-            Some(path) => path,
-            None => {
-                app.last_thumb = None;
-                return Ok(());
-            }
-        },
-        SearchResultItem::Channel(_) => {
-            app.last_thumb = None;
+    if let Some(last) = app.last_thumb.as_ref() {
+        if last.path == path && last.area == area {
             return Ok(());
         }
-    };
+    }
 
-    if let Some(last) = app.last_thumb.as_ref() {
-        if last.path == *path && last.area == area {
-            return Ok(());
+    if let Err(err) = draw_image(app.config.graphics, &path, area) {
+        log::warn!("Could not draw {}: {err}", path.display());
+    }
+    app.last_thumb = Some(ThumbRender { path, area });
+    Ok(())
+}
+
+/// Whether an image printed earlier is no longer laid out where it is, so
+/// its pixels would stay behind: the selection moved to a result with
+/// another size of thumbnail or none, the pane was resized, the grid
+/// scrolled or the view changed. One in the same place is drawn over.
+fn images_moved(app: &App) -> bool {
+    app.last_thumb
+        .iter()
+        .chain(app.last_avatar.iter())
+        .chain(app.grid_drawn.iter())
+        .any(|printed| !app.image_areas.contains(&printed.area))
+}
+
+/// Forgets which images are on the terminal after it was cleared.
+fn forget_printed_images(app: &mut App) {
+    app.last_thumb = None;
+    app.last_avatar = None;
+    app.grid_drawn.clear();
+}
+
+/// Prints the thumbnails of the grid cells laid out in the last frame.
+fn render_grid_thumbnails(app: &mut App) -> io::Result<()> {
+    let wanted: Vec<ThumbRender> = app
+        .grid_cells
+        .iter()
+        .filter_map(|&(index, area)| match app.screen.results.get(index) {
+            Some(SearchResultItem::Video(video)) => video
+                .thumbnail_path
+                .clone()
+                .map(|path| ThumbRender { path, area }),
+            _ => None,
+        })
+        .collect();
+    for thumb in wanted {
+        if app.grid_drawn.contains(&thumb) {
+            continue;
         }
+        if let Err(err) = draw_image(app.config.graphics, &thumb.path, thumb.area) {
+            log::warn!("Could not draw {}: {err}", thumb.path.display());
+        }
+        app.grid_drawn.push(thumb);
     }
+    Ok(())
+}
 
+/// Draws the image in `path` into `area` the way `graphics` says. iTerm2
+/// and WezTerm get inline images from `iterm` even in Auto, as both also
+/// answer the sixel query but draw sixels badly.
+fn draw_image(graphics: Graphics, path: &Path, area: ratatui::layout::Rect) -> Result<(), String> {
+    let (use_kitty, use_sixel) = match graphics {
+        Graphics::Iterm => return iterm::print(path, area.x, area.y, area.width, area.height),
+        Graphics::Auto if iterm::supported() => {
+            return iterm::print(path, area.x, area.y, area.width, area.height)
+        }
+        Graphics::Auto => (true, true),
+        Graphics::Kitty => (true, false),
+        Graphics::Sixel => (false, true),
+        Graphics::Blocks => (false, false),
+        Graphics::Off => return Ok(()),
+    };
     let config = ViuerConfig {
         x: area.x,
         y: area.y as i16,
         width: Some(u32::from(area.width)),
         height: Some(u32::from(area.height)),
-        use_sixel: true,
+        use_kitty,
+        use_iterm: false,
+        use_sixel,
         ..Default::default()
     };
+    viuer::print_from_file(path, &config)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
 
-    let _ = viuer::print_from_file(path, &config);
-    app.last_thumb = Some(ThumbRender {
-        path: path.clone(),
-        area,
-    });
+fn render_channel_avatar(app: &mut App) -> io::Result<()> {
+    let (Some(area), Some(path)) = (
+        app.avatar_area,
+        app.screen
+            .channel_header
+            .as_ref()
+            .and_then(|header| header.avatar_path.clone()),
+    ) else {
+        app.last_avatar = None;
+        return Ok(());
+    };
+
+    if let Some(last) = app.last_avatar.as_ref() {
+        if last.path == path && last.area == area {
+            return Ok(());
+        }
+    }
+
+    if let Err(err) = draw_image(app.config.graphics, &path, area) {
+        log::warn!("Could not draw {}: {err}", path.display());
+    }
+    app.last_avatar = Some(ThumbRender { path, area });
     Ok(())
 }
 
 fn rustypipe_client() -> &'static RustyPipe {
     RUSTYPIPE.get_or_init(|| {
-        let storage_dir = rustypipe_storage_dir();
         RustyPipe::builder()
-            .storage_dir(storage_dir)
+            .storage_dir(rustypipe_storage_dir())
+            .storage(Box::new(credentials::Storage::new(rustypipe_cache_file())))
             .build()
             .expect("Failed to initialize RustyPipe")
     })
 }
 
-fn rustypipe_storage_dir() -> PathBuf {
-    if let Ok(dir) = std::env::var("XDG_DATA_HOME") {
-        return Path::new(&dir).join("rustypipe");
-    }
+fn rustypipe_query() -> RustyPipeQuery {
+    let (language, country) = current_locale();
+    rustypipe_client().query().lang(language).country(country)
+}
 
-    if let Ok(home) = std::env::var("HOME") {
-        return Path::new(&home).join(".local").join("share").join("rustypipe");
-    }
+fn rustypipe_cache_file() -> PathBuf {
+    rustypipe_storage_dir().join("rustypipe_cache.json")
+}
 
-    PathBuf::from(".")
+fn rustypipe_storage_dir() -> PathBuf {
+    paths::rustypipe_dir().unwrap_or_else(|_| PathBuf::from("."))
 }
 
 fn video_item_to_video(video: VideoItem) -> Video {
@@ -1101,20 +11286,59 @@ fn video_item_to_video(video: VideoItem) -> Video {
         Some(channel) => (Some(channel.name), Some(channel.id)),
         None => (None, None),
     };
-    let thumbnail_url = video.thumbnail.into_iter().next().map(|t| t.url);
+    let thumbnails = video
+        .thumbnail
+        .into_iter()
+        .map(|thumb| (thumb.width, thumb.url))
+        .collect();
     Video {
-        title: video.name,
         url: format!("https://www.youtube.com/watch?v={}", video.id),
+        id: video.id,
+        title: video.name,
         channel,
         channel_id,
         duration: video.duration.map(u64::from),
         view_count: video.view_count,
         publish_date: video.publish_date,
         publish_date_txt: video.publish_date_txt,
-        thumbnail_url,
+        thumbnails,
         thumbnail_path: None,
         thumbnail_size: None,
         thumbnail_loading: false,
+        is_live: video.is_live,
+        is_short: video.is_short,
+        chapters: None,
+        chapters_loading: false,
+        sponsor_segments: None,
+        sponsor_loading: false,
+    }
+}
+
+fn comment_to_entry(video_id: &str, comment: Comment) -> CommentEntry {
+    // Timestamps in comments come as links to the same video.
+    let text = comment
+        .text
+        .0
+        .into_iter()
+        .map(|component| match component {
+            TextComponent::YouTube {
+                text,
+                target: UrlTarget::Video { id, start_time },
+            } if id == video_id => (text, Some(start_time)),
+            other => (other.get_text().to_string(), None),
+        })
+        .collect();
+    CommentEntry {
+        author: comment
+            .author
+            .map(|author| author.name)
+            .unwrap_or_else(|| "Unknown".to_string()),
+        published: comment.publish_date_txt,
+        like_count: comment.like_count,
+        reply_count: comment.reply_count,
+        pinned: comment.pinned,
+        by_owner: comment.by_owner,
+        text,
     }
 }
 
@@ -1132,42 +11356,168 @@ fn channel_item_to_channel(channel: ChannelItem) -> Channel {
     }
 }
 
+fn playlist_item_to_playlist(playlist: PlaylistItem) -> Playlist {
+    let (channel, channel_id) = match playlist.channel {
+        Some(channel) => (Some(channel.name), Some(channel.id)),
+        None => (None, None),
+    };
+    Playlist {
+        name: playlist.name,
+        id: playlist.id,
+        channel,
+        channel_id,
+        video_count: playlist.video_count,
+    }
+}
+
 fn youtube_item_to_result(item: YouTubeItem) -> Option<SearchResultItem> {
     match item {
         YouTubeItem::Video(video) => Some(SearchResultItem::Video(video_item_to_video(video))),
         YouTubeItem::Channel(channel) => {
             Some(SearchResultItem::Channel(channel_item_to_channel(channel)))
         }
-        YouTubeItem::Playlist(_) => None,
+        YouTubeItem::Playlist(playlist) => Some(SearchResultItem::Playlist(
+            playlist_item_to_playlist(playlist),
+        )),
+    }
+}
+
+/// Decides whether search input should be opened directly instead of being
+/// searched for: YouTube URLs, and bare video IDs that don't read like a word.
+fn looks_like_youtube_link(input: &str) -> bool {
+    if input.contains(char::is_whitespace) {
+        return false;
+    }
+    let lower = input.to_ascii_lowercase();
+    let without_scheme = lower
+        .strip_prefix("https://")
+        .or_else(|| lower.strip_prefix("http://"))
+        .unwrap_or(&lower);
+    let host = without_scheme.split('/').next().unwrap_or_default();
+    if host == "youtu.be" || host == "youtube.com" || host.ends_with(".youtube.com") {
+        return without_scheme.len() > host.len();
     }
+
+    let is_id_charset = input.len() == 11
+        && input
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let has_id_marker = input
+        .chars()
+        .skip(1)
+        .any(|c| c.is_ascii_digit() || c.is_ascii_uppercase() || c == '-' || c == '_');
+    is_id_charset && has_id_marker
+}
+
+fn playlist_url(playlist_id: &str) -> String {
+    format!("https://www.youtube.com/playlist?list={playlist_id}")
+}
+
+fn channel_url(channel_id: &str) -> String {
+    format!("https://www.youtube.com/channel/{channel_id}")
 }
 
-fn download_thumbnail(url: &str) -> Result<PathBuf, String> {
+/// Returns the cached copy of `url`, downloading it first when missing and
+/// then evicting the least recently used files past `limit` bytes.
+fn download_thumbnail(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    limit: u64,
+) -> Result<PathBuf, String> {
     let cache_dir = thumbnail_cache_dir()?;
     fs::create_dir_all(&cache_dir).map_err(|e| format!("Cache dir error: {e}"))?;
 
     let filename = safe_filename(url);
     let path = cache_dir.join(filename);
     if path.exists() {
-        let _ = enforce_thumbnail_cache_limit(&cache_dir);
+        // The modification time doubles as the last use, for eviction.
+        let touched = fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(std::time::SystemTime::now()));
+        if let Err(err) = touched {
+            log::debug!("Could not mark {} as used: {err}", path.display());
+        }
         return Ok(path);
     }
 
-    let response = reqwest::blocking::get(url).map_err(|e| format!("Download error: {e}"))?;
+    let response = client
+        .get(url)
+        .send()
+        .and_then(reqwest::blocking::Response::error_for_status)
+        .map_err(|e| format!("Download error: {e}"))?;
     let bytes = response.bytes().map_err(|e| format!("Read error: {e}"))?;
     fs::write(&path, &bytes).map_err(|e| format!("Write error: {e}"))?;
-    let _ = enforce_thumbnail_cache_limit(&cache_dir);
+    if let Err(err) = enforce_thumbnail_cache_limit(&cache_dir, limit) {
+        log::warn!("Thumbnail cache not trimmed: {err}");
+    }
 
     Ok(path)
 }
 
-fn thumbnail_cache_dir() -> Result<PathBuf, String> {
-    if let Ok(dir) = std::env::var("XDG_CACHE_HOME") {
-        return Ok(Path::new(&dir).join("ytbv").join("thumbs"));
+/// Cuts frames for a hover preview out of the video's storyboard, at the
+/// smallest size that fills `width` pixels, into the thumbnail cache.
+fn storyboard_frames(
+    client: &reqwest::blocking::Client,
+    video_id: &str,
+    width: u32,
+    limit: u64,
+) -> Result<Vec<PathBuf>, String> {
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")
+    });
+    let player = runtime
+        .block_on(rustypipe_query().player(video_id))
+        .map_err(|err| format!("RustyPipe player failed: {err}"))?;
+    let frameset = storyboard::pick(&player.preview_frames, width)
+        .ok_or_else(|| "No storyboard".to_string())?;
+    let cache_dir = thumbnail_cache_dir()?;
+    let frames = storyboard::frames(client, frameset, video_id, &cache_dir)?;
+    if let Err(err) = enforce_thumbnail_cache_limit(&cache_dir, limit) {
+        log::warn!("Thumbnail cache not trimmed: {err}");
+    }
+    Ok(frames)
+}
+
+fn show_cache_stats(app: &mut App) {
+    let dir = match thumbnail_cache_dir() {
+        Ok(dir) => dir,
+        Err(err) => {
+            app.toasts.push(Level::Error, err);
+            return;
+        }
+    };
+    let usage =
+        thumbnail_cache_entries(&dir).and_then(|entries| Ok((entries, search_cache::usage()?)));
+    let (entries, (searches, search_bytes)) = match usage {
+        Ok(usage) => usage,
+        Err(err) => {
+            app.toasts.push(Level::Error, err);
+            return;
+        }
+    };
+    app.popup = Some(Popup::CacheStats(CacheStats {
+        files: entries.len(),
+        bytes: entries.iter().map(|(_, _, size)| size).sum(),
+        limit: app.config.thumbnail_cache_bytes(),
+        dir,
+        searches,
+        search_bytes,
+        search_ttl: app.config.search_cache_secs(),
+    }));
+    app.needs_full_redraw = true;
+}
+
+fn purge_thumbnail_cache() -> Result<(), String> {
+    match fs::remove_dir_all(thumbnail_cache_dir()?) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(format!("Cache delete error: {err}")),
     }
+}
 
-    let home = std::env::var("HOME").map_err(|_| "HOME not set".to_string())?;
-    Ok(Path::new(&home).join(".cache").join("ytbv").join("thumbs"))
+fn thumbnail_cache_dir() -> Result<PathBuf, String> {
+    Ok(paths::cache_dir()?.join("thumbs"))
 }
 
 fn safe_filename(url: &str) -> String {
@@ -1186,28 +11536,41 @@ fn safe_filename(url: &str) -> String {
     format!("{name}.img")
 }
 
-fn enforce_thumbnail_cache_limit(cache_dir: &Path) -> Result<(), String> {
+/// `(path, last use, size)` of every file in the thumbnail cache; a missing
+/// cache is empty.
+fn thumbnail_cache_entries(
+    cache_dir: &Path,
+) -> Result<Vec<(PathBuf, std::time::SystemTime, u64)>, String> {
     let mut entries = Vec::new();
-    let mut total_bytes = 0u64;
-    for entry in fs::read_dir(cache_dir).map_err(|e| format!("Cache read error: {e}"))? {
+    let dir = match fs::read_dir(cache_dir) {
+        Ok(dir) => dir,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(entries),
+        Err(err) => return Err(format!("Cache read error: {err}")),
+    };
+    for entry in dir {
         let entry = entry.map_err(|e| format!("Cache read error: {e}"))?;
-        let meta = entry.metadata().map_err(|e| format!("Cache meta error: {e}"))?;
+        let meta = entry
+            .metadata()
+            .map_err(|e| format!("Cache meta error: {e}"))?;
         if !meta.is_file() {
             continue;
         }
         let modified = meta.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
-        let size = meta.len();
-        total_bytes = total_bytes.saturating_add(size);
-        entries.push((entry.path(), modified, size));
+        entries.push((entry.path(), modified, meta.len()));
     }
+    Ok(entries)
+}
 
-    if total_bytes <= THUMB_CACHE_MAX_BYTES {
+fn enforce_thumbnail_cache_limit(cache_dir: &Path, limit: u64) -> Result<(), String> {
+    let mut entries = thumbnail_cache_entries(cache_dir)?;
+    let mut total_bytes: u64 = entries.iter().map(|(_, _, size)| size).sum();
+    if total_bytes <= limit {
         return Ok(());
     }
 
     entries.sort_by_key(|(_, modified, _)| *modified);
     for (path, _, size) in entries {
-        if total_bytes <= THUMB_CACHE_MAX_BYTES {
+        if total_bytes <= limit {
             break;
         }
         if fs::remove_file(&path).is_ok() {
@@ -1245,6 +11608,41 @@ fn format_duration(secs: u64) -> String {
     format!("{minutes:02}:{seconds:02}")
 }
 
+/// Writes `count` in full with thousands separators, like `1,234,567`.
+fn group_digits(count: u64) -> String {
+    let digits = count.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Short count like `1.2K` or `3.45M`.
+fn format_count(count: u64) -> String {
+    let (value, suffix) = if count >= 1_000_000_000 {
+        (count as f64 / 1_000_000_000.0, "B")
+    } else if count >= 1_000_000 {
+        (count as f64 / 1_000_000.0, "M")
+    } else if count >= 1_000 {
+        (count as f64 / 1_000.0, "K")
+    } else {
+        return count.to_string();
+    };
+
+    let mut s = format!("{value:.2}");
+    while s.ends_with('0') {
+        s.pop();
+    }
+    if s.ends_with('.') {
+        s.pop();
+    }
+    format!("{s}{suffix}")
+}
+
 fn format_views(views: u64) -> String {
     let (value, suffix) = if views >= 1_000_000_000 {
         (views as f64 / 1_000_000_000.0, "B")
@@ -1268,7 +11666,7 @@ fn format_views(views: u64) -> String {
 
 fn format_published(relative: Option<&str>, date: Option<OffsetDateTime>) -> String {
     let absolute = date.and_then(|d| {
-        let format = format_description::parse("[day]/[month]/[year]").ok()?;
+        let format = format_description::parse_borrowed::<2>("[day]/[month]/[year]").ok()?;
         d.format(&format).ok()
     });
 
@@ -1324,7 +11722,7 @@ fn thumbnail_size_from_path(path: &Path) -> Option<(u32, u32)> {
 }
 
 fn selected_channel_info(app: &App) -> Option<(String, String)> {
-    match app.results.get(app.selected) {
+    match app.screen.results.get(app.screen.selected) {
         Some(SearchResultItem::Video(video)) => {
             let channel_id = video.channel_id.clone()?;
             let channel_name = video.channel.clone().unwrap_or_else(|| "Channel".to_string());
@@ -1333,14 +11731,90 @@ fn selected_channel_info(app: &App) -> Option<(String, String)> {
         Some(SearchResultItem::Channel(channel)) => {
             Some((channel.id.clone(), channel.name.clone()))
         }
+        Some(SearchResultItem::Playlist(playlist)) => {
+            let channel_id = playlist.channel_id.clone()?;
+            let channel_name = playlist
+                .channel
+                .clone()
+                .unwrap_or_else(|| "Channel".to_string());
+            Some((channel_id, channel_name))
+        }
         None => None,
     }
 }
 
+/// Returns the playlist to play with `p`: the selected playlist result, or
+/// the playlist whose videos are currently listed.
+fn current_playlist_info(app: &App) -> Option<(String, String)> {
+    if let Some(SearchResultItem::Playlist(playlist)) = app.screen.results.get(app.screen.selected)
+    {
+        return Some((playlist.id.clone(), playlist.name.clone()));
+    }
+    match &app.screen.source {
+        Some(ResultsSource::Playlist { id, name }) => Some((id.clone(), name.clone())),
+        _ => None,
+    }
+}
+
+fn results_title(app: &App) -> String {
+    let base = match &app.screen.source {
+        Some(ResultsSource::Channel { name, tab, .. }) => {
+            format!("Channel: {name} — {}", tab.label())
+        }
+        Some(ResultsSource::Playlist { name, .. }) => format!("Playlist: {name}"),
+        Some(ResultsSource::Trending(category)) => format!("Trending — {}", category.label()),
+        Some(ResultsSource::Related { title, .. }) => format!("Related: {title}"),
+        Some(ResultsSource::Feed) => "Feed".to_string(),
+        Some(ResultsSource::Search(_)) | None => "Results".to_string(),
+    };
+    let base = if app.screen.visible.is_empty() {
+        base
+    } else {
+        let row = app.screen.selected_row.min(app.screen.visible.len() - 1) + 1;
+        format!("{base} ({row}/{})", app.screen.visible.len())
+    };
+    // Only sources that come in pages have more than one.
+    let base = if app.screen.pages_loaded > 1 || app.screen.search_ctoken.is_some() {
+        format!("{base} — page {}", app.screen.pages_loaded)
+    } else {
+        base
+    };
+    let base = if app.sort == SortOrder::Default {
+        base
+    } else {
+        format!("{base} [{}]", app.sort.label())
+    };
+    let base = if app.live_filter == LiveFilter::All {
+        base
+    } else {
+        format!("{base} [{}]", app.live_filter.label())
+    };
+    let base = if app.screen.filter.is_empty() {
+        base
+    } else {
+        format!(
+            "{base} [/{}: {} of {}]",
+            app.screen.filter,
+            app.screen.visible.len(),
+            app.screen.results.len()
+        )
+    };
+    if app.screen.searching {
+        let elapsed = app.screen.search_started.elapsed();
+        // One frame per 200ms tick of the main loop.
+        let frame = (elapsed.as_millis() / 200) as usize % SPINNER.len();
+        format!("{base} {} {}s", SPINNER[frame], elapsed.as_secs())
+    } else if app.screen.loading_more {
+        format!("{base} (loading more…)")
+    } else {
+        base
+    }
+}
+
 fn selected_has_thumbnail(app: &App) -> bool {
-    match app.results.get(app.selected) {
+    match app.screen.results.get(app.screen.selected) {
         Some(SearchResultItem::Video(video)) => video.thumbnail_path.is_some(),
-        Some(SearchResultItem::Channel(_)) => false,
+        Some(SearchResultItem::Channel(_)) | Some(SearchResultItem::Playlist(_)) => false,
         None => false,
     }
 }
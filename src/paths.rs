@@ -0,0 +1,109 @@
+//! Where ytbv keeps its files, in order of precedence:
+//!
+//! - `--data-dir` and `--cache-dir`, each for its own directory;
+//! - `$YTBV_HOME`, with `data`, `cache` and `config` directories in it;
+//! - portable mode (`--portable`, or a file named `portable` next to the
+//!   binary), which keeps the same three directories next to the binary;
+//! - the XDG variables, then the platform's usual places: `~/.local/share`,
+//!   `~/.cache` and `~/.config` on Unix, `%APPDATA%` and `%LOCALAPPDATA%`
+//!   on Windows.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static OVERRIDES: OnceLock<Overrides> = OnceLock::new();
+
+/// Directories given on the command line.
+#[derive(Debug, Default)]
+pub struct Overrides {
+    pub data_dir: Option<PathBuf>,
+    pub cache_dir: Option<PathBuf>,
+    pub portable: bool,
+}
+
+/// Applies the command line; the paths are fixed from then on.
+pub fn init(overrides: Overrides) {
+    let _ = OVERRIDES.set(overrides);
+}
+
+pub fn data_dir() -> Result<PathBuf, String> {
+    if let Some(dir) = overrides().data_dir.clone() {
+        return Ok(dir);
+    }
+    if let Some(home) = ytbv_home() {
+        return Ok(home.join("data"));
+    }
+    if let Some(dir) = env::var_os("XDG_DATA_HOME") {
+        return Ok(Path::new(&dir).join("ytbv"));
+    }
+    if cfg!(windows) {
+        return Ok(windows_dir("APPDATA")?.join("ytbv").join("data"));
+    }
+    Ok(home_dir()?.join(".local").join("share").join("ytbv"))
+}
+
+pub fn cache_dir() -> Result<PathBuf, String> {
+    if let Some(dir) = overrides().cache_dir.clone() {
+        return Ok(dir);
+    }
+    if let Some(home) = ytbv_home() {
+        return Ok(home.join("cache"));
+    }
+    if let Some(dir) = env::var_os("XDG_CACHE_HOME") {
+        return Ok(Path::new(&dir).join("ytbv"));
+    }
+    if cfg!(windows) {
+        return Ok(windows_dir("LOCALAPPDATA")?.join("ytbv").join("cache"));
+    }
+    Ok(home_dir()?.join(".cache").join("ytbv"))
+}
+
+pub fn config_dir() -> Result<PathBuf, String> {
+    if let Some(home) = ytbv_home() {
+        return Ok(home.join("config"));
+    }
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME") {
+        return Ok(Path::new(&dir).join("ytbv"));
+    }
+    if cfg!(windows) {
+        return Ok(windows_dir("APPDATA")?.join("ytbv"));
+    }
+    Ok(home_dir()?.join(".config").join("ytbv"))
+}
+
+/// RustyPipe's cache and login. Shared with other RustyPipe programs in
+/// the default data dir, kept in ytbv's own once that is moved.
+pub fn rustypipe_dir() -> Result<PathBuf, String> {
+    if overrides().data_dir.is_some() || ytbv_home().is_some() {
+        return Ok(data_dir()?.join("rustypipe"));
+    }
+    Ok(data_dir()?.with_file_name("rustypipe"))
+}
+
+/// The user's home directory: `$HOME`, or `%USERPROFILE%` on Windows.
+pub fn home_dir() -> Result<PathBuf, String> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE").filter(|_| cfg!(windows)))
+        .map(PathBuf::from)
+        .ok_or_else(|| "HOME not set".to_string())
+}
+
+fn overrides() -> &'static Overrides {
+    OVERRIDES.get_or_init(Overrides::default)
+}
+
+/// `$YTBV_HOME`, or the binary's directory in portable mode.
+fn ytbv_home() -> Option<PathBuf> {
+    if let Some(home) = env::var_os("YTBV_HOME").filter(|home| !home.is_empty()) {
+        return Some(PathBuf::from(home));
+    }
+    let exe_dir = env::current_exe().ok()?.parent()?.to_path_buf();
+    (overrides().portable || exe_dir.join("portable").is_file()).then_some(exe_dir)
+}
+
+fn windows_dir(variable: &str) -> Result<PathBuf, String> {
+    env::var_os(variable)
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("{variable} not set"))
+}
@@ -0,0 +1,451 @@
+//! mpv playback controlled over its JSON IPC socket.
+//!
+//! The socket is a Unix domain socket, so on other systems mpv is started
+//! like any other player: it plays, but without controls or progress.
+
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+static NEXT_PLAYER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// How long to wait for mpv to create its IPC socket.
+#[cfg(unix)]
+const CONNECT_ATTEMPTS: u32 = 100;
+const CONNECT_INTERVAL: Duration = Duration::from_millis(100);
+/// Lines of player stderr kept for the error details.
+const STDERR_LINES: usize = 50;
+
+type StderrLog = Arc<Mutex<VecDeque<String>>>;
+/// The write half of the IPC connection, once it is up.
+type Writer = Arc<Mutex<Option<Box<dyn Write + Send>>>>;
+
+#[derive(Debug, Clone)]
+pub enum PlayerEvent {
+    /// Only sent over IPC, which needs Unix sockets.
+    #[cfg_attr(not(unix), allow(dead_code))]
+    Property(PlayerProperty),
+    /// The current file stopped playing; `reason` is mpv's (`eof`, `stop`, ...).
+    EndFile {
+        reason: String,
+    },
+    /// The player exited unsuccessfully; sent just before `Exited`.
+    Failed {
+        status: String,
+        /// The last lines the player wrote to stderr.
+        stderr: Vec<String>,
+    },
+    /// mpv quit or the IPC connection was lost.
+    Exited,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(not(unix), allow(dead_code))]
+pub enum PlayerProperty {
+    Title(String),
+    Position(f64),
+    Duration(f64),
+    Paused(bool),
+    Volume(f64),
+    /// Index of the current playlist entry; -1 when there is none.
+    PlaylistPos(i64),
+}
+
+/// Last known state of the running mpv instance.
+#[derive(Debug, Clone, Default)]
+pub struct PlaybackState {
+    pub title: Option<String>,
+    pub position: Option<f64>,
+    pub duration: Option<f64>,
+    pub paused: bool,
+    pub volume: Option<f64>,
+    /// Name of the player, when it's a fallback or has no IPC controls.
+    pub player: Option<String>,
+}
+
+impl PlaybackState {
+    pub fn apply(&mut self, property: PlayerProperty) {
+        match property {
+            PlayerProperty::Title(title) => self.title = Some(title),
+            PlayerProperty::Position(position) => self.position = Some(position),
+            PlayerProperty::Duration(duration) => self.duration = Some(duration),
+            PlayerProperty::Paused(paused) => self.paused = paused,
+            PlayerProperty::Volume(volume) => self.volume = Some(volume),
+            PlayerProperty::PlaylistPos(_) => {}
+        }
+    }
+}
+
+pub struct Player {
+    id: u64,
+    child: Arc<Mutex<Child>>,
+    writer: Writer,
+    /// False for players started without an IPC socket.
+    ipc: bool,
+}
+
+impl Player {
+    /// Starts `mpv_bin` with `args` and an IPC socket. Events are passed to
+    /// `on_event` from a background thread, tagged with the player id.
+    #[cfg(unix)]
+    pub fn spawn<F>(mpv_bin: &str, args: &[String], on_event: F) -> Result<Player, String>
+    where
+        F: Fn(u64, PlayerEvent) + Send + 'static,
+    {
+        let id = NEXT_PLAYER_ID.fetch_add(1, Ordering::Relaxed);
+        let socket_path = socket_path(id);
+        let _ = std::fs::remove_file(&socket_path);
+
+        let mut child = Command::new(mpv_bin)
+            .arg(format!("--input-ipc-server={}", socket_path.display()))
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| format!("Failed to start mpv ('{mpv_bin}'): {err}"))
+            .inspect_err(|err| log::error!("{err}"))?;
+        log::info!("Started {mpv_bin} {}", args.join(" "));
+
+        let stderr = capture_stderr(&mut child);
+        let child = Arc::new(Mutex::new(child));
+        let writer = Arc::new(Mutex::new(None));
+        let thread_child = Arc::clone(&child);
+        let thread_writer = Arc::clone(&writer);
+        thread::spawn(move || {
+            run_ipc(socket_path, thread_child, thread_writer, stderr, |event| {
+                on_event(id, event)
+            });
+        });
+
+        Ok(Player {
+            id,
+            child,
+            writer,
+            ipc: true,
+        })
+    }
+
+    /// Starts `mpv_bin` with `args`. Without Unix sockets there is no IPC,
+    /// so only its exit is reported, as for `spawn_plain`.
+    #[cfg(not(unix))]
+    pub fn spawn<F>(mpv_bin: &str, args: &[String], on_event: F) -> Result<Player, String>
+    where
+        F: Fn(u64, PlayerEvent) + Send + 'static,
+    {
+        Player::spawn_plain(mpv_bin, args, on_event)
+    }
+
+    /// Starts a player that has no IPC interface. Only its exit is
+    /// reported: a clean exit counts as an `eof` end of file.
+    pub fn spawn_plain<F>(program: &str, args: &[String], on_event: F) -> Result<Player, String>
+    where
+        F: Fn(u64, PlayerEvent) + Send + 'static,
+    {
+        let id = NEXT_PLAYER_ID.fetch_add(1, Ordering::Relaxed);
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| format!("Failed to start player ('{program}'): {err}"))
+            .inspect_err(|err| log::error!("{err}"))?;
+        log::info!("Started {program} {}", args.join(" "));
+
+        let stderr = capture_stderr(&mut child);
+        let child = Arc::new(Mutex::new(child));
+        let thread_child = Arc::clone(&child);
+        thread::spawn(move || loop {
+            let status = thread_child
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .try_wait();
+            match status {
+                Ok(None) => thread::sleep(CONNECT_INTERVAL),
+                Ok(Some(status)) => {
+                    match failure(status, &stderr) {
+                        Some(failed) => on_event(id, failed),
+                        None => on_event(
+                            id,
+                            PlayerEvent::EndFile {
+                                reason: "eof".to_string(),
+                            },
+                        ),
+                    }
+                    on_event(id, PlayerEvent::Exited);
+                    break;
+                }
+                Err(_) => {
+                    on_event(id, PlayerEvent::Exited);
+                    break;
+                }
+            }
+        });
+
+        Ok(Player {
+            id,
+            child,
+            writer: Arc::new(Mutex::new(None)),
+            ipc: false,
+        })
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn toggle_pause(&self) -> Result<(), String> {
+        self.command(json!(["cycle", "pause"]))
+    }
+
+    pub fn seek(&self, seconds: f64) -> Result<(), String> {
+        self.command(json!(["seek", seconds, "relative"]))
+    }
+
+    pub fn seek_to(&self, seconds: f64) -> Result<(), String> {
+        self.command(json!(["seek", seconds, "absolute"]))
+    }
+
+    pub fn playlist_next(&self) -> Result<(), String> {
+        self.command(json!(["playlist-next"]))
+    }
+
+    pub fn playlist_prev(&self) -> Result<(), String> {
+        self.command(json!(["playlist-prev"]))
+    }
+
+    /// Adds `url` to the end of the playlist.
+    pub fn append(&self, url: &str) -> Result<(), String> {
+        self.command(json!(["loadfile", url, "append"]))
+    }
+
+    pub fn playlist_remove(&self, index: usize) -> Result<(), String> {
+        self.command(json!(["playlist-remove", index]))
+    }
+
+    pub fn add_volume(&self, delta: f64) -> Result<(), String> {
+        self.command(json!(["add", "volume", delta]))
+    }
+
+    pub fn set_property(&self, name: &str, value: Value) -> Result<(), String> {
+        self.command(json!(["set_property", name, value]))
+    }
+
+    /// Asks mpv to quit, killing it if the socket isn't connected yet.
+    pub fn quit(&self) {
+        if self.command(json!(["quit"])).is_err() {
+            let mut child = self.child.lock().unwrap_or_else(|err| err.into_inner());
+            if let Err(err) = child.kill() {
+                log::debug!("Could not kill the player: {err}");
+            }
+        }
+        let mut child = self.child.lock().unwrap_or_else(|err| err.into_inner());
+        if let Err(err) = child.try_wait() {
+            log::debug!("Could not reap the player: {err}");
+        }
+    }
+
+    fn command(&self, command: Value) -> Result<(), String> {
+        let mut writer = self.writer.lock().unwrap_or_else(|err| err.into_inner());
+        let Some(stream) = writer.as_mut() else {
+            if !self.ipc {
+                return Err(if cfg!(unix) {
+                    "Playback controls need mpv."
+                } else {
+                    "Playback controls aren't available on this system."
+                }
+                .to_string());
+            }
+            return Err("mpv is still starting.".to_string());
+        };
+        let mut line = json!({ "command": command }).to_string();
+        line.push('\n');
+        stream
+            .write_all(line.as_bytes())
+            .map_err(|err| format!("mpv IPC error: {err}"))
+    }
+}
+
+/// Keeps the last lines of the child's stderr in the background.
+fn capture_stderr(child: &mut Child) -> StderrLog {
+    let log = Arc::new(Mutex::new(VecDeque::new()));
+    if let Some(stderr) = child.stderr.take() {
+        let log = Arc::clone(&log);
+        thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                let mut log = log.lock().unwrap_or_else(|err| err.into_inner());
+                if log.len() == STDERR_LINES {
+                    log.pop_front();
+                }
+                log.push_back(line);
+            }
+        });
+    }
+    log
+}
+
+/// Builds the `Failed` event for an unsuccessful exit.
+fn failure(status: ExitStatus, stderr: &StderrLog) -> Option<PlayerEvent> {
+    if status.success() {
+        return None;
+    }
+    // Give the reader a moment to pick up the final lines.
+    thread::sleep(CONNECT_INTERVAL);
+    let stderr = stderr.lock().unwrap_or_else(|err| err.into_inner());
+    Some(PlayerEvent::Failed {
+        status: status.to_string(),
+        stderr: stderr.iter().cloned().collect(),
+    })
+}
+
+#[cfg(unix)]
+fn socket_path(id: u64) -> PathBuf {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    dir.join(format!("ytbv-mpv-{}-{id}.sock", std::process::id()))
+}
+
+#[cfg(unix)]
+fn run_ipc(
+    socket_path: PathBuf,
+    child: Arc<Mutex<Child>>,
+    writer: Writer,
+    stderr: StderrLog,
+    on_event: impl Fn(PlayerEvent),
+) {
+    let mut stream = None;
+    for _ in 0..CONNECT_ATTEMPTS {
+        if let Ok(connected) = UnixStream::connect(&socket_path) {
+            stream = Some(connected);
+            break;
+        }
+        let exited = child
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .try_wait()
+            .map_or(true, |status| status.is_some());
+        if exited {
+            break;
+        }
+        thread::sleep(CONNECT_INTERVAL);
+    }
+    let Some(mut stream) = stream else {
+        let status = child
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .try_wait();
+        if let Some(failed) = status
+            .ok()
+            .flatten()
+            .and_then(|status| failure(status, &stderr))
+        {
+            on_event(failed);
+        }
+        on_event(PlayerEvent::Exited);
+        return;
+    };
+
+    for (index, name) in [
+        "media-title",
+        "time-pos",
+        "duration",
+        "pause",
+        "volume",
+        "playlist-pos",
+    ]
+    .iter()
+    .enumerate()
+    {
+        let line = json!({ "command": ["observe_property", index + 1, name] }).to_string();
+        if stream.write_all(format!("{line}\n").as_bytes()).is_err() {
+            on_event(PlayerEvent::Exited);
+            return;
+        }
+    }
+    match stream.try_clone() {
+        Ok(clone) => {
+            *writer.lock().unwrap_or_else(|err| err.into_inner()) = Some(Box::new(clone));
+        }
+        Err(_) => {
+            on_event(PlayerEvent::Exited);
+            return;
+        }
+    }
+
+    // time-pos changes every frame; only forward whole-second steps.
+    let mut last_second = None;
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let Ok(message) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        match message.get("event").and_then(Value::as_str) {
+            Some("property-change") => {
+                let data = message.get("data");
+                let property = match message.get("name").and_then(Value::as_str) {
+                    Some("media-title") => data
+                        .and_then(Value::as_str)
+                        .map(|title| PlayerProperty::Title(title.to_string())),
+                    Some("time-pos") => data.and_then(Value::as_f64).and_then(|position| {
+                        let second = position as u64;
+                        (last_second != Some(second)).then(|| {
+                            last_second = Some(second);
+                            PlayerProperty::Position(position)
+                        })
+                    }),
+                    Some("duration") => data.and_then(Value::as_f64).map(PlayerProperty::Duration),
+                    Some("pause") => data.and_then(Value::as_bool).map(PlayerProperty::Paused),
+                    Some("volume") => data.and_then(Value::as_f64).map(PlayerProperty::Volume),
+                    Some("playlist-pos") => data
+                        .and_then(Value::as_i64)
+                        .map(PlayerProperty::PlaylistPos),
+                    _ => None,
+                };
+                if let Some(property) = property {
+                    on_event(PlayerEvent::Property(property));
+                }
+            }
+            Some("end-file") => {
+                let reason = message
+                    .get("reason")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown")
+                    .to_string();
+                on_event(PlayerEvent::EndFile { reason });
+            }
+            _ => {}
+        }
+    }
+
+    *writer.lock().unwrap_or_else(|err| err.into_inner()) = None;
+    // Poll rather than block in `wait` so `quit` can take the lock.
+    let status = loop {
+        let status = child
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .try_wait();
+        match status {
+            Ok(None) => thread::sleep(CONNECT_INTERVAL),
+            Ok(Some(status)) => break Ok(status),
+            Err(err) => break Err(err),
+        }
+    };
+    let _ = std::fs::remove_file(&socket_path);
+    if let Some(failed) = status.ok().and_then(|status| failure(status, &stderr)) {
+        on_event(failed);
+    }
+    on_event(PlayerEvent::Exited);
+}
@@ -0,0 +1,57 @@
+//! A few worker threads sharing one HTTP client, for the thumbnail and
+//! avatar downloads.
+//!
+//! Jobs run in the order they were handed in, at most `workers` at once, and
+//! reuse the client's connections to YouTube's image servers instead of
+//! opening new ones per image.
+
+use reqwest::blocking::Client;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// For the whole request, body included.
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+type Job = Box<dyn FnOnce(&Client) + Send>;
+
+pub struct Pool {
+    jobs: Sender<Job>,
+}
+
+impl Pool {
+    pub fn new(workers: usize) -> Pool {
+        let client = Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(TIMEOUT)
+            .pool_max_idle_per_host(workers)
+            .build()
+            .expect("Failed to create HTTP client");
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        for _ in 0..workers {
+            let (client, queue) = (client.clone(), Arc::clone(&queue));
+            thread::spawn(move || work(&client, &queue));
+        }
+        Pool { jobs }
+    }
+
+    pub fn spawn(&self, job: impl FnOnce(&Client) + Send + 'static) {
+        if self.jobs.send(Box::new(job)).is_err() {
+            log::error!("Download workers are gone");
+        }
+    }
+}
+
+fn work(client: &Client, queue: &Mutex<Receiver<Job>>) {
+    loop {
+        // The lock is released before the job runs.
+        let job = queue.lock().unwrap_or_else(|e| e.into_inner()).recv();
+        match job {
+            Ok(job) => job(client),
+            Err(_) => return,
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Search responses kept in the cache dir, so repeating a recent query is
+//! instant and earlier results can still be shown while YouTube is
+//! unreachable.
+//!
+//! Each response is a row of an SQLite database, holding it as JSON under
+//! its key (the query and locale). The oldest rows go once there are more
+//! than `MAX_ENTRIES`.
+
+use crate::paths;
+use crate::sqlite::Database;
+use rustypipe::model::paginator::ContinuationEndpoint;
+use rustypipe::model::YouTubeItem;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const MAX_ENTRIES: usize = 200;
+const DATABASE_FILE: &str = "search.db";
+
+const MIGRATIONS: &[&str] = &["
+    CREATE TABLE searches (
+        key TEXT PRIMARY KEY,
+        fetched_at INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX searches_fetched_at ON searches (fetched_at);
+"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedSearch {
+    /// What the response is for: the query and locale.
+    pub key: String,
+    /// Unix timestamp (seconds) of when it was fetched.
+    pub fetched_at: i64,
+    pub items: Vec<YouTubeItem>,
+    pub ctoken: Option<String>,
+    pub visitor_data: Option<String>,
+    pub endpoint: ContinuationEndpoint,
+}
+
+/// The cached response for `key`, whatever its age. Unreadable rows count
+/// as missing.
+pub fn load(key: &str) -> Option<CachedSearch> {
+    let db = open(&paths::cache_dir().ok()?).ok()?;
+    let data = db
+        .query(
+            "SELECT data FROM searches WHERE key = ?",
+            &[key.into()],
+            |row| row.text(0),
+        )
+        .ok()?;
+    serde_json::from_str(data.first()?).ok()
+}
+
+pub fn save(entry: &CachedSearch) -> Result<(), String> {
+    let data = serde_json::to_string(entry).map_err(|e| format!("Encode error: {e}"))?;
+    open(&paths::cache_dir()?)?.transaction(|db| {
+        db.execute(
+            "INSERT OR REPLACE INTO searches VALUES (?, ?, ?)",
+            &[(&entry.key).into(), entry.fetched_at.into(), (&data).into()],
+        )?;
+        db.execute(
+            "DELETE FROM searches WHERE key NOT IN \
+             (SELECT key FROM searches ORDER BY fetched_at DESC LIMIT ?)",
+            &[(MAX_ENTRIES as i64).into()],
+        )
+    })
+}
+
+/// How many responses are cached and the size of the database in bytes.
+pub fn usage() -> Result<(usize, u64), String> {
+    let dir = paths::cache_dir()?;
+    let size = match fs::metadata(dir.join(DATABASE_FILE)) {
+        Ok(meta) => meta.len(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok((0, 0)),
+        Err(err) => return Err(format!("Cache meta error: {err}")),
+    };
+    let count = open(&dir)?.query("SELECT count(*) FROM searches", &[], |row| row.integer(0))?;
+    Ok((count.first().map_or(0, |count| *count as usize), size))
+}
+
+pub fn purge() -> Result<(), String> {
+    let db = open(&paths::cache_dir()?)?;
+    // VACUUM gives the freed pages back, so the file shrinks as well.
+    db.execute_batch("DELETE FROM searches; VACUUM")
+}
+
+/// Opens the database in `dir`. Creating it removes the directory of JSON
+/// files earlier releases cached responses in.
+fn open(dir: &Path) -> Result<Database, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Cache dir error: {e}"))?;
+    let db = Database::open(&dir.join(DATABASE_FILE))?;
+    db.migrate(MIGRATIONS, |_| {
+        remove_old_dir(&dir.join("search"));
+        Ok(())
+    })?;
+    Ok(db)
+}
+
+fn remove_old_dir(dir: &Path) {
+    match fs::remove_dir_all(dir) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => log::debug!("Could not remove {}: {err}", dir.display()),
+    }
+}
@@ -0,0 +1,137 @@
+//! The first-run setup: a few questions, with what could be detected
+//! preselected, whose answers become the initial config file.
+
+use crate::config::{Config, Graphics, PlayerCommand, PlayerSetting};
+use crate::{iterm, theme};
+
+/// Players looked for on the `PATH`, in order of preference. mpv is the
+/// built-in one, with the playback controls.
+const PLAYERS: [&str; 3] = ["mpv", "vlc", "celluloid"];
+/// Resolutions offered as the default quality.
+const HEIGHTS: [u32; 6] = [2160, 1440, 1080, 720, 480, 360];
+
+const PLAYER: usize = 0;
+const GRAPHICS: usize = 1;
+const QUALITY: usize = 2;
+pub const THEME: usize = 3;
+
+pub struct Step {
+    pub title: &'static str,
+    pub hint: &'static str,
+    pub choices: Vec<String>,
+    pub selected: usize,
+}
+
+pub struct Setup {
+    pub steps: [Step; 4],
+    pub step: usize,
+    players: Vec<&'static str>,
+}
+
+impl Setup {
+    pub fn new(config: &Config) -> Setup {
+        let mut players: Vec<&str> = PLAYERS
+            .into_iter()
+            .filter(|program| on_path(program))
+            .collect();
+        let player_choices = if players.is_empty() {
+            players.push(PLAYERS[0]);
+            vec!["mpv (not found, install it before playing)".to_string()]
+        } else {
+            players.iter().map(|program| program.to_string()).collect()
+        };
+
+        let detected = detect_graphics();
+        let graphics_choices = Graphics::CHOICES
+            .iter()
+            .map(|graphics| {
+                if *graphics == detected {
+                    format!("{} (detected)", graphics.label())
+                } else {
+                    graphics.label().to_string()
+                }
+            })
+            .collect();
+
+        let height = config.max_height();
+        let theme = config.theme.name.as_deref().unwrap_or(theme::BUILTIN[0]);
+        Setup {
+            steps: [
+                Step {
+                    title: "Video player",
+                    hint: "Players found on your PATH. mpv gets pause, seek and the quality menu.",
+                    choices: player_choices,
+                    selected: 0,
+                },
+                Step {
+                    title: "Thumbnails",
+                    hint: "How images are drawn in this terminal.",
+                    choices: graphics_choices,
+                    selected: position(&Graphics::CHOICES, &detected),
+                },
+                Step {
+                    title: "Default quality",
+                    hint: "Highest resolution videos play at; Alt+Enter picks another.",
+                    choices: HEIGHTS.iter().map(|height| format!("{height}p")).collect(),
+                    selected: position(&HEIGHTS, &height),
+                },
+                Step {
+                    title: "Theme",
+                    hint: "Previewed as you move; :theme switches later.",
+                    choices: theme::BUILTIN.iter().map(|name| name.to_string()).collect(),
+                    selected: position(&theme::BUILTIN, &theme),
+                },
+            ],
+            step: 0,
+            players,
+        }
+    }
+
+    /// The theme picked so far, for previewing.
+    pub fn theme(&self) -> &'static str {
+        theme::BUILTIN[self.steps[THEME].selected]
+    }
+
+    /// Writes the answers into `config`.
+    pub fn apply(&self, config: &mut Config) {
+        let program = self.players[self.steps[PLAYER].selected];
+        config.player = (program != PLAYERS[0]).then(|| {
+            PlayerSetting::Single(PlayerCommand {
+                program: program.to_string(),
+                args: Vec::new(),
+            })
+        });
+        config.graphics = Graphics::CHOICES[self.steps[GRAPHICS].selected];
+        config.max_height = Some(HEIGHTS[self.steps[QUALITY].selected]);
+        config.theme.name = Some(self.theme().to_string());
+    }
+}
+
+/// The best image protocol the terminal answers to.
+fn detect_graphics() -> Graphics {
+    if viuer::get_kitty_support() != viuer::KittySupport::None {
+        Graphics::Kitty
+    } else if iterm::supported() {
+        Graphics::Iterm
+    } else if viuer::is_sixel_supported() {
+        Graphics::Sixel
+    } else {
+        Graphics::Blocks
+    }
+}
+
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path).any(|dir| {
+            let file = dir.join(program);
+            file.is_file() || cfg!(windows) && file.with_extension("exe").is_file()
+        })
+    })
+}
+
+fn position<T: PartialEq>(options: &[T], option: &T) -> usize {
+    options
+        .iter()
+        .position(|entry| entry == option)
+        .unwrap_or(0)
+}
@@ -0,0 +1,482 @@
+//! A small safe wrapper over the system's SQLite library: opening a
+//! database, running statements with bound parameters, reading rows and
+//! bringing the schema up to date with numbered migrations.
+//!
+//! Only what the stores need is bound. Every value crosses as an integer,
+//! a real or UTF-8 text; SQLite copies bound text, so parameters only have
+//! to outlive the call binding them.
+
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::ptr;
+
+const SQLITE_OK: c_int = 0;
+const SQLITE_ROW: c_int = 100;
+const SQLITE_DONE: c_int = 101;
+const SQLITE_NULL: c_int = 5;
+const SQLITE_OPEN_READWRITE: c_int = 0x2;
+const SQLITE_OPEN_CREATE: c_int = 0x4;
+/// Tells SQLite to copy bound text before the call returns.
+const SQLITE_TRANSIENT: isize = -1;
+/// How long a statement waits for another process's lock, in milliseconds.
+const BUSY_TIMEOUT_MS: c_int = 5000;
+
+#[repr(C)]
+struct RawDatabase {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct RawStatement {
+    _private: [u8; 0],
+}
+
+#[link(name = "sqlite3")]
+extern "C" {
+    fn sqlite3_open_v2(
+        filename: *const c_char,
+        db: *mut *mut RawDatabase,
+        flags: c_int,
+        vfs: *const c_char,
+    ) -> c_int;
+    fn sqlite3_close(db: *mut RawDatabase) -> c_int;
+    fn sqlite3_errmsg(db: *mut RawDatabase) -> *const c_char;
+    fn sqlite3_busy_timeout(db: *mut RawDatabase, ms: c_int) -> c_int;
+    fn sqlite3_exec(
+        db: *mut RawDatabase,
+        sql: *const c_char,
+        callback: *const c_void,
+        arg: *mut c_void,
+        errmsg: *mut *mut c_char,
+    ) -> c_int;
+    fn sqlite3_prepare_v2(
+        db: *mut RawDatabase,
+        sql: *const c_char,
+        bytes: c_int,
+        stmt: *mut *mut RawStatement,
+        tail: *mut *const c_char,
+    ) -> c_int;
+    fn sqlite3_finalize(stmt: *mut RawStatement) -> c_int;
+    fn sqlite3_reset(stmt: *mut RawStatement) -> c_int;
+    fn sqlite3_clear_bindings(stmt: *mut RawStatement) -> c_int;
+    fn sqlite3_step(stmt: *mut RawStatement) -> c_int;
+    fn sqlite3_bind_null(stmt: *mut RawStatement, index: c_int) -> c_int;
+    fn sqlite3_bind_int64(stmt: *mut RawStatement, index: c_int, value: i64) -> c_int;
+    fn sqlite3_bind_double(stmt: *mut RawStatement, index: c_int, value: f64) -> c_int;
+    fn sqlite3_bind_text(
+        stmt: *mut RawStatement,
+        index: c_int,
+        value: *const c_char,
+        bytes: c_int,
+        destructor: isize,
+    ) -> c_int;
+    fn sqlite3_column_type(stmt: *mut RawStatement, index: c_int) -> c_int;
+    fn sqlite3_column_int64(stmt: *mut RawStatement, index: c_int) -> i64;
+    fn sqlite3_column_double(stmt: *mut RawStatement, index: c_int) -> f64;
+    fn sqlite3_column_text(stmt: *mut RawStatement, index: c_int) -> *const u8;
+    fn sqlite3_column_bytes(stmt: *mut RawStatement, index: c_int) -> c_int;
+}
+
+/// A parameter bound to a `?` in a statement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value<'a> {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(&'a str),
+}
+
+impl<'a> From<&'a str> for Value<'a> {
+    fn from(text: &'a str) -> Value<'a> {
+        Value::Text(text)
+    }
+}
+
+impl<'a> From<&'a String> for Value<'a> {
+    fn from(text: &'a String) -> Value<'a> {
+        Value::Text(text)
+    }
+}
+
+impl From<i64> for Value<'_> {
+    fn from(value: i64) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl From<f64> for Value<'_> {
+    fn from(value: f64) -> Self {
+        Value::Real(value)
+    }
+}
+
+impl<'a, T: Into<Value<'a>>> From<Option<T>> for Value<'a> {
+    fn from(value: Option<T>) -> Value<'a> {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+pub struct Database {
+    raw: *mut RawDatabase,
+}
+
+impl Database {
+    /// Opens the database at `path`, creating the file when missing.
+    pub fn open(path: &Path) -> Result<Database, String> {
+        let name = path
+            .to_str()
+            .and_then(|name| CString::new(name).ok())
+            .ok_or_else(|| format!("Unusable database path: {}", path.display()))?;
+        let mut raw = ptr::null_mut();
+        // SAFETY: `name` is a valid C string and `raw` a valid out pointer.
+        let code = unsafe {
+            sqlite3_open_v2(
+                name.as_ptr(),
+                &mut raw,
+                SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                ptr::null(),
+            )
+        };
+        // SQLite allocates a handle even when opening fails; closing it on
+        // drop frees it.
+        let db = Database { raw };
+        if code != SQLITE_OK {
+            return Err(format!(
+                "Database error ({}): {}",
+                path.display(),
+                db.error()
+            ));
+        }
+        // SAFETY: `raw` is an open handle.
+        unsafe { sqlite3_busy_timeout(db.raw, BUSY_TIMEOUT_MS) };
+        Ok(db)
+    }
+
+    /// Runs one or more statements without parameters.
+    pub fn execute_batch(&self, sql: &str) -> Result<(), String> {
+        let sql = CString::new(sql).map_err(|_| "Database error: NUL in SQL".to_string())?;
+        // SAFETY: `sql` is a valid C string; no callback or error message
+        // is asked for.
+        let code = unsafe {
+            sqlite3_exec(
+                self.raw,
+                sql.as_ptr(),
+                ptr::null(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+        self.check(code)
+    }
+
+    /// Runs one statement with `params`.
+    pub fn execute(&self, sql: &str, params: &[Value<'_>]) -> Result<(), String> {
+        self.prepare(sql)?.execute(params)
+    }
+
+    /// Runs a query and maps each row with `map`.
+    pub fn query<T>(
+        &self,
+        sql: &str,
+        params: &[Value<'_>],
+        map: impl FnMut(&Row<'_>) -> T,
+    ) -> Result<Vec<T>, String> {
+        self.prepare(sql)?.query(params, map)
+    }
+
+    pub fn prepare(&self, sql: &str) -> Result<Statement<'_>, String> {
+        let bytes = c_int::try_from(sql.len()).map_err(|_| "Database error: SQL too long")?;
+        let mut raw = ptr::null_mut();
+        // SAFETY: `sql` is valid for `bytes` bytes and `raw` a valid out
+        // pointer.
+        let code = unsafe {
+            sqlite3_prepare_v2(
+                self.raw,
+                sql.as_ptr().cast(),
+                bytes,
+                &mut raw,
+                ptr::null_mut(),
+            )
+        };
+        self.check(code)?;
+        Ok(Statement { raw, db: self })
+    }
+
+    /// Runs `f` in a transaction, committed when it succeeds and rolled
+    /// back when it fails. The write lock is taken up front, so two
+    /// processes can't both read and then write.
+    pub fn transaction<T>(
+        &self,
+        f: impl FnOnce(&Database) -> Result<T, String>,
+    ) -> Result<T, String> {
+        self.execute_batch("BEGIN IMMEDIATE")?;
+        match f(self) {
+            Ok(value) => {
+                self.execute_batch("COMMIT")?;
+                Ok(value)
+            }
+            Err(err) => {
+                if let Err(rollback) = self.execute_batch("ROLLBACK") {
+                    log::warn!("Rollback failed: {rollback}");
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Runs the `migrations` the database hasn't had yet, in one
+    /// transaction, and records how many it has had in `user_version`.
+    /// `on_create` runs in the same transaction when the database is new.
+    pub fn migrate(
+        &self,
+        migrations: &[&str],
+        on_create: impl FnOnce(&Database) -> Result<(), String>,
+    ) -> Result<(), String> {
+        if self.user_version()? >= migrations.len() as i64 {
+            return Ok(());
+        }
+        self.transaction(|db| {
+            // Another process may have migrated it since the check above.
+            let version = db.user_version()?;
+            if version >= migrations.len() as i64 {
+                return Ok(());
+            }
+            for migration in &migrations[version as usize..] {
+                db.execute_batch(migration)?;
+            }
+            if version == 0 {
+                on_create(db)?;
+            }
+            db.execute_batch(&format!("PRAGMA user_version = {}", migrations.len()))
+        })
+    }
+
+    fn user_version(&self) -> Result<i64, String> {
+        let versions = self.query("PRAGMA user_version", &[], |row| row.integer(0))?;
+        Ok(versions.first().copied().unwrap_or(0))
+    }
+
+    fn check(&self, code: c_int) -> Result<(), String> {
+        if code == SQLITE_OK {
+            Ok(())
+        } else {
+            Err(format!("Database error: {}", self.error()))
+        }
+    }
+
+    fn error(&self) -> String {
+        // SAFETY: `sqlite3_errmsg` returns a valid C string for any handle,
+        // null included, that stays valid until the next call.
+        unsafe { CStr::from_ptr(sqlite3_errmsg(self.raw)) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+impl Drop for Database {
+    fn drop(&mut self) {
+        // SAFETY: every statement borrows the database, so all of them
+        // are finalized by now.
+        unsafe { sqlite3_close(self.raw) };
+    }
+}
+
+pub struct Statement<'db> {
+    raw: *mut RawStatement,
+    db: &'db Database,
+}
+
+impl Statement<'_> {
+    /// Runs the statement with `params`, ignoring any rows. Can be called
+    /// again with other parameters.
+    pub fn execute(&mut self, params: &[Value<'_>]) -> Result<(), String> {
+        self.query(params, |_| ())?;
+        Ok(())
+    }
+
+    /// Runs the statement with `params` and maps each row with `map`.
+    pub fn query<T>(
+        &mut self,
+        params: &[Value<'_>],
+        mut map: impl FnMut(&Row<'_>) -> T,
+    ) -> Result<Vec<T>, String> {
+        // SAFETY: `raw` is a prepared statement; resetting an unused one
+        // is a no-op.
+        unsafe {
+            sqlite3_reset(self.raw);
+            sqlite3_clear_bindings(self.raw);
+        }
+        for (index, param) in params.iter().enumerate() {
+            self.bind(index as c_int + 1, param)?;
+        }
+        let mut rows = Vec::new();
+        loop {
+            // SAFETY: `raw` is a prepared statement with its parameters
+            // bound.
+            match unsafe { sqlite3_step(self.raw) } {
+                SQLITE_ROW => rows.push(map(&Row {
+                    raw: self.raw,
+                    _statement: PhantomData,
+                })),
+                SQLITE_DONE => return Ok(rows),
+                code => return self.db.check(code).map(|()| rows),
+            }
+        }
+    }
+
+    fn bind(&mut self, index: c_int, param: &Value<'_>) -> Result<(), String> {
+        // SAFETY: `raw` is a prepared statement, and SQLite copies text
+        // (`SQLITE_TRANSIENT`) before returning.
+        let code = unsafe {
+            match *param {
+                Value::Null => sqlite3_bind_null(self.raw, index),
+                Value::Integer(value) => sqlite3_bind_int64(self.raw, index, value),
+                Value::Real(value) => sqlite3_bind_double(self.raw, index, value),
+                Value::Text(text) => {
+                    let bytes = c_int::try_from(text.len())
+                        .map_err(|_| "Database error: text too long".to_string())?;
+                    sqlite3_bind_text(
+                        self.raw,
+                        index,
+                        text.as_ptr().cast(),
+                        bytes,
+                        SQLITE_TRANSIENT,
+                    )
+                }
+            }
+        };
+        self.db.check(code)
+    }
+}
+
+impl Drop for Statement<'_> {
+    fn drop(&mut self) {
+        // SAFETY: `raw` was prepared and isn't used after this.
+        unsafe { sqlite3_finalize(self.raw) };
+    }
+}
+
+/// The row a query is on, read by column index from 0.
+pub struct Row<'s> {
+    raw: *mut RawStatement,
+    _statement: PhantomData<&'s Statement<'s>>,
+}
+
+impl Row<'_> {
+    pub fn integer(&self, index: usize) -> i64 {
+        // SAFETY: the statement is on a row; out-of-range columns read as 0.
+        unsafe { sqlite3_column_int64(self.raw, index as c_int) }
+    }
+
+    pub fn real(&self, index: usize) -> f64 {
+        // SAFETY: as in `integer`.
+        unsafe { sqlite3_column_double(self.raw, index as c_int) }
+    }
+
+    /// The column as text; NULL reads as empty.
+    pub fn text(&self, index: usize) -> String {
+        // SAFETY: the pointer from `sqlite3_column_text` is valid for the
+        // `sqlite3_column_bytes` bytes that follow, until the next step.
+        unsafe {
+            let text = sqlite3_column_text(self.raw, index as c_int);
+            if text.is_null() {
+                return String::new();
+            }
+            let len = sqlite3_column_bytes(self.raw, index as c_int) as usize;
+            String::from_utf8_lossy(std::slice::from_raw_parts(text, len)).into_owned()
+        }
+    }
+
+    pub fn is_null(&self, index: usize) -> bool {
+        // SAFETY: as in `integer`.
+        unsafe { sqlite3_column_type(self.raw, index as c_int) == SQLITE_NULL }
+    }
+
+    pub fn optional_text(&self, index: usize) -> Option<String> {
+        (!self.is_null(index)).then(|| self.text(index))
+    }
+
+    pub fn optional_integer(&self, index: usize) -> Option<i64> {
+        (!self.is_null(index)).then(|| self.integer(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory() -> Database {
+        Database::open(Path::new(":memory:")).unwrap()
+    }
+
+    #[test]
+    fn reads_back_what_it_wrote() {
+        let db = memory();
+        db.execute_batch("CREATE TABLE t (i INTEGER, r REAL, s TEXT)")
+            .unwrap();
+        let mut insert = db.prepare("INSERT INTO t VALUES (?, ?, ?)").unwrap();
+        insert
+            .execute(&[7.into(), 0.5.into(), "ünïcode, \"quoted\"".into()])
+            .unwrap();
+        insert
+            .execute(&[Value::Null, Value::Null, None::<&str>.into()])
+            .unwrap();
+        drop(insert);
+
+        let rows = db
+            .query("SELECT i, r, s FROM t ORDER BY rowid", &[], |row| {
+                (row.optional_integer(0), row.real(1), row.optional_text(2))
+            })
+            .unwrap();
+        assert_eq!(
+            rows,
+            [
+                (Some(7), 0.5, Some("ünïcode, \"quoted\"".to_string())),
+                (None, 0.0, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_sqlites_message() {
+        let db = memory();
+        let err = db.execute("SELECT * FROM missing", &[]).unwrap_err();
+        assert_eq!(err, "Database error: no such table: missing");
+    }
+
+    #[test]
+    fn rolls_back_failed_transactions() {
+        let db = memory();
+        db.execute_batch("CREATE TABLE t (i INTEGER)").unwrap();
+        let result: Result<(), String> = db.transaction(|db| {
+            db.execute("INSERT INTO t VALUES (1)", &[])?;
+            Err("stop".to_string())
+        });
+        assert_eq!(result, Err("stop".to_string()));
+        let count = db
+            .query("SELECT count(*) FROM t", &[], |row| row.integer(0))
+            .unwrap();
+        assert_eq!(count, [0]);
+    }
+
+    #[test]
+    fn runs_each_migration_once() {
+        let db = memory();
+        let mut created = 0;
+        let migrations = ["CREATE TABLE a (i INTEGER)"];
+        db.migrate(&migrations, |_| {
+            created += 1;
+            Ok(())
+        })
+        .unwrap();
+        db.migrate(&migrations, |_| unreachable!()).unwrap();
+        assert_eq!(created, 1);
+
+        // A later release adds a migration; only that one runs.
+        let migrations = ["CREATE TABLE a (i INTEGER)", "CREATE TABLE b (i INTEGER)"];
+        db.migrate(&migrations, |_| unreachable!()).unwrap();
+        assert_eq!(db.user_version().unwrap(), 2);
+        db.execute("INSERT INTO b VALUES (1)", &[]).unwrap();
+    }
+}
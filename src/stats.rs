@@ -0,0 +1,131 @@
+//! Watch statistics worked out from the history: how much was watched, the
+//! channels watched most and how many videos were played per day and week.
+//!
+//! Days and weeks are UTC ones, weeks starting on Monday.
+
+use crate::storage::HistoryEntry;
+use std::collections::HashMap;
+use time::OffsetDateTime;
+
+/// Days shown in the per-day chart, today last.
+const DAYS: usize = 14;
+/// Weeks shown in the per-week chart, the current one last.
+const WEEKS: usize = 12;
+/// Channels listed as the most watched.
+const TOP_CHANNELS: usize = 10;
+
+const DAY_SECS: i64 = 86_400;
+
+pub struct ChannelStats {
+    pub name: String,
+    pub videos: u64,
+    pub watched_secs: u64,
+}
+
+pub struct WatchStats {
+    /// Plays, counting rewatches.
+    pub plays: u64,
+    pub distinct_videos: u64,
+    /// How far playback got, summed over every play that recorded it.
+    pub watched_secs: u64,
+    /// Unix timestamp of the first play.
+    pub since: Option<i64>,
+    pub top_channels: Vec<ChannelStats>,
+    /// Day of the month and plays, for each of the last `DAYS` days.
+    pub per_day: Vec<(String, u64)>,
+    /// ISO week number and plays, for each of the last `WEEKS` weeks.
+    pub per_week: Vec<(String, u64)>,
+}
+
+impl WatchStats {
+    pub fn new(history: &[HistoryEntry], now: i64) -> WatchStats {
+        let today = now.div_euclid(DAY_SECS);
+        let this_week = week(today);
+        let mut per_day = [0; DAYS];
+        let mut per_week = [0; WEEKS];
+        let mut channels: HashMap<&str, (u64, u64)> = HashMap::new();
+        let mut videos: Vec<&str> = Vec::with_capacity(history.len());
+        let mut watched_secs = 0;
+
+        for entry in history {
+            let day = entry.watched_at.div_euclid(DAY_SECS);
+            if let Some(slot) = slot(today - day, DAYS) {
+                per_day[slot] += 1;
+            }
+            if let Some(slot) = slot(this_week - week(day), WEEKS) {
+                per_week[slot] += 1;
+            }
+            let secs = entry.watched_secs.unwrap_or(0);
+            watched_secs += secs;
+            if let Some(channel) = entry.channel.as_deref() {
+                let totals = channels.entry(channel).or_default();
+                totals.0 += 1;
+                totals.1 += secs;
+            }
+            videos.push(&entry.id);
+        }
+        videos.sort_unstable();
+        videos.dedup();
+
+        let mut top_channels: Vec<ChannelStats> = channels
+            .into_iter()
+            .map(|(name, (videos, watched_secs))| ChannelStats {
+                name: name.to_string(),
+                videos,
+                watched_secs,
+            })
+            .collect();
+        top_channels.sort_by(|a, b| {
+            b.videos
+                .cmp(&a.videos)
+                .then(b.watched_secs.cmp(&a.watched_secs))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        top_channels.truncate(TOP_CHANNELS);
+
+        WatchStats {
+            plays: history.len() as u64,
+            distinct_videos: videos.len() as u64,
+            watched_secs,
+            since: history.iter().map(|entry| entry.watched_at).min(),
+            top_channels,
+            per_day: per_day
+                .into_iter()
+                .enumerate()
+                .map(|(slot, plays)| {
+                    let day = today - (DAYS - 1 - slot) as i64;
+                    (
+                        date(day).map_or(String::new(), |d| d.day().to_string()),
+                        plays,
+                    )
+                })
+                .collect(),
+            per_week: per_week
+                .into_iter()
+                .enumerate()
+                .map(|(slot, plays)| {
+                    let monday = (this_week - (WEEKS - 1 - slot) as i64) * 7 - 3;
+                    let label =
+                        date(monday).map_or(String::new(), |d| format!("w{}", d.iso_week()));
+                    (label, plays)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Weeks since the Monday before the epoch, which was a Thursday.
+fn week(day: i64) -> i64 {
+    (day + 3).div_euclid(7)
+}
+
+/// The chart slot of something `ago` days or weeks back, oldest first.
+fn slot(ago: i64, len: usize) -> Option<usize> {
+    (0..len as i64)
+        .contains(&ago)
+        .then(|| len - 1 - ago as usize)
+}
+
+fn date(day: i64) -> Option<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp(day * DAY_SECS).ok()
+}
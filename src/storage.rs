@@ -0,0 +1,618 @@
+//! Local persistence for user data, kept in an SQLite database in the ytbv
+//! data directory.
+//!
+//! Each list is a table whose rows keep the list's order in `position`;
+//! saving a list replaces its rows in one transaction. A new database
+//! imports the JSON files earlier releases kept, once, and leaves them in
+//! place as a backup.
+
+use crate::paths;
+use crate::sqlite::{Database, Row, Value};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DATABASE_FILE: &str = "ytbv.db";
+
+/// The schema, one step per release that changed it. Steps are only ever
+/// appended.
+const MIGRATIONS: &[&str] = &["
+    CREATE TABLE subscriptions (
+        position INTEGER NOT NULL,
+        id TEXT NOT NULL,
+        name TEXT NOT NULL
+    );
+    CREATE TABLE history (
+        position INTEGER NOT NULL,
+        id TEXT NOT NULL,
+        title TEXT NOT NULL,
+        channel TEXT,
+        watched_at INTEGER NOT NULL,
+        watched_secs INTEGER
+    );
+    CREATE TABLE bookmarks (
+        position INTEGER NOT NULL,
+        id TEXT NOT NULL,
+        title TEXT NOT NULL,
+        channel TEXT,
+        tags TEXT NOT NULL,
+        added_at INTEGER NOT NULL
+    );
+    CREATE TABLE watch_later (
+        position INTEGER NOT NULL,
+        id TEXT NOT NULL,
+        title TEXT NOT NULL,
+        channel TEXT,
+        added_at INTEGER NOT NULL
+    );
+    CREATE TABLE playlists (
+        position INTEGER NOT NULL,
+        name TEXT PRIMARY KEY
+    );
+    CREATE TABLE playlist_videos (
+        playlist TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        id TEXT NOT NULL,
+        title TEXT NOT NULL,
+        url TEXT NOT NULL
+    );
+    CREATE INDEX playlist_videos_playlist ON playlist_videos (playlist, position);
+    CREATE TABLE progress (
+        id TEXT PRIMARY KEY,
+        position REAL NOT NULL,
+        duration REAL NOT NULL
+    );
+"];
+
+// The files earlier releases kept the data in, imported into a new database.
+const SUBSCRIPTIONS_FILE: &str = "subscriptions.json";
+const HISTORY_FILE: &str = "history.json";
+const PROGRESS_FILE: &str = "progress.json";
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+const PLAYLISTS_FILE: &str = "playlists.json";
+const WATCH_LATER_FILE: &str = "watch_later.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Subscription {
+    pub id: String,
+    pub name: String,
+}
+
+/// A played video; the history table holds these oldest first.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub title: String,
+    pub channel: Option<String>,
+    /// Unix timestamp (seconds) of when playback started.
+    pub watched_at: i64,
+    /// How far playback got, in seconds, once it stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watched_secs: Option<u64>,
+}
+
+/// A bookmarked video; the bookmarks table holds these oldest first.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Bookmark {
+    pub id: String,
+    pub title: String,
+    pub channel: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Unix timestamp (seconds) of when it was bookmarked.
+    pub added_at: i64,
+}
+
+/// A video saved for later; the watch later table holds these oldest first.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WatchLaterEntry {
+    pub id: String,
+    pub title: String,
+    pub channel: Option<String>,
+    /// Unix timestamp (seconds) of when it was added.
+    pub added_at: i64,
+}
+
+/// A named playlist in play order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LocalPlaylist {
+    pub name: String,
+    pub videos: Vec<PlaylistVideo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PlaylistVideo {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+}
+
+/// Where playback of a partially watched video stopped, in seconds.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WatchProgress {
+    pub position: f64,
+    pub duration: f64,
+}
+
+impl WatchProgress {
+    pub fn fraction(&self) -> f64 {
+        if self.duration > 0.0 {
+            (self.position / self.duration).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+pub fn load_subscriptions() -> Result<Vec<Subscription>, String> {
+    read_subscriptions(&open()?)
+}
+
+pub fn save_subscriptions(subscriptions: &[Subscription]) -> Result<(), String> {
+    open()?.transaction(|db| write_subscriptions(db, subscriptions))
+}
+
+pub fn load_history() -> Result<Vec<HistoryEntry>, String> {
+    read_history(&open()?)
+}
+
+pub fn save_history(history: &[HistoryEntry]) -> Result<(), String> {
+    open()?.transaction(|db| write_history(db, history))
+}
+
+pub fn load_bookmarks() -> Result<Vec<Bookmark>, String> {
+    read_bookmarks(&open()?)
+}
+
+pub fn save_bookmarks(bookmarks: &[Bookmark]) -> Result<(), String> {
+    open()?.transaction(|db| write_bookmarks(db, bookmarks))
+}
+
+pub fn load_watch_later() -> Result<Vec<WatchLaterEntry>, String> {
+    read_watch_later(&open()?)
+}
+
+pub fn save_watch_later(entries: &[WatchLaterEntry]) -> Result<(), String> {
+    open()?.transaction(|db| write_watch_later(db, entries))
+}
+
+pub fn load_playlists() -> Result<Vec<LocalPlaylist>, String> {
+    read_playlists(&open()?)
+}
+
+pub fn save_playlists(playlists: &[LocalPlaylist]) -> Result<(), String> {
+    open()?.transaction(|db| write_playlists(db, playlists))
+}
+
+/// Saved positions keyed by video ID.
+pub fn load_progress() -> Result<HashMap<String, WatchProgress>, String> {
+    read_progress(&open()?)
+}
+
+pub fn save_progress(progress: &HashMap<String, WatchProgress>) -> Result<(), String> {
+    open()?.transaction(|db| write_progress(db, progress))
+}
+
+fn open() -> Result<Database, String> {
+    open_in(&paths::data_dir()?)
+}
+
+/// Opens the database in `dir`, creating it from the JSON files there
+/// when it doesn't exist yet.
+fn open_in(dir: &Path) -> Result<Database, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Data dir error: {e}"))?;
+    let db = Database::open(&dir.join(DATABASE_FILE))?;
+    db.migrate(MIGRATIONS, |db| import_json(db, dir))?;
+    Ok(db)
+}
+
+/// Copies the JSON files in `dir` into the new database. A file that
+/// can't be read is skipped, so the rest still make it across.
+fn import_json(db: &Database, dir: &Path) -> Result<(), String> {
+    fn import<T: DeserializeOwned + Default>(
+        dir: &Path,
+        name: &str,
+        write: impl FnOnce(&T) -> Result<(), String>,
+    ) -> Result<(), String> {
+        match load_json_file::<T>(&dir.join(name)) {
+            Ok(data) => write(&data),
+            Err(err) => {
+                log::warn!("Not importing {name}: {err}");
+                Ok(())
+            }
+        }
+    }
+    import(dir, SUBSCRIPTIONS_FILE, |data: &Vec<_>| {
+        write_subscriptions(db, data)
+    })?;
+    import(dir, HISTORY_FILE, |data: &Vec<_>| write_history(db, data))?;
+    import(dir, BOOKMARKS_FILE, |data: &Vec<_>| {
+        write_bookmarks(db, data)
+    })?;
+    import(dir, WATCH_LATER_FILE, |data: &Vec<_>| {
+        write_watch_later(db, data)
+    })?;
+    import(dir, PLAYLISTS_FILE, |data: &Vec<_>| {
+        write_playlists(db, data)
+    })?;
+    import(dir, PROGRESS_FILE, |data| write_progress(db, data))
+}
+
+fn read_subscriptions(db: &Database) -> Result<Vec<Subscription>, String> {
+    db.query(
+        "SELECT id, name FROM subscriptions ORDER BY position",
+        &[],
+        |row| Subscription {
+            id: row.text(0),
+            name: row.text(1),
+        },
+    )
+}
+
+fn write_subscriptions(db: &Database, subscriptions: &[Subscription]) -> Result<(), String> {
+    db.execute("DELETE FROM subscriptions", &[])?;
+    let mut insert = db.prepare("INSERT INTO subscriptions VALUES (?, ?, ?)")?;
+    for (position, sub) in subscriptions.iter().enumerate() {
+        insert.execute(&[
+            position_value(position),
+            (&sub.id).into(),
+            (&sub.name).into(),
+        ])?;
+    }
+    Ok(())
+}
+
+fn read_history(db: &Database) -> Result<Vec<HistoryEntry>, String> {
+    db.query(
+        "SELECT id, title, channel, watched_at, watched_secs FROM history ORDER BY position",
+        &[],
+        |row| HistoryEntry {
+            id: row.text(0),
+            title: row.text(1),
+            channel: row.optional_text(2),
+            watched_at: row.integer(3),
+            watched_secs: row.optional_integer(4).map(|secs| secs.max(0) as u64),
+        },
+    )
+}
+
+fn write_history(db: &Database, history: &[HistoryEntry]) -> Result<(), String> {
+    db.execute("DELETE FROM history", &[])?;
+    let mut insert = db.prepare("INSERT INTO history VALUES (?, ?, ?, ?, ?, ?)")?;
+    for (position, entry) in history.iter().enumerate() {
+        insert.execute(&[
+            position_value(position),
+            (&entry.id).into(),
+            (&entry.title).into(),
+            entry.channel.as_ref().into(),
+            entry.watched_at.into(),
+            entry.watched_secs.map(|secs| secs as i64).into(),
+        ])?;
+    }
+    Ok(())
+}
+
+fn read_bookmarks(db: &Database) -> Result<Vec<Bookmark>, String> {
+    db.query(
+        "SELECT id, title, channel, tags, added_at FROM bookmarks ORDER BY position",
+        &[],
+        |row| Bookmark {
+            id: row.text(0),
+            title: row.text(1),
+            channel: row.optional_text(2),
+            tags: json_list(row, 3),
+            added_at: row.integer(4),
+        },
+    )
+}
+
+fn write_bookmarks(db: &Database, bookmarks: &[Bookmark]) -> Result<(), String> {
+    db.execute("DELETE FROM bookmarks", &[])?;
+    let mut insert = db.prepare("INSERT INTO bookmarks VALUES (?, ?, ?, ?, ?, ?)")?;
+    for (position, bookmark) in bookmarks.iter().enumerate() {
+        let tags =
+            serde_json::to_string(&bookmark.tags).map_err(|e| format!("Encode error: {e}"))?;
+        insert.execute(&[
+            position_value(position),
+            (&bookmark.id).into(),
+            (&bookmark.title).into(),
+            bookmark.channel.as_ref().into(),
+            (&tags).into(),
+            bookmark.added_at.into(),
+        ])?;
+    }
+    Ok(())
+}
+
+fn read_watch_later(db: &Database) -> Result<Vec<WatchLaterEntry>, String> {
+    db.query(
+        "SELECT id, title, channel, added_at FROM watch_later ORDER BY position",
+        &[],
+        |row| WatchLaterEntry {
+            id: row.text(0),
+            title: row.text(1),
+            channel: row.optional_text(2),
+            added_at: row.integer(3),
+        },
+    )
+}
+
+fn write_watch_later(db: &Database, entries: &[WatchLaterEntry]) -> Result<(), String> {
+    db.execute("DELETE FROM watch_later", &[])?;
+    let mut insert = db.prepare("INSERT INTO watch_later VALUES (?, ?, ?, ?, ?)")?;
+    for (position, entry) in entries.iter().enumerate() {
+        insert.execute(&[
+            position_value(position),
+            (&entry.id).into(),
+            (&entry.title).into(),
+            entry.channel.as_ref().into(),
+            entry.added_at.into(),
+        ])?;
+    }
+    Ok(())
+}
+
+fn read_playlists(db: &Database) -> Result<Vec<LocalPlaylist>, String> {
+    let mut playlists = db.query("SELECT name FROM playlists ORDER BY position", &[], |row| {
+        LocalPlaylist {
+            name: row.text(0),
+            videos: Vec::new(),
+        }
+    })?;
+    let mut videos = db.prepare(
+        "SELECT id, title, url FROM playlist_videos WHERE playlist = ? ORDER BY position",
+    )?;
+    for playlist in &mut playlists {
+        playlist.videos = videos.query(&[(&playlist.name).into()], |row| PlaylistVideo {
+            id: row.text(0),
+            title: row.text(1),
+            url: row.text(2),
+        })?;
+    }
+    Ok(playlists)
+}
+
+fn write_playlists(db: &Database, playlists: &[LocalPlaylist]) -> Result<(), String> {
+    db.execute_batch("DELETE FROM playlist_videos; DELETE FROM playlists")?;
+    let mut insert_playlist = db.prepare("INSERT INTO playlists VALUES (?, ?)")?;
+    let mut insert_video = db.prepare("INSERT INTO playlist_videos VALUES (?, ?, ?, ?, ?)")?;
+    for (position, playlist) in playlists.iter().enumerate() {
+        insert_playlist.execute(&[position_value(position), (&playlist.name).into()])?;
+        for (position, video) in playlist.videos.iter().enumerate() {
+            insert_video.execute(&[
+                (&playlist.name).into(),
+                position_value(position),
+                (&video.id).into(),
+                (&video.title).into(),
+                (&video.url).into(),
+            ])?;
+        }
+    }
+    Ok(())
+}
+
+fn read_progress(db: &Database) -> Result<HashMap<String, WatchProgress>, String> {
+    let rows = db.query("SELECT id, position, duration FROM progress", &[], |row| {
+        (
+            row.text(0),
+            WatchProgress {
+                position: row.real(1),
+                duration: row.real(2),
+            },
+        )
+    })?;
+    Ok(rows.into_iter().collect())
+}
+
+fn write_progress(db: &Database, progress: &HashMap<String, WatchProgress>) -> Result<(), String> {
+    db.execute("DELETE FROM progress", &[])?;
+    let mut insert = db.prepare("INSERT INTO progress VALUES (?, ?, ?)")?;
+    for (id, saved) in progress {
+        insert.execute(&[id.into(), saved.position.into(), saved.duration.into()])?;
+    }
+    Ok(())
+}
+
+fn position_value(position: usize) -> Value<'static> {
+    Value::Integer(position as i64)
+}
+
+/// A column holding a JSON array of strings; anything else reads as empty.
+fn json_list(row: &Row<'_>, index: usize) -> Vec<String> {
+    serde_json::from_str(&row.text(index)).unwrap_or_default()
+}
+
+/// Reads `path`, treating a missing file as empty. A file that doesn't
+/// parse is renamed to `<name>.corrupt-<unix time>` first, so the empty
+/// data the app goes on with can't be saved over it.
+fn load_json_file<T: DeserializeOwned + Default>(path: &Path) -> Result<T, String> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(err) => return Err(format!("Read error ({}): {err}", path.display())),
+    };
+    serde_json::from_slice(&data).map_err(|e| {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let mut backup = path.as_os_str().to_os_string();
+        backup.push(format!(".corrupt-{secs}"));
+        let backup = PathBuf::from(backup);
+        match fs::rename(path, &backup) {
+            Ok(()) => format!(
+                "Parse error ({}): {e}; moved it to {}",
+                path.display(),
+                backup.display()
+            ),
+            Err(err) => format!(
+                "Parse error ({}): {e}; could not move it aside: {err}",
+                path.display()
+            ),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ytbv-storage-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn history_entry(id: &str, watched_secs: Option<u64>) -> HistoryEntry {
+        HistoryEntry {
+            id: id.to_string(),
+            title: format!("Video {id}"),
+            channel: watched_secs.map(|_| "Channel".to_string()),
+            watched_at: 1_700_000_000,
+            watched_secs,
+        }
+    }
+
+    #[test]
+    fn saves_and_loads_every_list_in_order() {
+        let dir = temp_dir("round-trip");
+        let db = open_in(&dir).unwrap();
+        let history = vec![history_entry("b", Some(42)), history_entry("a", None)];
+        let bookmarks = vec![Bookmark {
+            id: "a".to_string(),
+            title: "Video a".to_string(),
+            channel: None,
+            tags: vec!["music".to_string(), "live, 2019".to_string()],
+            added_at: 5,
+        }];
+        let playlists = vec![
+            LocalPlaylist {
+                name: "Later".to_string(),
+                videos: vec![],
+            },
+            LocalPlaylist {
+                name: "Mix".to_string(),
+                videos: vec![
+                    PlaylistVideo {
+                        id: "b".to_string(),
+                        title: "Video b".to_string(),
+                        url: "https://youtu.be/b".to_string(),
+                    },
+                    PlaylistVideo {
+                        id: "a".to_string(),
+                        title: "Video a".to_string(),
+                        url: "https://youtu.be/a".to_string(),
+                    },
+                ],
+            },
+        ];
+        let progress = HashMap::from([(
+            "a".to_string(),
+            WatchProgress {
+                position: 12.5,
+                duration: 60.0,
+            },
+        )]);
+        db.transaction(|db| {
+            write_history(db, &history)?;
+            write_bookmarks(db, &bookmarks)?;
+            write_playlists(db, &playlists)?;
+            write_progress(db, &progress)
+        })
+        .unwrap();
+        drop(db);
+
+        let db = open_in(&dir).unwrap();
+        assert_eq!(read_history(&db).unwrap(), history);
+        assert_eq!(read_bookmarks(&db).unwrap(), bookmarks);
+        assert_eq!(read_playlists(&db).unwrap(), playlists);
+        assert_eq!(read_progress(&db).unwrap(), progress);
+        assert!(read_subscriptions(&db).unwrap().is_empty());
+
+        // Saving a shorter list drops the rows it no longer has.
+        write_history(&db, &history[1..]).unwrap();
+        assert_eq!(read_history(&db).unwrap(), &history[1..]);
+        drop(db);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn imports_json_files_once() {
+        let dir = temp_dir("import");
+        let history = vec![history_entry("a", Some(3))];
+        fs::write(
+            dir.join(HISTORY_FILE),
+            serde_json::to_vec(&history).unwrap(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join(SUBSCRIPTIONS_FILE),
+            br#"[{"id": "UC1", "name": "One"}]"#,
+        )
+        .unwrap();
+        fs::write(dir.join(BOOKMARKS_FILE), b"not json").unwrap();
+
+        let db = open_in(&dir).unwrap();
+        assert_eq!(read_history(&db).unwrap(), history);
+        assert_eq!(
+            read_subscriptions(&db).unwrap(),
+            [Subscription {
+                id: "UC1".to_string(),
+                name: "One".to_string(),
+            }]
+        );
+        // The broken file is skipped and moved aside; the rest stay as a
+        // backup.
+        assert!(read_bookmarks(&db).unwrap().is_empty());
+        assert!(!dir.join(BOOKMARKS_FILE).exists());
+        assert!(dir.join(HISTORY_FILE).exists());
+
+        // Later changes to the files aren't imported again.
+        write_history(&db, &[]).unwrap();
+        drop(db);
+        let db = open_in(&dir).unwrap();
+        assert!(read_history(&db).unwrap().is_empty());
+        drop(db);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn loads_missing_file_as_empty() {
+        let dir = temp_dir("missing");
+        let history: Vec<HistoryEntry> = load_json_file(&dir.join(HISTORY_FILE)).unwrap();
+        assert!(history.is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn moves_corrupt_file_aside() {
+        let dir = temp_dir("corrupt");
+        let path = dir.join(HISTORY_FILE);
+        fs::write(&path, b"[{\"id\": \"abc\",").unwrap();
+
+        let err = load_json_file::<Vec<HistoryEntry>>(&path).unwrap_err();
+        assert!(err.starts_with("Parse error"), "{err}");
+        assert!(!path.exists());
+        let backups: Vec<PathBuf> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(backups.len(), 1);
+        let name = backups[0]
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned();
+        assert!(name.starts_with("history.json.corrupt-"), "{name}");
+        assert_eq!(fs::read(&backups[0]).unwrap(), b"[{\"id\": \"abc\",");
+
+        // The store starts over empty and leaves the backup alone.
+        let history: Vec<HistoryEntry> = load_json_file(&path).unwrap();
+        assert!(history.is_empty());
+        assert!(backups[0].exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,85 @@
+//! Hover previews from the storyboards YouTube makes for its seek bar.
+//!
+//! A storyboard is a set of pages, each a grid of small frames taken at
+//! even steps through the video. A few frames spread over the whole video
+//! are cut out of the pages they are on and written as images of their own
+//! next to the thumbnails, so they are drawn and evicted the same way.
+
+use reqwest::blocking::Client;
+use rustypipe::model::Frameset;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Frames shown per video, first to last.
+pub const FRAMES: u32 = 24;
+
+/// The smallest frameset at least `width` pixels wide, or the largest.
+pub fn pick(framesets: &[Frameset], width: u32) -> Option<&Frameset> {
+    framesets
+        .iter()
+        .filter(|frameset| frameset.frame_width >= width)
+        .min_by_key(|frameset| frameset.frame_width)
+        .or_else(|| framesets.iter().max_by_key(|frameset| frameset.frame_width))
+}
+
+/// Writes `FRAMES` frames of `frameset` into `dir`, downloading only the
+/// pages they are on. Frames written before are reused.
+pub fn frames(
+    client: &Client,
+    frameset: &Frameset,
+    video_id: &str,
+    dir: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    let per_page = frameset.frames_per_page_x * frameset.frames_per_page_y;
+    if per_page == 0 || frameset.total_count == 0 {
+        return Err("Empty storyboard".to_string());
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Cache dir error: {e}"))?;
+    let count = FRAMES.min(frameset.total_count);
+    let mut page = None;
+    let mut paths = Vec::with_capacity(count as usize);
+    for step in 0..count {
+        let frame = step * frameset.total_count / count;
+        let path = dir.join(format!(
+            "storyboard_{video_id}_{}_{frame}.png",
+            frameset.frame_width
+        ));
+        if !path.exists() {
+            let index = frame / per_page;
+            let image = match page.take() {
+                Some((loaded, image)) if loaded == index => image,
+                _ => download_page(client, frameset, index)?,
+            };
+            let cell = frame % per_page;
+            let (x, y) = (
+                cell % frameset.frames_per_page_x * frameset.frame_width,
+                cell / frameset.frames_per_page_x * frameset.frame_height,
+            );
+            image
+                .crop_imm(x, y, frameset.frame_width, frameset.frame_height)
+                .save(&path)
+                .map_err(|e| format!("Write error: {e}"))?;
+            page = Some((index, image));
+        }
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+fn download_page(
+    client: &Client,
+    frameset: &Frameset,
+    index: u32,
+) -> Result<image::DynamicImage, String> {
+    let url = frameset
+        .urls()
+        .nth(index as usize)
+        .ok_or_else(|| "Storyboard page missing".to_string())?;
+    let bytes = client
+        .get(url)
+        .send()
+        .and_then(reqwest::blocking::Response::error_for_status)
+        .and_then(reqwest::blocking::Response::bytes)
+        .map_err(|e| format!("Download error: {e}"))?;
+    image::load_from_memory(&bytes).map_err(|e| format!("Decode error: {e}"))
+}
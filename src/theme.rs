@@ -0,0 +1,126 @@
+//! Color themes.
+//!
+//! Every color in the UI comes from one of the roles below. A theme is a
+//! built-in palette picked by name, with any role overridden from the
+//! `"theme"` section of the config.
+
+use crate::config::ThemeConfig;
+use ratatui::style::Color;
+use std::str::FromStr;
+
+/// Names of the built-in themes, in the order `M` cycles through them.
+pub const BUILTIN: [&str; 4] = ["default", "gruvbox", "solarized", "monochrome"];
+
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    /// Focused borders, key hints, prompts and the NEW badge.
+    pub accent: Color,
+    /// The selected row of a list.
+    pub selection: Color,
+    /// Secondary text: watched videos, separators, placeholders.
+    pub muted: Color,
+    /// Errors, progress bars, dislikes and the LIVE badge.
+    pub alert: Color,
+    /// Likes, lengths, finished downloads and the playing icon.
+    pub positive: Color,
+    /// View and subscriber counts, playback time and the 4K/HD badges.
+    pub stats: Color,
+    /// Channel names and links.
+    pub channel: Color,
+    /// Upload dates and descriptions.
+    pub date: Color,
+    /// Repeat, shuffle and speed indicators.
+    pub mode: Color,
+    /// Text of the badges and of anything else drawn on a colored background.
+    pub badge_text: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            accent: Color::Cyan,
+            selection: Color::Yellow,
+            muted: Color::DarkGray,
+            alert: Color::Red,
+            positive: Color::Green,
+            stats: Color::Yellow,
+            channel: Color::Blue,
+            date: Color::LightMagenta,
+            mode: Color::Magenta,
+            badge_text: Color::White,
+        }
+    }
+}
+
+impl Theme {
+    /// The built-in theme called `name`.
+    pub fn builtin(name: &str) -> Option<Theme> {
+        let theme = match name {
+            "default" => Theme::default(),
+            "gruvbox" => Theme {
+                accent: Color::Rgb(0x8e, 0xc0, 0x7c),
+                selection: Color::Rgb(0xfa, 0xbd, 0x2f),
+                muted: Color::Rgb(0x92, 0x83, 0x74),
+                alert: Color::Rgb(0xfb, 0x49, 0x34),
+                positive: Color::Rgb(0xb8, 0xbb, 0x26),
+                stats: Color::Rgb(0xfe, 0x80, 0x19),
+                channel: Color::Rgb(0x83, 0xa5, 0x98),
+                date: Color::Rgb(0xd3, 0x86, 0x9b),
+                mode: Color::Rgb(0xd3, 0x86, 0x9b),
+                badge_text: Color::Rgb(0x28, 0x28, 0x28),
+            },
+            "solarized" => Theme {
+                accent: Color::Rgb(0x2a, 0xa1, 0x98),
+                selection: Color::Rgb(0xb5, 0x89, 0x00),
+                muted: Color::Rgb(0x58, 0x6e, 0x75),
+                alert: Color::Rgb(0xdc, 0x32, 0x2f),
+                positive: Color::Rgb(0x85, 0x99, 0x00),
+                stats: Color::Rgb(0xcb, 0x4b, 0x16),
+                channel: Color::Rgb(0x26, 0x8b, 0xd2),
+                date: Color::Rgb(0x6c, 0x71, 0xc4),
+                mode: Color::Rgb(0xd3, 0x36, 0x82),
+                badge_text: Color::Rgb(0xfd, 0xf6, 0xe3),
+            },
+            "monochrome" => Theme {
+                accent: Color::White,
+                selection: Color::White,
+                muted: Color::DarkGray,
+                alert: Color::Gray,
+                positive: Color::Gray,
+                stats: Color::Gray,
+                channel: Color::Gray,
+                date: Color::Gray,
+                mode: Color::White,
+                badge_text: Color::Black,
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Builds the configured theme. Colors are names (`"light blue"`),
+    /// `"#rrggbb"` or 256-color indexes.
+    pub fn from_config(config: &ThemeConfig) -> Result<Theme, String> {
+        let name = config.name.as_deref().unwrap_or(BUILTIN[0]);
+        let mut theme = Theme::builtin(name).ok_or_else(|| format!("Unknown theme '{name}'."))?;
+        for (role, value) in &config.colors {
+            let color = Color::from_str(value)
+                .map_err(|_| format!("Invalid theme color '{value}' for {role}."))?;
+            let slot = match role.as_str() {
+                "accent" => &mut theme.accent,
+                "selection" => &mut theme.selection,
+                "muted" => &mut theme.muted,
+                "alert" => &mut theme.alert,
+                "positive" => &mut theme.positive,
+                "stats" => &mut theme.stats,
+                "channel" => &mut theme.channel,
+                "date" => &mut theme.date,
+                "mode" => &mut theme.mode,
+                "badge_text" => &mut theme.badge_text,
+                _ => return Err(format!("Unknown theme color '{role}'.")),
+            };
+            *slot = color;
+        }
+        Ok(theme)
+    }
+}
@@ -0,0 +1,96 @@
+//! Notifications stacked in a corner of the screen.
+//!
+//! Every message becomes a toast that stays up for a time depending on its
+//! level, so a routine message that follows an error doesn't replace it.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Toasts kept at once; the oldest go first when more arrive.
+const MAX_TOASTS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn timeout(self) -> Duration {
+        match self {
+            Level::Info => Duration::from_secs(4),
+            Level::Warning => Duration::from_secs(8),
+            Level::Error => Duration::from_secs(12),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub level: Level,
+    pub text: String,
+    shown: Instant,
+}
+
+#[derive(Debug, Default)]
+pub struct Toasts {
+    toasts: VecDeque<Toast>,
+    /// Set while a search tab in the background handles its messages,
+    /// whose info toasts would be about a tab that isn't shown.
+    quiet: bool,
+}
+
+impl Toasts {
+    /// Shows `text`. Repeating the newest toast only restarts its timeout.
+    /// While quiet, info messages are only logged.
+    pub fn push(&mut self, level: Level, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        if self.quiet && level == Level::Info {
+            log::info!("{text}");
+            return;
+        }
+        if let Some(last) = self.toasts.back_mut() {
+            if last.level == level && last.text == text {
+                last.shown = Instant::now();
+                return;
+            }
+        }
+        match level {
+            Level::Info => log::info!("{text}"),
+            Level::Warning => log::warn!("{text}"),
+            Level::Error => log::error!("{text}"),
+        }
+        if self.toasts.len() == MAX_TOASTS {
+            // Errors outlive the messages that come after them.
+            let oldest = self
+                .toasts
+                .iter()
+                .position(|toast| toast.level != Level::Error)
+                .unwrap_or(0);
+            self.toasts.remove(oldest);
+        }
+        self.toasts.push_back(Toast {
+            level,
+            text,
+            shown: Instant::now(),
+        });
+    }
+
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+
+    /// Drops the toasts whose time is up.
+    pub fn expire(&mut self) {
+        self.toasts
+            .retain(|toast| toast.shown.elapsed() < toast.level.timeout());
+    }
+
+    /// Oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Toast> {
+        self.toasts.iter()
+    }
+}
@@ -0,0 +1,134 @@
+//! The Downloads view: yt-dlp downloads with their progress.
+
+use crate::{ask_confirmation, start_download, App, ConfirmAction, DownloadState};
+use crossterm::event::KeyCode;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+#[derive(Default)]
+pub struct DownloadsView {
+    pub selected: usize,
+}
+
+pub fn handle_key(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Up => {
+            app.views.downloads.selected = app.views.downloads.selected.saturating_sub(1)
+        }
+        KeyCode::Down => {
+            app.views.downloads.selected =
+                (app.views.downloads.selected + 1).min(app.downloads.len().saturating_sub(1));
+        }
+        KeyCode::Char('c') => {
+            if let Some(item) = app.downloads.get(app.views.downloads.selected) {
+                if item.handle.is_some() {
+                    let question = format!("Cancel the download of {}?", item.title);
+                    let action = ConfirmAction::CancelDownload(item.id);
+                    ask_confirmation(app, question, action);
+                }
+            }
+        }
+        KeyCode::Char('r') => {
+            let index = app.views.downloads.selected;
+            let retry = app.downloads.get(index).is_some_and(|item| {
+                matches!(
+                    item.state,
+                    DownloadState::Failed(_) | DownloadState::Cancelled
+                )
+            });
+            if retry {
+                start_download(app, index);
+            }
+        }
+        KeyCode::Char('d') | KeyCode::Delete => {
+            let index = app.views.downloads.selected;
+            if index < app.downloads.len() {
+                let item = app.downloads.remove(index);
+                if let Some(handle) = item.handle {
+                    handle.cancel();
+                }
+                app.views.downloads.selected = index.min(app.downloads.len().saturating_sub(1));
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn render(f: &mut Frame<'_>, app: &App, area: ratatui::layout::Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Downloads — c cancel, r retry, d remove, Esc back")
+        .border_style(Style::default().fg(app.theme.accent));
+    draw(f, app, block, area);
+}
+
+/// Draws the downloads as a pane beside the results, highlighted while it
+/// has the focus.
+pub fn render_pane(f: &mut Frame<'_>, app: &App, area: ratatui::layout::Rect, focused: bool) {
+    let running = app
+        .downloads
+        .iter()
+        .filter(|item| item.state == DownloadState::Running)
+        .count();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Downloads ({running} running)"))
+        .border_style(if focused {
+            Style::default().fg(app.theme.accent)
+        } else {
+            Style::default()
+        });
+    draw(f, app, block, area);
+}
+
+fn draw(f: &mut Frame<'_>, app: &App, block: Block<'_>, area: ratatui::layout::Rect) {
+    let theme = app.theme;
+    if app.downloads.is_empty() {
+        f.render_widget(
+            Paragraph::new("No downloads. Press D on a video to download it.").block(block),
+            area,
+        );
+        return;
+    }
+    const BAR_WIDTH: usize = 20;
+    let items: Vec<ListItem> = app
+        .downloads
+        .iter()
+        .map(|item| {
+            let filled = ((item.progress / 100.0) * BAR_WIDTH as f64).round() as usize;
+            let filled = filled.min(BAR_WIDTH);
+            let (state, color) = match &item.state {
+                DownloadState::Running => (format!("{:>5.1}%", item.progress), theme.stats),
+                DownloadState::Done => ("done".to_string(), theme.positive),
+                DownloadState::Failed(err) => (format!("failed: {err}"), theme.alert),
+                DownloadState::Cancelled => ("cancelled".to_string(), theme.muted),
+            };
+            ListItem::new(vec![
+                Line::from(item.title.clone()),
+                Line::from(vec![
+                    Span::styled(
+                        format!(
+                            "  [{}{}] ",
+                            "#".repeat(filled),
+                            "-".repeat(BAR_WIDTH - filled)
+                        ),
+                        Style::default().fg(color),
+                    ),
+                    Span::styled(state, Style::default().fg(color)),
+                ]),
+            ])
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(
+        app.views.downloads.selected.min(app.downloads.len() - 1),
+    ));
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .fg(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+}
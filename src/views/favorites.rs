@@ -0,0 +1,104 @@
+//! The Favorites view: the bookmarked videos, newest first.
+
+use crate::{replay, save_bookmarks, App, Popup, TagsView};
+use crossterm::event::KeyCode;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+#[derive(Default)]
+pub struct FavoritesView {
+    /// Counted from the newest bookmark.
+    pub selected: usize,
+}
+
+pub fn handle_key(app: &mut App, key: KeyCode) {
+    let last = app.bookmarks.len().saturating_sub(1);
+    // Listed newest first.
+    let index = last.checked_sub(app.views.favorites.selected.min(last));
+    let selected = index.filter(|_| !app.bookmarks.is_empty());
+    match key {
+        KeyCode::Up => {
+            app.views.favorites.selected = app.views.favorites.selected.saturating_sub(1)
+        }
+        KeyCode::Down => {
+            app.views.favorites.selected = (app.views.favorites.selected + 1).min(last)
+        }
+        KeyCode::Home => app.views.favorites.selected = 0,
+        KeyCode::End => app.views.favorites.selected = last,
+        KeyCode::Enter => {
+            if let Some(bookmark) = selected.map(|index| &app.bookmarks[index]) {
+                let (id, title) = (bookmark.id.clone(), bookmark.title.clone());
+                replay(app, &id, &title);
+            }
+        }
+        KeyCode::Char('t') => {
+            if let Some(bookmark) = selected.map(|index| &app.bookmarks[index]) {
+                app.popup = Some(Popup::Tags(TagsView {
+                    video_id: bookmark.id.clone(),
+                    input: bookmark.tags.join(", "),
+                }));
+                app.needs_full_redraw = true;
+            }
+        }
+        KeyCode::Char('d') | KeyCode::Delete => {
+            if let Some(index) = selected {
+                let bookmark = app.bookmarks.remove(index);
+                app.views.favorites.selected =
+                    app.views.favorites.selected.min(last.saturating_sub(1));
+                save_bookmarks(app, format!("Removed {} from Favorites.", bookmark.title));
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn render(f: &mut Frame<'_>, app: &App, area: ratatui::layout::Rect) {
+    let theme = app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "Favorites ({}) — ⏎ play, t tags, d remove, Esc back",
+            app.bookmarks.len()
+        ))
+        .border_style(Style::default().fg(theme.accent));
+    if app.bookmarks.is_empty() {
+        f.render_widget(
+            Paragraph::new("No bookmarks yet. Press m on a video to add it.").block(block),
+            area,
+        );
+        return;
+    }
+    let items: Vec<ListItem> = app
+        .bookmarks
+        .iter()
+        .rev()
+        .map(|bookmark| {
+            let mut spans = vec![Span::raw(bookmark.title.clone())];
+            if let Some(channel) = &bookmark.channel {
+                spans.push(Span::styled(
+                    format!(" · {channel}"),
+                    Style::default().fg(theme.channel),
+                ));
+            }
+            if !bookmark.tags.is_empty() {
+                spans.push(Span::styled(
+                    format!(" [{}]", bookmark.tags.join(", ")),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(
+        app.views.favorites.selected.min(app.bookmarks.len() - 1),
+    ));
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .fg(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+}
@@ -0,0 +1,179 @@
+//! The History view: the videos played, newest first, with a search over
+//! their titles and channels.
+
+use crate::storage;
+use crate::toast::Level;
+use crate::{
+    ask_confirmation, format_age, format_duration, progress_bar, replay, App, ConfirmAction,
+};
+use crossterm::event::KeyCode;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+use time::OffsetDateTime;
+
+#[derive(Default)]
+pub struct HistoryView {
+    /// Selection among the entries shown, counted from the newest.
+    pub selected: usize,
+    /// Only entries whose title or channel contain this are shown.
+    pub search: String,
+    pub search_editing: bool,
+}
+
+pub fn handle_key(app: &mut App, key: KeyCode) {
+    let rows = rows(app);
+    let last = rows.len().saturating_sub(1);
+    let selected = rows.get(app.views.history.selected.min(last)).copied();
+    match key {
+        KeyCode::Up => app.views.history.selected = app.views.history.selected.saturating_sub(1),
+        KeyCode::Down => app.views.history.selected = (app.views.history.selected + 1).min(last),
+        KeyCode::Home => app.views.history.selected = 0,
+        KeyCode::End => app.views.history.selected = last,
+        KeyCode::Char('/') => {
+            app.views.history.search_editing = true;
+            app.views.history.selected = 0;
+        }
+        KeyCode::Enter => {
+            let Some(entry) = selected.map(|index| &app.history[index]) else {
+                return;
+            };
+            let (id, title) = (entry.id.clone(), entry.title.clone());
+            replay(app, &id, &title);
+        }
+        KeyCode::Char('D') if !app.history.is_empty() => {
+            let question = format!("Clear all {} videos from the history?", app.history.len());
+            ask_confirmation(app, question, ConfirmAction::ClearHistory);
+        }
+        KeyCode::Char('d') | KeyCode::Delete if selected.is_some() => {
+            let entry = app.history.remove(selected.unwrap_or_default());
+            app.watched = app.history.iter().map(|entry| entry.id.clone()).collect();
+            app.views.history.selected =
+                app.views.history.selected.min(rows.len().saturating_sub(2));
+            match storage::save_history(&app.history) {
+                Ok(()) => app.toasts.push(
+                    Level::Info,
+                    format!("Removed {} from the history.", entry.title),
+                ),
+                Err(err) => app
+                    .toasts
+                    .push(Level::Error, format!("Failed to save watch history: {err}")),
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Typing in the History search: Enter keeps the search, Esc clears it.
+pub fn handle_search_key(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc => {
+            app.views.history.search_editing = false;
+            app.views.history.search.clear();
+        }
+        KeyCode::Enter => {
+            app.views.history.search_editing = false;
+            app.toasts.push(
+                Level::Info,
+                format!(
+                    "{} of {} history entries match.",
+                    rows(app).len(),
+                    app.history.len()
+                ),
+            );
+        }
+        KeyCode::Backspace => {
+            app.views.history.search.pop();
+        }
+        KeyCode::Char(c) => app.views.history.search.push(c),
+        _ => return,
+    }
+    app.views.history.selected = 0;
+}
+
+/// Indices into `app.history` of the entries matching the History search,
+/// newest first.
+pub fn rows(app: &App) -> Vec<usize> {
+    let needle = app.views.history.search.to_lowercase();
+    (0..app.history.len())
+        .rev()
+        .filter(|&index| {
+            let entry = &app.history[index];
+            needle.is_empty()
+                || entry.title.to_lowercase().contains(&needle)
+                || entry
+                    .channel
+                    .as_ref()
+                    .is_some_and(|channel| channel.to_lowercase().contains(&needle))
+        })
+        .collect()
+}
+
+pub fn render(f: &mut Frame<'_>, app: &App, area: ratatui::layout::Rect) {
+    let theme = app.theme;
+    let rows = rows(app);
+    let count = if app.views.history.search.is_empty() {
+        app.history.len().to_string()
+    } else {
+        format!(
+            "{} of {}, /{}",
+            rows.len(),
+            app.history.len(),
+            app.views.history.search
+        )
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "History ({count}) — ⏎ play, / search, d remove, Esc back"
+        ))
+        .border_style(Style::default().fg(theme.accent));
+    if app.history.is_empty() {
+        f.render_widget(Paragraph::new("Nothing watched yet.").block(block), area);
+        return;
+    }
+    if rows.is_empty() {
+        f.render_widget(Paragraph::new("No entries match.").block(block), area);
+        return;
+    }
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|&index| {
+            let entry = &app.history[index];
+            let mut spans = vec![Span::raw(entry.title.clone())];
+            if let Some(channel) = &entry.channel {
+                spans.push(Span::styled(
+                    format!(" · {channel}"),
+                    Style::default().fg(theme.channel),
+                ));
+            }
+            spans.push(Span::styled(
+                format!(" · {}", format_age((now - entry.watched_at).max(0) as u64)),
+                Style::default().fg(theme.date),
+            ));
+            if let Some(secs) = entry.watched_secs {
+                spans.push(Span::styled(
+                    format!(" · watched {}", format_duration(secs)),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+            if let Some(progress) = app.progress.get(&entry.id) {
+                spans.push(Span::styled(
+                    format!(" {}", progress_bar(progress.fraction(), 8)),
+                    Style::default().fg(theme.alert),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(app.views.history.selected.min(rows.len() - 1)));
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .fg(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+}
@@ -0,0 +1,223 @@
+//! The screens listed in the view bar. Each view keeps its own state in
+//! `Views`, so switching away and back finds it as it was left.
+//!
+//! The Search and Feed views show a `ResultsScreen`, drawn and driven by
+//! the crate root; the others are lists drawn and handled by the modules
+//! here.
+
+mod downloads;
+mod favorites;
+mod history;
+mod playlists;
+mod queue;
+mod stats;
+mod watch_later;
+
+use crate::{
+    add_to_watch_later, handle_player_key, open_playlist_picker, set_focus, switch_view, App,
+    Focus, QueueItem, ResultsScreen,
+};
+use crossterm::event::KeyCode;
+use ratatui::Frame;
+use std::io;
+
+pub use downloads::DownloadsView;
+pub use favorites::FavoritesView;
+pub use history::HistoryView;
+pub use playlists::PlaylistsView;
+pub use queue::QueueView;
+pub use watch_later::WatchLaterView;
+
+/// The screens listed in the view bar, switched with 1-9.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Search,
+    Feed,
+    History,
+    Queue,
+    Downloads,
+    Favorites,
+    Playlists,
+    WatchLater,
+    Stats,
+}
+
+impl View {
+    pub const ALL: [View; 9] = [
+        View::Search,
+        View::Feed,
+        View::History,
+        View::Queue,
+        View::Downloads,
+        View::Favorites,
+        View::Playlists,
+        View::WatchLater,
+        View::Stats,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            View::Search => "Search",
+            View::Feed => "Feed",
+            View::History => "History",
+            View::Queue => "Queue",
+            View::Downloads => "Downloads",
+            View::Favorites => "Favorites",
+            View::Playlists => "Playlists",
+            View::WatchLater => "Watch later",
+            View::Stats => "Stats",
+        }
+    }
+}
+
+/// The view shown, and the state of every view while it is hidden.
+pub struct Views {
+    pub current: View,
+    /// The results screen of whichever of the Search and Feed views isn't
+    /// `App::screen`: the Feed while a search tab is shown, the active
+    /// search tab while the Feed is.
+    pub parked: Option<ResultsScreen>,
+    pub history: HistoryView,
+    pub queue: QueueView,
+    pub downloads: DownloadsView,
+    pub favorites: FavoritesView,
+    pub playlists: PlaylistsView,
+    pub watch_later: WatchLaterView,
+}
+
+impl Default for Views {
+    fn default() -> Self {
+        Views {
+            current: View::Search,
+            parked: None,
+            history: HistoryView::default(),
+            queue: QueueView::default(),
+            downloads: DownloadsView::default(),
+            favorites: FavoritesView::default(),
+            playlists: PlaylistsView::default(),
+            watch_later: WatchLaterView::default(),
+        }
+    }
+}
+
+/// Draws the current view into `area`, unless it is Search or Feed.
+pub fn render(f: &mut Frame<'_>, app: &App, area: ratatui::layout::Rect) {
+    match app.views.current {
+        View::History => history::render(f, app, area),
+        View::Queue => queue::render(f, app, area),
+        View::Downloads => downloads::render(f, app, area),
+        View::Favorites => favorites::render(f, app, area),
+        View::Playlists => playlists::render(f, app, area),
+        View::WatchLater => watch_later::render(f, app, area),
+        View::Stats => stats::render(f, app, area),
+        View::Search | View::Feed => {}
+    }
+}
+
+/// Draws side pane `pane` of the Search and Feed views into `area`.
+pub fn render_pane(f: &mut Frame<'_>, app: &App, pane: Focus, area: ratatui::layout::Rect) {
+    let focused = app.screen.focus == pane;
+    match pane {
+        Focus::Queue => queue::render_pane(f, app, area, focused),
+        Focus::Downloads => downloads::render_pane(f, app, area, focused),
+        Focus::Search | Focus::Results | Focus::Details => {}
+    }
+}
+
+/// Keys of the focused side pane, which work as in its own view; Esc goes
+/// back to the results. Returns false for keys left to the Search or Feed
+/// view.
+pub fn handle_pane_key(app: &mut App, key: KeyCode) -> bool {
+    match (app.screen.focus, key) {
+        (_, KeyCode::Esc) => set_focus(app, Focus::Results),
+        (
+            Focus::Queue,
+            KeyCode::Up | KeyCode::Down | KeyCode::Enter | KeyCode::Char('d') | KeyCode::Delete,
+        ) => queue::handle_key(app, key),
+        (
+            Focus::Downloads,
+            KeyCode::Up | KeyCode::Down | KeyCode::Char('c' | 'r' | 'd') | KeyCode::Delete,
+        ) => downloads::handle_key(app, key),
+        _ => return false,
+    }
+    true
+}
+
+/// Keys of the views other than Search and Feed. Returns true to quit.
+pub fn handle_key(app: &mut App, key: KeyCode) -> io::Result<bool> {
+    if app.views.history.search_editing {
+        history::handle_search_key(app, key);
+        return Ok(false);
+    }
+    match key {
+        KeyCode::Char('q') => return Ok(true),
+        KeyCode::Esc
+            if app.views.current == View::Playlists && app.views.playlists.open.is_some() =>
+        {
+            app.views.playlists.open = None;
+        }
+        KeyCode::Esc => switch_view(app, View::Search),
+        KeyCode::Char('l') if !matches!(app.views.current, View::Downloads | View::Stats) => {
+            if let Some(item) = selected_item(app) {
+                open_playlist_picker(app, vec![item]);
+            }
+        }
+        KeyCode::Char('w')
+            if !matches!(
+                app.views.current,
+                View::Downloads | View::WatchLater | View::Stats
+            ) =>
+        {
+            if let Some(item) = selected_item(app) {
+                add_to_watch_later(app, item.id, item.title, None);
+            }
+        }
+        KeyCode::Char(c) if handle_player_key(app, c) => {}
+        _ => match app.views.current {
+            View::History => history::handle_key(app, key),
+            View::Queue => queue::handle_key(app, key),
+            View::Downloads => downloads::handle_key(app, key),
+            View::Favorites => favorites::handle_key(app, key),
+            View::Playlists => playlists::handle_key(app, key),
+            View::WatchLater => watch_later::handle_key(app, key),
+            View::Search | View::Feed | View::Stats => {}
+        },
+    }
+    Ok(false)
+}
+
+/// The video selected in the History, Queue, Favorites, Playlists or Watch
+/// later view.
+pub fn selected_item(app: &App) -> Option<QueueItem> {
+    let item = |id: &str, title: &str| QueueItem {
+        id: id.to_string(),
+        title: title.to_string(),
+        url: format!("https://www.youtube.com/watch?v={id}"),
+    };
+    match app.views.current {
+        View::History => {
+            let index = *history::rows(app).get(app.views.history.selected)?;
+            let entry = &app.history[index];
+            Some(item(&entry.id, &entry.title))
+        }
+        View::Queue => app.queue.get(app.views.queue.selected).cloned(),
+        View::Favorites => {
+            let index = app
+                .bookmarks
+                .len()
+                .checked_sub(app.views.favorites.selected + 1)?;
+            let bookmark = &app.bookmarks[index];
+            Some(item(&bookmark.id, &bookmark.title))
+        }
+        View::Playlists => {
+            let playlist = &app.playlists[app.views.playlists.open?];
+            let video = playlist.videos.get(app.views.playlists.video_selected)?;
+            Some(item(&video.id, &video.title))
+        }
+        View::WatchLater => {
+            let entry = app.watch_later.get(app.views.watch_later.selected)?;
+            Some(item(&entry.id, &entry.title))
+        }
+        View::Search | View::Feed | View::Downloads | View::Stats => None,
+    }
+}
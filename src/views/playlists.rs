@@ -0,0 +1,170 @@
+//! The Playlists view: the local playlists, and the videos of the one
+//! opened.
+
+use crate::{
+    ask_confirmation, play_local_playlist, save_playlists, App, ConfirmAction, PlaylistPickerView,
+    Popup,
+};
+use crossterm::event::KeyCode;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+#[derive(Default)]
+pub struct PlaylistsView {
+    pub selected: usize,
+    /// The playlist whose videos are listed, if any.
+    pub open: Option<usize>,
+    pub video_selected: usize,
+}
+
+pub fn handle_key(app: &mut App, key: KeyCode) {
+    let Some(open) = app
+        .views
+        .playlists
+        .open
+        .filter(|&open| open < app.playlists.len())
+    else {
+        app.views.playlists.open = None;
+        let last = app.playlists.len().saturating_sub(1);
+        let selected = app.views.playlists.selected.min(last);
+        match key {
+            KeyCode::Up => {
+                app.views.playlists.selected = app.views.playlists.selected.saturating_sub(1)
+            }
+            KeyCode::Down => {
+                app.views.playlists.selected = (app.views.playlists.selected + 1).min(last)
+            }
+            KeyCode::Home => app.views.playlists.selected = 0,
+            KeyCode::End => app.views.playlists.selected = last,
+            KeyCode::Enter if !app.playlists.is_empty() => {
+                app.views.playlists.open = Some(selected);
+                app.views.playlists.video_selected = 0;
+            }
+            KeyCode::Char('P') if !app.playlists.is_empty() => {
+                play_local_playlist(app, selected, 0);
+            }
+            KeyCode::Char('n') => {
+                app.popup = Some(Popup::AddToPlaylist(PlaylistPickerView {
+                    items: Vec::new(),
+                    selected: 0,
+                    new_name: Some(String::new()),
+                }));
+                app.needs_full_redraw = true;
+            }
+            KeyCode::Char('d') | KeyCode::Delete if !app.playlists.is_empty() => {
+                let name = app.playlists[selected].name.clone();
+                let question = format!("Delete the playlist {name}?");
+                ask_confirmation(app, question, ConfirmAction::DeleteLocalPlaylist(name));
+            }
+            _ => {}
+        }
+        return;
+    };
+    let videos = &mut app.playlists[open].videos;
+    let last = videos.len().saturating_sub(1);
+    let selected = app.views.playlists.video_selected.min(last);
+    match key {
+        KeyCode::Up => app.views.playlists.video_selected = selected.saturating_sub(1),
+        KeyCode::Down => app.views.playlists.video_selected = (selected + 1).min(last),
+        KeyCode::Home => app.views.playlists.video_selected = 0,
+        KeyCode::End => app.views.playlists.video_selected = last,
+        KeyCode::Enter if !videos.is_empty() => play_local_playlist(app, open, selected),
+        KeyCode::Char('P') => play_local_playlist(app, open, 0),
+        KeyCode::Char('K') if selected > 0 => {
+            videos.swap(selected, selected - 1);
+            app.views.playlists.video_selected = selected - 1;
+            save_playlists(app, String::new());
+        }
+        KeyCode::Char('J') if selected < last => {
+            videos.swap(selected, selected + 1);
+            app.views.playlists.video_selected = selected + 1;
+            save_playlists(app, String::new());
+        }
+        KeyCode::Char('d') | KeyCode::Delete if !videos.is_empty() => {
+            let video = videos.remove(selected);
+            app.views.playlists.video_selected = selected.min(last.saturating_sub(1));
+            save_playlists(app, format!("Removed {} from the playlist.", video.title));
+        }
+        _ => {}
+    }
+}
+
+pub fn render(f: &mut Frame<'_>, app: &App, area: ratatui::layout::Rect) {
+    let theme = app.theme;
+    let highlight = Style::default()
+        .fg(theme.selection)
+        .add_modifier(Modifier::BOLD);
+    if let Some(playlist) = app
+        .views
+        .playlists
+        .open
+        .and_then(|open| app.playlists.get(open))
+    {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                "Playlist: {} ({}) — ⏎ play from here, P play all, J/K move, d remove, Esc back",
+                playlist.name,
+                playlist.videos.len()
+            ))
+            .border_style(Style::default().fg(theme.accent));
+        if playlist.videos.is_empty() {
+            f.render_widget(
+                Paragraph::new("No videos yet. Press l on a video to add it.").block(block),
+                area,
+            );
+            return;
+        }
+        let items: Vec<ListItem> = playlist
+            .videos
+            .iter()
+            .enumerate()
+            .map(|(index, video)| ListItem::new(format!("{}. {}", index + 1, video.title)))
+            .collect();
+        let mut state = ListState::default();
+        state.select(Some(
+            app.views
+                .playlists
+                .video_selected
+                .min(playlist.videos.len() - 1),
+        ));
+        let list = List::new(items).block(block).highlight_style(highlight);
+        f.render_stateful_widget(list, area, &mut state);
+        return;
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "Playlists ({}) — ⏎ open, P play, n new, d delete, Esc back",
+            app.playlists.len()
+        ))
+        .border_style(Style::default().fg(theme.accent));
+    if app.playlists.is_empty() {
+        f.render_widget(
+            Paragraph::new("No playlists. Press n to create one, or l on a video.").block(block),
+            area,
+        );
+        return;
+    }
+    let items: Vec<ListItem> = app
+        .playlists
+        .iter()
+        .map(|playlist| {
+            ListItem::new(Line::from(vec![
+                Span::raw(playlist.name.clone()),
+                Span::styled(
+                    format!(" · {} videos", playlist.videos.len()),
+                    Style::default().fg(theme.muted),
+                ),
+            ]))
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(
+        app.views.playlists.selected.min(app.playlists.len() - 1),
+    ));
+    let list = List::new(items).block(block).highlight_style(highlight);
+    f.render_stateful_widget(list, area, &mut state);
+}
@@ -0,0 +1,91 @@
+//! The Queue view: the videos to play after the current one.
+
+use crate::{play_queue_item, remove_queued, App};
+use crossterm::event::KeyCode;
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+#[derive(Default)]
+pub struct QueueView {
+    pub selected: usize,
+}
+
+pub fn handle_key(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Up => app.views.queue.selected = app.views.queue.selected.saturating_sub(1),
+        KeyCode::Down => {
+            app.views.queue.selected =
+                (app.views.queue.selected + 1).min(app.queue.len().saturating_sub(1));
+        }
+        KeyCode::Char('d') | KeyCode::Delete => {
+            let index = app.views.queue.selected;
+            if index < app.queue.len() {
+                app.views.queue.selected = index.min(app.queue.len().saturating_sub(2));
+                remove_queued(app, index);
+            }
+        }
+        KeyCode::Enter => {
+            if let Some(item) = app.queue.remove(app.views.queue.selected) {
+                app.views.queue.selected = app
+                    .views
+                    .queue
+                    .selected
+                    .min(app.queue.len().saturating_sub(1));
+                play_queue_item(app, item);
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn render(f: &mut Frame<'_>, app: &App, area: ratatui::layout::Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "Queue ({}{}) — ⏎ play now, d remove, Esc back",
+            app.queue.len(),
+            if app.shuffle { ", shuffled" } else { "" }
+        ))
+        .border_style(Style::default().fg(app.theme.accent));
+    draw(f, app, block, area);
+}
+
+/// Draws the queue as a pane beside the results, highlighted while it has
+/// the focus.
+pub fn render_pane(f: &mut Frame<'_>, app: &App, area: ratatui::layout::Rect, focused: bool) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Queue ({})", app.queue.len()))
+        .border_style(if focused {
+            Style::default().fg(app.theme.accent)
+        } else {
+            Style::default()
+        });
+    draw(f, app, block, area);
+}
+
+fn draw(f: &mut Frame<'_>, app: &App, block: Block<'_>, area: ratatui::layout::Rect) {
+    let theme = app.theme;
+    if app.queue.is_empty() {
+        f.render_widget(
+            Paragraph::new("Nothing queued. Press a on a video to add it.").block(block),
+            area,
+        );
+        return;
+    }
+    let items: Vec<ListItem> = app
+        .queue
+        .iter()
+        .enumerate()
+        .map(|(index, item)| ListItem::new(format!("{}. {}", index + 1, item.title)))
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(app.views.queue.selected.min(app.queue.len() - 1)));
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .fg(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+}
@@ -0,0 +1,147 @@
+//! The Stats view: what the watch history adds up to.
+
+use crate::stats::WatchStats;
+use crate::theme::Theme;
+use crate::{format_age, group_digits, progress_bar, App};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{BarChart, Block, Borders, Paragraph};
+use ratatui::Frame;
+use time::OffsetDateTime;
+
+pub fn render(f: &mut Frame<'_>, app: &App, area: ratatui::layout::Rect) {
+    let theme = app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Stats — from the watch history, Esc back")
+        .border_style(Style::default().fg(theme.accent));
+    if app.history.is_empty() {
+        f.render_widget(Paragraph::new("Nothing watched yet.").block(block), area);
+        return;
+    }
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let stats = WatchStats::new(&app.history, now);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(10),
+            Constraint::Min(0),
+        ])
+        .split(inner);
+
+    let label = Style::default().fg(theme.muted);
+    let value = Style::default().fg(theme.stats);
+    let since = stats.since.map_or(String::new(), |since| {
+        format_age((now - since).max(0) as u64)
+    });
+    let summary = vec![
+        Line::from(vec![
+            Span::styled("Watch time  ", label),
+            Span::styled(format_watch_time(stats.watched_secs), value),
+        ]),
+        Line::from(vec![
+            Span::styled("Plays       ", label),
+            Span::styled(group_digits(stats.plays), value),
+            Span::styled(
+                format!(
+                    " of {} videos, since {since}",
+                    group_digits(stats.distinct_videos)
+                ),
+                label,
+            ),
+        ]),
+    ];
+    f.render_widget(Paragraph::new(summary), rows[0]);
+
+    let charts = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(rows[1]);
+    f.render_widget(
+        chart("Videos per day", &stats.per_day, charts[0], &theme),
+        charts[0],
+    );
+    f.render_widget(
+        chart("Videos per week", &stats.per_week, charts[1], &theme),
+        charts[1],
+    );
+
+    let most = stats
+        .top_channels
+        .first()
+        .map_or(1, |channel| channel.videos);
+    let name_width = stats
+        .top_channels
+        .iter()
+        .map(|channel| channel.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(30);
+    let mut lines = vec![
+        Line::default(),
+        Line::from(Span::styled("Most watched channels", label)),
+    ];
+    lines.extend(stats.top_channels.iter().map(|channel| {
+        let name: String = channel.name.chars().take(name_width).collect();
+        let plays = if channel.videos == 1 { "play" } else { "plays" };
+        Line::from(vec![
+            Span::styled(
+                format!("{name:<name_width$} "),
+                Style::default().fg(theme.channel),
+            ),
+            Span::styled(
+                progress_bar(channel.videos as f64 / most as f64, 20),
+                Style::default().fg(theme.accent),
+            ),
+            Span::styled(format!(" {} {plays}", channel.videos), value),
+            Span::styled(
+                format!(" · {}", format_watch_time(channel.watched_secs)),
+                label,
+            ),
+        ])
+    }));
+    if stats.top_channels.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No channel names recorded.",
+            label,
+        )));
+    }
+    f.render_widget(Paragraph::new(lines), rows[2]);
+}
+
+/// A bar per `(label, plays)`, as wide as fits in `area`.
+pub fn chart<'a>(
+    title: &'a str,
+    data: &'a [(String, u64)],
+    area: ratatui::layout::Rect,
+    theme: &Theme,
+) -> BarChart<'a> {
+    let data: Vec<(&str, u64)> = data
+        .iter()
+        .map(|(label, plays)| (label.as_str(), *plays))
+        .collect();
+    let gaps = data.len().saturating_sub(1) as u16;
+    let width = (area.width.saturating_sub(gaps) / data.len().max(1) as u16).clamp(1, 4);
+    BarChart::default()
+        .block(Block::default().title(title))
+        .data(&data)
+        .bar_width(width)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(theme.accent))
+        .value_style(Style::default().fg(theme.badge_text).bg(theme.accent))
+        .label_style(Style::default().fg(theme.muted))
+}
+
+/// A long duration in hours and minutes, like `12h 05m`.
+pub fn format_watch_time(secs: u64) -> String {
+    let (hours, minutes) = (secs / 3600, secs / 60 % 60);
+    if hours == 0 {
+        format!("{minutes}m")
+    } else {
+        format!("{hours}h {minutes:02}m")
+    }
+}
@@ -0,0 +1,103 @@
+//! The Watch later view: videos saved to watch some other time, oldest
+//! first.
+
+use crate::{enqueue_items, format_age, replay, save_watch_later, App, QueueItem};
+use crossterm::event::KeyCode;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+use time::OffsetDateTime;
+
+#[derive(Default)]
+pub struct WatchLaterView {
+    pub selected: usize,
+}
+
+pub fn handle_key(app: &mut App, key: KeyCode) {
+    let last = app.watch_later.len().saturating_sub(1);
+    let selected = app.views.watch_later.selected.min(last);
+    match key {
+        KeyCode::Up => app.views.watch_later.selected = selected.saturating_sub(1),
+        KeyCode::Down => app.views.watch_later.selected = (selected + 1).min(last),
+        KeyCode::Home => app.views.watch_later.selected = 0,
+        KeyCode::End => app.views.watch_later.selected = last,
+        KeyCode::Enter => {
+            if let Some(entry) = app.watch_later.get(selected) {
+                let (id, title) = (entry.id.clone(), entry.title.clone());
+                replay(app, &id, &title);
+            }
+        }
+        KeyCode::Char('P') if !app.watch_later.is_empty() => {
+            let items = app
+                .watch_later
+                .iter()
+                .map(|entry| QueueItem {
+                    id: entry.id.clone(),
+                    title: entry.title.clone(),
+                    url: format!("https://www.youtube.com/watch?v={}", entry.id),
+                })
+                .collect();
+            enqueue_items(app, items);
+        }
+        KeyCode::Char('d') | KeyCode::Delete if !app.watch_later.is_empty() => {
+            let entry = app.watch_later.remove(selected);
+            app.views.watch_later.selected = selected.min(last.saturating_sub(1));
+            save_watch_later(app, format!("Removed {} from Watch later.", entry.title));
+        }
+        _ => {}
+    }
+}
+
+pub fn render(f: &mut Frame<'_>, app: &App, area: ratatui::layout::Rect) {
+    let theme = app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "Watch later ({}) — ⏎ play, P play all, d remove, Esc back",
+            app.watch_later.len()
+        ))
+        .border_style(Style::default().fg(theme.accent));
+    if app.watch_later.is_empty() {
+        f.render_widget(
+            Paragraph::new("Nothing to watch later. Press w on a video to add it.").block(block),
+            area,
+        );
+        return;
+    }
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let items: Vec<ListItem> = app
+        .watch_later
+        .iter()
+        .map(|entry| {
+            let mut spans = vec![Span::raw(entry.title.clone())];
+            if let Some(channel) = &entry.channel {
+                spans.push(Span::styled(
+                    format!(" · {channel}"),
+                    Style::default().fg(theme.channel),
+                ));
+            }
+            spans.push(Span::styled(
+                format!(
+                    " · added {}",
+                    format_age((now - entry.added_at).max(0) as u64)
+                ),
+                Style::default().fg(theme.muted),
+            ));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(
+        app.views
+            .watch_later
+            .selected
+            .min(app.watch_later.len() - 1),
+    ));
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .fg(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+}